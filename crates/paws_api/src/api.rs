@@ -1,6 +1,8 @@
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use futures::stream::BoxStream;
 use paws_app::dto::ToolsOverview;
 use paws_app::{User, UserUsage};
@@ -20,6 +22,10 @@ pub trait API: Sync + Send {
     /// environment
     async fn get_tools(&self) -> anyhow::Result<ToolsOverview>;
 
+    /// Executes a tool call outside of a conversation as the active agent,
+    /// enforcing its tool restrictions, hooks and the workflow policies
+    async fn call_tool(&self, call: ToolCallFull) -> anyhow::Result<ToolResult>;
+
     /// Provides a list of models available in the current environment
     async fn get_models(&self) -> Result<Vec<Model>>;
     /// Provides a list of agents available in the current environment
@@ -61,6 +67,14 @@ pub trait API: Sync + Send {
     /// Finds the last active conversation for the current workspace
     async fn last_conversation(&self) -> Result<Option<Conversation>>;
 
+    /// Searches the titles and messages of the conversations in the active
+    /// workspace, best matches first
+    async fn search_conversations(
+        &self,
+        query: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<ConversationMatch>>;
+
     /// Permanently deletes a conversation
     ///
     /// # Arguments
@@ -70,6 +84,10 @@ pub trait API: Sync + Send {
     /// Returns an error if the operation fails
     async fn delete_conversation(&self, conversation_id: &ConversationId) -> Result<()>;
 
+    /// Returns the usage of every workspace recorded since the given instant,
+    /// summed per day, workspace, provider and model
+    async fn usage_report(&self, since: DateTime<Utc>) -> Result<Vec<UsageReportEntry>>;
+
     /// Compacts the context of the main agent for the given conversation and
     /// persists it. Returns metrics about the compaction (original vs.
     /// compacted tokens and messages).
@@ -78,6 +96,38 @@ pub trait API: Sync + Send {
         conversation_id: &ConversationId,
     ) -> Result<CompactionResult>;
 
+    /// Rewinds the conversation to the end of the given turn, restoring every
+    /// file modified afterwards and removing the messages of the later turns.
+    /// Rewinds the latest turn when no turn is provided.
+    async fn rewind_conversation(
+        &self,
+        conversation_id: &ConversationId,
+        turn: Option<usize>,
+    ) -> Result<RewindResult>;
+
+    /// Lists the snapshots recorded for the given file, oldest first
+    async fn list_snapshots(&self, path: &Path) -> Result<Vec<Snapshot>>;
+
+    /// Reads the content stored in a snapshot, `None` if it no longer exists
+    async fn read_snapshot(&self, snapshot: &Snapshot) -> Result<Option<Vec<u8>>>;
+
+    /// Restores a file to the content of one of its snapshots
+    async fn restore_snapshot(&self, snapshot: &Snapshot) -> Result<()>;
+
+    /// Reverts the most recent undo of the given file
+    async fn redo_snapshot(&self, path: &Path) -> Result<()>;
+
+    /// Indexes the workspace for semantic search, re-embedding only the files
+    /// that changed since the last build
+    async fn build_index(&self) -> Result<IndexStatus>;
+
+    /// Reports the state of the semantic search index of the workspace
+    async fn index_status(&self) -> Result<IndexStatus>;
+
+    /// Deletes the semantic search index of the workspace, returning whether
+    /// one existed
+    async fn clear_index(&self) -> Result<bool>;
+
     /// Executes a shell command using the shell tool infrastructure
     async fn execute_shell_command(
         &self,
@@ -146,6 +196,17 @@ pub trait API: Sync + Send {
     /// Refresh MCP caches by fetching fresh data
     async fn reload_mcp(&self) -> Result<()>;
 
+    /// Lists the tools, resources and prompts exposed by the MCP servers
+    async fn get_mcp_servers(&self) -> Result<McpServers>;
+
+    /// Renders a prompt exposed by an MCP server into its messages
+    async fn render_mcp_prompt(
+        &self,
+        server: &ServerName,
+        name: &str,
+        arguments: BTreeMap<String, String>,
+    ) -> Result<Vec<McpPromptMessage>>;
+
     /// List of commands defined in .md file(s)
     async fn get_commands(&self) -> Result<Vec<Command>>;
 
@@ -1,13 +1,15 @@
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use futures::stream::BoxStream;
 use paws_app::dto::ToolsOverview;
 use paws_app::{
-    AgentProviderResolver, AgentRegistry, AppConfigService, AuthService, CommandInfra,
-    CommandLoaderService, ConversationService, DataGenerationApp, EnvironmentInfra,
+    AgentProviderResolver, AgentRegistry, AppConfigService, AuthService, CodeIndexService,
+    CommandInfra, CommandLoaderService, ConversationService, DataGenerationApp, EnvironmentInfra,
     EnvironmentService, FileDiscoveryService, McpConfigManager, McpService, PawsApp,
     ProviderAuthService, ProviderService, Services, User, UserUsage, Walker,
 };
@@ -40,8 +42,8 @@ impl<A, F> PawsAPI<A, F> {
 }
 
 impl PawsAPI<PawsServices<PawsRepo<PawsInfra>>, PawsRepo<PawsInfra>> {
-    pub fn init(restricted: bool, cwd: PathBuf) -> Self {
-        let infra = Arc::new(PawsInfra::new(restricted, cwd));
+    pub fn init(restricted: bool, os_sandbox: bool, headless: bool, cwd: PathBuf) -> Self {
+        let infra = Arc::new(PawsInfra::new(restricted, os_sandbox, cwd).headless(headless));
         let repo = Arc::new(PawsRepo::new(infra.clone()));
         let app = Arc::new(PawsServices::new(repo.clone()));
         PawsAPI::new(app, repo)
@@ -54,8 +56,10 @@ impl PawsAPI<PawsServices<PawsRepo<PawsInfra>>, PawsRepo<PawsInfra>> {
 }
 
 #[async_trait::async_trait]
-impl<A: Services, F: CommandInfra + EnvironmentInfra + SkillRepository + AppConfigRepository> API
-    for PawsAPI<A, F>
+impl<
+    A: Services,
+    F: CommandInfra + EnvironmentInfra + SkillRepository + AppConfigRepository + SnapshotRepository,
+> API for PawsAPI<A, F>
 {
     async fn discover(&self) -> Result<Vec<File>> {
         let environment = self.services.get_environment();
@@ -67,6 +71,15 @@ impl<A: Services, F: CommandInfra + EnvironmentInfra + SkillRepository + AppConf
         self.app().list_tools().await
     }
 
+    async fn call_tool(&self, call: ToolCallFull) -> anyhow::Result<ToolResult> {
+        let agent_id = self
+            .services
+            .get_active_agent_id()
+            .await?
+            .unwrap_or_default();
+        self.app().call_tool(agent_id, call).await
+    }
+
     async fn get_models(&self) -> Result<Vec<Model>> {
         self.app().get_models().await
     }
@@ -90,11 +103,14 @@ impl<A: Services, F: CommandInfra + EnvironmentInfra + SkillRepository + AppConf
         &self,
         chat: ChatRequest,
     ) -> anyhow::Result<MpscStream<Result<ChatResponse, anyhow::Error>>> {
-        let agent_id = self
-            .services
-            .get_active_agent_id()
-            .await?
-            .unwrap_or_default();
+        let agent_id = match chat.agent_id.clone() {
+            Some(agent_id) => agent_id,
+            None => self
+                .services
+                .get_active_agent_id()
+                .await?
+                .unwrap_or_default(),
+        };
         self.app().chat(agent_id, chat).await
     }
 
@@ -116,6 +132,42 @@ impl<A: Services, F: CommandInfra + EnvironmentInfra + SkillRepository + AppConf
             .await
     }
 
+    async fn rewind_conversation(
+        &self,
+        conversation_id: &ConversationId,
+        turn: Option<usize>,
+    ) -> anyhow::Result<RewindResult> {
+        self.app().rewind_conversation(conversation_id, turn).await
+    }
+
+    async fn list_snapshots(&self, path: &Path) -> Result<Vec<Snapshot>> {
+        self.infra.list_snapshots(path).await
+    }
+
+    async fn read_snapshot(&self, snapshot: &Snapshot) -> Result<Option<Vec<u8>>> {
+        self.infra.read_snapshot(snapshot).await
+    }
+
+    async fn restore_snapshot(&self, snapshot: &Snapshot) -> Result<()> {
+        self.infra.restore_snapshot(snapshot).await
+    }
+
+    async fn redo_snapshot(&self, path: &Path) -> Result<()> {
+        self.infra.redo_snapshot(path).await
+    }
+
+    async fn build_index(&self) -> Result<IndexStatus> {
+        self.services.build_index().await
+    }
+
+    async fn index_status(&self) -> Result<IndexStatus> {
+        self.services.index_status().await
+    }
+
+    async fn clear_index(&self) -> Result<bool> {
+        self.services.clear_index().await
+    }
+
     fn environment(&self) -> Environment {
         self.services.get_environment().clone()
     }
@@ -147,10 +199,22 @@ impl<A: Services, F: CommandInfra + EnvironmentInfra + SkillRepository + AppConf
         self.services.last_conversation().await
     }
 
+    async fn search_conversations(
+        &self,
+        query: &str,
+        limit: Option<usize>,
+    ) -> anyhow::Result<Vec<ConversationMatch>> {
+        self.services.search_conversations(query, limit).await
+    }
+
     async fn delete_conversation(&self, conversation_id: &ConversationId) -> anyhow::Result<()> {
         self.services.delete_conversation(conversation_id).await
     }
 
+    async fn usage_report(&self, since: DateTime<Utc>) -> anyhow::Result<Vec<UsageReportEntry>> {
+        self.services.usage_report(since).await
+    }
+
     async fn execute_shell_command(
         &self,
         command: &str,
@@ -262,6 +326,22 @@ impl<A: Services, F: CommandInfra + EnvironmentInfra + SkillRepository + AppConf
     async fn reload_mcp(&self) -> Result<()> {
         self.services.mcp_service().reload_mcp().await
     }
+
+    async fn get_mcp_servers(&self) -> Result<McpServers> {
+        self.services.mcp_service().get_mcp_servers().await
+    }
+
+    async fn render_mcp_prompt(
+        &self,
+        server: &ServerName,
+        name: &str,
+        arguments: BTreeMap<String, String>,
+    ) -> Result<Vec<McpPromptMessage>> {
+        self.services
+            .mcp_service()
+            .render_mcp_prompt(server, name, arguments)
+            .await
+    }
     async fn get_commands(&self) -> Result<Vec<Command>> {
         self.services.get_commands().await
     }
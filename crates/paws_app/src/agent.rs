@@ -1,13 +1,15 @@
 use std::sync::Arc;
 
+use chrono::{DateTime, Utc};
 use paws_domain::{
-    Agent, ChatCompletionMessage, Context, Conversation, ModelId, ProviderId, ResultStream,
-    ToolCallContext, ToolCallFull, ToolResult,
+    Agent, ChatCompletionMessage, Context, Conversation, ConversationId, Hook, HookOutcome,
+    HookPayload, ModelId, ProviderId, ResultStream, ToolCallContext, ToolCallFull, ToolResult,
+    Usage,
 };
 
 use crate::services::AppConfigService;
 use crate::tool_registry::ToolRegistry;
-use crate::{ConversationService, ProviderService, Services};
+use crate::{ConversationService, HookService, ProviderService, Services};
 
 /// Agent service trait that provides core chat and tool call functionality.
 /// This trait abstracts the essential operations needed by the Orchestrator.
@@ -31,6 +33,24 @@ pub trait AgentService: Send + Sync + 'static {
 
     /// Synchronize the on-going conversation
     async fn update(&self, conversation: Conversation) -> anyhow::Result<()>;
+
+    /// Record the usage of a completion in the workspace usage ledger
+    async fn record_usage(
+        &self,
+        conversation_id: &ConversationId,
+        usage: &Usage,
+        model: &ModelId,
+        provider: &ProviderId,
+    ) -> anyhow::Result<()>;
+
+    /// Total usage of the workspace since the given instant
+    async fn usage_since(&self, since: DateTime<Utc>) -> anyhow::Result<Usage>;
+
+    /// Total usage recorded for a conversation in the usage ledger
+    async fn conversation_usage(&self, conversation_id: &ConversationId) -> anyhow::Result<Usage>;
+
+    /// Runs the hooks of an agent lifecycle event
+    async fn run_hooks(&self, hooks: &[Hook], payload: HookPayload) -> anyhow::Result<HookOutcome>;
 }
 
 /// Blanket implementation of AgentService for any type that implements Services
@@ -64,4 +84,26 @@ impl<T: Services> AgentService for T {
     async fn update(&self, conversation: Conversation) -> anyhow::Result<()> {
         self.upsert_conversation(conversation).await
     }
+
+    async fn record_usage(
+        &self,
+        conversation_id: &ConversationId,
+        usage: &Usage,
+        model: &ModelId,
+        provider: &ProviderId,
+    ) -> anyhow::Result<()> {
+        ConversationService::record_usage(self, conversation_id, usage, model, provider).await
+    }
+
+    async fn usage_since(&self, since: DateTime<Utc>) -> anyhow::Result<Usage> {
+        ConversationService::usage_since(self, since).await
+    }
+
+    async fn conversation_usage(&self, conversation_id: &ConversationId) -> anyhow::Result<Usage> {
+        ConversationService::conversation_usage(self, conversation_id).await
+    }
+
+    async fn run_hooks(&self, hooks: &[Hook], payload: HookPayload) -> anyhow::Result<HookOutcome> {
+        HookService::run_hooks(self, hooks, payload).await
+    }
 }
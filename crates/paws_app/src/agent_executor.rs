@@ -82,6 +82,8 @@ impl<S: Services> AgentExecutor<S> {
                 ChatResponse::ToolCallEnd(_) => ctx.send(message).await?,
                 ChatResponse::RetryAttempt { .. } => ctx.send(message).await?,
                 ChatResponse::Interrupt { .. } => ctx.send(message).await?,
+                // The parent's front-end answers the questions of sub-agents
+                ChatResponse::FollowUp { .. } => ctx.send(message).await?,
             }
         }
 
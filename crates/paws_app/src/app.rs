@@ -3,6 +3,7 @@ use std::sync::Arc;
 
 use anyhow::Result;
 use chrono::Local;
+use merge::Merge;
 use paws_common::stream::MpscStream;
 use paws_domain::{InitAuth, *};
 
@@ -22,7 +23,7 @@ use crate::tool_resolver::ToolResolver;
 use crate::user_prompt::UserPromptGenerator;
 use crate::{
     AgentProviderResolver, ConversationService, EnvironmentService, FileDiscoveryService,
-    ProviderService, Services, WorkflowService,
+    FsUndoService, ProviderService, Services, WorkflowService,
 };
 
 /// PawsApp handles the core chat functionality by orchestrating various
@@ -82,7 +83,7 @@ impl<S: Services> PawsApp<S> {
         let agent_provider_resolver = AgentProviderResolver::new(services.clone());
 
         // Get agent and apply workflow config
-        let agent = self
+        let mut agent = self
             .services
             .get_agent(&agent_id)
             .await?
@@ -90,6 +91,11 @@ impl<S: Services> PawsApp<S> {
             .apply_workflow_config(&workflow)
             .set_compact_model_if_none();
 
+        // Limits raised by the user after a budget was reached take precedence
+        if let Some(budget) = chat.budget.clone() {
+            agent.budget.get_or_insert_default().merge(budget);
+        }
+
         let agent_provider = agent_provider_resolver
             .get_provider(Some(agent.id.clone()))
             .await?;
@@ -140,6 +146,8 @@ impl<S: Services> PawsApp<S> {
             .apply(conversation);
         let conversation = SetConversationId.apply(conversation);
 
+        let agent_fallbacks = agent.fallbacks.clone().unwrap_or_default();
+
         // Create the orchestrator with all necessary dependencies
         let orch = Orchestrator::new(
             services.clone(),
@@ -150,7 +158,7 @@ impl<S: Services> PawsApp<S> {
         )
         .error_tracker(ToolErrorTracker::new(max_tool_failure_per_turn))
         .tool_definitions(tool_definitions)
-        .models(models);
+        .models(self.with_fallback_models(&agent_fallbacks, models).await);
 
         // Create and return the stream
         let stream = MpscStream::spawn(
@@ -178,6 +186,43 @@ impl<S: Services> PawsApp<S> {
         Ok(stream)
     }
 
+    /// Adds the models of the fallback providers, so that the context window
+    /// and tool support of a fallback model are known once it takes over.
+    /// Providers that can't list their models are skipped.
+    async fn with_fallback_models(
+        &self,
+        fallbacks: &[ModelFallback],
+        mut models: Vec<Model>,
+    ) -> Vec<Model> {
+        let mut providers: Vec<&ProviderId> = Vec::new();
+        for fallback in fallbacks {
+            if !providers.contains(&&fallback.provider) {
+                providers.push(&fallback.provider);
+            }
+        }
+
+        for provider_id in providers {
+            let fallback_models = async {
+                let provider = self.services.get_provider(provider_id.clone()).await?;
+                self.services.models(provider).await
+            }
+            .await;
+            match fallback_models {
+                Ok(fallback_models) => {
+                    for model in fallback_models {
+                        if models.iter().all(|known| known.id != model.id) {
+                            models.push(model);
+                        }
+                    }
+                }
+                Err(error) => {
+                    tracing::warn!(provider = %provider_id, error = ?error, "Failed to list fallback models")
+                }
+            }
+        }
+        models
+    }
+
     /// Compacts the context of the main agent for the given conversation and
     /// persists it. Returns metrics about the compaction (original vs.
     /// compacted tokens and messages).
@@ -223,11 +268,10 @@ impl<S: Services> PawsApp<S> {
         };
 
         // Get compact config from the agent
-        let Some(compact) = agent
+        let agent = agent
             .apply_workflow_config(&workflow)
-            .set_compact_model_if_none()
-            .compact
-        else {
+            .set_compact_model_if_none();
+        let Some(compact) = agent.compact.clone() else {
             return Ok(CompactionResult::new(
                 original_token_count,
                 0,
@@ -238,7 +282,15 @@ impl<S: Services> PawsApp<S> {
 
         // Apply compaction using the Compactor
         let environment = self.services.get_environment();
-        let compacted_context = Compactor::new(compact, environment).compact(context, true)?;
+        let compacted_context = Compactor::new(compact, environment)
+            .compact_with(
+                self.services.as_ref(),
+                &agent,
+                conversation_id,
+                context,
+                true,
+            )
+            .await?;
 
         let compacted_messages = compacted_context.messages.len();
         let compacted_tokens = *compacted_context.token_count();
@@ -257,10 +309,78 @@ impl<S: Services> PawsApp<S> {
         ))
     }
 
+    /// Rewinds the conversation to the end of the given turn, restoring every
+    /// file modified afterwards and removing the messages of the later turns.
+    /// Rewinds the latest turn when no turn is provided.
+    pub async fn rewind_conversation(
+        &self,
+        conversation_id: &ConversationId,
+        turn: Option<usize>,
+    ) -> Result<RewindResult> {
+        let mut conversation = self
+            .services
+            .find_conversation(conversation_id)
+            .await?
+            .ok_or_else(|| paws_domain::Error::ConversationNotFound(*conversation_id))?;
+
+        let turn = turn.unwrap_or_else(|| {
+            conversation
+                .metrics
+                .checkpoints
+                .last()
+                .map(|checkpoint| checkpoint.turn)
+                .unwrap_or_default()
+                .saturating_sub(1)
+        });
+
+        let original_messages = conversation
+            .context
+            .as_ref()
+            .map(|context| context.messages.len())
+            .unwrap_or_default();
+
+        // Only restore the workspace once the rewound conversation is saved, and
+        // put the original conversation back if the files can't be restored
+        let original = conversation.clone();
+        let files = conversation.rewind(turn)?;
+        let remaining_messages = conversation
+            .context
+            .as_ref()
+            .map(|context| context.messages.len())
+            .unwrap_or_default();
+
+        self.services.upsert_conversation(conversation).await?;
+        let restored_files = match self.services.rewind(files).await {
+            Ok(restored_files) => restored_files,
+            Err(error) => {
+                self.services.upsert_conversation(original).await?;
+                return Err(error);
+            }
+        };
+
+        Ok(RewindResult::new(
+            turn,
+            original_messages - remaining_messages,
+            restored_files,
+        ))
+    }
+
     pub async fn list_tools(&self) -> Result<ToolsOverview> {
         self.tool_registry.tools_overview().await
     }
 
+    /// Executes a single tool call outside of a conversation on behalf of the
+    /// given agent, subject to its tool restrictions and hooks.
+    pub async fn call_tool(&self, agent_id: AgentId, call: ToolCallFull) -> Result<ToolResult> {
+        let agent = self
+            .services
+            .get_agent(&agent_id)
+            .await?
+            .ok_or(crate::Error::AgentNotFound(agent_id))?;
+        let context = ToolCallContext::new(Metrics::default());
+        Ok(self.tool_registry.call(&agent, &context, call).await)
+    }
+
     /// Gets available models for the default provider with automatic credential
     /// refresh.
     pub async fn get_models(&self) -> Result<Vec<Model>> {
@@ -179,6 +179,7 @@ mod tests {
                     auth_details: AuthDetails::ApiKey("test-key".to_string().into()),
                     url_params: Default::default(),
                 }),
+                pricing: Default::default(),
             })
         }
 
@@ -1,11 +1,18 @@
 use paws_domain::{
-    Compact, CompactionStrategy, Context, ContextMessage, ContextSummary, Environment, Transformer,
+    Agent, ChatCompletionMessageFull, Compact, CompactionStrategy, Context, ContextMessage,
+    ContextSummary, ConversationId, Environment, MessageEntry, ResultStreamExt, SummaryStrategy,
+    SummaryTag, Transformer, extract_tag_content,
 };
-use tracing::info;
+use tracing::{info, warn};
 
 use crate::TemplateEngine;
+use crate::agent::AgentService;
 use crate::transformers::SummaryTransformer;
 
+/// Maximum number of characters of the transcript sent to the summarization
+/// model, older messages are left out beyond it
+const MAX_TRANSCRIPT_CHARS: usize = 400_000;
+
 /// A service dedicated to handling context compaction.
 pub struct Compactor {
     compact: Compact,
@@ -38,6 +45,56 @@ impl Compactor {
 impl Compactor {
     /// Apply compaction to the context if requested.
     pub fn compact(&self, context: Context, max: bool) -> anyhow::Result<Context> {
+        match self.eviction_range(&context, max) {
+            Some(sequence) => self.compress_single_sequence(context, sequence),
+            None => Ok(context),
+        }
+    }
+
+    /// Applies compaction like [`Compactor::compact`]. With the LLM summary
+    /// strategy, the compaction model of the agent is asked for a narrative
+    /// of the evicted messages which is merged with the structured summary.
+    /// The structured summary is used alone when the model fails. The usage of
+    /// the summary request is recorded for `conversation_id`.
+    pub async fn compact_with<S: AgentService>(
+        &self,
+        services: &S,
+        agent: &Agent,
+        conversation_id: &ConversationId,
+        context: Context,
+        max: bool,
+    ) -> anyhow::Result<Context> {
+        if self.compact.summary_strategy.unwrap_or_default() != SummaryStrategy::Llm {
+            return self.compact(context, max);
+        }
+        let Some(sequence) = self.eviction_range(&context, max) else {
+            return Ok(context);
+        };
+
+        let (start, end) = sequence;
+        let narrative = match self
+            .summarize(
+                services,
+                agent,
+                conversation_id,
+                &context.messages[start..=end],
+            )
+            .await
+        {
+            Ok(narrative) => Some(narrative),
+            Err(error) => {
+                warn!(
+                    agent_id = %agent.id,
+                    error = ?error,
+                    "Failed to summarize the compacted messages, using the structured summary"
+                );
+                None
+            }
+        };
+        self.compress_sequence(context, sequence, narrative)
+    }
+
+    fn eviction_range(&self, context: &Context, max: bool) -> Option<(usize, usize)> {
         let eviction = CompactionStrategy::evict(self.compact.eviction_window);
         let retention = CompactionStrategy::retain(self.compact.retention_window);
 
@@ -47,20 +104,112 @@ impl Compactor {
         } else {
             eviction.min(retention)
         };
+        strategy.eviction_range(context)
+    }
 
-        match strategy.eviction_range(&context) {
-            Some(sequence) => self.compress_single_sequence(context, sequence),
-            None => Ok(context),
+    fn summary_tag(&self) -> SummaryTag {
+        self.compact.summary_tag.clone().unwrap_or_default()
+    }
+
+    /// Asks the compaction model for a narrative summary of `messages`. The
+    /// narrative of an earlier compaction found in the messages is passed
+    /// along as the previous summary instead of its whole frame, so each
+    /// compaction only summarizes the messages added since the last one.
+    async fn summarize<S: AgentService>(
+        &self,
+        services: &S,
+        agent: &Agent,
+        conversation_id: &ConversationId,
+        messages: &[MessageEntry],
+    ) -> anyhow::Result<String> {
+        let tag = self.summary_tag();
+        let mut previous = None;
+        let mut transcript = String::new();
+        for message in messages.iter().filter(|message| !message.is_droppable()) {
+            match message
+                .content()
+                .and_then(|content| extract_tag_content(content, tag.as_str()))
+            {
+                Some(narrative) => previous = Some(narrative.to_string()),
+                None => transcript.push_str(&message.to_text()),
+            }
+        }
+        if transcript.len() > MAX_TRANSCRIPT_CHARS {
+            let mut cut = transcript.len() - MAX_TRANSCRIPT_CHARS;
+            while !transcript.is_char_boundary(cut) {
+                cut += 1;
+            }
+            transcript.drain(..cut);
+        }
+
+        let system = TemplateEngine::default().render(
+            "paws-system-prompt-context-summarizer.md",
+            &serde_json::json!({
+                "summary_tag": tag.as_str(),
+                "max_tokens": self.compact.max_tokens,
+            }),
+        )?;
+        let mut prompt = String::new();
+        if let Some(previous) = previous {
+            prompt.push_str(&format!(
+                "<previous_summary>\n{previous}\n</previous_summary>\n"
+            ));
+        }
+        prompt.push_str(&format!("<chat_history>{transcript}</chat_history>"));
+
+        let model = self
+            .compact
+            .model
+            .clone()
+            .unwrap_or_else(|| agent.model.clone());
+        let mut context = Context::default()
+            .conversation_id(ConversationId::generate())
+            .add_message(ContextMessage::system(system))
+            .add_message(ContextMessage::user(prompt, Some(model.clone())));
+        if let Some(max_tokens) = self.compact.max_tokens {
+            context = context.max_tokens(max_tokens);
         }
+
+        let stream = services
+            .chat_agent(&model, context, Some(agent.provider.clone()))
+            .await?;
+        let ChatCompletionMessageFull { content, usage, .. } =
+            stream.into_full(false, None).await?;
+        if let Err(error) = services
+            .record_usage(conversation_id, &usage, &model, &agent.provider)
+            .await
+        {
+            warn!(conversation_id = %conversation_id, error = ?error, "Failed to record usage");
+        }
+        let narrative = extract_tag_content(&content, tag.as_str())
+            .unwrap_or(&content)
+            .trim();
+        anyhow::ensure!(
+            !narrative.is_empty(),
+            "The summarization model returned an empty summary"
+        );
+        Ok(narrative.to_string())
     }
 
     /// Compress a single identified sequence of assistant messages.
     fn compress_single_sequence(
+        &self,
+        context: Context,
+        sequence: (usize, usize),
+    ) -> anyhow::Result<Context> {
+        self.compress_sequence(context, sequence, None)
+    }
+
+    /// Replaces the sequence with a summary frame, including the narrative
+    /// produced by the summarization model when available
+    fn compress_sequence(
         &self,
         mut context: Context,
         sequence: (usize, usize),
+        narrative: Option<String>,
     ) -> anyhow::Result<Context> {
         let (start, end) = sequence;
+        let tag = self.summary_tag();
 
         // The sequence from the original message that needs to be compacted
         // Filter out droppable messages (e.g., attachments) from compaction
@@ -70,8 +219,17 @@ impl Compactor {
             .cloned()
             .collect::<Vec<_>>();
 
-        // Create a temporary context for the sequence to generate summary
-        let sequence_context = Context::default().messages(compaction_sequence.clone());
+        // Create a temporary context for the sequence to generate summary. The
+        // new narrative supersedes the ones of earlier summaries.
+        let summarized_sequence = if narrative.is_some() {
+            compaction_sequence
+                .iter()
+                .map(|message| strip_narrative(message, tag.as_str()))
+                .collect()
+        } else {
+            compaction_sequence.clone()
+        };
+        let sequence_context = Context::default().messages(summarized_sequence);
 
         // Generate context summary with tool call information
         let context_summary = ContextSummary::from(&sequence_context);
@@ -88,7 +246,11 @@ impl Compactor {
 
         let summary = TemplateEngine::default().render(
             "paws-partial-summary-frame.md",
-            &serde_json::json!({"messages": context_summary.messages}),
+            &serde_json::json!({
+                "messages": context_summary.messages,
+                "narrative": narrative,
+                "summary_tag": tag.as_str(),
+            }),
         )?;
 
         // Extended thinking reasoning chain preservation
@@ -146,6 +308,21 @@ impl Compactor {
     }
 }
 
+/// Removes the narrative wrapped in `tag` from the content of a message
+fn strip_narrative(message: &MessageEntry, tag: &str) -> MessageEntry {
+    let mut message = message.clone();
+    if let ContextMessage::Text(text) = &mut *message {
+        let (opening, closing) = (format!("<{tag}>"), format!("</{tag}>"));
+        if let (Some(start), Some(end)) =
+            (text.content.find(&opening), text.content.rfind(&closing))
+            && start < end
+        {
+            text.content.replace_range(start..end + closing.len(), "");
+        }
+    }
+    message
+}
+
 #[cfg(test)]
 mod tests {
     use std::path::PathBuf;
@@ -323,6 +500,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_compress_sequence_replaces_previous_narrative() {
+        let compactor = Compactor::new(Compact::new(), test_environment());
+        let context = Context::default()
+            .add_message(ContextMessage::user("M1", None))
+            .add_message(ContextMessage::assistant("R1", None, None))
+            .add_message(ContextMessage::user("M2", None))
+            .add_message(ContextMessage::assistant("R2", None, None));
+        let context = compactor
+            .compress_sequence(context, (0, 1), Some("First narrative".to_string()))
+            .unwrap()
+            .add_message(ContextMessage::user("M3", None))
+            .add_message(ContextMessage::assistant("R3", None, None));
+
+        let actual = compactor
+            .compress_sequence(context, (0, 2), Some("Second narrative".to_string()))
+            .unwrap();
+
+        let summary = actual.messages[0].content().unwrap();
+        assert!(summary.contains("Second narrative"));
+        assert!(!summary.contains("First narrative"));
+        assert_eq!(
+            extract_tag_content(summary, SummaryTag::default().as_str()),
+            Some("Second narrative")
+        );
+    }
+
     fn render_template(data: &serde_json::Value) -> String {
         TemplateEngine::default()
             .render("paws-partial-summary-frame.md", data)
@@ -418,6 +622,7 @@ mod tests {
             prompt_tokens: TokenCount::Actual(45000),
             completion_tokens: TokenCount::Actual(5000),
             cached_tokens: TokenCount::Actual(0),
+            cache_write_tokens: TokenCount::Actual(0),
             cost: Some(1.5),
         };
 
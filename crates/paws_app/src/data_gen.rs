@@ -1,13 +1,16 @@
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use anyhow::{Context as _, Result};
 use futures::StreamExt;
 use futures::stream::{self, BoxStream};
 use paws_domain::{
-    Context, ContextMessage, DataGenerationParameters, ResultStreamExt, Template, ToolDefinition,
+    ChatCompletionMessage, Context, ContextMessage, DataGenerationParameters, RateLimit,
+    ResultStreamExt, Template, ToolDefinition,
 };
 use schemars::schema::RootSchema;
+use tokio::time::Instant;
 use tracing::{debug, info};
 
 use crate::{
@@ -108,6 +111,8 @@ impl<A: Services> DataGenerationApp<A> {
         }
 
         let services = self.services.clone();
+        let retry_config = services.get_environment().retry_config;
+        let throttle = Arc::new(Throttle::new(concurrency));
 
         let json_stream = input.into_iter().map(move |input| {
             let provider = provider.clone();
@@ -115,6 +120,8 @@ impl<A: Services> DataGenerationApp<A> {
             let user_prompt = user_prompt.clone();
             let model_id = model_id.clone();
             let services = services.clone();
+            let retry_config = retry_config.clone();
+            let throttle = throttle.clone();
 
             async move {
                 debug!("Processing data generation request");
@@ -130,8 +137,34 @@ impl<A: Services> DataGenerationApp<A> {
                 context =
                     context.add_message(ContextMessage::user(content, Some(model_id.clone())));
 
-                let stream = services.chat(&model_id, context, provider.clone()).await?;
-                let response = stream.into_full(false, None).await?;
+                let request = || async {
+                    throttle.wait().await;
+                    let response = async {
+                        let stream = services
+                            .chat(&model_id, context.clone(), provider.clone())
+                            .await?;
+                        let throttle = throttle.clone();
+                        let stream: paws_domain::BoxStream<_, _> =
+                            Box::pin(stream.inspect(move |message| {
+                                if let Ok(ChatCompletionMessage {
+                                    rate_limit: Some(rate_limit),
+                                    ..
+                                }) = message
+                                {
+                                    throttle.observe_rate_limit(rate_limit);
+                                }
+                            }));
+                        stream.into_full(false, None).await
+                    }
+                    .await;
+                    response.inspect_err(|error| throttle.observe(error))
+                };
+                let response = crate::retry::retry_with_config(
+                    &retry_config,
+                    request,
+                    None::<fn(&anyhow::Error, Duration)>,
+                )
+                .await?;
 
                 anyhow::Ok((input, response))
             }
@@ -163,3 +196,113 @@ impl<A: Services> DataGenerationApp<A> {
         Ok(json_stream)
     }
 }
+
+/// Holds back the concurrent requests once the provider reports a rate limit
+/// reached or about to be, so that they don't all fail until the limit resets
+struct Throttle {
+    resume_at: Mutex<Option<Instant>>,
+    /// Number of requests that can be in flight at once
+    concurrency: u64,
+}
+
+impl Throttle {
+    fn new(concurrency: usize) -> Self {
+        Self { resume_at: Mutex::default(), concurrency: concurrency as u64 }
+    }
+
+    /// Waits until the reported limits allow a new request
+    async fn wait(&self) {
+        let resume_at = *self.resume_at.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(resume_at) = resume_at {
+            tokio::time::sleep_until(resume_at).await;
+        }
+    }
+
+    /// Pauses every request for as long as the rate limit reported by the
+    /// error requires
+    fn observe(&self, error: &anyhow::Error) {
+        if let Some(delay) = RateLimit::find(error).and_then(RateLimit::delay) {
+            self.pause(delay);
+        }
+    }
+
+    /// Pauses every request until the quota resets once the rate limit
+    /// reported by a successful response leaves fewer requests than can be in
+    /// flight at once
+    fn observe_rate_limit(&self, rate_limit: &RateLimit) {
+        if let Some(delay) = rate_limit.hold_back(self.concurrency) {
+            self.pause(delay);
+        }
+    }
+
+    fn pause(&self, delay: Duration) {
+        let resume_at = Instant::now() + delay;
+        let mut current = self.resume_at.lock().unwrap_or_else(|e| e.into_inner());
+        if current.is_none_or(|current| current < resume_at) {
+            *current = Some(resume_at);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use paws_domain::RateLimitWindow;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn rate_limited(retry_after: u64) -> anyhow::Error {
+        let rate_limit = RateLimit {
+            retry_after: Some(Duration::from_secs(retry_after)),
+            ..Default::default()
+        };
+        paws_domain::Error::Retryable(anyhow::Error::msg(rate_limit)).into()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_throttle_waits_for_longest_reported_delay() {
+        let fixture = Throttle::new(4);
+        let start = Instant::now();
+
+        fixture.observe(&rate_limited(10));
+        fixture.observe(&rate_limited(3));
+        fixture.observe(&anyhow::anyhow!("Invalid API key"));
+        fixture.wait().await;
+
+        assert_eq!(start.elapsed(), Duration::from_secs(10));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_throttle_holds_back_before_limit_is_reached() {
+        let fixture = Throttle::new(4);
+        let start = Instant::now();
+        let rate_limit = |remaining| RateLimit {
+            requests: Some(RateLimitWindow {
+                limit: Some(100),
+                remaining: Some(remaining),
+                reset_after: Some(Duration::from_secs(30)),
+            }),
+            ..Default::default()
+        };
+
+        fixture.observe_rate_limit(&rate_limit(50));
+        fixture.wait().await;
+        let plenty_left = start.elapsed();
+        fixture.observe_rate_limit(&rate_limit(3));
+        fixture.wait().await;
+        let actual = (plenty_left, start.elapsed());
+
+        let expected = (Duration::ZERO, Duration::from_secs(30));
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_throttle_does_not_wait_without_rate_limit() {
+        let fixture = Throttle::new(4);
+        let start = Instant::now();
+
+        fixture.wait().await;
+
+        assert_eq!(start.elapsed(), Duration::ZERO);
+    }
+}
@@ -158,6 +158,7 @@ impl From<Usage> for paws_domain::Usage {
             completion_tokens,
             total_tokens,
             cached_tokens,
+            cache_write_tokens: TokenCount::Actual(cache_creation),
             ..Default::default()
         }
     }
@@ -0,0 +1,12 @@
+use serde::Deserialize;
+
+/// Error body returned by the Gemini API, either as the response to a failed
+/// request or as an event on the stream
+#[derive(Debug, Deserialize, Clone, PartialEq, thiserror::Error)]
+#[error("{status} ({code}): {message}")]
+pub struct Error {
+    pub code: u16,
+    pub message: String,
+    #[serde(default)]
+    pub status: String,
+}
@@ -0,0 +1,7 @@
+mod error;
+mod request;
+mod response;
+
+pub use error::*;
+pub use request::*;
+pub use response::*;
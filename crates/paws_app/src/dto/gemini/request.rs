@@ -0,0 +1,565 @@
+use derive_setters::Setters;
+use paws_domain::{ContextMessage, Image, ToolValue};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Default, Setters)]
+#[serde(rename_all = "camelCase")]
+#[setters(into, strip_option)]
+pub struct Request {
+    pub contents: Vec<Content>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_instruction: Option<Content>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<Tool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_config: Option<ToolConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub generation_config: Option<GenerationConfig>,
+}
+
+#[derive(Serialize, Default, Debug, PartialEq, Setters)]
+#[serde(rename_all = "camelCase")]
+#[setters(into, strip_option)]
+pub struct GenerationConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_k: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thinking_config: Option<ThinkingConfig>,
+}
+
+impl GenerationConfig {
+    fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ThinkingConfig {
+    /// Number of tokens the model may spend thinking, `-1` lets the model
+    /// decide
+    pub thinking_budget: i64,
+    pub include_thoughts: bool,
+}
+
+impl TryFrom<paws_domain::Context> for Request {
+    type Error = anyhow::Error;
+    fn try_from(request: paws_domain::Context) -> std::result::Result<Self, Self::Error> {
+        let system_parts = request
+            .messages
+            .iter()
+            .filter_map(|msg| match &**msg {
+                ContextMessage::Text(msg) if msg.has_role(paws_domain::Role::System) => {
+                    Some(Part::text(msg.content.clone()))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let mut contents: Vec<Content> = Vec::new();
+        for message in request
+            .messages
+            .into_iter()
+            .filter(|message| !message.has_role(paws_domain::Role::System))
+        {
+            let content = Content::try_from(message.message)?;
+            // Gemini expects the responses to parallel function calls in a single turn, so
+            // consecutive messages from the same role are merged.
+            match contents.last_mut() {
+                Some(last) if last.role == content.role => last.parts.extend(content.parts),
+                _ => contents.push(content),
+            }
+        }
+
+        let thinking_config = request.reasoning.and_then(|reasoning| {
+            reasoning
+                .enabled
+                .filter(|enabled| *enabled)
+                .map(|_| ThinkingConfig {
+                    thinking_budget: reasoning.max_tokens.map(|t| t as i64).unwrap_or(-1),
+                    include_thoughts: !reasoning.exclude.unwrap_or_default(),
+                })
+        });
+
+        let generation_config = GenerationConfig {
+            max_output_tokens: request.max_tokens.map(|t| t as u64),
+            temperature: request.temperature.map(|t| t.value()),
+            top_p: request.top_p.map(|t| t.value()),
+            top_k: request.top_k.map(|t| t.value() as u64),
+            thinking_config,
+        };
+
+        let function_declarations = request
+            .tools
+            .into_iter()
+            .map(FunctionDeclaration::try_from)
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            contents,
+            system_instruction: (!system_parts.is_empty())
+                .then_some(Content { role: None, parts: system_parts }),
+            tools: if function_declarations.is_empty() {
+                vec![]
+            } else {
+                vec![Tool { function_declarations }]
+            },
+            tool_config: request.tool_choice.map(ToolConfig::from),
+            generation_config: (!generation_config.is_empty()).then_some(generation_config),
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct Content {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<Role>,
+    #[serde(default)]
+    pub parts: Vec<Part>,
+}
+
+impl TryFrom<ContextMessage> for Content {
+    type Error = anyhow::Error;
+    fn try_from(value: ContextMessage) -> std::result::Result<Self, Self::Error> {
+        Ok(match value {
+            ContextMessage::Text(chat_message) => {
+                let role = match chat_message.role {
+                    paws_domain::Role::User => Role::User,
+                    paws_domain::Role::Assistant => Role::Model,
+                    paws_domain::Role::System => {
+                        // note: system messages are sent as the system instruction and are
+                        // already filtered out. so this state is unreachable.
+                        return Err(
+                            paws_domain::Error::UnsupportedRole("System".to_string()).into()
+                        );
+                    }
+                };
+
+                let signature = chat_message
+                    .reasoning_details
+                    .into_iter()
+                    .flatten()
+                    .find_map(|reasoning| reasoning.signature);
+
+                let mut parts = Vec::new();
+                if !chat_message.content.is_empty() {
+                    parts.push(Part::text(chat_message.content));
+                }
+                let text_parts = parts.len();
+                for tool_call in chat_message.tool_calls.into_iter().flatten() {
+                    parts.push(Part::try_from(tool_call)?);
+                }
+
+                // The signature has to be returned on the part it was received with: the
+                // first function call when the model called tools, the text otherwise.
+                let signed = if parts.len() > text_parts {
+                    parts.get_mut(text_parts)
+                } else {
+                    parts.last_mut()
+                };
+                if let Some(part) = signed {
+                    part.thought_signature = signature;
+                }
+
+                Content { role: Some(role), parts }
+            }
+            ContextMessage::Tool(tool_result) => {
+                // Function responses only carry text, images are sent alongside
+                let images = tool_result
+                    .output
+                    .values
+                    .iter()
+                    .filter_map(|value| match value {
+                        ToolValue::Image(image) => Some(Part::from(image.clone())),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+                let mut parts = vec![Part::from(tool_result)];
+                parts.extend(images);
+                Content { role: Some(Role::User), parts }
+            }
+            ContextMessage::Image(img) => {
+                Content { role: Some(Role::User), parts: vec![Part::from(img)] }
+            }
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    User,
+    Model,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Part {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// Marks the text as a thought summary rather than part of the answer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thought: Option<bool>,
+    /// Opaque signature of the model's reasoning that must be sent back with
+    /// the part it was attached to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thought_signature: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inline_data: Option<Blob>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub function_call: Option<FunctionCall>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub function_response: Option<FunctionResponse>,
+}
+
+impl Part {
+    pub fn text(text: impl Into<String>) -> Self {
+        Self { text: Some(text.into()), ..Default::default() }
+    }
+
+    /// Returns true if the part carries a thought summary
+    pub fn is_thought(&self) -> bool {
+        self.thought.unwrap_or_default()
+    }
+}
+
+impl From<Image> for Part {
+    fn from(value: Image) -> Self {
+        Self {
+            inline_data: Some(Blob {
+                mime_type: value.mime_type().to_string(),
+                data: value.data().to_string(),
+            }),
+            ..Default::default()
+        }
+    }
+}
+
+impl TryFrom<paws_domain::ToolCallFull> for Part {
+    type Error = anyhow::Error;
+    fn try_from(value: paws_domain::ToolCallFull) -> std::result::Result<Self, Self::Error> {
+        let args = if value.arguments.clone().into_string().trim().is_empty() {
+            serde_json::Value::Object(Default::default())
+        } else {
+            value.arguments.parse()?
+        };
+
+        Ok(Self {
+            function_call: Some(FunctionCall { id: None, name: value.name.to_string(), args }),
+            ..Default::default()
+        })
+    }
+}
+
+impl From<paws_domain::ToolResult> for Part {
+    fn from(value: paws_domain::ToolResult) -> Self {
+        let output = value
+            .output
+            .values
+            .iter()
+            .filter_map(|item| item.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let key = if value.is_error() { "error" } else { "output" };
+
+        Self {
+            function_response: Some(FunctionResponse {
+                name: value.name.to_string(),
+                response: serde_json::json!({ key: output }),
+            }),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Blob {
+    pub mime_type: String,
+    /// Base64 encoded bytes
+    pub data: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FunctionCall {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub name: String,
+    #[serde(default)]
+    pub args: serde_json::Value,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FunctionResponse {
+    pub name: String,
+    pub response: serde_json::Value,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Tool {
+    pub function_declarations: Vec<FunctionDeclaration>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FunctionDeclaration {
+    pub name: String,
+    pub description: String,
+    /// Sent as JSON Schema rather than the OpenAPI subset accepted by
+    /// `parameters`, so tool schemas reach the model unchanged
+    pub parameters_json_schema: serde_json::Value,
+}
+
+impl TryFrom<paws_domain::ToolDefinition> for FunctionDeclaration {
+    type Error = anyhow::Error;
+    fn try_from(value: paws_domain::ToolDefinition) -> std::result::Result<Self, Self::Error> {
+        let mut schema = serde_json::to_value(value.input_schema)?;
+        if let Some(obj) = schema.as_object_mut() {
+            // The meta-schema and title are not part of the supported keywords
+            obj.remove("$schema");
+            obj.remove("title");
+        }
+
+        Ok(FunctionDeclaration {
+            name: value.name.to_string(),
+            description: value.description,
+            parameters_json_schema: schema,
+        })
+    }
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolConfig {
+    pub function_calling_config: FunctionCallingConfig,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FunctionCallingConfig {
+    pub mode: FunctionCallingMode,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub allowed_function_names: Vec<String>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum FunctionCallingMode {
+    Auto,
+    Any,
+    None,
+}
+
+// To understand the mappings refer: https://ai.google.dev/gemini-api/docs/function-calling#function_calling_modes
+impl From<paws_domain::ToolChoice> for ToolConfig {
+    fn from(value: paws_domain::ToolChoice) -> Self {
+        let (mode, allowed_function_names) = match value {
+            paws_domain::ToolChoice::Auto => (FunctionCallingMode::Auto, vec![]),
+            paws_domain::ToolChoice::Required => (FunctionCallingMode::Any, vec![]),
+            paws_domain::ToolChoice::Call(tool_name) => {
+                (FunctionCallingMode::Any, vec![tool_name.to_string()])
+            }
+            paws_domain::ToolChoice::None => (FunctionCallingMode::None, vec![]),
+        };
+        ToolConfig {
+            function_calling_config: FunctionCallingConfig { mode, allowed_function_names },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use paws_domain::{
+        Context, ModelId, ReasoningConfig, ReasoningFull, TextMessage, ToolCallFull, ToolCallId,
+        ToolName, ToolOutput, ToolResult,
+    };
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use strum::IntoEnumIterator;
+
+    use super::*;
+
+    #[test]
+    fn test_request_conversion() {
+        let model_id = ModelId::new("gemini-2.5-pro");
+        let fixture = Context::default()
+            .add_message(ContextMessage::system("You are a calculator."))
+            .add_message(ContextMessage::user("what's 2 + 2 ?", model_id.into()))
+            .add_message(ContextMessage::Text(
+                TextMessage::new(paws_domain::Role::Assistant, "")
+                    .tool_calls(vec![
+                        ToolCallFull::new("math")
+                            .call_id(ToolCallId::new("call-1"))
+                            .arguments(json!({"expression": "2 + 2"})),
+                        ToolCallFull::new("math")
+                            .call_id(ToolCallId::new("call-2"))
+                            .arguments(json!({"expression": "2 * 2"})),
+                    ])
+                    .reasoning_details(vec![
+                        ReasoningFull::default().signature("sig-1".to_string()),
+                    ]),
+            ))
+            .add_tool_results(vec![
+                ToolResult::new("math")
+                    .call_id(ToolCallId::new("call-1"))
+                    .output(Ok(ToolOutput::text("4"))),
+                ToolResult::new("math")
+                    .call_id(ToolCallId::new("call-2"))
+                    .output(Ok(ToolOutput::text("overflow").is_error(true))),
+            ]);
+
+        let actual = serde_json::to_value(Request::try_from(fixture).unwrap()).unwrap();
+
+        let expected = json!({
+            "contents": [
+                {"role": "user", "parts": [{"text": "what's 2 + 2 ?"}]},
+                {"role": "model", "parts": [
+                    {"functionCall": {"name": "math", "args": {"expression": "2 + 2"}}, "thoughtSignature": "sig-1"},
+                    {"functionCall": {"name": "math", "args": {"expression": "2 * 2"}}}
+                ]},
+                {"role": "user", "parts": [
+                    {"functionResponse": {"name": "math", "response": {"output": "4"}}},
+                    {"functionResponse": {"name": "math", "response": {"error": "overflow"}}}
+                ]}
+            ],
+            "systemInstruction": {"parts": [{"text": "You are a calculator."}]}
+        });
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_signature_on_text_without_tool_calls() {
+        let fixture = ContextMessage::Text(
+            TextMessage::new(paws_domain::Role::Assistant, "Done")
+                .reasoning_details(vec![ReasoningFull::default().signature("sig".to_string())]),
+        );
+
+        let actual = Content::try_from(fixture).unwrap();
+
+        let expected = Content {
+            role: Some(Role::Model),
+            parts: vec![Part {
+                thought_signature: Some("sig".to_string()),
+                ..Part::text("Done")
+            }],
+        };
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_image_becomes_inline_data() {
+        let fixture = ContextMessage::Image(Image::new_base64("aGk=".to_string(), "image/png"));
+
+        let actual = serde_json::to_value(Content::try_from(fixture).unwrap()).unwrap();
+
+        let expected = json!({
+            "role": "user",
+            "parts": [{"inlineData": {"mimeType": "image/png", "data": "aGk="}}]
+        });
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_tool_result_image_becomes_inline_data() {
+        let fixture = ContextMessage::Tool(
+            ToolResult::new("read_image")
+                .call_id(ToolCallId::new("call-1"))
+                .output(Ok(ToolOutput::image(Image::new_base64(
+                    "aGk=".to_string(),
+                    "image/png",
+                )))),
+        );
+
+        let actual = serde_json::to_value(Content::try_from(fixture).unwrap()).unwrap();
+
+        let expected = json!({
+            "role": "user",
+            "parts": [
+                {"functionResponse": {"name": "read_image", "response": {"output": ""}}},
+                {"inlineData": {"mimeType": "image/png", "data": "aGk="}}
+            ]
+        });
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_reasoning_enabled_sets_thinking_budget() {
+        let fixture = Context::default().reasoning(ReasoningConfig {
+            enabled: Some(true),
+            max_tokens: Some(8000),
+            effort: None,
+            exclude: None,
+        });
+
+        let actual = Request::try_from(fixture)
+            .unwrap()
+            .generation_config
+            .and_then(|config| config.thinking_config);
+
+        let expected = Some(ThinkingConfig { thinking_budget: 8000, include_thoughts: true });
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_reasoning_without_max_tokens_uses_dynamic_budget() {
+        let fixture = Context::default().reasoning(ReasoningConfig {
+            enabled: Some(true),
+            max_tokens: None,
+            effort: None,
+            exclude: Some(true),
+        });
+
+        let actual = Request::try_from(fixture)
+            .unwrap()
+            .generation_config
+            .and_then(|config| config.thinking_config);
+
+        let expected = Some(ThinkingConfig { thinking_budget: -1, include_thoughts: false });
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_no_reasoning_omits_generation_config() {
+        let fixture = Context::default();
+
+        let actual = Request::try_from(fixture).unwrap().generation_config;
+
+        assert_eq!(actual, None);
+    }
+
+    #[test]
+    fn test_tool_choice_call_restricts_function_names() {
+        let fixture = paws_domain::ToolChoice::Call(ToolName::new("read"));
+
+        let actual = serde_json::to_value(ToolConfig::from(fixture)).unwrap();
+
+        let expected = json!({
+            "functionCallingConfig": {"mode": "ANY", "allowedFunctionNames": ["read"]}
+        });
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_function_declaration_keeps_json_schema() {
+        let fixture = paws_domain::ToolCatalog::iter()
+            .next()
+            .unwrap()
+            .definition();
+        let schema = serde_json::to_value(&fixture.input_schema).unwrap();
+
+        let actual = FunctionDeclaration::try_from(fixture).unwrap();
+
+        assert_eq!(actual.parameters_json_schema.get("$schema"), None);
+        assert_eq!(
+            actual.parameters_json_schema.get("properties"),
+            schema.get("properties")
+        );
+    }
+}
@@ -0,0 +1,398 @@
+use paws_domain::{
+    ChatCompletionMessage, Content as MessageContent, ModelId, Reasoning, ReasoningPart,
+    TokenCount, ToolCallId, ToolCallPart, ToolName,
+};
+use serde::Deserialize;
+
+use super::request::Content;
+use crate::dto::gemini::Error;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListModelResponse {
+    #[serde(default)]
+    pub models: Vec<Model>,
+    pub next_page_token: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Model {
+    /// Resource name in the form `models/{model}`
+    pub name: String,
+    pub display_name: Option<String>,
+    pub description: Option<String>,
+    pub input_token_limit: Option<u64>,
+    #[serde(default)]
+    pub supported_generation_methods: Vec<String>,
+    pub thinking: Option<bool>,
+}
+
+impl Model {
+    /// Returns true if the model can be used with `generateContent`
+    pub fn supports_generate_content(&self) -> bool {
+        self.supported_generation_methods
+            .iter()
+            .any(|method| method == "generateContent")
+    }
+}
+
+impl From<Model> for paws_domain::Model {
+    fn from(value: Model) -> Self {
+        let id = value
+            .name
+            .strip_prefix("models/")
+            .unwrap_or(&value.name)
+            .to_string();
+        Self {
+            id: ModelId::new(id),
+            name: value.display_name,
+            description: value.description,
+            context_length: value.input_token_limit,
+            tools_supported: Some(true),
+            supports_parallel_tool_calls: Some(true),
+            supports_reasoning: value.thinking,
+        }
+    }
+}
+
+/// A single event of a `streamGenerateContent` response
+#[derive(Deserialize, PartialEq, Clone, Debug)]
+#[serde(untagged)]
+pub enum EventData {
+    Error { error: Error },
+    Response(Response),
+}
+
+#[derive(Deserialize, PartialEq, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Response {
+    #[serde(default)]
+    pub candidates: Vec<Candidate>,
+    pub usage_metadata: Option<UsageMetadata>,
+    pub prompt_feedback: Option<PromptFeedback>,
+    pub model_version: Option<String>,
+}
+
+#[derive(Deserialize, PartialEq, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Candidate {
+    pub content: Option<Content>,
+    pub finish_reason: Option<FinishReason>,
+}
+
+#[derive(Deserialize, PartialEq, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptFeedback {
+    pub block_reason: Option<String>,
+}
+
+#[derive(Deserialize, PartialEq, Clone, Debug)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum FinishReason {
+    Stop,
+    MaxTokens,
+    Safety,
+    Recitation,
+    Blocklist,
+    ProhibitedContent,
+    Spii,
+    ImageSafety,
+    MalformedFunctionCall,
+    #[serde(other)]
+    Other,
+}
+
+impl FinishReason {
+    fn into_domain(self, has_tool_calls: bool) -> Option<paws_domain::FinishReason> {
+        match self {
+            // Gemini reports `STOP` for turns that end in function calls
+            FinishReason::Stop if has_tool_calls => Some(paws_domain::FinishReason::ToolCalls),
+            FinishReason::Stop => Some(paws_domain::FinishReason::Stop),
+            FinishReason::MaxTokens => Some(paws_domain::FinishReason::Length),
+            FinishReason::Safety
+            | FinishReason::Recitation
+            | FinishReason::Blocklist
+            | FinishReason::ProhibitedContent
+            | FinishReason::Spii
+            | FinishReason::ImageSafety => Some(paws_domain::FinishReason::ContentFilter),
+            FinishReason::MalformedFunctionCall | FinishReason::Other => None,
+        }
+    }
+}
+
+#[derive(Deserialize, PartialEq, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageMetadata {
+    pub prompt_token_count: Option<usize>,
+    pub candidates_token_count: Option<usize>,
+    pub thoughts_token_count: Option<usize>,
+    pub cached_content_token_count: Option<usize>,
+    pub total_token_count: Option<usize>,
+}
+
+impl From<UsageMetadata> for paws_domain::Usage {
+    fn from(usage: UsageMetadata) -> Self {
+        // Gemini token breakdown:
+        // - prompt_token_count: all input tokens, including the cached ones
+        // - cached_content_token_count: input tokens served from the context cache
+        // - thoughts_token_count: reasoning tokens, billed as output but not part of
+        //   candidates_token_count
+        let prompt_tokens = usage
+            .prompt_token_count
+            .map(TokenCount::Actual)
+            .unwrap_or_default();
+        let completion_tokens = TokenCount::Actual(
+            usage.candidates_token_count.unwrap_or_default()
+                + usage.thoughts_token_count.unwrap_or_default(),
+        );
+        let cached_tokens = usage
+            .cached_content_token_count
+            .map(TokenCount::Actual)
+            .unwrap_or_default();
+        let total_tokens = usage
+            .total_token_count
+            .map(TokenCount::Actual)
+            .unwrap_or(prompt_tokens + completion_tokens);
+
+        paws_domain::Usage {
+            prompt_tokens,
+            completion_tokens,
+            total_tokens,
+            cached_tokens,
+            ..Default::default()
+        }
+    }
+}
+
+impl TryFrom<EventData> for ChatCompletionMessage {
+    type Error = anyhow::Error;
+    fn try_from(value: EventData) -> Result<Self, Self::Error> {
+        match value {
+            EventData::Error { error } => Err(error.into()),
+            EventData::Response(response) => ChatCompletionMessage::try_from(response),
+        }
+    }
+}
+
+impl TryFrom<Response> for ChatCompletionMessage {
+    type Error = anyhow::Error;
+    fn try_from(value: Response) -> Result<Self, Self::Error> {
+        if value.candidates.is_empty()
+            && let Some(reason) = value.prompt_feedback.and_then(|f| f.block_reason)
+        {
+            anyhow::bail!("Prompt was blocked by Gemini: {reason}");
+        }
+
+        let mut text = String::new();
+        let mut thoughts = String::new();
+        let mut signature = None;
+        let mut tool_calls = Vec::new();
+        let mut finish_reason = None;
+
+        if let Some(candidate) = value.candidates.into_iter().next() {
+            finish_reason = candidate.finish_reason;
+            for part in candidate.content.into_iter().flat_map(|c| c.parts) {
+                if part.thought_signature.is_some() {
+                    signature = part.thought_signature.clone();
+                }
+                if let Some(call) = part.function_call {
+                    // note: We've to check if the args are empty or null. else we end up adding
+                    // empty object `{}` as prefix to tool args.
+                    let is_empty =
+                        call.args.is_null() || call.args.as_object().is_some_and(|m| m.is_empty());
+                    tool_calls.push(ToolCallPart {
+                        call_id: Some(
+                            call.id
+                                .map(ToolCallId::new)
+                                .unwrap_or_else(ToolCallId::generate),
+                        ),
+                        name: Some(ToolName::new(call.name)),
+                        arguments_part: if is_empty {
+                            "".to_string()
+                        } else {
+                            serde_json::to_string(&call.args)?
+                        },
+                    });
+                } else if let Some(part_text) = part.text.as_deref() {
+                    if part.is_thought() {
+                        thoughts.push_str(part_text);
+                    } else {
+                        text.push_str(part_text);
+                    }
+                }
+            }
+        }
+
+        let has_tool_calls = !tool_calls.is_empty();
+        let mut message = ChatCompletionMessage::assistant(MessageContent::part(text));
+        for tool_call in tool_calls {
+            message = message.add_tool_call(tool_call);
+        }
+        if !thoughts.is_empty() {
+            message = message.reasoning(MessageContent::part(thoughts.clone()));
+        }
+        if !thoughts.is_empty() || signature.is_some() {
+            message = message.add_reasoning_detail(Reasoning::Part(vec![
+                ReasoningPart::default()
+                    .text((!thoughts.is_empty()).then_some(thoughts))
+                    .signature(signature),
+            ]));
+        }
+        if let Some(reason) = finish_reason.and_then(|r| r.into_domain(has_tool_calls)) {
+            message = message.finish_reason(reason);
+        }
+        if let Some(usage) = value.usage_metadata {
+            message = message.usage(usage);
+        }
+
+        Ok(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use paws_domain::{FinishReason as DomainFinishReason, Usage};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn parse(event: &str) -> ChatCompletionMessage {
+        let event = serde_json::from_str::<EventData>(event).unwrap();
+        ChatCompletionMessage::try_from(event).unwrap()
+    }
+
+    #[test]
+    fn test_text_chunk() {
+        let fixture = r#"{"candidates":[{"content":{"role":"model","parts":[{"text":"Hello"},{"text":" world"}]},"index":0}],"modelVersion":"gemini-2.5-flash"}"#;
+
+        let actual = parse(fixture);
+
+        let expected = ChatCompletionMessage::assistant(MessageContent::part("Hello world"));
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_thought_chunk_with_signature() {
+        let fixture = r#"{"candidates":[{"content":{"role":"model","parts":[{"text":"Let me think","thought":true},{"text":"Answer","thoughtSignature":"c2ln"}]}}]}"#;
+
+        let actual = parse(fixture);
+
+        let expected = ChatCompletionMessage::assistant(MessageContent::part("Answer"))
+            .reasoning(MessageContent::part("Let me think"))
+            .add_reasoning_detail(Reasoning::Part(vec![
+                ReasoningPart::default()
+                    .text(Some("Let me think".to_string()))
+                    .signature(Some("c2ln".to_string())),
+            ]));
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_function_call_chunk() {
+        let fixture = r#"{"candidates":[{"content":{"role":"model","parts":[{"functionCall":{"id":"call-1","name":"read","args":{"path":"a.rs"}},"thoughtSignature":"c2ln"},{"functionCall":{"id":"call-2","name":"list","args":{}}}]},"finishReason":"STOP"}]}"#;
+
+        let actual = parse(fixture);
+
+        let expected = ChatCompletionMessage::assistant(MessageContent::part(""))
+            .add_tool_call(ToolCallPart {
+                call_id: Some(ToolCallId::new("call-1")),
+                name: Some(ToolName::new("read")),
+                arguments_part: r#"{"path":"a.rs"}"#.to_string(),
+            })
+            .add_tool_call(ToolCallPart {
+                call_id: Some(ToolCallId::new("call-2")),
+                name: Some(ToolName::new("list")),
+                arguments_part: "".to_string(),
+            })
+            .add_reasoning_detail(Reasoning::Part(vec![
+                ReasoningPart::default().signature(Some("c2ln".to_string())),
+            ]))
+            .finish_reason(DomainFinishReason::ToolCalls);
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_function_call_without_id_gets_generated_id() {
+        let fixture = r#"{"candidates":[{"content":{"role":"model","parts":[{"functionCall":{"name":"read","args":{"path":"a.rs"}}}]}}]}"#;
+
+        let actual = parse(fixture);
+
+        assert_eq!(actual.tool_calls.len(), 1);
+        assert!(actual.tool_calls[0].as_partial().unwrap().call_id.is_some());
+    }
+
+    #[test]
+    fn test_usage_with_cached_and_thought_tokens() {
+        let fixture = r#"{"candidates":[{"content":{"role":"model","parts":[{"text":""}]},"finishReason":"MAX_TOKENS"}],"usageMetadata":{"promptTokenCount":100,"candidatesTokenCount":20,"thoughtsTokenCount":30,"cachedContentTokenCount":80,"totalTokenCount":150}}"#;
+
+        let actual = parse(fixture);
+
+        let expected = ChatCompletionMessage::assistant(MessageContent::part(""))
+            .finish_reason(DomainFinishReason::Length)
+            .usage(Usage {
+                prompt_tokens: TokenCount::Actual(100),
+                completion_tokens: TokenCount::Actual(50),
+                total_tokens: TokenCount::Actual(150),
+                cached_tokens: TokenCount::Actual(80),
+                cache_write_tokens: TokenCount::Actual(0),
+                cost: None,
+            });
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_unknown_finish_reason() {
+        let fixture = r#"{"candidates":[{"content":{"role":"model","parts":[{"text":"x"}]},"finishReason":"SOMETHING_NEW"}]}"#;
+
+        let actual = parse(fixture).finish_reason;
+
+        assert_eq!(actual, None);
+    }
+
+    #[test]
+    fn test_error_event() {
+        let fixture = r#"{"error":{"code":429,"message":"Resource exhausted","status":"RESOURCE_EXHAUSTED"}}"#;
+        let event = serde_json::from_str::<EventData>(fixture).unwrap();
+
+        let actual = ChatCompletionMessage::try_from(event)
+            .unwrap_err()
+            .to_string();
+
+        let expected = "RESOURCE_EXHAUSTED (429): Resource exhausted";
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_blocked_prompt() {
+        let fixture = r#"{"promptFeedback":{"blockReason":"SAFETY"}}"#;
+        let event = serde_json::from_str::<EventData>(fixture).unwrap();
+
+        let actual = ChatCompletionMessage::try_from(event)
+            .unwrap_err()
+            .to_string();
+
+        let expected = "Prompt was blocked by Gemini: SAFETY";
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_model_conversion() {
+        let fixture = r#"{"name":"models/gemini-2.5-pro","displayName":"Gemini 2.5 Pro","inputTokenLimit":1048576,"supportedGenerationMethods":["generateContent","countTokens"],"thinking":true}"#;
+        let model = serde_json::from_str::<Model>(fixture).unwrap();
+        assert!(model.supports_generate_content());
+
+        let actual = paws_domain::Model::from(model);
+
+        let expected = paws_domain::Model {
+            id: ModelId::new("gemini-2.5-pro"),
+            name: Some("Gemini 2.5 Pro".to_string()),
+            description: None,
+            context_length: Some(1048576),
+            tools_supported: Some(true),
+            supports_parallel_tool_calls: Some(true),
+            supports_reasoning: Some(true),
+        };
+        assert_eq!(actual, expected);
+    }
+}
@@ -1,7 +1,9 @@
 // Due to a conflict between names of Anthropic and OpenAI we will namespace the
 // DTOs instead of using Prefixes for type names
 pub mod anthropic;
+pub mod gemini;
 pub mod openai;
+pub mod openai_responses;
 
 mod tools_overview;
 
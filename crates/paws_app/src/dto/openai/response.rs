@@ -187,6 +187,7 @@ impl From<ResponseUsage> for Usage {
                 .prompt_tokens_details
                 .map(|token_details| TokenCount::Actual(token_details.cached_tokens))
                 .unwrap_or_default(),
+            cache_write_tokens: TokenCount::Actual(0),
             cost,
         }
     }
@@ -102,6 +102,7 @@ mod tests {
             models: Some(ModelSource::Url(
                 Url::parse("https://antinomy.ai/api/v1/models").unwrap(),
             )),
+            pricing: Default::default(),
         }
     }
 
@@ -117,6 +118,7 @@ mod tests {
             models: Some(ModelSource::Url(
                 Url::parse("https://api.z.ai/api/paas/v4/models").unwrap(),
             )),
+            pricing: Default::default(),
         }
     }
 
@@ -132,6 +134,7 @@ mod tests {
             models: Some(ModelSource::Url(
                 Url::parse("https://api.z.ai/api/paas/v4/models").unwrap(),
             )),
+            pricing: Default::default(),
         }
     }
 
@@ -147,6 +150,7 @@ mod tests {
             models: Some(ModelSource::Url(
                 Url::parse("https://api.openai.com/v1/models").unwrap(),
             )),
+            pricing: Default::default(),
         }
     }
 
@@ -162,6 +166,7 @@ mod tests {
             models: Some(ModelSource::Url(
                 Url::parse("https://api.x.ai/v1/models").unwrap(),
             )),
+            pricing: Default::default(),
         }
     }
 
@@ -177,6 +182,7 @@ mod tests {
             models: Some(ModelSource::Url(
                 Url::parse("https://api.requesty.ai/v1/models").unwrap(),
             )),
+            pricing: Default::default(),
         }
     }
 
@@ -192,6 +198,7 @@ mod tests {
             models: Some(ModelSource::Url(
                 Url::parse("https://openrouter.ai/api/v1/models").unwrap(),
             )),
+            pricing: Default::default(),
         }
     }
 
@@ -207,6 +214,7 @@ mod tests {
             models: Some(ModelSource::Url(
                 Url::parse("https://api.anthropic.com/v1/models").unwrap(),
             )),
+            pricing: Default::default(),
         }
     }
 
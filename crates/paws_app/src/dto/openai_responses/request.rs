@@ -0,0 +1,413 @@
+use derive_setters::Setters;
+use paws_domain::{ContextMessage, Effort, ReasoningFull};
+use serde::Serialize;
+
+/// `type_of` used for reasoning items received from the Responses API, so
+/// they can be told apart from the reasoning of other providers
+pub const REASONING_TYPE: &str = "reasoning.encrypted";
+
+/// `format` used for reasoning items received from the Responses API
+pub const REASONING_FORMAT: &str = "openai-responses-v1";
+
+#[derive(Serialize, Default, Setters)]
+#[setters(into, strip_option)]
+pub struct Request {
+    pub model: String,
+    pub input: Vec<InputItem>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<Tool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<ToolChoice>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<Reasoning>,
+    /// Extra output to return, used to receive the encrypted reasoning items
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream: Option<bool>,
+    /// Responses are not stored server side, reasoning is carried between
+    /// turns through the encrypted reasoning items instead
+    pub store: bool,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Reasoning {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effort: Option<Effort>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+}
+
+impl TryFrom<paws_domain::Context> for Request {
+    type Error = anyhow::Error;
+    fn try_from(request: paws_domain::Context) -> std::result::Result<Self, Self::Error> {
+        let reasoning_supported = request.is_reasoning_supported();
+        let instructions = request
+            .messages
+            .iter()
+            .filter_map(|msg| match &**msg {
+                ContextMessage::Text(msg) if msg.has_role(paws_domain::Role::System) => {
+                    Some(msg.content.clone())
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let mut input = Vec::new();
+        for message in request
+            .messages
+            .into_iter()
+            .filter(|message| !message.has_role(paws_domain::Role::System))
+        {
+            input.extend(InputItem::try_from_message(message.message)?);
+        }
+
+        let reasoning = request
+            .reasoning
+            .filter(|_| reasoning_supported)
+            .map(|reasoning| Reasoning {
+                effort: reasoning.effort,
+                summary: (!reasoning.exclude.unwrap_or_default()).then(|| "auto".to_string()),
+            });
+
+        Ok(Self {
+            input,
+            instructions: (!instructions.is_empty()).then(|| instructions.join("\n\n")),
+            tools: request
+                .tools
+                .into_iter()
+                .map(Tool::try_from)
+                .collect::<std::result::Result<Vec<_>, _>>()?,
+            tool_choice: request.tool_choice.map(ToolChoice::from),
+            include: if reasoning.is_some() {
+                vec!["reasoning.encrypted_content".to_string()]
+            } else {
+                vec![]
+            },
+            reasoning,
+            max_output_tokens: request.max_tokens.map(|t| t as u64),
+            temperature: request.temperature.map(|t| t.value()),
+            top_p: request.top_p.map(|t| t.value()),
+            stream: Some(request.stream.unwrap_or(true)),
+            ..Default::default()
+        })
+    }
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum InputItem {
+    Message {
+        role: Role,
+        content: Vec<InputContent>,
+    },
+    Reasoning {
+        id: String,
+        summary: Vec<Summary>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        encrypted_content: Option<String>,
+    },
+    FunctionCall {
+        call_id: String,
+        name: String,
+        arguments: String,
+    },
+    FunctionCallOutput {
+        call_id: String,
+        output: String,
+    },
+}
+
+impl InputItem {
+    /// Converts a context message into input items. An assistant message
+    /// expands into its reasoning items, its text and its function calls.
+    fn try_from_message(value: ContextMessage) -> anyhow::Result<Vec<Self>> {
+        Ok(match value {
+            ContextMessage::Text(chat_message) => match chat_message.role {
+                paws_domain::Role::User => vec![InputItem::Message {
+                    role: Role::User,
+                    content: vec![InputContent::InputText { text: chat_message.content }],
+                }],
+                paws_domain::Role::Assistant => {
+                    let mut items = chat_message
+                        .reasoning_details
+                        .into_iter()
+                        .flatten()
+                        .filter_map(InputItem::from_reasoning)
+                        .collect::<Vec<_>>();
+
+                    if !chat_message.content.is_empty() {
+                        items.push(InputItem::Message {
+                            role: Role::Assistant,
+                            content: vec![InputContent::OutputText { text: chat_message.content }],
+                        });
+                    }
+
+                    for tool_call in chat_message.tool_calls.into_iter().flatten() {
+                        let call_id = tool_call
+                            .call_id
+                            .ok_or(paws_domain::Error::ToolCallMissingId)?;
+                        items.push(InputItem::FunctionCall {
+                            call_id: call_id.as_str().to_string(),
+                            name: tool_call.name.to_string(),
+                            arguments: tool_call.arguments.into_string(),
+                        });
+                    }
+                    items
+                }
+                paws_domain::Role::System => {
+                    // note: system messages are sent as instructions and are already
+                    // filtered out. so this state is unreachable.
+                    return Err(paws_domain::Error::UnsupportedRole("System".to_string()).into());
+                }
+            },
+            ContextMessage::Tool(tool_result) => {
+                let call_id = tool_result
+                    .call_id
+                    .as_ref()
+                    .ok_or(paws_domain::Error::ToolCallMissingId)?;
+                vec![InputItem::FunctionCallOutput {
+                    call_id: call_id.as_str().to_string(),
+                    output: tool_result
+                        .output
+                        .values
+                        .iter()
+                        .filter_map(|item| item.as_str())
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                }]
+            }
+            ContextMessage::Image(img) => vec![InputItem::Message {
+                role: Role::User,
+                content: vec![InputContent::InputImage { image_url: img.url().clone() }],
+            }],
+        })
+    }
+
+    /// Replays a reasoning item received from the Responses API. Reasoning
+    /// from other providers can't be replayed and is skipped.
+    fn from_reasoning(reasoning: ReasoningFull) -> Option<Self> {
+        if reasoning.type_of.as_deref() != Some(REASONING_TYPE) {
+            return None;
+        }
+        Some(InputItem::Reasoning {
+            id: reasoning.id?,
+            summary: reasoning
+                .text
+                .into_iter()
+                .map(|text| Summary::SummaryText { text })
+                .collect(),
+            encrypted_content: reasoning.data,
+        })
+    }
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum InputContent {
+    InputText { text: String },
+    InputImage { image_url: String },
+    OutputText { text: String },
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum Summary {
+    SummaryText { text: String },
+}
+
+#[derive(Serialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    User,
+    Assistant,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Tool {
+    pub r#type: String,
+    pub name: String,
+    pub description: String,
+    pub parameters: serde_json::Value,
+}
+
+impl TryFrom<paws_domain::ToolDefinition> for Tool {
+    type Error = anyhow::Error;
+    fn try_from(value: paws_domain::ToolDefinition) -> std::result::Result<Self, Self::Error> {
+        Ok(Tool {
+            r#type: "function".to_string(),
+            name: value.name.to_string(),
+            description: value.description,
+            parameters: serde_json::to_value(value.input_schema)?,
+        })
+    }
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolChoice {
+    None,
+    Auto,
+    Required,
+    #[serde(untagged)]
+    Function {
+        r#type: String,
+        name: String,
+    },
+}
+
+impl From<paws_domain::ToolChoice> for ToolChoice {
+    fn from(value: paws_domain::ToolChoice) -> Self {
+        match value {
+            paws_domain::ToolChoice::None => ToolChoice::None,
+            paws_domain::ToolChoice::Auto => ToolChoice::Auto,
+            paws_domain::ToolChoice::Required => ToolChoice::Required,
+            paws_domain::ToolChoice::Call(tool_name) => {
+                ToolChoice::Function { r#type: "function".to_string(), name: tool_name.to_string() }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use paws_domain::{
+        Context, ModelId, ReasoningConfig, TextMessage, ToolCallFull, ToolCallId, ToolName,
+        ToolOutput, ToolResult,
+    };
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+
+    fn reasoning_fixture() -> ReasoningFull {
+        ReasoningFull {
+            text: Some("Thinking about math".to_string()),
+            data: Some("encrypted".to_string()),
+            id: Some("rs_1".to_string()),
+            format: Some(REASONING_FORMAT.to_string()),
+            type_of: Some(REASONING_TYPE.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_request_conversion() {
+        let fixture = Context::default()
+            .add_message(ContextMessage::system("You are a calculator."))
+            .add_message(ContextMessage::user(
+                "what's 2 + 2 ?",
+                ModelId::new("o3").into(),
+            ))
+            .add_message(ContextMessage::Text(
+                TextMessage::new(paws_domain::Role::Assistant, "Let me check.")
+                    .tool_calls(vec![
+                        ToolCallFull::new("math")
+                            .call_id(ToolCallId::new("call_1"))
+                            .arguments(json!({"expression": "2 + 2"})),
+                    ])
+                    .reasoning_details(vec![reasoning_fixture()]),
+            ))
+            .add_tool_results(vec![
+                ToolResult::new("math")
+                    .call_id(ToolCallId::new("call_1"))
+                    .output(Ok(ToolOutput::text("4"))),
+            ])
+            .tool_choice(paws_domain::ToolChoice::Call(ToolName::new("math")));
+
+        let actual = serde_json::to_value(Request::try_from(fixture).unwrap().model("o3")).unwrap();
+
+        let expected = json!({
+            "model": "o3",
+            "input": [
+                {"type": "message", "role": "user", "content": [{"type": "input_text", "text": "what's 2 + 2 ?"}]},
+                {"type": "reasoning", "id": "rs_1", "summary": [{"type": "summary_text", "text": "Thinking about math"}], "encrypted_content": "encrypted"},
+                {"type": "message", "role": "assistant", "content": [{"type": "output_text", "text": "Let me check."}]},
+                {"type": "function_call", "call_id": "call_1", "name": "math", "arguments": "{\"expression\":\"2 + 2\"}"},
+                {"type": "function_call_output", "call_id": "call_1", "output": "4"}
+            ],
+            "instructions": "You are a calculator.",
+            "tool_choice": {"type": "function", "name": "math"},
+            "stream": true,
+            "store": false
+        });
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_reasoning_from_other_providers_is_skipped() {
+        let fixture = ReasoningFull {
+            text: Some("thinking".to_string()),
+            signature: Some("sig".to_string()),
+            ..Default::default()
+        };
+
+        let actual = InputItem::from_reasoning(fixture);
+
+        assert_eq!(actual, None);
+    }
+
+    #[test]
+    fn test_reasoning_requests_encrypted_content() {
+        let fixture = Context::default().reasoning(ReasoningConfig {
+            enabled: None,
+            max_tokens: None,
+            effort: Some(Effort::High),
+            exclude: None,
+        });
+
+        let actual = Request::try_from(fixture).unwrap();
+
+        assert_eq!(
+            actual.reasoning,
+            Some(Reasoning {
+                effort: Some(Effort::High),
+                summary: Some("auto".to_string())
+            })
+        );
+        assert_eq!(
+            actual.include,
+            vec!["reasoning.encrypted_content".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_disabled_reasoning_is_omitted() {
+        let fixture = Context::default().reasoning(ReasoningConfig {
+            enabled: Some(false),
+            max_tokens: None,
+            effort: Some(Effort::High),
+            exclude: None,
+        });
+
+        let actual = Request::try_from(fixture).unwrap();
+
+        assert_eq!(actual.reasoning, None);
+        assert!(actual.include.is_empty());
+    }
+
+    #[test]
+    fn test_image_becomes_input_image() {
+        let fixture = ContextMessage::Image(paws_domain::Image::new_base64(
+            "aGk=".to_string(),
+            "image/png",
+        ));
+
+        let actual = serde_json::to_value(InputItem::try_from_message(fixture).unwrap()).unwrap();
+
+        let expected = json!([{
+            "type": "message",
+            "role": "user",
+            "content": [{"type": "input_image", "image_url": "data:image/png;base64,aGk="}]
+        }]);
+        assert_eq!(actual, expected);
+    }
+}
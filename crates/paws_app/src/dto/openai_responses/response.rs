@@ -0,0 +1,369 @@
+use paws_domain::{
+    ChatCompletionMessage, Content, FinishReason, Reasoning, ReasoningPart, TokenCount, ToolCallId,
+    ToolCallPart, ToolName,
+};
+use serde::Deserialize;
+
+use super::request::{REASONING_FORMAT, REASONING_TYPE};
+
+/// A single event of a streamed response.
+///
+/// Ref: <https://platform.openai.com/docs/api-reference/responses-streaming>
+#[derive(Deserialize, PartialEq, Clone, Debug)]
+#[serde(tag = "type")]
+pub enum Event {
+    #[serde(rename = "response.output_item.added")]
+    OutputItemAdded { item: OutputItem },
+    #[serde(rename = "response.output_item.done")]
+    OutputItemDone { item: OutputItem },
+    #[serde(rename = "response.output_text.delta")]
+    OutputTextDelta { delta: String },
+    #[serde(rename = "response.reasoning_summary_part.added")]
+    ReasoningSummaryPartAdded { summary_index: u32 },
+    #[serde(rename = "response.reasoning_summary_text.delta")]
+    ReasoningSummaryTextDelta { delta: String },
+    #[serde(rename = "response.function_call_arguments.delta")]
+    FunctionCallArgumentsDelta { delta: String },
+    #[serde(rename = "response.completed")]
+    Completed { response: Response },
+    #[serde(rename = "response.incomplete")]
+    Incomplete { response: Response },
+    #[serde(rename = "response.failed")]
+    Failed { response: Response },
+    #[serde(rename = "error")]
+    Error {
+        code: Option<String>,
+        message: String,
+    },
+}
+
+#[derive(Deserialize, PartialEq, Clone, Debug)]
+#[serde(untagged)]
+pub enum EventData {
+    KnownEvent(Event),
+    // Lifecycle events such as `response.created` and the `*.done` events
+    // that repeat streamed content carry nothing new
+    Unknown(serde_json::Value),
+}
+
+#[derive(Deserialize, PartialEq, Clone, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OutputItem {
+    FunctionCall {
+        call_id: String,
+        name: String,
+        #[serde(default)]
+        arguments: String,
+    },
+    Reasoning {
+        id: String,
+        #[serde(default)]
+        encrypted_content: Option<String>,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Deserialize, PartialEq, Clone, Debug, Default)]
+pub struct Response {
+    #[serde(default)]
+    pub output: Vec<OutputItem>,
+    pub usage: Option<Usage>,
+    pub incomplete_details: Option<IncompleteDetails>,
+    pub error: Option<Error>,
+}
+
+#[derive(Deserialize, PartialEq, Clone, Debug)]
+pub struct IncompleteDetails {
+    pub reason: Option<String>,
+}
+
+#[derive(Deserialize, PartialEq, Clone, Debug, thiserror::Error)]
+#[error("{message}")]
+pub struct Error {
+    pub code: Option<String>,
+    pub message: String,
+}
+
+#[derive(Deserialize, PartialEq, Clone, Debug, Default)]
+pub struct Usage {
+    pub input_tokens: Option<usize>,
+    pub input_tokens_details: Option<InputTokensDetails>,
+    pub output_tokens: Option<usize>,
+    pub total_tokens: Option<usize>,
+}
+
+#[derive(Deserialize, PartialEq, Clone, Debug, Default)]
+pub struct InputTokensDetails {
+    pub cached_tokens: Option<usize>,
+}
+
+impl From<Usage> for paws_domain::Usage {
+    fn from(usage: Usage) -> Self {
+        // input_tokens already includes the cached tokens and output_tokens the
+        // reasoning tokens
+        let prompt_tokens = usage
+            .input_tokens
+            .map(TokenCount::Actual)
+            .unwrap_or_default();
+        let completion_tokens = usage
+            .output_tokens
+            .map(TokenCount::Actual)
+            .unwrap_or_default();
+        let cached_tokens = usage
+            .input_tokens_details
+            .and_then(|details| details.cached_tokens)
+            .map(TokenCount::Actual)
+            .unwrap_or_default();
+        let total_tokens = usage
+            .total_tokens
+            .map(TokenCount::Actual)
+            .unwrap_or(prompt_tokens + completion_tokens);
+
+        paws_domain::Usage {
+            prompt_tokens,
+            completion_tokens,
+            total_tokens,
+            cached_tokens,
+            ..Default::default()
+        }
+    }
+}
+
+fn reasoning_part() -> ReasoningPart {
+    ReasoningPart::default()
+        .type_of(Some(REASONING_TYPE.to_string()))
+        .format(Some(REASONING_FORMAT.to_string()))
+}
+
+impl TryFrom<EventData> for ChatCompletionMessage {
+    type Error = anyhow::Error;
+    fn try_from(value: EventData) -> Result<Self, Self::Error> {
+        match value {
+            EventData::KnownEvent(event) => ChatCompletionMessage::try_from(event),
+            EventData::Unknown(_) => Ok(ChatCompletionMessage::assistant(Content::part(""))),
+        }
+    }
+}
+
+impl TryFrom<Event> for ChatCompletionMessage {
+    type Error = anyhow::Error;
+    fn try_from(value: Event) -> Result<Self, Self::Error> {
+        let empty = ChatCompletionMessage::assistant(Content::part(""));
+        let result = match value {
+            Event::OutputTextDelta { delta } => {
+                ChatCompletionMessage::assistant(Content::part(delta))
+            }
+            Event::ReasoningSummaryPartAdded { summary_index } if summary_index > 0 => empty
+                .reasoning(Content::part("\n\n"))
+                .add_reasoning_detail(Reasoning::Part(vec![
+                    reasoning_part().text(Some("\n\n".to_string())),
+                ])),
+            Event::ReasoningSummaryTextDelta { delta } => empty
+                .reasoning(Content::part(delta.clone()))
+                .add_reasoning_detail(Reasoning::Part(vec![reasoning_part().text(Some(delta))])),
+            Event::OutputItemAdded {
+                item: OutputItem::FunctionCall { call_id, name, arguments },
+            } => empty.add_tool_call(ToolCallPart {
+                call_id: Some(ToolCallId::new(call_id)),
+                name: Some(ToolName::new(name)),
+                arguments_part: arguments,
+            }),
+            Event::FunctionCallArgumentsDelta { delta } => empty.add_tool_call(ToolCallPart {
+                call_id: None,
+                name: None,
+                arguments_part: delta,
+            }),
+            Event::OutputItemDone { item: OutputItem::Reasoning { id, encrypted_content } } => {
+                empty.add_reasoning_detail(Reasoning::Part(vec![
+                    reasoning_part().id(Some(id)).data(encrypted_content),
+                ]))
+            }
+            Event::Completed { response } => {
+                let has_tool_calls = response
+                    .output
+                    .iter()
+                    .any(|item| matches!(item, OutputItem::FunctionCall { .. }));
+                let message = empty.finish_reason(if has_tool_calls {
+                    FinishReason::ToolCalls
+                } else {
+                    FinishReason::Stop
+                });
+                match response.usage {
+                    Some(usage) => message.usage(usage),
+                    None => message,
+                }
+            }
+            Event::Incomplete { response } => {
+                let reason = response
+                    .incomplete_details
+                    .and_then(|details| details.reason);
+                let message = match reason.as_deref() {
+                    Some("max_output_tokens") => empty.finish_reason(FinishReason::Length),
+                    Some("content_filter") => empty.finish_reason(FinishReason::ContentFilter),
+                    _ => empty,
+                };
+                match response.usage {
+                    Some(usage) => message.usage(usage),
+                    None => message,
+                }
+            }
+            Event::Failed { response } => {
+                return Err(response
+                    .error
+                    .map(anyhow::Error::from)
+                    .unwrap_or_else(|| anyhow::anyhow!("Response failed without an error")));
+            }
+            Event::Error { code, message } => {
+                return Err(Error { code, message }.into());
+            }
+            Event::OutputItemAdded { .. }
+            | Event::OutputItemDone { .. }
+            | Event::ReasoningSummaryPartAdded { .. } => empty,
+        };
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn parse(event: &str) -> anyhow::Result<ChatCompletionMessage> {
+        ChatCompletionMessage::try_from(serde_json::from_str::<EventData>(event)?)
+    }
+
+    #[test]
+    fn test_unknown_event() {
+        let fixture =
+            r#"{"type":"response.created","sequence_number":0,"response":{"id":"resp_1"}}"#;
+
+        let actual = serde_json::from_str::<EventData>(fixture).unwrap();
+
+        assert!(matches!(actual, EventData::Unknown(_)));
+    }
+
+    #[test]
+    fn test_output_text_delta() {
+        let fixture = r#"{"type":"response.output_text.delta","item_id":"msg_1","output_index":1,"content_index":0,"delta":"Hello"}"#;
+
+        let actual = parse(fixture).unwrap();
+
+        let expected = ChatCompletionMessage::assistant(Content::part("Hello"));
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_function_call_events() {
+        let added = r#"{"type":"response.output_item.added","output_index":1,"item":{"type":"function_call","id":"fc_1","call_id":"call_1","name":"read","arguments":"","status":"in_progress"}}"#;
+        let delta = r#"{"type":"response.function_call_arguments.delta","item_id":"fc_1","output_index":1,"delta":"{\"path\":"}"#;
+
+        let actual = vec![parse(added).unwrap(), parse(delta).unwrap()];
+
+        let expected = vec![
+            ChatCompletionMessage::assistant(Content::part("")).add_tool_call(ToolCallPart {
+                call_id: Some(ToolCallId::new("call_1")),
+                name: Some(ToolName::new("read")),
+                arguments_part: "".to_string(),
+            }),
+            ChatCompletionMessage::assistant(Content::part("")).add_tool_call(ToolCallPart {
+                call_id: None,
+                name: None,
+                arguments_part: r#"{"path":"#.to_string(),
+            }),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_reasoning_item_done_carries_encrypted_content() {
+        let fixture = r#"{"type":"response.output_item.done","output_index":0,"item":{"type":"reasoning","id":"rs_1","summary":[],"encrypted_content":"gAAAA"}}"#;
+
+        let actual = parse(fixture).unwrap();
+
+        let expected = ChatCompletionMessage::assistant(Content::part("")).add_reasoning_detail(
+            Reasoning::Part(vec![
+                reasoning_part()
+                    .id(Some("rs_1".to_string()))
+                    .data(Some("gAAAA".to_string())),
+            ]),
+        );
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_reasoning_parts_round_trip_into_reasoning_full() {
+        let fixture = vec![
+            r#"{"type":"response.reasoning_summary_text.delta","item_id":"rs_1","output_index":0,"summary_index":0,"delta":"Checking "}"#,
+            r#"{"type":"response.reasoning_summary_text.delta","item_id":"rs_1","output_index":0,"summary_index":0,"delta":"the file"}"#,
+            r#"{"type":"response.output_item.done","output_index":0,"item":{"type":"reasoning","id":"rs_1","summary":[],"encrypted_content":"gAAAA"}}"#,
+        ];
+
+        let parts = fixture
+            .into_iter()
+            .flat_map(|event| parse(event).unwrap().reasoning_details.unwrap())
+            .filter_map(|reasoning| reasoning.as_partial().cloned())
+            .collect::<Vec<_>>();
+        let actual = Reasoning::from_parts(parts);
+
+        let expected = vec![paws_domain::ReasoningFull {
+            text: Some("Checking the file".to_string()),
+            data: Some("gAAAA".to_string()),
+            id: Some("rs_1".to_string()),
+            format: Some(REASONING_FORMAT.to_string()),
+            type_of: Some(REASONING_TYPE.to_string()),
+            ..Default::default()
+        }];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_completed_with_tool_calls_and_usage() {
+        let fixture = r#"{"type":"response.completed","response":{"id":"resp_1","status":"completed","output":[{"type":"reasoning","id":"rs_1","summary":[]},{"type":"function_call","id":"fc_1","call_id":"call_1","name":"read","arguments":"{}"}],"usage":{"input_tokens":120,"input_tokens_details":{"cached_tokens":100},"output_tokens":40,"output_tokens_details":{"reasoning_tokens":32},"total_tokens":160}}}"#;
+
+        let actual = parse(fixture).unwrap();
+
+        let expected = ChatCompletionMessage::assistant(Content::part(""))
+            .finish_reason(FinishReason::ToolCalls)
+            .usage(paws_domain::Usage {
+                prompt_tokens: TokenCount::Actual(120),
+                completion_tokens: TokenCount::Actual(40),
+                total_tokens: TokenCount::Actual(160),
+                cached_tokens: TokenCount::Actual(100),
+                cache_write_tokens: TokenCount::Actual(0),
+                cost: None,
+            });
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_incomplete_max_output_tokens() {
+        let fixture = r#"{"type":"response.incomplete","response":{"status":"incomplete","incomplete_details":{"reason":"max_output_tokens"},"output":[]}}"#;
+
+        let actual = parse(fixture).unwrap().finish_reason;
+
+        assert_eq!(actual, Some(FinishReason::Length));
+    }
+
+    #[test]
+    fn test_failed_response() {
+        let fixture = r#"{"type":"response.failed","response":{"status":"failed","output":[],"error":{"code":"server_error","message":"The model failed"}}}"#;
+
+        let actual = parse(fixture).unwrap_err().to_string();
+
+        assert_eq!(actual, "The model failed");
+    }
+
+    #[test]
+    fn test_error_event() {
+        let fixture =
+            r#"{"type":"error","code":"rate_limit_exceeded","message":"Slow down","param":null}"#;
+
+        let actual = parse(fixture).unwrap_err().to_string();
+
+        assert_eq!(actual, "Slow down");
+    }
+}
@@ -1,4 +1,4 @@
-use paws_domain::{ToolCallArgumentError, ToolName};
+use paws_domain::{HookEvent, ToolCallArgumentError, ToolName};
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
@@ -33,4 +33,7 @@ pub enum Error {
 
     #[error("No active model configured")]
     NoActiveModel,
+
+    #[error("Blocked by {event} hook: {reason}")]
+    HookBlocked { event: HookEvent, reason: String },
 }
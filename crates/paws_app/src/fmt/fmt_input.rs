@@ -60,6 +60,32 @@ impl FormatContent for ToolCatalog {
                 };
                 Some(TitleFormat::debug(title).into())
             }
+            ToolCatalog::SemSearch(input) => {
+                let queries = input
+                    .queries
+                    .iter()
+                    .map(|query| format!("'{}'", query.query))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                Some(
+                    TitleFormat::debug("Semantic Search")
+                        .sub_title(queries)
+                        .into(),
+                )
+            }
+            ToolCatalog::Outline(input) => {
+                let display_path = display_path_for(&input.path);
+                Some(TitleFormat::debug("Outline").sub_title(display_path).into())
+            }
+            ToolCatalog::FindSymbol(input) => {
+                let title = match &input.path {
+                    Some(path) => {
+                        format!("Find symbol '{}' at {}", input.name, display_path_for(path))
+                    }
+                    None => format!("Find symbol '{}'", input.name),
+                };
+                Some(TitleFormat::debug(title).into())
+            }
             ToolCatalog::Remove(input) => {
                 let display_path = display_path_for(&input.path);
                 Some(TitleFormat::debug("Remove").sub_title(display_path).into())
@@ -72,15 +98,46 @@ impl FormatContent for ToolCatalog {
                         .into(),
                 )
             }
+            ToolCatalog::MultiPatch(input) => {
+                let sub_title = match input.paths(&env.cwd).as_slice() {
+                    [path] => display_path_for(&path.to_string_lossy()),
+                    paths => format!("{} files", paths.len()),
+                };
+                Some(
+                    TitleFormat::debug("Multi Patch")
+                        .sub_title(sub_title)
+                        .into(),
+                )
+            }
             ToolCatalog::Undo(input) => {
                 let display_path = display_path_for(&input.path);
                 Some(TitleFormat::debug("Undo").sub_title(display_path).into())
             }
+            ToolCatalog::Shell(input) if input.background => Some(
+                TitleFormat::debug(format!("Background [{}]", env.shell))
+                    .sub_title(&input.command)
+                    .into(),
+            ),
             ToolCatalog::Shell(input) => Some(
                 TitleFormat::debug(format!("Execute [{}]", env.shell))
                     .sub_title(&input.command)
                     .into(),
             ),
+            ToolCatalog::ShellRead(input) => Some(
+                TitleFormat::debug("Read Job")
+                    .sub_title(input.job_id.to_string())
+                    .into(),
+            ),
+            ToolCatalog::ShellWait(input) => Some(
+                TitleFormat::debug("Wait Job")
+                    .sub_title(format!("{} ({}s)", input.job_id, input.timeout_secs))
+                    .into(),
+            ),
+            ToolCatalog::ShellKill(input) => Some(
+                TitleFormat::debug("Kill Job")
+                    .sub_title(input.job_id.to_string())
+                    .into(),
+            ),
             ToolCatalog::Fetch(input) => {
                 Some(TitleFormat::debug("GET").sub_title(&input.url).into())
             }
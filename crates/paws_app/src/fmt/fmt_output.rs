@@ -22,14 +22,35 @@ impl FormatContent for ToolOperation {
             }
             ToolOperation::FsRemove { input: _, output: _ } => None,
             ToolOperation::FsSearch { input: _, output: _ } => None,
+            ToolOperation::SemSearch { input: _, output: _ } => None,
+            ToolOperation::Outline { input: _, output: _ } => None,
+            ToolOperation::FindSymbol { input: _, output: _ } => None,
             ToolOperation::FsPatch { input: _, output } => Some(ChatResponseContent::PlainText(
                 DiffFormat::format(&output.before, &output.after)
                     .diff()
                     .to_string(),
             )),
+            ToolOperation::FsMultiPatch { input: _, output } => {
+                Some(ChatResponseContent::PlainText(
+                    output
+                        .files
+                        .iter()
+                        .map(|file| {
+                            let before = file.before.as_deref().unwrap_or_default();
+                            format!(
+                                "{}\n{}",
+                                format_display_path(&file.path, &env.cwd),
+                                DiffFormat::format(before, &file.after).diff()
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                ))
+            }
             ToolOperation::FsUndo { input: _, output: _ } => None,
             ToolOperation::NetFetch { input: _, output: _ } => None,
             ToolOperation::Shell { output: _ } => None,
+            ToolOperation::ShellJob { output: _ } => None,
             ToolOperation::FollowUp { output: _ } => None,
             ToolOperation::PlanCreate { input: _, output } => Some({
                 let title = TitleFormat::debug(format!(
@@ -143,6 +164,8 @@ mod tests {
                 before: None,
 
                 content_hash: crate::compute_hash(content),
+                snapshot: None,
+                syntax_errors: vec![],
             },
         };
         let env = fixture_environment();
@@ -167,6 +190,8 @@ mod tests {
                 before: Some("old content".to_string()),
 
                 content_hash: crate::compute_hash(content),
+                snapshot: None,
+                syntax_errors: vec![],
             },
         };
         let env = fixture_environment();
@@ -195,6 +220,8 @@ mod tests {
                 before: None,
 
                 content_hash: crate::compute_hash(content),
+                snapshot: None,
+                syntax_errors: vec![],
             },
         };
         let env = fixture_environment();
@@ -209,7 +236,7 @@ mod tests {
     fn test_fs_remove() {
         let fixture = ToolOperation::FsRemove {
             input: paws_domain::FSRemove { path: "/home/user/project/file.txt".to_string() },
-            output: FsRemoveOutput { content: "".to_string() },
+            output: FsRemoveOutput { content: "".to_string(), snapshot: None },
         };
         let env = fixture_environment();
 
@@ -315,6 +342,8 @@ mod tests {
                 before: "Hello world\nThis is a test".to_string(),
                 after: after_content.to_string(),
                 content_hash: crate::compute_hash(after_content),
+                snapshot: None,
+                syntax_errors: vec![],
             },
         };
         let env = fixture_environment();
@@ -337,6 +366,8 @@ mod tests {
                 before: "line1\nline2".to_string(),
                 after: after_content.to_string(),
                 content_hash: crate::compute_hash(after_content),
+                snapshot: None,
+                syntax_errors: vec![],
             },
         };
         let env = fixture_environment();
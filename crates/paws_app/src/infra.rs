@@ -1,16 +1,20 @@
 use std::collections::BTreeMap;
 use std::hash::Hash;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use anyhow::Result;
 use bytes::Bytes;
+use futures::StreamExt;
+use futures::stream::BoxStream;
 use paws_domain::{
-    AuthCodeParams, CommandOutput, Environment, FileInfo, McpServerConfig, OAuthConfig,
-    OAuthTokenResponse, ToolDefinition, ToolName, ToolOutput,
+    AuthCodeParams, CommandOutput, ConversationId, Environment, FileInfo, JobId, JobOutput,
+    McpPrompt, McpPromptMessage, McpResource, McpResourceContent, McpServerConfig, OAuthConfig,
+    OAuthTokenResponse, RateLimit, ToolDefinition, ToolName, ToolOutput,
 };
 use reqwest::Response;
 use reqwest::header::HeaderMap;
-use reqwest_eventsource::EventSource;
+use reqwest_eventsource::{Event, EventSource};
 use serde::de::DeserializeOwned;
 use url::Url;
 
@@ -123,6 +127,48 @@ pub trait CommandInfra: Send + Sync {
         working_dir: PathBuf,
         env_vars: Option<Vec<String>>,
     ) -> anyhow::Result<std::process::ExitStatus>;
+
+    /// Executes a command silently with the input written to its stdin. The
+    /// restrictions applied to the commands of agents don't apply.
+    async fn execute_command_with_input(
+        &self,
+        command: String,
+        working_dir: PathBuf,
+        input: String,
+    ) -> anyhow::Result<CommandOutput>;
+
+    /// Starts a command in the background for the given conversation and
+    /// returns its job handle without waiting for it to exit
+    async fn spawn_job(
+        &self,
+        conversation_id: ConversationId,
+        command: String,
+        working_dir: PathBuf,
+        env_vars: Option<Vec<String>>,
+    ) -> anyhow::Result<JobOutput>;
+
+    /// Returns the output a background job produced since the previous read
+    async fn read_job(
+        &self,
+        conversation_id: ConversationId,
+        job_id: JobId,
+    ) -> anyhow::Result<JobOutput>;
+
+    /// Waits up to `timeout` for a background job to exit, then returns the
+    /// output it produced since the previous read
+    async fn wait_job(
+        &self,
+        conversation_id: ConversationId,
+        job_id: JobId,
+        timeout: Duration,
+    ) -> anyhow::Result<JobOutput>;
+
+    /// Kills a background job and returns its remaining output
+    async fn kill_job(
+        &self,
+        conversation_id: ConversationId,
+        job_id: JobId,
+    ) -> anyhow::Result<JobOutput>;
 }
 
 #[async_trait::async_trait]
@@ -168,6 +214,20 @@ pub trait McpClientInfra: Clone + Send + Sync + 'static {
         tool_name: &ToolName,
         input: serde_json::Value,
     ) -> anyhow::Result<ToolOutput>;
+
+    /// Lists the resources exposed by the server, empty if the server doesn't
+    /// support resources
+    async fn list_resources(&self) -> anyhow::Result<Vec<McpResource>>;
+    async fn read_resource(&self, uri: &str) -> anyhow::Result<Vec<McpResourceContent>>;
+
+    /// Lists the prompts exposed by the server, empty if the server doesn't
+    /// support prompts
+    async fn list_prompts(&self) -> anyhow::Result<Vec<McpPrompt>>;
+    async fn get_prompt(
+        &self,
+        name: &str,
+        arguments: BTreeMap<String, String>,
+    ) -> anyhow::Result<Vec<McpPromptMessage>>;
 }
 
 #[async_trait::async_trait]
@@ -187,11 +247,40 @@ pub trait WalkerInfra: Send + Sync {
     async fn walk(&self, config: Walker) -> anyhow::Result<Vec<WalkedFile>>;
 }
 
+/// Server-sent events of a response, along with the rate limits reported in
+/// its headers
+pub struct EventStream {
+    /// Rate limits of a successful response, `None` when the provider reported
+    /// none or the request failed
+    pub rate_limit: Option<RateLimit>,
+    pub events: BoxStream<'static, Result<Event, reqwest_eventsource::Error>>,
+}
+
+impl EventStream {
+    pub fn new(
+        rate_limit: Option<RateLimit>,
+        events: impl futures::Stream<Item = Result<Event, reqwest_eventsource::Error>> + Send + 'static,
+    ) -> Self {
+        Self { rate_limit, events: events.boxed() }
+    }
+}
+
+impl From<EventSource> for EventStream {
+    fn from(source: EventSource) -> Self {
+        Self::new(None, source)
+    }
+}
+
 /// HTTP service trait for making HTTP requests
 #[async_trait::async_trait]
 pub trait HttpInfra: Send + Sync + 'static {
     async fn http_get(&self, url: &Url, headers: Option<HeaderMap>) -> anyhow::Result<Response>;
-    async fn http_post(&self, url: &Url, body: bytes::Bytes) -> anyhow::Result<Response>;
+    async fn http_post(
+        &self,
+        url: &Url,
+        headers: Option<HeaderMap>,
+        body: bytes::Bytes,
+    ) -> anyhow::Result<Response>;
     async fn http_delete(&self, url: &Url) -> anyhow::Result<Response>;
 
     /// Posts JSON data and returns a server-sent events stream
@@ -200,7 +289,7 @@ pub trait HttpInfra: Send + Sync + 'static {
         url: &Url,
         headers: Option<HeaderMap>,
         body: Bytes,
-    ) -> anyhow::Result<EventSource>;
+    ) -> anyhow::Result<EventStream>;
 }
 /// Service for reading multiple files from a directory asynchronously
 #[async_trait::async_trait]
@@ -31,6 +31,7 @@ mod title_generator;
 mod tool_executor;
 mod tool_registry;
 mod tool_resolver;
+mod tool_scheduler;
 mod transformers;
 mod truncation;
 mod user;
@@ -6,8 +6,9 @@ use derive_setters::Setters;
 use paws_common::display::DiffFormat;
 use paws_common::template::Element;
 use paws_domain::{
-    Environment, FSPatch, FSRead, FSRemove, FSSearch, FSUndo, FSWrite, FileOperation, LineNumbers,
-    Metrics, NetFetch, PlanCreate, ToolKind,
+    CommandOutput, Environment, FSMultiPatch, FSOutline, FSPatch, FSRead, FSRemove, FSSearch,
+    FSUndo, FSWrite, FileOperation, FindSymbol, JobOutput, LineNumbers, Metrics, NetFetch,
+    PlanCreate, SemanticSearch, ToolKind,
 };
 
 use crate::truncation::{
@@ -16,8 +17,9 @@ use crate::truncation::{
 };
 use crate::utils::{compute_hash, format_display_path};
 use crate::{
-    FsCreateOutput, FsRemoveOutput, FsUndoOutput, HttpResponse, PatchOutput, PlanCreateOutput,
-    ReadOutput, ResponseContext, SearchResult, ShellOutput,
+    FsCreateOutput, FsRemoveOutput, FsUndoOutput, HttpResponse, MultiPatchOutput, OutlineOutput,
+    PatchOutput, PlanCreateOutput, QueryHits, ReadOutput, ResponseContext, SearchResult,
+    ShellOutput, Symbol, SymbolLocation, SymbolSearchOutput, SyntaxError,
 };
 
 #[derive(Debug, Default, Setters)]
@@ -48,10 +50,27 @@ pub enum ToolOperation {
         input: FSSearch,
         output: Option<SearchResult>,
     },
+    SemSearch {
+        input: SemanticSearch,
+        output: Vec<QueryHits>,
+    },
+    Outline {
+        input: FSOutline,
+        output: OutlineOutput,
+    },
+    FindSymbol {
+        input: FindSymbol,
+        output: SymbolSearchOutput,
+    },
     FsPatch {
         input: FSPatch,
         output: PatchOutput,
     },
+    FsMultiPatch {
+        #[allow(dead_code)]
+        input: FSMultiPatch,
+        output: MultiPatchOutput,
+    },
     FsUndo {
         input: FSUndo,
         output: FsUndoOutput,
@@ -63,6 +82,9 @@ pub enum ToolOperation {
     Shell {
         output: ShellOutput,
     },
+    ShellJob {
+        output: JobOutput,
+    },
     FollowUp {
         output: Option<String>,
     },
@@ -185,6 +207,81 @@ fn create_stream_element<T: StreamElement>(
 
     Some(elem)
 }
+/// Appends the truncated stdout and stderr of a command to `parent`
+fn append_streams(
+    parent: Element,
+    output: &CommandOutput,
+    content_files: &TempContentFiles,
+    env: &Environment,
+) -> Element {
+    let truncated_output = truncate_shell_output(
+        &output.stdout,
+        &output.stderr,
+        env.stdout_max_prefix_length,
+        env.stdout_max_suffix_length,
+        env.stdout_max_line_length,
+    );
+
+    let stdout_elem =
+        create_stream_element(&truncated_output.stdout, content_files.stdout.as_deref());
+    let stderr_elem =
+        create_stream_element(&truncated_output.stderr, content_files.stderr.as_deref());
+
+    parent.append(stdout_elem).append(stderr_elem)
+}
+
+/// Reports errors of a sandboxed command that may come from operations the
+/// sandbox denied, so the model doesn't retry them blindly
+fn sandbox_denials(output: &CommandOutput, env: &Environment) -> Option<Element> {
+    let policy = env.sandbox.as_ref()?;
+    let denials = policy.denials(output, &env.cwd);
+    if denials.is_empty() {
+        return None;
+    }
+    let elm = Element::new("sandbox_denials")
+        .text(format!(
+            "{} These errors may come from operations the sandbox denied. Work within these limits or ask the user to run the command themselves:",
+            policy.describe(&env.cwd)
+        ))
+        .append(
+            denials
+                .into_iter()
+                .map(|denial| Element::new("error").text(denial)),
+        );
+    Some(elm)
+}
+
+/// Renders symbols as `start-end signature` lines, indenting nested symbols
+fn outline_lines(symbols: &[Symbol], depth: usize, lines: &mut Vec<String>) {
+    for symbol in symbols {
+        lines.push(format!(
+            "{}{}-{} {}",
+            "  ".repeat(depth),
+            symbol.start_line,
+            symbol.end_line,
+            symbol.signature
+        ));
+        outline_lines(&symbol.children, depth + 1, lines);
+    }
+}
+
+/// Warns the model about parse errors left in a file it has just written
+fn syntax_warning(errors: &[SyntaxError]) -> Option<Element> {
+    if errors.is_empty() {
+        return None;
+    }
+    let elm = Element::new("syntax_warning")
+        .attr("errors", errors.len())
+        .text("The file was written but does not parse. Fix these errors:")
+        .append(errors.iter().map(|error| {
+            Element::new("error")
+                .attr("line", error.line)
+                .attr("column", error.column)
+                .text(&error.message)
+        }));
+    Some(elm)
+}
+
 impl ToolOperation {
     pub fn into_tool_output(
         self,
@@ -228,13 +325,16 @@ impl ToolOperation {
                 );
                 let diff = console::strip_ansi_codes(diff_result.diff()).to_string();
 
-                *metrics = metrics.clone().insert(
-                    input.path.clone(),
-                    FileOperation::new(tool_kind)
-                        .lines_added(diff_result.lines_added())
-                        .lines_removed(diff_result.lines_removed())
-                        .content_hash(Some(output.content_hash.clone())),
-                );
+                *metrics = metrics
+                    .clone()
+                    .insert(
+                        input.path.clone(),
+                        FileOperation::new(tool_kind)
+                            .lines_added(diff_result.lines_added())
+                            .lines_removed(diff_result.lines_removed())
+                            .content_hash(Some(output.content_hash.clone())),
+                    )
+                    .record_snapshot(input.path.clone(), output.snapshot);
 
                 let mut elm = if output.before.as_ref().is_some() {
                     Element::new("file_overwritten").append(Element::new("file_diff").cdata(diff))
@@ -244,7 +344,8 @@ impl ToolOperation {
 
                 elm = elm
                     .attr("path", input.path)
-                    .attr("total_lines", input.content.lines().count());
+                    .attr("total_lines", input.content.lines().count())
+                    .append(syntax_warning(&output.syntax_errors));
 
                 paws_domain::ToolOutput::text(elm)
             }
@@ -258,6 +359,11 @@ impl ToolOperation {
                         .lines_removed(output.content.lines().count() as u64)
                         .content_hash(content_hash),
                 );
+                if let Some(snapshot) = output.snapshot {
+                    *metrics = metrics
+                        .clone()
+                        .record_snapshot(input.path.clone(), Some(snapshot));
+                }
 
                 let display_path = format_display_path(Path::new(&input.path), env.cwd.as_path());
                 let elem = Element::new("file_removed")
@@ -328,6 +434,88 @@ impl ToolOperation {
                     paws_domain::ToolOutput::text(elm)
                 }
             },
+            ToolOperation::SemSearch { input, output } => {
+                let queries = output.into_iter().map(|query_hits| {
+                    Element::new("query")
+                        .attr("text", query_hits.query.query)
+                        .attr("use_case", query_hits.query.use_case)
+                        .append(query_hits.hits.into_iter().map(|hit| {
+                            Element::new("result")
+                                .attr("path", hit.path)
+                                .attr(
+                                    "lines",
+                                    format!("{}-{}", hit.chunk.start_line, hit.chunk.end_line),
+                                )
+                                .attr("score", format!("{:.2}", hit.score))
+                                .cdata(hit.chunk.content)
+                        }))
+                });
+                let elm = Element::new("sem_search_results")
+                    .attr_if_some("file_extension", input.file_extension)
+                    .append(queries);
+
+                paws_domain::ToolOutput::text(elm)
+            }
+            ToolOperation::Outline { input, output } => {
+                let file_count = output.files.len();
+                let files = output.files.into_iter().map(|file| {
+                    let mut lines = Vec::new();
+                    outline_lines(&file.symbols, 0, &mut lines);
+                    let elm = Element::new("file")
+                        .attr("path", file.path)
+                        .attr("total_lines", file.total_lines);
+                    if lines.is_empty() {
+                        elm
+                    } else {
+                        elm.cdata(lines.join("\n"))
+                    }
+                });
+                let elm = Element::new("outline")
+                    .attr("path", &input.path)
+                    .attr("files", file_count)
+                    .attr_if_some(
+                        "reason",
+                        output.truncated.then_some(
+                            "Files were left out to keep the outline small. Outline a subdirectory to see them",
+                        ),
+                    )
+                    .append(files);
+
+                paws_domain::ToolOutput::text(elm)
+            }
+            ToolOperation::FindSymbol { input, output } => {
+                let locations = |tag: &str, locations: Vec<SymbolLocation>| {
+                    let elm = Element::new(tag).attr("count", locations.len());
+                    if locations.is_empty() {
+                        return elm;
+                    }
+                    let lines = locations
+                        .into_iter()
+                        .map(|location| match location.end_line {
+                            Some(end_line) => format!(
+                                "{}:{}-{} {}",
+                                location.path, location.line, end_line, location.text
+                            ),
+                            None => {
+                                format!("{}:{} {}", location.path, location.line, location.text)
+                            }
+                        })
+                        .collect::<Vec<_>>();
+                    elm.cdata(lines.join("\n"))
+                };
+                let elm = Element::new("symbol_search")
+                    .attr("name", &input.name)
+                    .attr_if_some("path", input.path)
+                    .append(locations("definitions", output.definitions))
+                    .append(locations("references", output.references).attr_if_some(
+                        "reason",
+                        output.truncated.then_some(
+                            "References were left out, search a narrower path to see them all",
+                        ),
+                    ));
+
+                paws_domain::ToolOutput::text(elm)
+            }
             ToolOperation::FsPatch { input, output } => {
                 let diff_result = DiffFormat::format(&output.before, &output.after);
                 let diff = console::strip_ansi_codes(diff_result.diff()).to_string();
@@ -335,7 +523,8 @@ impl ToolOperation {
                 let elm = Element::new("file_diff")
                     .attr("path", &input.path)
                     .attr("total_lines", output.after.lines().count())
-                    .cdata(diff);
+                    .cdata(diff)
+                    .append(syntax_warning(&output.syntax_errors));
 
                 *metrics = metrics.clone().insert(
                     input.path.clone(),
@@ -344,6 +533,53 @@ impl ToolOperation {
                         .lines_removed(diff_result.lines_removed())
                         .content_hash(Some(output.content_hash.clone())),
                 );
+                if let Some(snapshot) = output.snapshot {
+                    *metrics = metrics
+                        .clone()
+                        .record_snapshot(input.path.clone(), Some(snapshot));
+                }
+
+                paws_domain::ToolOutput::text(elm)
+            }
+            ToolOperation::FsMultiPatch { input: _, output } => {
+                let files = output.files.into_iter().map(|file| {
+                    let path = file.path.display().to_string();
+                    let before = file.before.as_deref().unwrap_or_default();
+                    let diff_result = DiffFormat::format(before, &file.after);
+                    let diff = console::strip_ansi_codes(diff_result.diff()).to_string();
+
+                    *metrics = metrics.clone().insert(
+                        path.clone(),
+                        FileOperation::new(tool_kind)
+                            .lines_added(diff_result.lines_added())
+                            .lines_removed(diff_result.lines_removed())
+                            .content_hash(Some(file.content_hash.clone())),
+                    );
+                    if let Some(snapshot) = file.snapshot {
+                        *metrics = metrics
+                            .clone()
+                            .record_snapshot(path.clone(), Some(snapshot));
+                    }
+
+                    Element::new("file_diff")
+                        .attr("path", path)
+                        .attr_if_some("status", file.before.is_none().then_some("created"))
+                        .attr("total_lines", file.after.lines().count())
+                        .cdata(diff)
+                        .append(file.hunks.iter().map(|hunk| {
+                            Element::new("hunk")
+                                .attr("line", hunk.line)
+                                .attr("match", hunk.matching)
+                                .attr("fuzz", hunk.fuzz)
+                                .text(&hunk.label)
+                        }))
+                        .append(syntax_warning(&file.syntax_errors))
+                });
+                let files: Vec<Element> = files.collect();
+
+                let elm = Element::new("multi_patch")
+                    .attr("files", files.len())
+                    .append(files);
 
                 paws_domain::ToolOutput::text(elm)
             }
@@ -439,26 +675,24 @@ impl ToolOperation {
                     parent_elem = parent_elem.attr("exit_code", exit_code);
                 }
 
-                let truncated_output = truncate_shell_output(
-                    &output.output.stdout,
-                    &output.output.stderr,
-                    env.stdout_max_prefix_length,
-                    env.stdout_max_suffix_length,
-                    env.stdout_max_line_length,
-                );
+                parent_elem = append_streams(parent_elem, &output.output, &content_files, env)
+                    .append(sandbox_denials(&output.output, env));
 
-                let stdout_elem = create_stream_element(
-                    &truncated_output.stdout,
-                    content_files.stdout.as_deref(),
-                );
+                paws_domain::ToolOutput::text(parent_elem)
+            }
+            ToolOperation::ShellJob { output } => {
+                let status = if output.running { "running" } else { "exited" };
+                let mut parent_elem = Element::new("shell_job")
+                    .attr("job_id", output.job_id)
+                    .attr("command", &output.output.command)
+                    .attr("status", status);
 
-                let stderr_elem = create_stream_element(
-                    &truncated_output.stderr,
-                    content_files.stderr.as_deref(),
-                );
+                if let Some(exit_code) = output.output.exit_code {
+                    parent_elem = parent_elem.attr("exit_code", exit_code);
+                }
 
-                parent_elem = parent_elem.append(stdout_elem);
-                parent_elem = parent_elem.append(stderr_elem);
+                parent_elem = append_streams(parent_elem, &output.output, &content_files, env)
+                    .append(sandbox_denials(&output.output, env));
 
                 paws_domain::ToolOutput::text(parent_elem)
             }
@@ -7,8 +7,10 @@ use paws_domain::{Environment, FSRead, Metrics, ToolKind, ToolValue};
 
 use crate::operation::*;
 use crate::{
-    Content, FsCreateOutput, FsRemoveOutput, FsUndoOutput, HttpResponse, Match, MatchResult,
-    PatchOutput, ReadOutput, ResponseContext, SearchResult, ShellOutput, compute_hash,
+    Content, FileOutline, FilePatchOutput, FsCreateOutput, FsRemoveOutput, FsUndoOutput,
+    HttpResponse, HunkMatch, HunkOutcome, Match, MatchResult, MultiPatchOutput, OutlineOutput,
+    PatchOutput, QueryHits, ReadOutput, ResponseContext, SearchResult, ShellOutput, Symbol,
+    SymbolKind, SymbolLocation, SymbolSearchOutput, SyntaxError, compute_hash,
 };
 
 fn fixture_environment() -> Environment {
@@ -189,6 +191,8 @@ fn test_fs_create_basic() {
             before: None,
 
             content_hash: compute_hash(content),
+            snapshot: None,
+            syntax_errors: vec![],
         },
     };
 
@@ -218,6 +222,8 @@ fn test_fs_create_overwrite() {
             before: Some("Old content".to_string()),
 
             content_hash: compute_hash(content),
+            snapshot: None,
+            syntax_errors: vec![],
         },
     };
 
@@ -257,6 +263,85 @@ fn test_shell_output_no_truncation() {
     insta::assert_snapshot!(to_value(actual));
 }
 
+#[test]
+fn test_shell_output_sandbox_denials() {
+    let fixture = ToolOperation::Shell {
+        output: ShellOutput {
+            output: paws_domain::CommandOutput {
+                command: "touch /etc/motd && curl -sS https://example.com".to_string(),
+                stdout: "".to_string(),
+                stderr: "touch: cannot touch '/etc/motd': Permission denied".to_string(),
+                exit_code: Some(1),
+            },
+            shell: "/bin/bash".to_string(),
+        },
+    };
+
+    let env = fixture_environment()
+        .cwd(std::path::PathBuf::from("/work"))
+        .sandbox(paws_domain::SandboxPolicy::default());
+    let actual = fixture.into_tool_output(
+        ToolKind::Shell,
+        TempContentFiles::default(),
+        &env,
+        &mut Metrics::default(),
+    );
+
+    insta::assert_snapshot!(to_value(actual));
+}
+
+#[test]
+fn test_shell_job_running() {
+    let fixture = ToolOperation::ShellJob {
+        output: paws_domain::JobOutput {
+            job_id: paws_domain::JobId::new(1),
+            output: paws_domain::CommandOutput {
+                command: "npm run dev".to_string(),
+                stdout: "Listening on http://localhost:3000".to_string(),
+                stderr: "".to_string(),
+                exit_code: None,
+            },
+            running: true,
+        },
+    };
+
+    let env = fixture_environment();
+    let actual = fixture.into_tool_output(
+        ToolKind::Shell,
+        TempContentFiles::default(),
+        &env,
+        &mut Metrics::default(),
+    );
+
+    insta::assert_snapshot!(to_value(actual));
+}
+
+#[test]
+fn test_shell_job_exited() {
+    let fixture = ToolOperation::ShellJob {
+        output: paws_domain::JobOutput {
+            job_id: paws_domain::JobId::new(2),
+            output: paws_domain::CommandOutput {
+                command: "cargo test".to_string(),
+                stdout: "test result: ok".to_string(),
+                stderr: "warning: unused import".to_string(),
+                exit_code: Some(0),
+            },
+            running: false,
+        },
+    };
+
+    let env = fixture_environment();
+    let actual = fixture.into_tool_output(
+        ToolKind::ShellWait,
+        TempContentFiles::default(),
+        &env,
+        &mut Metrics::default(),
+    );
+
+    insta::assert_snapshot!(to_value(actual));
+}
+
 #[test]
 fn test_shell_output_stdout_truncation_only() {
     // Create stdout with more lines than the truncation limit
@@ -693,6 +778,8 @@ fn test_fs_create_with_warning() {
             before: None,
 
             content_hash: compute_hash(content),
+            snapshot: None,
+            syntax_errors: vec![],
         },
     };
 
@@ -712,7 +799,7 @@ fn test_fs_create_with_warning() {
 fn test_fs_remove_success() {
     let fixture = ToolOperation::FsRemove {
         input: paws_domain::FSRemove { path: "/home/user/file_to_delete.txt".to_string() },
-        output: FsRemoveOutput { content: "content".to_string() },
+        output: FsRemoveOutput { content: "content".to_string(), snapshot: None },
     };
 
     let env = fixture_environment();
@@ -794,6 +881,132 @@ fn test_fs_search_no_results() {
     insta::assert_snapshot!(to_value(actual));
 }
 
+#[test]
+fn test_sem_search() {
+    let query = paws_domain::SearchQuery::new(
+        "retry with exponential backoff",
+        "Find where failed provider requests are retried",
+    );
+    let fixture = ToolOperation::SemSearch {
+        input: paws_domain::SemanticSearch {
+            queries: vec![query.clone()],
+            file_extension: Some(".rs".to_string()),
+        },
+        output: vec![QueryHits {
+            query,
+            hits: vec![paws_domain::SearchHit {
+                path: "src/retry.rs".to_string(),
+                chunk: paws_domain::CodeChunk {
+                    start_line: 10,
+                    end_line: 12,
+                    content:
+                        "fn backoff(attempt: u32) -> Duration {\n    BASE * 2u32.pow(attempt)\n}"
+                            .to_string(),
+                },
+                score: 0.8312,
+            }],
+        }],
+    };
+
+    let env = fixture_environment();
+
+    let actual = fixture.into_tool_output(
+        ToolKind::SemSearch,
+        TempContentFiles::default(),
+        &env,
+        &mut Metrics::default(),
+    );
+
+    insta::assert_snapshot!(to_value(actual));
+}
+
+#[test]
+fn test_outline() {
+    let method = Symbol {
+        kind: SymbolKind::Method,
+        name: "new".to_string(),
+        signature: "pub fn new(name: String) -> Self".to_string(),
+        start_line: 8,
+        end_line: 10,
+        children: vec![],
+    };
+    let fixture = ToolOperation::Outline {
+        input: paws_domain::FSOutline { path: "/home/user/project/src".to_string() },
+        output: OutlineOutput {
+            files: vec![FileOutline {
+                path: "user.rs".to_string(),
+                total_lines: 24,
+                symbols: vec![
+                    Symbol {
+                        kind: SymbolKind::Struct,
+                        name: "User".to_string(),
+                        signature: "pub struct User".to_string(),
+                        start_line: 2,
+                        end_line: 5,
+                        children: vec![],
+                    },
+                    Symbol {
+                        kind: SymbolKind::Impl,
+                        name: "User".to_string(),
+                        signature: "impl User".to_string(),
+                        start_line: 7,
+                        end_line: 11,
+                        children: vec![method],
+                    },
+                ],
+            }],
+            truncated: true,
+        },
+    };
+
+    let env = fixture_environment();
+
+    let actual = fixture.into_tool_output(
+        ToolKind::Outline,
+        TempContentFiles::default(),
+        &env,
+        &mut Metrics::default(),
+    );
+
+    insta::assert_snapshot!(to_value(actual));
+}
+
+#[test]
+fn test_find_symbol() {
+    let fixture = ToolOperation::FindSymbol {
+        input: paws_domain::FindSymbol {
+            name: "User".to_string(),
+            path: Some("/home/user/project/src".to_string()),
+        },
+        output: SymbolSearchOutput {
+            definitions: vec![SymbolLocation {
+                path: "user.rs".to_string(),
+                line: 2,
+                end_line: Some(5),
+                text: "pub struct User".to_string(),
+            }],
+            references: vec![SymbolLocation {
+                path: "main.rs".to_string(),
+                line: 12,
+                end_line: None,
+                text: "let user = User::new(name);".to_string(),
+            }],
+            truncated: false,
+        },
+    };
+
+    let env = fixture_environment();
+
+    let actual = fixture.into_tool_output(
+        ToolKind::FindSymbol,
+        TempContentFiles::default(),
+        &env,
+        &mut Metrics::default(),
+    );
+
+    insta::assert_snapshot!(to_value(actual));
+}
+
 #[test]
 fn test_fs_patch_basic() {
     let after_content = "Hello universe\nThis is a test";
@@ -808,6 +1021,8 @@ fn test_fs_patch_basic() {
             before: "Hello world\nThis is a test".to_string(),
             after: after_content.to_string(),
             content_hash: compute_hash(after_content),
+            snapshot: None,
+            syntax_errors: vec![],
         },
     };
 
@@ -837,6 +1052,76 @@ fn test_fs_patch_with_warning() {
             before: "line1\nline2".to_string(),
             after: after_content.to_string(),
             content_hash: compute_hash(after_content),
+            snapshot: None,
+            syntax_errors: vec![],
+        },
+    };
+
+    let env = fixture_environment();
+
+    let actual = fixture.into_tool_output(
+        ToolKind::Patch,
+        TempContentFiles::default(),
+        &env,
+        &mut Metrics::default(),
+    );
+
+    insta::assert_snapshot!(to_value(actual));
+}
+
+#[test]
+fn test_fs_create_with_syntax_errors() {
+    let content = "fn main() {\n    let x = ;\n";
+    let fixture = ToolOperation::FsCreate {
+        input: paws_domain::FSWrite {
+            path: "/home/user/main.rs".to_string(),
+            content: content.to_string(),
+            overwrite: false,
+        },
+        output: FsCreateOutput {
+            path: "/home/user/main.rs".to_string(),
+            before: None,
+            content_hash: compute_hash(content),
+            snapshot: None,
+            syntax_errors: vec![
+                SyntaxError { line: 2, column: 13, message: "unexpected `;`".to_string() },
+                SyntaxError { line: 3, column: 1, message: "missing `}`".to_string() },
+            ],
+        },
+    };
+
+    let env = fixture_environment();
+
+    let actual = fixture.into_tool_output(
+        ToolKind::Write,
+        TempContentFiles::default(),
+        &env,
+        &mut Metrics::default(),
+    );
+
+    insta::assert_snapshot!(to_value(actual));
+}
+
+#[test]
+fn test_fs_patch_with_syntax_errors() {
+    let after_content = "{\"a\": [1, 2}";
+    let fixture = ToolOperation::FsPatch {
+        input: paws_domain::FSPatch {
+            path: "/home/user/data.json".to_string(),
+            search: Some("]".to_string()),
+            operation: paws_domain::PatchOperation::Replace,
+            content: "".to_string(),
+        },
+        output: PatchOutput {
+            before: "{\"a\": [1, 2]}".to_string(),
+            after: after_content.to_string(),
+            content_hash: compute_hash(after_content),
+            snapshot: None,
+            syntax_errors: vec![SyntaxError {
+                line: 1,
+                column: 13,
+                message: "missing `]`".to_string(),
+            }],
         },
     };
 
@@ -852,6 +1137,60 @@ fn test_fs_patch_with_warning() {
     insta::assert_snapshot!(to_value(actual));
 }
 
+#[test]
+fn test_fs_multi_patch() {
+    let lib_after = "fn a() {}\nfn b() -> u8 {\n    1\n}\n";
+    let new_after = "pub struct New;\n";
+    let fixture = ToolOperation::FsMultiPatch {
+        input: paws_domain::FSMultiPatch::default(),
+        output: MultiPatchOutput {
+            files: vec![
+                FilePatchOutput {
+                    path: PathBuf::from("/home/user/src/lib.rs"),
+                    before: Some("fn a() {}\nfn b() {}\n".to_string()),
+                    after: lib_after.to_string(),
+                    content_hash: compute_hash(lib_after),
+                    snapshot: None,
+                    syntax_errors: vec![],
+                    hunks: vec![HunkOutcome {
+                        label: "@@ -1,2 +1,4 @@".to_string(),
+                        line: 1,
+                        matching: HunkMatch::Indentation,
+                        fuzz: 1,
+                    }],
+                },
+                FilePatchOutput {
+                    path: PathBuf::from("/home/user/src/new.rs"),
+                    before: None,
+                    after: new_after.to_string(),
+                    content_hash: compute_hash(new_after),
+                    snapshot: None,
+                    syntax_errors: vec![],
+                    hunks: vec![HunkOutcome {
+                        label: "@@ -0,0 +1 @@".to_string(),
+                        line: 1,
+                        matching: HunkMatch::Exact,
+                        fuzz: 0,
+                    }],
+                },
+            ],
+        },
+    };
+
+    let env = fixture_environment();
+    let mut metrics = Metrics::default();
+
+    let actual = fixture.into_tool_output(
+        ToolKind::MultiPatch,
+        TempContentFiles::default(),
+        &env,
+        &mut metrics,
+    );
+
+    insta::assert_snapshot!(to_value(actual));
+    assert_eq!(metrics.file_operations.len(), 2);
+}
+
 #[test]
 fn test_fs_undo_no_changes() {
     let fixture = ToolOperation::FsUndo {
@@ -1115,3 +1454,59 @@ fn test_skill_operation() {
 
     insta::assert_snapshot!(to_value(actual));
 }
+
+#[test]
+fn test_file_modifications_are_recorded_in_checkpoint() {
+    let snapshot = paws_domain::Snapshot::create(PathBuf::from("/home/user/lib.rs")).unwrap();
+    let create = ToolOperation::FsCreate {
+        input: paws_domain::FSWrite {
+            path: "/home/user/main.rs".to_string(),
+            content: "fn main() {}".to_string(),
+            overwrite: false,
+        },
+        output: FsCreateOutput {
+            path: "/home/user/main.rs".to_string(),
+            before: None,
+            content_hash: compute_hash("fn main() {}"),
+            snapshot: None,
+            syntax_errors: vec![],
+        },
+    };
+    let patch = ToolOperation::FsPatch {
+        input: paws_domain::FSPatch {
+            path: "/home/user/lib.rs".to_string(),
+            search: None,
+            operation: paws_domain::PatchOperation::Append,
+            content: "\n".to_string(),
+        },
+        output: PatchOutput {
+            before: "".to_string(),
+            after: "\n".to_string(),
+            content_hash: compute_hash("\n"),
+            snapshot: Some(snapshot.clone()),
+            syntax_errors: vec![],
+        },
+    };
+    let env = fixture_environment();
+    let mut metrics = Metrics::default().begin_turn(0, chrono::Utc::now());
+
+    create.into_tool_output(
+        ToolKind::Write,
+        TempContentFiles::default(),
+        &env,
+        &mut metrics,
+    );
+    patch.into_tool_output(
+        ToolKind::Patch,
+        TempContentFiles::default(),
+        &env,
+        &mut metrics,
+    );
+
+    let actual = metrics.checkpoints[0].files.clone();
+    let expected = vec![
+        paws_domain::FileCheckpoint::new("/home/user/main.rs", None),
+        paws_domain::FileCheckpoint::new("/home/user/lib.rs", Some(snapshot)),
+    ];
+    pretty_assertions::assert_eq!(actual, expected);
+}
@@ -4,7 +4,9 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use async_recursion::async_recursion;
+use chrono::{Local, NaiveTime, Utc};
 use derive_setters::Setters;
+use futures::StreamExt;
 use paws_common::template::Element;
 use paws_domain::{Agent, *};
 use tokio::task::JoinHandle;
@@ -13,7 +15,9 @@ use tracing::{debug, info, warn};
 use crate::TemplateEngine;
 use crate::agent::AgentService;
 use crate::compact::Compactor;
+use crate::error::Error;
 use crate::title_generator::TitleGenerator;
+use crate::tool_scheduler::ToolScheduler;
 
 #[derive(Clone, Setters)]
 #[setters(into)]
@@ -63,7 +67,6 @@ impl<S: AgentService> Orchestrator<S> {
         tool_context: &ToolCallContext,
     ) -> anyhow::Result<Vec<(ToolCallFull, ToolResult)>> {
         let agent = &self.agent;
-        // Always process tool calls sequentially
         let mut tool_call_records = Vec::with_capacity(tool_calls.len());
 
         let system_tools = self
@@ -72,38 +75,57 @@ impl<S: AgentService> Orchestrator<S> {
             .map(|tool| &tool.name)
             .collect::<HashSet<_>>();
 
-        for tool_call in tool_calls {
+        // Calls within a batch don't conflict with each other and are executed
+        // concurrently, batches themselves are processed sequentially.
+        let concurrency = self.environment.max_parallel_tool_calls.max(1);
+        let batches = ToolScheduler::new(self.environment.cwd.clone()).batches(tool_calls);
+
+        for batch in batches {
+            debug!(agent_id = %agent.id, batch_size = batch.len(), "Executing tool call batch");
+
             // Send the start notification for system tools and not agent as a tool
-            let is_system_tool = system_tools.contains(&tool_call.name);
-            if is_system_tool {
-                self.send(ChatResponse::ToolCallStart(tool_call.clone()))
-                    .await?;
+            for tool_call in batch.iter() {
+                if system_tools.contains(&tool_call.name) {
+                    self.send(ChatResponse::ToolCallStart((*tool_call).clone()))
+                        .await?;
+                }
             }
 
-            // Execute the tool
-            let tool_result = self
-                .services
-                .call(agent, tool_context, tool_call.clone())
-                .await;
+            // Results are yielded in the original order irrespective of the order in
+            // which the calls complete
+            let calls = batch
+                .into_iter()
+                .cloned()
+                .map(|tool_call| async move {
+                    let tool_result = self
+                        .services
+                        .call(agent, tool_context, tool_call.clone())
+                        .await;
+                    (tool_call, tool_result)
+                })
+                .collect::<Vec<_>>();
+            let mut results = futures::stream::iter(calls).buffered(concurrency);
 
-            if tool_result.is_error() {
-                warn!(
-                    agent_id = %agent.id,
-                    name = %tool_call.name,
-                    arguments = %tool_call.arguments.to_owned().into_string(),
-                    output = ?tool_result.output,
-                    "Tool call failed",
-                );
-            }
+            while let Some((tool_call, tool_result)) = results.next().await {
+                if tool_result.is_error() {
+                    warn!(
+                        agent_id = %agent.id,
+                        name = %tool_call.name,
+                        arguments = %tool_call.arguments.to_owned().into_string(),
+                        output = ?tool_result.output,
+                        "Tool call failed",
+                    );
+                }
 
-            // Send the end notification for system tools and not agent as a tool
-            if is_system_tool {
-                self.send(ChatResponse::ToolCallEnd(tool_result.clone()))
-                    .await?;
+                // Send the end notification for system tools and not agent as a tool
+                if system_tools.contains(&tool_call.name) {
+                    self.send(ChatResponse::ToolCallEnd(tool_result.clone()))
+                        .await?;
+                }
+                // Ensure all tool calls and results are recorded
+                // Adding task completion records is critical for compaction to work correctly
+                tool_call_records.push((tool_call, tool_result));
             }
-            // Ensure all tool calls and results are recorded
-            // Adding task completion records is critical for compaction to work correctly
-            tool_call_records.push((tool_call.clone(), tool_result));
         }
 
         Ok(tool_call_records)
@@ -169,16 +191,24 @@ impl<S: AgentService> Orchestrator<S> {
             .await
     }
     /// Checks if compaction is needed and performs it if necessary
-    fn check_and_compact(&self, context: &Context) -> anyhow::Result<Option<Context>> {
+    async fn check_and_compact(&self, context: &Context) -> anyhow::Result<Option<Context>> {
         let agent = &self.agent;
         // Estimate token count for compaction decision
         let token_count = context.token_count();
-        if agent.should_compact(context, *token_count)
+        if agent.should_compact(context, *token_count, self.context_length())
             && let Some(compact) = agent.compact.clone()
+            && self.is_compaction_allowed().await?
         {
             info!(agent_id = %agent.id, "Compaction needed");
             Compactor::new(compact, self.environment.clone())
-                .compact(context.clone(), false)
+                .compact_with(
+                    self.services.as_ref(),
+                    agent,
+                    &self.conversation.id,
+                    context.clone(),
+                    false,
+                )
+                .await
                 .map(Some)
         } else {
             debug!(agent_id = %agent.id, "Compaction not needed");
@@ -186,6 +216,107 @@ impl<S: AgentService> Orchestrator<S> {
         }
     }
 
+    /// Compacts the context when the next request, including the tokens
+    /// reserved for the response, would not fit in the context window of the
+    /// model. Providers reject such requests, so the context is compacted as
+    /// far as the retention window allows.
+    async fn compact_to_fit(&self, context: &Context) -> anyhow::Result<Option<Context>> {
+        let Some(context_length) = self.context_length() else {
+            return Ok(None);
+        };
+        let reserved = self
+            .agent
+            .max_tokens
+            .map_or(0, |max_tokens| *max_tokens as usize);
+        let token_count = *context.token_count();
+        if token_count + reserved <= context_length as usize
+            || !self.is_compaction_allowed().await?
+        {
+            return Ok(None);
+        }
+
+        let compact = self.agent.compact.clone().unwrap_or_default();
+        let compacted = Compactor::new(compact, self.environment.clone())
+            .compact_with(
+                self.services.as_ref(),
+                &self.agent,
+                &self.conversation.id,
+                context.clone(),
+                true,
+            )
+            .await?;
+        if compacted.messages.len() == context.messages.len() {
+            warn!(
+                agent_id = %self.agent.id,
+                token_count,
+                context_length,
+                "Request exceeds the context window and nothing is left to compact"
+            );
+            return Ok(None);
+        }
+
+        let compacted_tokens = *compacted.token_count();
+        info!(
+            agent_id = %self.agent.id,
+            token_count,
+            compacted_tokens,
+            context_length,
+            "Compacted context to fit the context window"
+        );
+        self.send(
+            TitleFormat::info("Compacted context")
+                .sub_title(format!(
+                    "{token_count} → {compacted_tokens} tokens to fit the {context_length} token window of {}",
+                    self.agent.model
+                ))
+                .into(),
+        )
+        .await?;
+        Ok(Some(compacted))
+    }
+
+    /// Returns whether the compaction hooks let the context be compacted
+    async fn is_compaction_allowed(&self) -> anyhow::Result<bool> {
+        match self
+            .run_hooks(self.hook_payload(HookEvent::Compaction))
+            .await?
+        {
+            HookOutcome::Block(reason) => {
+                info!(agent_id = %self.agent.id, reason, "Compaction blocked by hook");
+                Ok(false)
+            }
+            HookOutcome::Modify(_) | HookOutcome::Continue => Ok(true),
+        }
+    }
+
+    fn hook_payload(&self, event: HookEvent) -> HookPayload {
+        HookPayload::new(event, self.environment.cwd.clone())
+            .conversation_id(self.conversation.id)
+            .agent_id(self.agent.id.clone())
+    }
+
+    /// Runs the hooks the agent has configured for the event of the payload
+    async fn run_hooks(&self, payload: HookPayload) -> anyhow::Result<HookOutcome> {
+        let hooks = self
+            .agent
+            .hooks
+            .as_ref()
+            .map(|hooks| hooks.get(payload.event))
+            .unwrap_or_default();
+        if hooks.is_empty() {
+            return Ok(HookOutcome::Continue);
+        }
+        self.services.run_hooks(hooks, payload).await
+    }
+
+    /// Context window of the model in use, when reported by its provider
+    fn context_length(&self) -> Option<u64> {
+        self.models
+            .iter()
+            .find(|model| model.id == self.agent.model)
+            .and_then(|model| model.context_length)
+    }
+
     // Create a helper method with the core functionality
     pub async fn run(&mut self) -> anyhow::Result<()> {
         let event = self.event.clone();
@@ -203,12 +334,34 @@ impl<S: AgentService> Orchestrator<S> {
             "Initializing agent"
         );
 
-        let model_id = self.get_model();
+        let mut model_id = self.get_model();
 
         let mut context = self.conversation.context.clone().unwrap_or_default();
 
-        // Create agent reference for the rest of the method
-        let agent = &self.agent;
+        let agent_id = self.agent.id.clone();
+
+        // Hooks can veto the start of a conversation and the prompt before the
+        // conversation is updated
+        let prompt = event
+            .value
+            .as_ref()
+            .and_then(|value| value.as_user_prompt())
+            .map(|prompt| prompt.to_string());
+        let is_new_session = !context
+            .messages
+            .iter()
+            .any(|message| message.has_role(Role::Assistant));
+        let events = [
+            is_new_session.then_some(HookEvent::SessionStart),
+            Some(HookEvent::UserPromptSubmit),
+        ];
+        for event in events.into_iter().flatten() {
+            let mut payload = self.hook_payload(event);
+            payload.prompt = prompt.clone();
+            if let HookOutcome::Block(reason) = self.run_hooks(payload).await? {
+                return Err(Error::HookBlocked { event, reason }.into());
+            }
+        }
 
         // Signals that the loop should suspend (task may or may not be completed)
         let mut should_yield = false;
@@ -219,48 +372,56 @@ impl<S: AgentService> Orchestrator<S> {
         let mut request_count = 0;
 
         // Retrieve the number of requests allowed per tick.
-        let max_requests_per_turn = agent.max_requests_per_turn;
+        let max_requests_per_turn = self.agent.max_requests_per_turn;
 
-        let tool_context =
-            ToolCallContext::new(self.conversation.metrics.clone()).sender(self.sender.clone());
+        let tool_context = ToolCallContext::new(self.conversation.metrics.clone())
+            .sender(self.sender.clone())
+            .conversation_id(Some(self.conversation.id));
 
         // Asynchronously generate a title for the provided task
         // TODO: Move into app.rs
         let title = self.generate_title(model_id.clone());
 
+        // Usage of the requests made during this turn
+        let mut turn_usage = Usage::default();
+
         while !should_yield {
+            // Stop before making a request that would go over the budget
+            if let Some(exceeded) = self.check_budget(&turn_usage).await? {
+                warn!(
+                    agent_id = %agent_id,
+                    model_id = %model_id,
+                    budget = %exceeded,
+                    "Agent has reached its budget"
+                );
+                self.send(ChatResponse::Interrupt {
+                    reason: InterruptionReason::BudgetLimitReached { budget: exceeded },
+                })
+                .await?;
+                break;
+            }
+
+            if let Some(compacted) = self.compact_to_fit(&context).await? {
+                context = compacted;
+            }
+
             // Set context for the current loop iteration
             self.conversation.context = Some(context.clone());
             self.services.update(self.conversation.clone()).await?;
 
-            let message = crate::retry::retry_with_config(
-                &self.environment.retry_config,
-                || self.execute_chat_turn(&model_id, context.clone(), context.is_reasoning_supported()),
-                self.sender.as_ref().map(|sender| {
-                    let sender = sender.clone();
-                    let agent_id = agent.id.clone();
-                    let model_id = model_id.clone();
-                    move |error: &anyhow::Error, duration: Duration| {
-                        let root_cause = error.root_cause();
-                        tracing::error!(agent_id = %agent_id, error = ?root_cause, model=%model_id, "Retry Attempt");
-                        let retry_event = ChatResponse::RetryAttempt {
-                            cause: error.into(),
-                            duration,
-                        };
-                        let _ = sender.try_send(Ok(retry_event));
-                    }
-                }),
-            ).await?;
+            let message = self.chat_with_fallbacks(&context).await?;
+            // A fallback model may have taken over the conversation
+            model_id = self.get_model();
 
             // FIXME: Add a unit test in orch spec, to guarantee that compaction is
             // triggered after receiving the response Trigger compaction after
             // making a request NOTE: Ideally compaction should be implemented
             // as a transformer
-            if let Some(c_context) = self.check_and_compact(&context)? {
-                info!(agent_id = %agent.id, "Using compacted context from execution");
+            if let Some(c_context) = self.check_and_compact(&context).await? {
+                info!(agent_id = %agent_id, "Using compacted context from execution");
                 context = c_context;
             } else {
-                debug!(agent_id = %agent.id, "No compaction was needed");
+                debug!(agent_id = %agent_id, "No compaction was needed");
             }
 
             info!(
@@ -274,7 +435,21 @@ impl<S: AgentService> Orchestrator<S> {
                 "Processing usage information"
             );
 
-            debug!(agent_id = %agent.id, tool_call_count = message.tool_calls.len(), "Tool call count");
+            turn_usage = turn_usage.accumulate(&message.usage);
+            if let Err(error) = self
+                .services
+                .record_usage(
+                    &self.conversation.id,
+                    &message.usage,
+                    &model_id,
+                    &self.agent.provider,
+                )
+                .await
+            {
+                warn!(conversation_id = %self.conversation.id, error = ?error, "Failed to record usage");
+            }
+
+            debug!(agent_id = %agent_id, tool_call_count = message.tool_calls.len(), "Tool call count");
 
             // Turn is completed, if finish_reason is 'stop'. Gemini models return stop as
             // finish reason with tool calls.
@@ -329,8 +504,24 @@ impl<S: AgentService> Orchestrator<S> {
                 message.reasoning_details,
                 message.usage,
                 tool_call_records,
+                model_id.clone(),
             );
 
+            // Turn completion hooks can send the agent back to work
+            if is_complete {
+                let payload = self
+                    .hook_payload(HookEvent::TurnComplete)
+                    .response(message.content.clone());
+                if let HookOutcome::Block(reason) = self.run_hooks(payload).await? {
+                    info!(agent_id = %agent_id, reason, "Turn completion blocked by hook");
+                    let feedback = Error::HookBlocked { event: HookEvent::TurnComplete, reason };
+                    context =
+                        context.add_message(ContextMessage::user(feedback, Some(model_id.clone())));
+                    is_complete = false;
+                    should_yield = false;
+                }
+            }
+
             if self.error_tracker.limit_reached() {
                 self.send(ChatResponse::Interrupt {
                     reason: InterruptionReason::MaxToolFailurePerTurnLimitReached {
@@ -353,7 +544,7 @@ impl<S: AgentService> Orchestrator<S> {
                 // Check if agent has reached the maximum request per turn limit
                 if request_count >= max_request_allowed {
                     warn!(
-                        agent_id = %agent.id,
+                        agent_id = %agent_id,
                         model_id = %model_id,
                         request_count,
                         max_request_allowed,
@@ -393,6 +584,118 @@ impl<S: AgentService> Orchestrator<S> {
         Ok(())
     }
 
+    /// Returns the budget limit reached by the usage of the turn, the
+    /// conversation or the workspace over the current day, if any
+    async fn check_budget(&self, turn_usage: &Usage) -> anyhow::Result<Option<BudgetExceeded>> {
+        let Some(budget) = self.agent.budget.as_ref() else {
+            return Ok(None);
+        };
+        // The ledger keeps the usage of messages dropped by compaction or rewinds
+        let conversation_usage = if budget.has_conversation_limit() {
+            self.services
+                .conversation_usage(&self.conversation.id)
+                .await?
+        } else {
+            Usage::default()
+        };
+        let day_usage = if budget.has_daily_limit() {
+            let midnight = Local::now()
+                .date_naive()
+                .and_time(NaiveTime::MIN)
+                .and_local_timezone(Local)
+                .earliest()
+                .map_or_else(Utc::now, |midnight| midnight.with_timezone(&Utc));
+            self.services.usage_since(midnight).await?
+        } else {
+            Usage::default()
+        };
+        Ok(budget.check(turn_usage, &conversation_usage, &day_usage))
+    }
+
+    /// Sends the request, switching to the next fallback model of the agent
+    /// whenever the one in use fails in a way another model could recover from
+    async fn chat_with_fallbacks(
+        &mut self,
+        context: &Context,
+    ) -> anyhow::Result<ChatCompletionMessageFull> {
+        loop {
+            let model_id = self.get_model();
+            let has_fallback = self
+                .agent
+                .fallbacks
+                .as_ref()
+                .is_some_and(|fallbacks| !fallbacks.is_empty());
+            let result = crate::retry::retry_with_config(
+                &self.environment.retry_config,
+                || async {
+                    let result = self
+                        .execute_chat_turn(&model_id, context.clone(), context.is_reasoning_supported())
+                        .await;
+                    // Quota and unknown model errors won't go away by retrying
+                    if has_fallback {
+                        result.map_err(FallbackReason::skip_retries)
+                    } else {
+                        result
+                    }
+                },
+                self.sender.as_ref().map(|sender| {
+                    let sender = sender.clone();
+                    let agent_id = self.agent.id.clone();
+                    let model_id = model_id.clone();
+                    move |error: &anyhow::Error, duration: Duration| {
+                        let root_cause = error.root_cause();
+                        tracing::error!(agent_id = %agent_id, error = ?root_cause, model=%model_id, "Retry Attempt");
+                        let retry_event = ChatResponse::RetryAttempt {
+                            cause: error.into(),
+                            duration,
+                            rate_limit: RateLimit::find(error).cloned(),
+                        };
+                        let _ = sender.try_send(Ok(retry_event));
+                    }
+                }),
+            )
+            .await;
+
+            let error = match result {
+                Ok(message) => return Ok(message),
+                Err(error) => error,
+            };
+            let Some(reason) = FallbackReason::classify(&error) else {
+                return Err(error);
+            };
+            let Some(fallback) = self
+                .agent
+                .fallbacks
+                .as_mut()
+                .filter(|fallbacks| !fallbacks.is_empty())
+                .map(|fallbacks| fallbacks.remove(0))
+            else {
+                return Err(error);
+            };
+
+            warn!(
+                agent_id = %self.agent.id,
+                model_id = %model_id,
+                fallback_provider = %fallback.provider,
+                fallback_model = %fallback.model,
+                reason = %reason,
+                error = ?error,
+                "Switching to fallback model"
+            );
+            self.send(
+                TitleFormat::warning(format!(
+                    "Switched to {} on {}",
+                    fallback.model, fallback.provider
+                ))
+                .sub_title(format!("{model_id} failed: {reason}"))
+                .into(),
+            )
+            .await?;
+            self.agent.provider = fallback.provider;
+            self.agent.model = fallback.model;
+        }
+    }
+
     fn get_model(&self) -> ModelId {
         self.agent.model.clone()
     }
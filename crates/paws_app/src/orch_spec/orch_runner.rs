@@ -1,10 +1,11 @@
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 
 use handlebars::{Handlebars, no_escape};
 use paws_domain::{
-    Attachment, ChatCompletionMessage, ChatResponse, Conversation, ConversationId, Event,
-    ProviderId, ToolCallFull, ToolErrorTracker, ToolResult,
+    Attachment, ChatCompletionMessage, ChatResponse, Conversation, ConversationId, Event, Hook,
+    HookEvent, HookOutcome, HookPayload, ModelId, ProviderId, ToolCallFull, ToolErrorTracker,
+    ToolResult, Usage,
 };
 use rust_embed::Embed;
 use tokio::sync::Mutex;
@@ -34,6 +35,16 @@ pub struct Runner {
     test_completions: Mutex<VecDeque<ChatCompletionMessage>>,
 
     attachments: Vec<Attachment>,
+
+    // Usage ledger of the workspace, usage of earlier days has no conversation
+    usage_records: Mutex<Vec<(Option<ConversationId>, Usage)>>,
+
+    // Models whose requests fail with the given error
+    failing_models: HashMap<ModelId, String>,
+
+    // Mock outcomes of hooks and the events hooks were run for
+    hook_outcomes: Mutex<VecDeque<(HookEvent, HookOutcome)>>,
+    hook_events: Mutex<Vec<HookEvent>>,
 }
 
 impl Runner {
@@ -51,6 +62,10 @@ impl Runner {
         Self {
             hb,
             attachments: setup.attachments.clone(),
+            usage_records: Mutex::new(vec![(None, setup.usage_today)]),
+            failing_models: setup.failing_models.clone(),
+            hook_outcomes: Mutex::new(VecDeque::from(setup.mock_hook_outcomes.clone())),
+            hook_events: Mutex::new(Vec::new()),
             conversation_history: Mutex::new(Vec::new()),
             test_tool_calls: Mutex::new(VecDeque::from(setup.mock_tool_call_responses.clone())),
             test_completions: Mutex::new(VecDeque::from(setup.mock_assistant_responses.clone())),
@@ -116,6 +131,7 @@ impl Runner {
         )
         .error_tracker(ToolErrorTracker::new(3))
         .tool_definitions(system_tools)
+        .models(setup.models.clone())
         .sender(tx);
 
         let (mut orch, runner) = (orch, services);
@@ -130,6 +146,19 @@ impl Runner {
             .output
             .conversation_history
             .extend(runner.get_history().await);
+        setup
+            .output
+            .hook_events
+            .extend(runner.hook_events.lock().await.clone());
+        setup.output.usage_records.extend(
+            runner
+                .usage_records
+                .lock()
+                .await
+                .iter()
+                .filter(|(id, _)| id.is_some())
+                .map(|(_, usage)| *usage),
+        );
 
         result
     }
@@ -139,10 +168,14 @@ impl Runner {
 impl AgentService for Runner {
     async fn chat_agent(
         &self,
-        _id: &paws_domain::ModelId,
+        id: &ModelId,
         context: paws_domain::Context,
         _provider_id: Option<ProviderId>,
     ) -> paws_domain::ResultStream<ChatCompletionMessage, anyhow::Error> {
+        if let Some(error) = self.failing_models.get(id) {
+            anyhow::bail!("{error}");
+        }
+
         let mut responses = self.test_completions.lock().await;
 
         if let Some(message) = responses.pop_front() {
@@ -179,6 +212,48 @@ impl AgentService for Runner {
         self.conversation_history.lock().await.push(conversation);
         Ok(())
     }
+
+    async fn record_usage(
+        &self,
+        conversation_id: &ConversationId,
+        usage: &Usage,
+        _: &ModelId,
+        _: &ProviderId,
+    ) -> anyhow::Result<()> {
+        self.usage_records
+            .lock()
+            .await
+            .push((Some(*conversation_id), *usage));
+        Ok(())
+    }
+
+    async fn usage_since(&self, _: chrono::DateTime<chrono::Utc>) -> anyhow::Result<Usage> {
+        let records = self.usage_records.lock().await;
+        Ok(records.iter().fold(Usage::default(), |total, (_, usage)| {
+            total.accumulate(usage)
+        }))
+    }
+
+    async fn conversation_usage(&self, conversation_id: &ConversationId) -> anyhow::Result<Usage> {
+        let records = self.usage_records.lock().await;
+        Ok(records
+            .iter()
+            .filter(|(id, _)| id.as_ref() == Some(conversation_id))
+            .fold(Usage::default(), |total, (_, usage)| {
+                total.accumulate(usage)
+            }))
+    }
+
+    async fn run_hooks(&self, _: &[Hook], payload: HookPayload) -> anyhow::Result<HookOutcome> {
+        self.hook_events.lock().await.push(payload.event);
+        let mut outcomes = self.hook_outcomes.lock().await;
+        let outcome = outcomes
+            .iter()
+            .position(|(event, _)| *event == payload.event)
+            .and_then(|index| outcomes.remove(index))
+            .map_or(HookOutcome::Continue, |(_, outcome)| outcome);
+        Ok(outcome)
+    }
 }
 
 #[async_trait::async_trait]
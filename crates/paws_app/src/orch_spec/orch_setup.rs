@@ -5,8 +5,8 @@ use chrono::{DateTime, Local};
 use derive_setters::Setters;
 use paws_domain::{
     Agent, AgentId, Attachment, ChatCompletionMessage, ChatResponse, Conversation, Environment,
-    Event, File, HttpConfig, MessageEntry, ModelId, ProviderId, RetryConfig, Role, Template,
-    ToolCallFull, ToolDefinition, ToolResult, Workflow,
+    Event, File, HookEvent, HookOutcome, HttpConfig, MessageEntry, Model, ModelId, ProviderId,
+    RetryConfig, Role, Template, ToolCallFull, ToolDefinition, ToolResult, Usage, Workflow,
 };
 use url::Url;
 
@@ -30,7 +30,16 @@ pub struct TestContext {
     pub current_time: DateTime<Local>,
     pub title: Option<String>,
     pub model: ModelId,
+    // Models reported by the provider of the agent
+    pub models: Vec<Model>,
     pub attachments: Vec<Attachment>,
+    // Usage already recorded in the workspace today
+    pub usage_today: Usage,
+    // Models whose requests fail with the given error
+    pub failing_models: HashMap<ModelId, String>,
+    // Outcomes of the hooks of an event, each used once in order. Hooks
+    // without an outcome continue.
+    pub mock_hook_outcomes: Vec<(HookEvent, HookOutcome)>,
 
     // Final output of the test is store in the context
     pub output: TestOutput,
@@ -42,6 +51,7 @@ impl Default for TestContext {
     fn default() -> Self {
         Self {
             model: ModelId::new("openai/gpt-1"),
+            models: Default::default(),
             output: TestOutput::default(),
             current_time: Local::now(),
             mock_assistant_responses: Default::default(),
@@ -50,6 +60,9 @@ impl Default for TestContext {
             templates: Default::default(),
             files: Default::default(),
             attachments: Default::default(),
+            usage_today: Default::default(),
+            failing_models: Default::default(),
+            mock_hook_outcomes: Default::default(),
             env: Environment {
                 os: "MacOS".to_string(),
                 pid: 1234,
@@ -70,6 +83,7 @@ impl Default for TestContext {
                     suppress_retry_errors: Default::default(),
                 },
                 tool_timeout: 300,
+                max_parallel_tool_calls: 8,
                 max_search_lines: 1000,
                 fetch_truncation_limit: 1024,
                 stdout_max_prefix_length: 256,
@@ -90,6 +104,10 @@ impl Default for TestContext {
                 override_model: None,
                 override_provider: None,
                 enable_permissions: false,
+                reject_syntax_errors: false,
+                embedding_provider: None,
+                embedding_model: None,
+                sandbox: None,
             },
             title: Some("test-conversation".into()),
             agent: Agent::new(
@@ -123,6 +141,10 @@ impl TestContext {
 pub struct TestOutput {
     pub conversation_history: Vec<Conversation>,
     pub chat_responses: Vec<anyhow::Result<ChatResponse>>,
+    // Events hooks were run for, in order
+    pub hook_events: Vec<HookEvent>,
+    // Usage recorded in the ledger for the conversation, in order
+    pub usage_records: Vec<Usage>,
 }
 
 impl TestOutput {
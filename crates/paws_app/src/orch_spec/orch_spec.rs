@@ -1,6 +1,11 @@
+use std::collections::HashMap;
+
 use paws_domain::{
-    ChatCompletionMessage, ChatResponse, Content, EventValue, FinishReason, ReasoningConfig, Role,
-    ToolCallArguments, ToolCallFull, ToolOutput, ToolResult,
+    Budget, BudgetExceeded, BudgetScope, ChatCompletionMessage, ChatResponse, ChatResponseContent,
+    Compact, Content, ContextMessage, EventValue, FinishReason, Hook, HookEvent, HookOutcome,
+    Hooks, InterruptionReason, Model, ModelFallback, ModelId, ProviderId, ReasoningConfig, Role,
+    SummaryStrategy, TokenCount, ToolCallArguments, ToolCallFull, ToolCallId, ToolDefinition,
+    ToolOutput, ToolResult, Usage,
 };
 use pretty_assertions::assert_eq;
 use serde_json::json;
@@ -500,3 +505,400 @@ async fn test_not_complete_when_stop_with_tool_calls() {
         "Should have 2 assistant messages, confirming is_complete was false with tool calls"
     );
 }
+
+#[tokio::test]
+async fn test_parallel_tool_calls_preserve_order() {
+    let read_a = ToolCallFull::new("read")
+        .call_id(ToolCallId::new("call_a"))
+        .arguments(ToolCallArguments::from(json!({"path": "/a.txt"})));
+    let read_b = ToolCallFull::new("read")
+        .call_id(ToolCallId::new("call_b"))
+        .arguments(ToolCallArguments::from(json!({"path": "/b.txt"})));
+    let result_a = ToolResult::new("read")
+        .call_id(ToolCallId::new("call_a"))
+        .output(Ok(ToolOutput::text("a")));
+    let result_b = ToolResult::new("read")
+        .call_id(ToolCallId::new("call_b"))
+        .output(Ok(ToolOutput::text("b")));
+
+    let mut ctx = TestContext::default()
+        .tools(vec![ToolDefinition::new("read")])
+        .mock_tool_call_responses(vec![
+            (read_b.clone(), result_b.clone()),
+            (read_a.clone(), result_a.clone()),
+        ])
+        .mock_assistant_responses(vec![
+            ChatCompletionMessage::assistant("Reading files")
+                .add_tool_call(read_a.clone())
+                .add_tool_call(read_b.clone()),
+            ChatCompletionMessage::assistant("Done").finish_reason(FinishReason::Stop),
+        ]);
+
+    ctx.run("Read two files").await.unwrap();
+
+    let actual = ctx
+        .output
+        .chat_responses
+        .iter()
+        .filter_map(|response| response.as_ref().ok().cloned())
+        .filter_map(|response| match response {
+            ChatResponse::ToolCallStart(call) => Some(format!("start:{}", call.call_id?.as_str())),
+            ChatResponse::ToolCallEnd(result) => Some(format!("end:{}", result.call_id?.as_str())),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let expected = vec!["start:call_a", "start:call_b", "end:call_a", "end:call_b"];
+    assert_eq!(actual, expected);
+
+    let actual = ctx
+        .output
+        .context_messages()
+        .into_iter()
+        .filter_map(|entry| match entry.message {
+            ContextMessage::Tool(result) => Some(result),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let expected = vec![result_a, result_b];
+    assert_eq!(actual, expected);
+}
+
+fn usage(tokens: usize) -> Usage {
+    Usage {
+        total_tokens: TokenCount::Actual(tokens),
+        ..Default::default()
+    }
+}
+
+fn budget_interrupts(responses: Vec<anyhow::Result<ChatResponse>>) -> Vec<BudgetExceeded> {
+    responses
+        .into_iter()
+        .filter_map(|response| response.ok())
+        .filter_map(|response| match response {
+            ChatResponse::Interrupt {
+                reason: InterruptionReason::BudgetLimitReached { budget },
+            } => Some(budget),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_turn_budget_interrupts_before_next_request() {
+    let tool_call =
+        ToolCallFull::new("fs_read").arguments(ToolCallArguments::from(json!({"path": "abc.txt"})));
+    let tool_result = ToolResult::new("fs_read").output(Ok(ToolOutput::text("Greetings")));
+
+    let mut ctx = TestContext::default()
+        .mock_tool_call_responses(vec![(tool_call.clone(), tool_result)])
+        .mock_assistant_responses(vec![
+            ChatCompletionMessage::assistant("Reading")
+                .add_tool_call(tool_call)
+                .usage(usage(150)),
+        ]);
+    ctx.agent.budget = Some(Budget::default().max_tokens_per_turn(100usize));
+
+    ctx.run("Read a file").await.unwrap();
+
+    let actual = budget_interrupts(ctx.output.chat_responses);
+    let expected =
+        vec![BudgetExceeded::Tokens { scope: BudgetScope::Turn, limit: 100, spent: 150 }];
+    assert_eq!(actual, expected);
+}
+
+#[tokio::test]
+async fn test_conversation_budget_counts_recorded_usage() {
+    let tool_call =
+        ToolCallFull::new("fs_read").arguments(ToolCallArguments::from(json!({"path": "abc.txt"})));
+    let tool_result = ToolResult::new("fs_read").output(Ok(ToolOutput::text("Greetings")));
+
+    let mut ctx = TestContext::default()
+        .mock_tool_call_responses(vec![(tool_call.clone(), tool_result)])
+        .mock_assistant_responses(vec![
+            ChatCompletionMessage::assistant("Reading")
+                .add_tool_call(tool_call)
+                .usage(usage(150)),
+        ]);
+    ctx.agent.budget = Some(Budget::default().max_tokens_per_conversation(100usize));
+
+    ctx.run("Read a file").await.unwrap();
+
+    let actual = budget_interrupts(ctx.output.chat_responses);
+    let expected =
+        vec![BudgetExceeded::Tokens { scope: BudgetScope::Conversation, limit: 100, spent: 150 }];
+    assert_eq!(actual, expected);
+}
+
+#[tokio::test]
+async fn test_daily_budget_interrupts_before_any_request() {
+    let mut ctx = TestContext::default().usage_today(usage(5000));
+    ctx.agent.budget = Some(Budget::default().max_tokens_per_day(1000usize));
+
+    ctx.run("Hi").await.unwrap();
+
+    let actual = budget_interrupts(ctx.output.chat_responses);
+    let expected =
+        vec![BudgetExceeded::Tokens { scope: BudgetScope::Day, limit: 1000, spent: 5000 }];
+    assert_eq!(actual, expected);
+}
+
+fn model(context_length: u64) -> Model {
+    Model {
+        id: ModelId::new("openai/gpt-1"),
+        name: None,
+        description: None,
+        context_length: Some(context_length),
+        tools_supported: Some(true),
+        supports_parallel_tool_calls: None,
+        supports_reasoning: None,
+    }
+}
+
+#[tokio::test]
+async fn test_compacts_before_request_exceeding_context_window() {
+    let tool_call =
+        ToolCallFull::new("fs_read").arguments(ToolCallArguments::from(json!({"path": "abc.txt"})));
+    let tool_result =
+        ToolResult::new("fs_read").output(Ok(ToolOutput::text("lorem ipsum ".repeat(200))));
+
+    let mut ctx = TestContext::default()
+        .models(vec![model(500)])
+        .mock_tool_call_responses(vec![(tool_call.clone(), tool_result)])
+        .mock_assistant_responses(vec![
+            ChatCompletionMessage::assistant("Reading").add_tool_call(tool_call),
+            ChatCompletionMessage::assistant("Done").finish_reason(FinishReason::Stop),
+        ]);
+
+    ctx.run("Read a file").await.unwrap();
+
+    let messages = ctx.output.context_messages();
+    let summary = messages
+        .iter()
+        .filter_map(|message| message.content())
+        .any(|content| content.contains("## Summary"));
+    assert!(summary, "Context should have been compacted into a summary");
+    assert!(
+        !messages.iter().any(|message| message.has_tool_result()),
+        "The large tool result should have been evicted"
+    );
+}
+
+#[tokio::test]
+async fn test_no_compaction_within_context_window() {
+    let tool_call =
+        ToolCallFull::new("fs_read").arguments(ToolCallArguments::from(json!({"path": "abc.txt"})));
+    let tool_result =
+        ToolResult::new("fs_read").output(Ok(ToolOutput::text("lorem ipsum ".repeat(200))));
+
+    let mut ctx = TestContext::default()
+        .models(vec![model(100_000)])
+        .mock_tool_call_responses(vec![(tool_call.clone(), tool_result)])
+        .mock_assistant_responses(vec![
+            ChatCompletionMessage::assistant("Reading").add_tool_call(tool_call),
+            ChatCompletionMessage::assistant("Done").finish_reason(FinishReason::Stop),
+        ]);
+
+    ctx.run("Read a file").await.unwrap();
+
+    let messages = ctx.output.context_messages();
+    assert!(messages.iter().any(|message| message.has_tool_result()));
+}
+
+/// Returns the summary the context was compacted into by
+/// [`compact_with_summary`]
+async fn compacted_summary(summary: ChatCompletionMessage) -> String {
+    compact_with_summary(summary)
+        .await
+        .output
+        .context_messages()
+        .iter()
+        .filter_map(|message| message.content().map(str::to_string))
+        .find(|content| content.contains("## Summary"))
+        .expect("Context should have been compacted into a summary")
+}
+
+/// Runs a turn reading a file with compaction after every request, the
+/// summarization model answering with `summary`
+async fn compact_with_summary(summary: ChatCompletionMessage) -> TestContext {
+    let tool_call =
+        ToolCallFull::new("fs_read").arguments(ToolCallArguments::from(json!({"path": "abc.txt"})));
+    let tool_result = ToolResult::new("fs_read").output(Ok(ToolOutput::text("Greetings")));
+
+    let mut ctx = TestContext::default()
+        .mock_tool_call_responses(vec![(tool_call.clone(), tool_result)])
+        .mock_assistant_responses(vec![
+            ChatCompletionMessage::assistant("Reading").add_tool_call(tool_call),
+            ChatCompletionMessage::assistant("Done").finish_reason(FinishReason::Stop),
+            summary,
+        ]);
+    ctx.agent.compact = Some(
+        Compact::new()
+            .token_threshold(1_usize)
+            .summary_strategy(SummaryStrategy::Llm),
+    );
+
+    ctx.run("Read a file").await.unwrap();
+    ctx
+}
+
+#[tokio::test]
+async fn test_llm_summary_is_merged_into_compaction() {
+    let actual = compacted_summary(ChatCompletionMessage::assistant(
+        "<paws_context_summary>The user asked to read abc.txt</paws_context_summary>",
+    ))
+    .await;
+
+    assert!(actual.contains("## Narrative"));
+    assert!(actual.contains("The user asked to read abc.txt"));
+    assert!(actual.contains("Reading"));
+}
+
+#[tokio::test]
+async fn test_llm_summary_falls_back_to_structured_summary() {
+    let actual = compacted_summary(ChatCompletionMessage::assistant("")).await;
+
+    assert!(!actual.contains("## Narrative"));
+    assert!(actual.contains("Reading"));
+}
+
+#[tokio::test]
+async fn test_switches_to_fallback_model() {
+    let mut ctx = TestContext::default()
+        .failing_models(HashMap::from([(
+            ModelId::new("openai/gpt-1"),
+            "model_not_found: The model `openai/gpt-1` does not exist".to_string(),
+        )]))
+        .mock_assistant_responses(vec![
+            ChatCompletionMessage::assistant("Hello!").finish_reason(FinishReason::Stop),
+        ]);
+    ctx.agent.fallbacks = Some(vec![ModelFallback::new(
+        ProviderId::OPENAI,
+        ModelId::new("gpt-4o"),
+    )]);
+
+    ctx.run("Hi").await.unwrap();
+
+    let switched = ctx
+        .output
+        .chat_responses
+        .iter()
+        .filter_map(|response| match response {
+            Ok(ChatResponse::TaskMessage { content: ChatResponseContent::Title(title) }) => {
+                Some((title.title.clone(), title.sub_title.clone()))
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let served_by = ctx
+        .output
+        .context_messages()
+        .into_iter()
+        .filter_map(|entry| match entry.message {
+            ContextMessage::Text(text) if text.role == Role::Assistant => text.model,
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        switched,
+        vec![(
+            "Switched to gpt-4o on OpenAI".to_string(),
+            Some("openai/gpt-1 failed: model not found".to_string())
+        )]
+    );
+    assert_eq!(served_by, vec![ModelId::new("gpt-4o")]);
+}
+
+#[tokio::test]
+async fn test_fallback_is_not_used_for_unrecoverable_errors() {
+    let mut ctx = TestContext::default().failing_models(HashMap::from([(
+        ModelId::new("openai/gpt-1"),
+        "Invalid API key".to_string(),
+    )]));
+    ctx.agent.fallbacks = Some(vec![ModelFallback::new(
+        ProviderId::OPENAI,
+        ModelId::new("gpt-4o"),
+    )]);
+
+    let actual = ctx.run("Hi").await.unwrap_err().to_string();
+
+    assert_eq!(actual, "Invalid API key");
+}
+
+#[tokio::test]
+async fn test_blocked_prompt_is_not_sent() {
+    let mut ctx = TestContext::default().mock_hook_outcomes(vec![(
+        HookEvent::UserPromptSubmit,
+        HookOutcome::Block("Secrets are not allowed".to_string()),
+    )]);
+    ctx.workflow.hooks = Some(Hooks {
+        session_start: vec![Hook::new("./setup.sh")],
+        user_prompt_submit: vec![Hook::new("./scan.sh")],
+        ..Default::default()
+    });
+
+    let actual = ctx.run("My key is sk-123").await.unwrap_err().to_string();
+
+    assert_eq!(
+        actual,
+        "Blocked by user_prompt_submit hook: Secrets are not allowed"
+    );
+    assert_eq!(
+        ctx.output.hook_events,
+        vec![HookEvent::SessionStart, HookEvent::UserPromptSubmit]
+    );
+    assert!(ctx.output.conversation_history.is_empty());
+}
+
+#[tokio::test]
+async fn test_blocked_turn_completion_continues_turn() {
+    let mut ctx = TestContext::default()
+        .mock_hook_outcomes(vec![(
+            HookEvent::TurnComplete,
+            HookOutcome::Block("Tests are failing".to_string()),
+        )])
+        .mock_assistant_responses(vec![
+            ChatCompletionMessage::assistant("Done").finish_reason(FinishReason::Stop),
+            ChatCompletionMessage::assistant("Fixed the tests").finish_reason(FinishReason::Stop),
+        ]);
+    ctx.workflow.hooks = Some(Hooks {
+        turn_complete: vec![Hook::new("cargo test")],
+        ..Default::default()
+    });
+
+    ctx.run("Fix the bug").await.unwrap();
+
+    assert_eq!(
+        ctx.output.hook_events,
+        vec![HookEvent::TurnComplete, HookEvent::TurnComplete]
+    );
+    let actual = ctx
+        .output
+        .context_messages()
+        .iter()
+        .rev()
+        .find(|entry| entry.message.has_role(Role::User))
+        .map(|entry| entry.message.content().unwrap_or_default().to_string());
+    let expected = Some("Blocked by turn_complete hook: Tests are failing".to_string());
+    assert_eq!(actual, expected);
+    assert!(
+        ctx.output
+            .chat_responses
+            .iter()
+            .any(|response| matches!(response, Ok(ChatResponse::TaskComplete)))
+    );
+}
+
+#[tokio::test]
+async fn test_llm_summary_usage_is_recorded() {
+    let ctx = compact_with_summary(
+        ChatCompletionMessage::assistant(
+            "<paws_context_summary>The user asked to read abc.txt</paws_context_summary>",
+        )
+        .usage(usage(70)),
+    )
+    .await;
+
+    let actual = ctx.output.usage_records.contains(&usage(70));
+    assert!(actual);
+}
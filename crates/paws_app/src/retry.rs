@@ -1,7 +1,7 @@
 use std::time::Duration;
 
 use backon::{ExponentialBuilder, Retryable};
-use paws_domain::{Error, RetryConfig};
+use paws_domain::{Error, RateLimit, RetryConfig};
 
 pub async fn retry_with_config<F, Fut, T, C>(
     config: &RetryConfig,
@@ -19,7 +19,11 @@ where
         .with_max_times(config.max_retry_attempts)
         .with_jitter();
 
-    let retryable = operation.retry(&strategy).when(should_retry);
+    let max_delay = config.max_delay.map(Duration::from_secs);
+    let retryable = operation
+        .retry(&strategy)
+        .when(should_retry)
+        .adjust(move |error, delay| delay.map(|delay| adjust_delay(error, delay, max_delay)));
 
     match notify {
         Some(callback) => retryable.notify(callback).await,
@@ -36,3 +40,48 @@ fn should_retry(error: &anyhow::Error) -> bool {
         .downcast_ref::<Error>()
         .is_some_and(|error| matches!(error, Error::Retryable(_)))
 }
+
+/// Waits at least as long as the provider asked through its rate limit
+/// headers, capped by the configured maximum delay
+fn adjust_delay(error: &anyhow::Error, delay: Duration, max_delay: Option<Duration>) -> Duration {
+    let Some(requested) = RateLimit::find(error).and_then(RateLimit::delay) else {
+        return delay;
+    };
+    let requested = max_delay.map_or(requested, |max_delay| requested.min(max_delay));
+    delay.max(requested)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn rate_limited(retry_after: u64) -> anyhow::Error {
+        let rate_limit = RateLimit {
+            retry_after: Some(Duration::from_secs(retry_after)),
+            ..Default::default()
+        };
+        Error::Retryable(anyhow::anyhow!("429 Too Many Requests").context(rate_limit)).into()
+    }
+
+    #[test]
+    fn test_adjust_delay() {
+        let backoff = Duration::from_secs(2);
+
+        let actual = [
+            adjust_delay(&rate_limited(10), backoff, None),
+            adjust_delay(&rate_limited(1), backoff, None),
+            adjust_delay(&rate_limited(600), backoff, Some(Duration::from_secs(60))),
+            adjust_delay(&anyhow::anyhow!("Overloaded"), backoff, None),
+        ];
+
+        let expected = [
+            Duration::from_secs(10),
+            backoff,
+            Duration::from_secs(60),
+            backoff,
+        ];
+        assert_eq!(actual, expected);
+    }
+}
@@ -1,22 +1,26 @@
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use bytes::Bytes;
+use chrono::{DateTime, Utc};
 use derive_setters::Setters;
 use merge::Merge;
 use paws_domain::{
     AgentId, AnyProvider, Attachment, AuthContextRequest, AuthContextResponse, AuthMethod,
-    ChatCompletionMessage, CommandOutput, Context, Conversation, ConversationId, Environment, File,
-    Image, InitAuth, LoginInfo, McpConfig, McpServers, Model, ModelId, PatchOperation, Provider,
-    ProviderId, ResultStream, Scope, Template, ToolCallFull, ToolOutput, Workflow,
+    ChatCompletionMessage, CommandOutput, Context, Conversation, ConversationId, ConversationMatch,
+    Environment, File, FileCheckpoint, FilePatch, Hook, HookOutcome, HookPayload, Image,
+    IndexStatus, InitAuth, JobId, JobOutput, LoginInfo, McpConfig, McpPromptMessage,
+    McpResourceContent, McpServers, Model, ModelId, PatchOperation, Provider, ProviderId,
+    ResultStream, Scope, SearchHit, SearchQuery, ServerName, Snapshot, Template, ToolCallFull,
+    ToolOutput, Usage, UsageReportEntry, Workflow,
 };
 use reqwest::Response;
 use reqwest::header::HeaderMap;
-use reqwest_eventsource::EventSource;
 use url::Url;
 
-use crate::Walker;
 use crate::user::{User, UserUsage};
+use crate::{EventStream, Walker};
 
 #[derive(Debug)]
 pub struct ShellOutput {
@@ -29,6 +33,63 @@ pub struct PatchOutput {
     pub before: String,
     pub after: String,
     pub content_hash: String,
+    // Snapshot of the file taken before patching
+    pub snapshot: Option<Snapshot>,
+    // Parse errors in the patched file
+    pub syntax_errors: Vec<SyntaxError>,
+}
+
+/// Result of applying a `multi_patch` call, with one entry per touched file
+#[derive(Debug)]
+pub struct MultiPatchOutput {
+    pub files: Vec<FilePatchOutput>,
+}
+
+#[derive(Debug)]
+pub struct FilePatchOutput {
+    pub path: PathBuf,
+    // Content before patching, `None` when the patch created the file
+    pub before: Option<String>,
+    pub after: String,
+    pub content_hash: String,
+    // Snapshot of the file taken before patching
+    pub snapshot: Option<Snapshot>,
+    // Parse errors in the patched file
+    pub syntax_errors: Vec<SyntaxError>,
+    pub hunks: Vec<HunkOutcome>,
+}
+
+/// Where and how a hunk was applied
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HunkOutcome {
+    /// The hunk header or a short description of a search/replace edit
+    pub label: String,
+    /// 1-based line of the original file where the hunk matched
+    pub line: usize,
+    pub matching: HunkMatch,
+    /// Number of leading and trailing context lines that had to be ignored
+    pub fuzz: usize,
+}
+
+/// How closely the lines expected by a hunk matched the file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, strum_macros::Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum HunkMatch {
+    Exact,
+    /// Matched after ignoring trailing whitespace
+    Whitespace,
+    /// Matched after ignoring indentation, the added lines were re-indented
+    Indentation,
+}
+
+/// A parse error found in a file written by a tool
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxError {
+    /// 1-based line of the error
+    pub line: usize,
+    /// 1-based column of the error
+    pub column: usize,
+    pub message: String,
 }
 
 #[derive(Debug, Setters)]
@@ -75,6 +136,83 @@ pub enum MatchResult {
     Found { line_number: usize, line: String },
 }
 
+/// Kind of a symbol listed in an outline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum_macros::Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum SymbolKind {
+    Function,
+    Method,
+    Struct,
+    Enum,
+    Union,
+    Trait,
+    Impl,
+    Class,
+    Interface,
+    Type,
+    Module,
+    Constant,
+    Macro,
+}
+
+/// A definition found in a source file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Symbol {
+    pub kind: SymbolKind,
+    pub name: String,
+    /// Declaration of the symbol up to its body, on a single line
+    pub signature: String,
+    /// 1-based first line of the definition
+    pub start_line: usize,
+    /// 1-based last line of the definition, inclusive
+    pub end_line: usize,
+    /// Symbols defined within the scope of this one, such as methods
+    pub children: Vec<Symbol>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileOutline {
+    /// Path relative to the outlined directory, or the outlined file itself
+    pub path: String,
+    pub total_lines: usize,
+    pub symbols: Vec<Symbol>,
+}
+
+#[derive(Debug, Default)]
+pub struct OutlineOutput {
+    pub files: Vec<FileOutline>,
+    /// Set when files were left out to keep the output small
+    pub truncated: bool,
+}
+
+/// A line where a symbol is defined or referenced
+#[derive(Debug, Clone, PartialEq)]
+pub struct SymbolLocation {
+    /// Path relative to the searched directory
+    pub path: String,
+    /// 1-based line of the location
+    pub line: usize,
+    /// Last line of the definition, `None` for references
+    pub end_line: Option<usize>,
+    /// The signature of a definition or the trimmed referencing line
+    pub text: String,
+}
+
+#[derive(Debug, Default)]
+pub struct SymbolSearchOutput {
+    pub definitions: Vec<SymbolLocation>,
+    pub references: Vec<SymbolLocation>,
+    /// Set when references were left out to keep the output small
+    pub truncated: bool,
+}
+
+/// Chunks matching a single semantic search query, best match first
+#[derive(Debug)]
+pub struct QueryHits {
+    pub query: SearchQuery,
+    pub hits: Vec<SearchHit>,
+}
+
 #[derive(Debug)]
 pub struct HttpResponse {
     pub content: String,
@@ -96,12 +234,20 @@ pub struct FsCreateOutput {
     pub before: Option<String>,
 
     pub content_hash: String,
+
+    // Set when the file already exists
+    pub snapshot: Option<Snapshot>,
+
+    // Parse errors in the written file
+    pub syntax_errors: Vec<SyntaxError>,
 }
 
 #[derive(Debug)]
 pub struct FsRemoveOutput {
     // Content of the file
     pub content: String,
+    // Snapshot of the file taken before removing it
+    pub snapshot: Option<Snapshot>,
 }
 
 #[derive(Debug)]
@@ -196,6 +342,15 @@ pub trait McpService: Send + Sync {
     async fn execute_mcp(&self, call: ToolCallFull) -> anyhow::Result<ToolOutput>;
     /// Refresh the MCP cache by fetching fresh data
     async fn reload_mcp(&self) -> anyhow::Result<()>;
+    /// Reads a resource exposed by one of the MCP servers
+    async fn read_mcp_resource(&self, uri: &str) -> anyhow::Result<Vec<McpResourceContent>>;
+    /// Renders a prompt exposed by the given MCP server
+    async fn render_mcp_prompt(
+        &self,
+        server: &ServerName,
+        name: &str,
+        arguments: BTreeMap<String, String>,
+    ) -> anyhow::Result<Vec<McpPromptMessage>>;
 }
 
 #[async_trait::async_trait]
@@ -220,8 +375,34 @@ pub trait ConversationService: Send + Sync {
     /// Find the last active conversation
     async fn last_conversation(&self) -> anyhow::Result<Option<Conversation>>;
 
+    /// Full-text search over conversation titles and messages
+    async fn search_conversations(
+        &self,
+        query: &str,
+        limit: Option<usize>,
+    ) -> anyhow::Result<Vec<ConversationMatch>>;
+
     /// Permanently deletes a conversation
     async fn delete_conversation(&self, conversation_id: &ConversationId) -> anyhow::Result<()>;
+
+    /// Appends the usage of a completion to the workspace usage ledger
+    async fn record_usage(
+        &self,
+        conversation_id: &ConversationId,
+        usage: &Usage,
+        model: &ModelId,
+        provider: &ProviderId,
+    ) -> anyhow::Result<()>;
+
+    /// Total usage of the workspace since the given instant
+    async fn usage_since(&self, since: DateTime<Utc>) -> anyhow::Result<Usage>;
+
+    /// Total usage recorded for a conversation
+    async fn conversation_usage(&self, conversation_id: &ConversationId) -> anyhow::Result<Usage>;
+
+    /// Usage of every workspace since the given instant, summed per day,
+    /// workspace, provider and model
+    async fn usage_report(&self, since: DateTime<Utc>) -> anyhow::Result<Vec<UsageReportEntry>>;
 }
 
 #[async_trait::async_trait]
@@ -312,6 +493,13 @@ pub trait FsPatchService: Send + Sync {
     ) -> anyhow::Result<PatchOutput>;
 }
 
+#[async_trait::async_trait]
+pub trait FsMultiPatchService: Send + Sync {
+    /// Applies the patches of several files atomically: either every hunk
+    /// applies and all files are written, or no file is changed.
+    async fn multi_patch(&self, patches: Vec<FilePatch>) -> anyhow::Result<MultiPatchOutput>;
+}
+
 #[async_trait::async_trait]
 pub trait FsReadService: Send + Sync {
     /// Reads a file at the specified path and returns its content.
@@ -346,6 +534,36 @@ pub trait FsSearchService: Send + Sync {
     ) -> anyhow::Result<Option<SearchResult>>;
 }
 
+#[async_trait::async_trait]
+pub trait CodeOutlineService: Send + Sync {
+    /// Lists the symbols defined in a file, or in every supported file of a
+    /// directory
+    async fn outline(&self, path: String) -> anyhow::Result<OutlineOutput>;
+
+    /// Finds the definitions of and references to the symbol called `name`
+    /// in a file or directory
+    async fn find_symbol(&self, name: String, path: String) -> anyhow::Result<SymbolSearchOutput>;
+}
+
+#[async_trait::async_trait]
+pub trait CodeIndexService: Send + Sync {
+    /// Indexes the files of the workspace that changed since the last build
+    async fn build_index(&self) -> anyhow::Result<IndexStatus>;
+
+    /// Reports the state of the index and the files pending indexing
+    async fn index_status(&self) -> anyhow::Result<IndexStatus>;
+
+    /// Deletes the index of the workspace, returning whether one existed
+    async fn clear_index(&self) -> anyhow::Result<bool>;
+
+    /// Refreshes the index and returns the chunks most relevant to each query
+    async fn search_index(
+        &self,
+        queries: Vec<SearchQuery>,
+        file_extension: Option<String>,
+    ) -> anyhow::Result<Vec<QueryHits>>;
+}
+
 #[async_trait::async_trait]
 pub trait FollowUpService: Send + Sync {
     /// Follows up on a tool call with the given context.
@@ -364,6 +582,11 @@ pub trait FsUndoService: Send + Sync {
     // TODO: We should move Snapshot service to Services from infra
     // and drop FsUndoService.
     async fn undo(&self, path: String) -> anyhow::Result<FsUndoOutput>;
+
+    /// Restores every file modified since a checkpoint to the state it was in
+    /// when the checkpoint was created. Files that didn't exist at that point
+    /// are removed. Returns the paths of the restored files.
+    async fn rewind(&self, files: Vec<FileCheckpoint>) -> anyhow::Result<Vec<String>>;
 }
 
 #[async_trait::async_trait]
@@ -383,6 +606,37 @@ pub trait ShellService: Send + Sync {
         silent: bool,
         env_vars: Option<Vec<String>>,
     ) -> anyhow::Result<ShellOutput>;
+
+    /// Starts a shell command in the background and returns its job handle.
+    async fn spawn_job(
+        &self,
+        conversation_id: ConversationId,
+        command: String,
+        cwd: PathBuf,
+        env_vars: Option<Vec<String>>,
+    ) -> anyhow::Result<JobOutput>;
+
+    /// Returns the output of a background job since the previous read.
+    async fn read_job(
+        &self,
+        conversation_id: ConversationId,
+        job_id: JobId,
+    ) -> anyhow::Result<JobOutput>;
+
+    /// Waits up to `timeout` for a background job to exit.
+    async fn wait_job(
+        &self,
+        conversation_id: ConversationId,
+        job_id: JobId,
+        timeout: Duration,
+    ) -> anyhow::Result<JobOutput>;
+
+    /// Kills a background job.
+    async fn kill_job(
+        &self,
+        conversation_id: ConversationId,
+        job_id: JobId,
+    ) -> anyhow::Result<JobOutput>;
 }
 
 #[async_trait::async_trait]
@@ -430,6 +684,14 @@ pub trait PolicyService: Send + Sync {
     ) -> anyhow::Result<PolicyDecision>;
 }
 
+#[async_trait::async_trait]
+pub trait HookService: Send + Sync {
+    /// Runs the hooks matching the payload in order and returns what to do
+    /// with the action that triggered them. The first hook to block wins and
+    /// tool arguments replaced by a hook are passed on to the next ones.
+    async fn run_hooks(&self, hooks: &[Hook], payload: HookPayload) -> anyhow::Result<HookOutcome>;
+}
+
 /// Skill fetch service
 #[async_trait::async_trait]
 pub trait SkillFetchService: Send + Sync {
@@ -491,10 +753,13 @@ pub trait Services: Send + Sync + 'static + Clone {
     type FsCreateService: FsCreateService;
     type PlanCreateService: PlanCreateService;
     type FsPatchService: FsPatchService;
+    type FsMultiPatchService: FsMultiPatchService;
     type FsReadService: FsReadService;
     type ImageReadService: ImageReadService;
     type FsRemoveService: FsRemoveService;
     type FsSearchService: FsSearchService;
+    type CodeIndexService: CodeIndexService;
+    type CodeOutlineService: CodeOutlineService;
     type FollowUpService: FollowUpService;
     type FsUndoService: FsUndoService;
     type NetFetchService: NetFetchService;
@@ -504,6 +769,7 @@ pub trait Services: Send + Sync + 'static + Clone {
     type AgentRegistry: AgentRegistry;
     type CommandLoaderService: CommandLoaderService;
     type PolicyService: PolicyService;
+    type HookService: HookService;
     type ProviderAuthService: ProviderAuthService;
     type SkillFetchService: SkillFetchService;
 
@@ -518,10 +784,13 @@ pub trait Services: Send + Sync + 'static + Clone {
     fn fs_create_service(&self) -> &Self::FsCreateService;
     fn plan_create_service(&self) -> &Self::PlanCreateService;
     fn fs_patch_service(&self) -> &Self::FsPatchService;
+    fn fs_multi_patch_service(&self) -> &Self::FsMultiPatchService;
     fn fs_read_service(&self) -> &Self::FsReadService;
     fn image_read_service(&self) -> &Self::ImageReadService;
     fn fs_remove_service(&self) -> &Self::FsRemoveService;
     fn fs_search_service(&self) -> &Self::FsSearchService;
+    fn code_index_service(&self) -> &Self::CodeIndexService;
+    fn code_outline_service(&self) -> &Self::CodeOutlineService;
     fn follow_up_service(&self) -> &Self::FollowUpService;
     fn fs_undo_service(&self) -> &Self::FsUndoService;
     fn net_fetch_service(&self) -> &Self::NetFetchService;
@@ -533,6 +802,7 @@ pub trait Services: Send + Sync + 'static + Clone {
     fn agent_registry(&self) -> &Self::AgentRegistry;
     fn command_loader_service(&self) -> &Self::CommandLoaderService;
     fn policy_service(&self) -> &Self::PolicyService;
+    fn hook_service(&self) -> &Self::HookService;
     fn provider_auth_service(&self) -> &Self::ProviderAuthService;
     fn skill_fetch_service(&self) -> &Self::SkillFetchService;
 }
@@ -568,11 +838,47 @@ impl<I: Services> ConversationService for I {
         self.conversation_service().last_conversation().await
     }
 
+    async fn search_conversations(
+        &self,
+        query: &str,
+        limit: Option<usize>,
+    ) -> anyhow::Result<Vec<ConversationMatch>> {
+        self.conversation_service()
+            .search_conversations(query, limit)
+            .await
+    }
+
     async fn delete_conversation(&self, conversation_id: &ConversationId) -> anyhow::Result<()> {
         self.conversation_service()
             .delete_conversation(conversation_id)
             .await
     }
+
+    async fn record_usage(
+        &self,
+        conversation_id: &ConversationId,
+        usage: &Usage,
+        model: &ModelId,
+        provider: &ProviderId,
+    ) -> anyhow::Result<()> {
+        self.conversation_service()
+            .record_usage(conversation_id, usage, model, provider)
+            .await
+    }
+
+    async fn usage_since(&self, since: DateTime<Utc>) -> anyhow::Result<Usage> {
+        self.conversation_service().usage_since(since).await
+    }
+
+    async fn conversation_usage(&self, conversation_id: &ConversationId) -> anyhow::Result<Usage> {
+        self.conversation_service()
+            .conversation_usage(conversation_id)
+            .await
+    }
+
+    async fn usage_report(&self, since: DateTime<Utc>) -> anyhow::Result<Vec<UsageReportEntry>> {
+        self.conversation_service().usage_report(since).await
+    }
 }
 #[async_trait::async_trait]
 impl<I: Services> ProviderService for I {
@@ -643,6 +949,21 @@ impl<I: Services> McpService for I {
     async fn reload_mcp(&self) -> anyhow::Result<()> {
         self.mcp_service().reload_mcp().await
     }
+
+    async fn read_mcp_resource(&self, uri: &str) -> anyhow::Result<Vec<McpResourceContent>> {
+        self.mcp_service().read_mcp_resource(uri).await
+    }
+
+    async fn render_mcp_prompt(
+        &self,
+        server: &ServerName,
+        name: &str,
+        arguments: BTreeMap<String, String>,
+    ) -> anyhow::Result<Vec<McpPromptMessage>> {
+        self.mcp_service()
+            .render_mcp_prompt(server, name, arguments)
+            .await
+    }
 }
 
 #[async_trait::async_trait]
@@ -734,6 +1055,13 @@ impl<I: Services> FsPatchService for I {
     }
 }
 
+#[async_trait::async_trait]
+impl<I: Services> FsMultiPatchService for I {
+    async fn multi_patch(&self, patches: Vec<FilePatch>) -> anyhow::Result<MultiPatchOutput> {
+        self.fs_multi_patch_service().multi_patch(patches).await
+    }
+}
+
 #[async_trait::async_trait]
 impl<I: Services> FsReadService for I {
     async fn read(
@@ -775,6 +1103,42 @@ impl<I: Services> FsSearchService for I {
     }
 }
 
+#[async_trait::async_trait]
+impl<I: Services> CodeOutlineService for I {
+    async fn outline(&self, path: String) -> anyhow::Result<OutlineOutput> {
+        self.code_outline_service().outline(path).await
+    }
+
+    async fn find_symbol(&self, name: String, path: String) -> anyhow::Result<SymbolSearchOutput> {
+        self.code_outline_service().find_symbol(name, path).await
+    }
+}
+
+#[async_trait::async_trait]
+impl<I: Services> CodeIndexService for I {
+    async fn build_index(&self) -> anyhow::Result<IndexStatus> {
+        self.code_index_service().build_index().await
+    }
+
+    async fn index_status(&self) -> anyhow::Result<IndexStatus> {
+        self.code_index_service().index_status().await
+    }
+
+    async fn clear_index(&self) -> anyhow::Result<bool> {
+        self.code_index_service().clear_index().await
+    }
+
+    async fn search_index(
+        &self,
+        queries: Vec<SearchQuery>,
+        file_extension: Option<String>,
+    ) -> anyhow::Result<Vec<QueryHits>> {
+        self.code_index_service()
+            .search_index(queries, file_extension)
+            .await
+    }
+}
+
 #[async_trait::async_trait]
 impl<I: Services> FollowUpService for I {
     async fn follow_up(
@@ -794,6 +1158,10 @@ impl<I: Services> FsUndoService for I {
     async fn undo(&self, path: String) -> anyhow::Result<FsUndoOutput> {
         self.fs_undo_service().undo(path).await
     }
+
+    async fn rewind(&self, files: Vec<FileCheckpoint>) -> anyhow::Result<Vec<String>> {
+        self.fs_undo_service().rewind(files).await
+    }
 }
 
 #[async_trait::async_trait]
@@ -817,6 +1185,45 @@ impl<I: Services> ShellService for I {
             .execute(command, cwd, keep_ansi, silent, env_vars)
             .await
     }
+
+    async fn spawn_job(
+        &self,
+        conversation_id: ConversationId,
+        command: String,
+        cwd: PathBuf,
+        env_vars: Option<Vec<String>>,
+    ) -> anyhow::Result<JobOutput> {
+        self.shell_service()
+            .spawn_job(conversation_id, command, cwd, env_vars)
+            .await
+    }
+
+    async fn read_job(
+        &self,
+        conversation_id: ConversationId,
+        job_id: JobId,
+    ) -> anyhow::Result<JobOutput> {
+        self.shell_service().read_job(conversation_id, job_id).await
+    }
+
+    async fn wait_job(
+        &self,
+        conversation_id: ConversationId,
+        job_id: JobId,
+        timeout: Duration,
+    ) -> anyhow::Result<JobOutput> {
+        self.shell_service()
+            .wait_job(conversation_id, job_id, timeout)
+            .await
+    }
+
+    async fn kill_job(
+        &self,
+        conversation_id: ConversationId,
+        job_id: JobId,
+    ) -> anyhow::Result<JobOutput> {
+        self.shell_service().kill_job(conversation_id, job_id).await
+    }
 }
 
 impl<I: Services> EnvironmentService for I {
@@ -874,7 +1281,7 @@ pub trait HttpClientService: Send + Sync + 'static {
         url: &Url,
         headers: Option<HeaderMap>,
         body: Bytes,
-    ) -> anyhow::Result<EventSource>;
+    ) -> anyhow::Result<EventStream>;
 }
 
 #[async_trait::async_trait]
@@ -919,6 +1326,13 @@ impl<I: Services> PolicyService for I {
     }
 }
 
+#[async_trait::async_trait]
+impl<I: Services> HookService for I {
+    async fn run_hooks(&self, hooks: &[Hook], payload: HookPayload) -> anyhow::Result<HookOutcome> {
+        self.hook_service().run_hooks(hooks, payload).await
+    }
+}
+
 #[async_trait::async_trait]
 impl<I: Services> AppConfigService for I {
     async fn get_default_provider(&self) -> anyhow::Result<Provider<Url>> {
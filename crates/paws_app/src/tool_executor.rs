@@ -1,17 +1,22 @@
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 use paws_common::template::Element;
-use paws_domain::{TitleFormat, ToolCallContext, ToolCallFull, ToolCatalog, ToolOutput};
+use paws_domain::{
+    CommandOutput, ConversationId, FollowUpQuestion, JobId, TitleFormat, ToolCallContext,
+    ToolCallFull, ToolCatalog, ToolOutput,
+};
 
 use crate::fmt::content::FormatContent;
 use crate::operation::{TempContentFiles, ToolOperation};
 use crate::services::ShellService;
 use crate::utils::format_display_path;
 use crate::{
-    ConversationService, EnvironmentService, FollowUpService, FsCreateService, FsPatchService,
-    FsReadService, FsRemoveService, FsSearchService, FsUndoService, ImageReadService,
-    NetFetchService, PlanCreateService, PolicyService, SkillFetchService,
+    CodeIndexService, CodeOutlineService, ConversationService, EnvironmentService, FollowUpService,
+    FsCreateService, FsMultiPatchService, FsPatchService, FsReadService, FsRemoveService,
+    FsSearchService, FsUndoService, ImageReadService, NetFetchService, PlanCreateService,
+    PolicyService, SkillFetchService,
 };
 
 pub struct ToolExecutor<S> {
@@ -23,9 +28,12 @@ impl<
         + ImageReadService
         + FsCreateService
         + FsSearchService
+        + CodeIndexService
+        + CodeOutlineService
         + NetFetchService
         + FsRemoveService
         + FsPatchService
+        + FsMultiPatchService
         + FsUndoService
         + ShellService
         + FollowUpService
@@ -47,8 +55,7 @@ impl<
         context: &ToolCallContext,
     ) -> anyhow::Result<bool> {
         let cwd = self.services.get_environment().cwd;
-        let operation = tool_input.to_policy_operation(cwd.clone());
-        if let Some(operation) = operation {
+        for operation in tool_input.to_policy_operations(cwd.clone()) {
             let decision = self.services.check_operation_permission(&operation).await?;
 
             // Send custom policy message to the user when a policy file was created
@@ -84,34 +91,42 @@ impl<
 
                 Ok(files)
             }
-            ToolOperation::Shell { output } => {
-                let env = self.services.get_environment();
-                let stdout_lines = output.output.stdout.lines().count();
-                let stderr_lines = output.output.stderr.lines().count();
-                let stdout_truncated =
-                    stdout_lines > env.stdout_max_prefix_length + env.stdout_max_suffix_length;
-                let stderr_truncated =
-                    stderr_lines > env.stdout_max_prefix_length + env.stdout_max_suffix_length;
+            ToolOperation::Shell { output } => self.dump_command_output(&output.output).await,
+            ToolOperation::ShellJob { output } => self.dump_command_output(&output.output).await,
+            _ => Ok(TempContentFiles::default()),
+        }
+    }
 
-                let mut files = TempContentFiles::default();
+    /// Writes stdout and stderr to temp files when they exceed the truncation
+    /// limits so that the full output stays accessible
+    async fn dump_command_output(
+        &self,
+        output: &CommandOutput,
+    ) -> anyhow::Result<TempContentFiles> {
+        let env = self.services.get_environment();
+        let stdout_lines = output.stdout.lines().count();
+        let stderr_lines = output.stderr.lines().count();
+        let stdout_truncated =
+            stdout_lines > env.stdout_max_prefix_length + env.stdout_max_suffix_length;
+        let stderr_truncated =
+            stderr_lines > env.stdout_max_prefix_length + env.stdout_max_suffix_length;
 
-                if stdout_truncated {
-                    files = files.stdout(
-                        self.create_temp_file("paws_shell_stdout_", ".txt", &output.output.stdout)
-                            .await?,
-                    );
-                }
-                if stderr_truncated {
-                    files = files.stderr(
-                        self.create_temp_file("paws_shell_stderr_", ".txt", &output.output.stderr)
-                            .await?,
-                    );
-                }
+        let mut files = TempContentFiles::default();
 
-                Ok(files)
-            }
-            _ => Ok(TempContentFiles::default()),
+        if stdout_truncated {
+            files = files.stdout(
+                self.create_temp_file("paws_shell_stdout_", ".txt", &output.stdout)
+                    .await?,
+            );
+        }
+        if stderr_truncated {
+            files = files.stderr(
+                self.create_temp_file("paws_shell_stderr_", ".txt", &output.stderr)
+                    .await?,
+            );
         }
+
+        Ok(files)
     }
 
     /// Converts a path to absolute by joining it with the current working
@@ -150,7 +165,18 @@ impl<
         Ok(path)
     }
 
-    async fn call_internal(&self, input: ToolCatalog) -> anyhow::Result<ToolOperation> {
+    async fn call_internal(
+        &self,
+        input: ToolCatalog,
+        context: &ToolCallContext,
+    ) -> anyhow::Result<ToolOperation> {
+        // Background jobs are scoped to the conversation that started them
+        let conversation_id = || -> anyhow::Result<ConversationId> {
+            context.conversation_id.ok_or_else(|| {
+                anyhow::anyhow!("Background shell jobs require an active conversation")
+            })
+        };
+
         Ok(match input {
             ToolCatalog::Read(input) => {
                 let normalized_path = self.normalize_path(input.path.clone());
@@ -190,6 +216,27 @@ impl<
                     .await?;
                 (input, output).into()
             }
+            ToolCatalog::SemSearch(input) => {
+                let output = self
+                    .services
+                    .search_index(input.queries.clone(), input.file_extension.clone())
+                    .await?;
+                (input, output).into()
+            }
+            ToolCatalog::Outline(input) => {
+                let normalized_path = self.normalize_path(input.path.clone());
+                let output = self.services.outline(normalized_path).await?;
+                (input, output).into()
+            }
+            ToolCatalog::FindSymbol(input) => {
+                let path = input
+                    .path
+                    .clone()
+                    .map(|path| self.normalize_path(path))
+                    .unwrap_or_else(|| self.services.get_environment().cwd.display().to_string());
+                let output = self.services.find_symbol(input.name.clone(), path).await?;
+                (input, output).into()
+            }
             ToolCatalog::Remove(input) => {
                 let normalized_path = self.normalize_path(input.path.clone());
                 let output = self.services.remove(normalized_path).await?;
@@ -208,11 +255,29 @@ impl<
                     .await?;
                 (input, output).into()
             }
+            ToolCatalog::MultiPatch(input) => {
+                let cwd = self.services.get_environment().cwd;
+                let output = self.services.multi_patch(input.file_patches(&cwd)?).await?;
+                (input, output).into()
+            }
             ToolCatalog::Undo(input) => {
                 let normalized_path = self.normalize_path(input.path.clone());
                 let output = self.services.undo(normalized_path).await?;
                 (input, output).into()
             }
+            ToolCatalog::Shell(input) if input.background => {
+                let normalized_cwd = self.normalize_path(input.cwd.display().to_string());
+                let output = self
+                    .services
+                    .spawn_job(
+                        conversation_id()?,
+                        input.command.clone(),
+                        PathBuf::from(normalized_cwd),
+                        input.env.clone(),
+                    )
+                    .await?;
+                output.into()
+            }
             ToolCatalog::Shell(input) => {
                 let normalized_cwd = self.normalize_path(input.cwd.display().to_string());
                 let output = self
@@ -227,27 +292,43 @@ impl<
                     .await?;
                 output.into()
             }
+            ToolCatalog::ShellRead(input) => self
+                .services
+                .read_job(conversation_id()?, JobId::new(input.job_id))
+                .await?
+                .into(),
+            ToolCatalog::ShellWait(input) => self
+                .services
+                .wait_job(
+                    conversation_id()?,
+                    JobId::new(input.job_id),
+                    Duration::from_secs(input.timeout_secs),
+                )
+                .await?
+                .into(),
+            ToolCatalog::ShellKill(input) => self
+                .services
+                .kill_job(conversation_id()?, JobId::new(input.job_id))
+                .await?
+                .into(),
             ToolCatalog::Fetch(input) => {
                 let output = self.services.fetch(input.url.clone(), input.raw).await?;
                 (input, output).into()
             }
             ToolCatalog::Followup(input) => {
-                let output = self
-                    .services
-                    .follow_up(
-                        input.question.clone(),
-                        input
-                            .option1
-                            .clone()
-                            .into_iter()
-                            .chain(input.option2.clone().into_iter())
-                            .chain(input.option3.clone().into_iter())
-                            .chain(input.option4.clone().into_iter())
-                            .chain(input.option5.clone().into_iter())
-                            .collect(),
-                        input.multiple,
-                    )
-                    .await?;
+                let question = FollowUpQuestion::from(input.clone());
+                // Front-ends answer through the response stream, calls made
+                // outside of a conversation fall back to the terminal
+                let output = if context.has_sender() {
+                    context
+                        .ask(question.clone())
+                        .await?
+                        .map(|answer| question.feedback(answer))
+                } else {
+                    self.services
+                        .follow_up(question.question, question.options, Some(question.multiple))
+                        .await?
+                };
                 output.into()
             }
             ToolCatalog::Plan(input) => {
@@ -293,7 +374,7 @@ impl<
             ));
         }
 
-        let execution_result = self.call_internal(tool_input.clone()).await;
+        let execution_result = self.call_internal(tool_input.clone(), context).await;
 
         if let Err(ref error) = execution_result {
             tracing::error!(error = ?error, "Tool execution failed");
@@ -5,8 +5,9 @@ use anyhow::Context;
 use console::style;
 use futures::future::join_all;
 use paws_domain::{
-    Agent, AgentId, AgentInput, ChatResponse, ChatResponseContent, ToolCallContext, ToolCallFull,
-    ToolCatalog, ToolDefinition, ToolName, ToolOutput, ToolResult,
+    Agent, AgentId, AgentInput, ChatResponse, ChatResponseContent, HookEvent, HookOutcome,
+    HookPayload, ToolCallContext, ToolCallFull, ToolCatalog, ToolDefinition, ToolName, ToolOutput,
+    ToolResult,
 };
 use strum::IntoEnumIterator;
 use tokio::time::timeout;
@@ -16,7 +17,7 @@ use crate::dto::ToolsOverview;
 use crate::error::Error;
 use crate::mcp_executor::McpExecutor;
 use crate::tool_executor::ToolExecutor;
-use crate::{EnvironmentService, McpService, Services, ToolResolver};
+use crate::{EnvironmentService, HookService, McpService, Services, ToolResolver};
 
 pub struct ToolRegistry<S> {
     tool_executor: ToolExecutor<S>,
@@ -57,11 +58,89 @@ impl<S: Services> ToolRegistry<S> {
     async fn call_inner(
         &self,
         agent: &Agent,
-        input: ToolCallFull,
+        mut input: ToolCallFull,
         context: &ToolCallContext,
     ) -> anyhow::Result<ToolOutput> {
         Self::validate_tool_call(agent, &input.name)?;
+        let Some(hooks) = agent.hooks.as_ref() else {
+            return self.dispatch(input, context).await;
+        };
+
+        if !hooks.pre_tool_use.is_empty() {
+            let payload = self.hook_payload(HookEvent::PreToolUse, agent, &input, context);
+            match self
+                .services
+                .run_hooks(&hooks.pre_tool_use, payload)
+                .await?
+            {
+                HookOutcome::Block(reason) => {
+                    return Err(Error::HookBlocked { event: HookEvent::PreToolUse, reason }.into());
+                }
+                HookOutcome::Modify(arguments) => input.arguments = arguments.into(),
+                HookOutcome::Continue => {}
+            }
+        }
+
+        let payload = self.hook_payload(HookEvent::PostToolUse, agent, &input, context);
+        let output = self.dispatch(input, context).await;
+        if hooks.post_tool_use.is_empty() {
+            return output;
+        }
 
+        let payload = match &output {
+            Ok(output) => payload
+                .tool_output(
+                    output
+                        .values
+                        .iter()
+                        .filter_map(|value| value.as_str())
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                )
+                .is_error(output.is_error),
+            Err(error) => payload.tool_output(format!("{error:#}")).is_error(true),
+        };
+
+        match self
+            .services
+            .run_hooks(&hooks.post_tool_use, payload)
+            .await?
+        {
+            // Blocking after the fact reports the reason to the model as a failure
+            HookOutcome::Block(reason) => {
+                let blocked = Error::HookBlocked { event: HookEvent::PostToolUse, reason };
+                match output {
+                    Ok(output) => Ok(output
+                        .combine(ToolOutput::text(blocked.to_string()))
+                        .is_error(true)),
+                    Err(error) => Err(error.context(blocked)),
+                }
+            }
+            HookOutcome::Modify(_) | HookOutcome::Continue => output,
+        }
+    }
+
+    fn hook_payload(
+        &self,
+        event: HookEvent,
+        agent: &Agent,
+        input: &ToolCallFull,
+        context: &ToolCallContext,
+    ) -> HookPayload {
+        HookPayload {
+            conversation_id: context.conversation_id,
+            tool_input: input.arguments.parse().ok(),
+            ..HookPayload::new(event, self.services.get_environment().cwd)
+        }
+        .agent_id(agent.id.clone())
+        .tool_name(input.name.clone())
+    }
+
+    async fn dispatch(
+        &self,
+        input: ToolCallFull,
+        context: &ToolCallContext,
+    ) -> anyhow::Result<ToolOutput> {
         tracing::info!(tool_name = %input.name, arguments = %input.arguments.clone().into_string(), "Executing tool call");
         let tool_name = input.name.clone();
 
@@ -0,0 +1,138 @@
+use std::path::PathBuf;
+
+use paws_domain::{ToolAccess, ToolCallFull, ToolCatalog};
+
+/// Splits the tool calls of a single response into consecutive batches whose
+/// calls don't conflict with each other and can therefore be executed
+/// concurrently. Batches must be executed one after the other, which
+/// guarantees that conflicting calls observe each other in the order the model
+/// requested them.
+pub struct ToolScheduler {
+    cwd: PathBuf,
+}
+
+impl ToolScheduler {
+    pub fn new(cwd: impl Into<PathBuf>) -> Self {
+        Self { cwd: cwd.into() }
+    }
+
+    /// Agent and MCP tools, as well as calls with unparsable arguments, are
+    /// treated as exclusive.
+    fn access(&self, call: &ToolCallFull) -> ToolAccess {
+        if !ToolCatalog::contains(&call.name) {
+            return ToolAccess::Exclusive;
+        }
+
+        ToolCatalog::try_from(call.clone())
+            .map(|tool| tool.access(&self.cwd))
+            .unwrap_or(ToolAccess::Exclusive)
+    }
+
+    pub fn batches<'a>(&self, calls: &'a [ToolCallFull]) -> Vec<Vec<&'a ToolCallFull>> {
+        let mut batches: Vec<Vec<&ToolCallFull>> = Vec::new();
+        let mut accesses: Vec<ToolAccess> = Vec::new();
+
+        for call in calls {
+            let access = self.access(call);
+            let conflicts = accesses.iter().any(|other| other.conflicts_with(&access));
+
+            match batches.last_mut() {
+                Some(batch) if !conflicts => batch.push(call),
+                _ => {
+                    batches.push(vec![call]);
+                    accesses.clear();
+                }
+            }
+            accesses.push(access);
+        }
+
+        batches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use paws_domain::{PatchOperation, ToolCallFull, ToolCatalog};
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+
+    fn names(batches: Vec<Vec<&ToolCallFull>>) -> Vec<Vec<String>> {
+        batches
+            .into_iter()
+            .map(|batch| {
+                batch
+                    .into_iter()
+                    .map(|call| call.name.to_string())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_reads_are_batched_together() {
+        let fixture = vec![
+            ToolCatalog::tool_call_read("/project/a.rs"),
+            ToolCatalog::tool_call_search("/project", Some("fn main")),
+            ToolCatalog::tool_call_fetch("https://example.com"),
+        ];
+
+        let actual = names(ToolScheduler::new("/project").batches(&fixture));
+        let expected = vec![vec!["read", "search", "fetch"]];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_writes_to_different_files_are_batched_together() {
+        let fixture = vec![
+            ToolCatalog::tool_call_write("/project/a.rs", "a"),
+            ToolCatalog::tool_call_patch("b.rs", "b", PatchOperation::Append, None),
+            ToolCatalog::tool_call_read("/project/c.rs"),
+        ];
+
+        let actual = names(ToolScheduler::new("/project").batches(&fixture));
+        let expected = vec![vec!["write", "patch", "read"]];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_conflicting_calls_start_a_new_batch() {
+        let fixture = vec![
+            ToolCatalog::tool_call_read("/project/a.rs"),
+            ToolCatalog::tool_call_write("/project/a.rs", "a"),
+            ToolCatalog::tool_call_read("/project/b.rs"),
+            ToolCatalog::tool_call_search("/project", None),
+        ];
+
+        let actual = names(ToolScheduler::new("/project").batches(&fixture));
+        let expected = vec![vec!["read"], vec!["write", "read"], vec!["search"]];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_exclusive_calls_run_alone() {
+        let fixture = vec![
+            ToolCatalog::tool_call_read("/project/a.rs"),
+            ToolCatalog::tool_call_shell("cargo test", "/project"),
+            ToolCatalog::tool_call_read("/project/b.rs"),
+        ];
+
+        let actual = names(ToolScheduler::new("/project").batches(&fixture));
+        let expected = vec![vec!["read"], vec!["shell"], vec!["read"]];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_unknown_tools_are_exclusive() {
+        let fixture = vec![
+            ToolCatalog::tool_call_read("/project/a.rs"),
+            ToolCallFull::new("sage").arguments(json!({"tasks": ["explore"]})),
+            ToolCallFull::new("mcp_github_list_issues"),
+        ];
+
+        let actual = names(ToolScheduler::new("/project").batches(&fixture));
+        let expected = vec![vec!["read"], vec!["sage"], vec!["mcp_github_list_issues"]];
+        assert_eq!(actual, expected);
+    }
+}
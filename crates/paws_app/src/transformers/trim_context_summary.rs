@@ -1,4 +1,4 @@
-use paws_domain::{ContextSummary, Role, SummaryMessage, SummaryTool, Transformer};
+use paws_domain::{ContextSummary, Role, SearchQuery, SummaryMessage, SummaryTool, Transformer};
 
 /// Removes redundant operations from the context summary.
 ///
@@ -21,6 +21,8 @@ enum Operation<'a> {
     Shell(&'a str),
     /// Search operation with a specific pattern
     Search(&'a str),
+    /// Semantic search with a specific set of queries
+    SemSearch(&'a [SearchQuery]),
     /// Fetch operation for a specific URL
     Fetch(&'a str),
     /// Follow-up question
@@ -43,6 +45,7 @@ fn to_op(tool: &SummaryTool) -> Operation<'_> {
         SummaryTool::Undo { path } => Operation::File(path),
         SummaryTool::Shell { command } => Operation::Shell(command),
         SummaryTool::Search { pattern } => Operation::Search(pattern),
+        SummaryTool::SemSearch { queries } => Operation::SemSearch(queries),
         SummaryTool::Fetch { url } => Operation::Fetch(url),
         SummaryTool::Followup { question } => Operation::Followup(question),
         SummaryTool::Plan { plan_name } => Operation::Plan(plan_name),
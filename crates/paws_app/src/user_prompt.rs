@@ -125,6 +125,12 @@ impl<S: AttachmentService> UserPromptGenerator<S> {
             };
 
         if let Some(content) = &content {
+            // Every prompt starts a new turn, record a checkpoint so that it can be rewound
+            conversation.metrics = conversation.metrics.clone().begin_turn(
+                context.prompt_indices().len(),
+                self.current_time.with_timezone(&chrono::Utc),
+            );
+
             // Create User Message
             let message = TextMessage {
                 role: Role::User,
@@ -294,4 +300,39 @@ mod tests {
             panic!("Expected TextMessage");
         }
     }
+
+    #[tokio::test]
+    async fn test_each_prompt_starts_a_checkpoint() {
+        let agent = fixture_agent_without_user_prompt();
+        let conversation = fixture_generator(agent.clone(), Event::new("First task"))
+            .add_user_prompt(fixture_conversation())
+            .await
+            .unwrap();
+
+        let actual = fixture_generator(agent.clone(), Event::new("Second task"))
+            .add_user_prompt(conversation)
+            .await
+            .unwrap()
+            .metrics
+            .checkpoints
+            .into_iter()
+            .map(|checkpoint| checkpoint.turn)
+            .collect::<Vec<_>>();
+
+        let expected = vec![1, 2];
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_empty_event_does_not_start_a_checkpoint() {
+        let agent = fixture_agent_without_user_prompt();
+        let generator = fixture_generator(agent.clone(), Event::empty());
+
+        let actual = generator
+            .add_user_prompt(fixture_conversation())
+            .await
+            .unwrap();
+
+        assert!(actual.metrics.checkpoints.is_empty());
+    }
 }
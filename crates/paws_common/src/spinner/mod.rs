@@ -53,6 +53,7 @@ pub struct SpinnerManager {
     message: Option<String>,        // current status text
     running: bool,
     hidden: bool,
+    quiet: bool,
 }
 
 impl SpinnerManager {
@@ -60,6 +61,13 @@ impl SpinnerManager {
         Self::default()
     }
 
+    /// Never draws the spinner and writes every message to stderr, keeping
+    /// stdout free for machine-readable output
+    pub fn quiet(mut self) -> Self {
+        self.quiet = true;
+        self
+    }
+
     /// Initialize the spinner thread and return a receiver for Ctrl+C events
     pub fn init(&mut self) -> Result<broadcast::Receiver<()>> {
         let (tx, rx) = mpsc::channel::<Cmd>();
@@ -199,7 +207,7 @@ impl SpinnerManager {
 
     /// Start the spinner with a message
     pub fn start(&mut self, message: Option<&str>) -> Result<()> {
-        if self.running {
+        if self.running || self.quiet {
             return Ok(());
         }
         let words = [
@@ -241,7 +249,11 @@ impl SpinnerManager {
 
         // Print trailing message if provided
         if let Some(msg) = message {
-            println!("{}", msg);
+            if self.quiet {
+                eprintln!("{}", msg);
+            } else {
+                println!("{}", msg);
+            }
         }
 
         self.running = false;
@@ -252,6 +264,10 @@ impl SpinnerManager {
 
     pub fn write_ln(&mut self, message: impl ToString) -> Result<()> {
         let s = message.to_string();
+        if self.quiet {
+            eprintln!("{}", s);
+            return Ok(());
+        }
         let normalized = s.replace('\n', "\n\x1b[0G");
 
         if let Some(tx) = &self.tx {
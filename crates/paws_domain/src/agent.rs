@@ -2,9 +2,9 @@ use derive_setters::Setters;
 use merge::Merge;
 
 use crate::{
-    AgentDefinition, AgentId, Compact, Context, Error, EventContext, MaxTokens, ModelId,
-    ProviderId, ReasoningConfig, Result, SystemContext, Temperature, Template, ToolDefinition,
-    ToolName, TopK, TopP, Workflow,
+    AgentDefinition, AgentId, Budget, Compact, Context, Error, EventContext, Hooks, MaxTokens,
+    ModelFallback, ModelId, ProviderId, ReasoningConfig, Result, SystemContext, Temperature,
+    Template, ToolDefinition, ToolName, TopK, TopP, Workflow,
 };
 
 /// Runtime agent representation with required model and provider
@@ -71,6 +71,16 @@ pub struct Agent {
 
     /// Maximum number of requests that can be made in a single turn
     pub max_requests_per_turn: Option<usize>,
+
+    /// Spending limits for the agent
+    pub budget: Option<Budget>,
+
+    /// Providers and models to switch to, in order, when the model in use
+    /// keeps failing
+    pub fallbacks: Option<Vec<ModelFallback>>,
+
+    /// Commands to run on lifecycle events of the agent
+    pub hooks: Option<Hooks>,
 }
 
 impl Agent {
@@ -96,6 +106,9 @@ impl Agent {
             reasoning: Default::default(),
             max_tool_failure_per_turn: Default::default(),
             max_requests_per_turn: Default::default(),
+            budget: Default::default(),
+            fallbacks: Default::default(),
+            hooks: Default::default(),
             path: Default::default(),
         }
     }
@@ -113,11 +126,20 @@ impl Agent {
             .description(self.description.clone().unwrap()))
     }
 
-    /// Checks if compaction should be applied
-    pub fn should_compact(&self, context: &Context, token_count: usize) -> bool {
+    /// Checks if compaction should be applied, resolving thresholds relative
+    /// to the context window of the model in use
+    pub fn should_compact(
+        &self,
+        context: &Context,
+        token_count: usize,
+        context_length: Option<u64>,
+    ) -> bool {
         // Return false if compaction is not configured
         if let Some(compact) = &self.compact {
-            compact.should_compact(context, token_count)
+            compact
+                .clone()
+                .for_context_window(context_length)
+                .should_compact(context, token_count)
         } else {
             false
         }
@@ -165,6 +187,23 @@ impl Agent {
             agent.max_requests_per_turn = Some(max_requests_per_turn);
         }
 
+        // Agent limits take priority over workflow limits
+        if let Some(workflow_budget) = workflow.budget.clone() {
+            let mut budget = workflow_budget;
+            if let Some(agent_budget) = agent.budget.take() {
+                budget.merge(agent_budget);
+            }
+            agent.budget = Some(budget);
+        }
+
+        if agent.fallbacks.is_none() {
+            agent.fallbacks = workflow.fallbacks.clone();
+        }
+
+        if agent.hooks.is_none() {
+            agent.hooks = workflow.hooks.clone();
+        }
+
         // Apply workflow compact configuration to agents
         if let Some(ref workflow_compact) = workflow.compact {
             if let Some(ref mut agent_compact) = agent.compact {
@@ -226,6 +265,9 @@ impl Agent {
             custom_rules: def.custom_rules,
             max_tool_failure_per_turn: def.max_tool_failure_per_turn,
             max_requests_per_turn: def.max_requests_per_turn,
+            budget: def.budget,
+            fallbacks: def.fallbacks,
+            hooks: Default::default(),
             path: def.path,
         }
     }
@@ -9,7 +9,10 @@ use serde::{Deserialize, Serialize};
 use crate::compact::Compact;
 use crate::temperature::Temperature;
 use crate::template::Template;
-use crate::{EventContext, MaxTokens, ModelId, ProviderId, SystemContext, ToolName, TopK, TopP};
+use crate::{
+    Budget, EventContext, MaxTokens, ModelFallback, ModelId, ProviderId, SystemContext, ToolName,
+    TopK, TopP,
+};
 
 // Unique identifier for an agent
 #[derive(Debug, Display, Eq, PartialEq, Hash, Clone, Serialize, Deserialize, JsonSchema)]
@@ -184,6 +187,19 @@ pub struct AgentDefinition {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[merge(strategy = crate::merge::option)]
     pub max_requests_per_turn: Option<usize>,
+
+    /// Spending limits for the agent
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub budget: Option<Budget>,
+
+    /// Providers and models to switch to, in order, when the model in use
+    /// keeps failing
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub fallbacks: Option<Vec<ModelFallback>>,
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize, Merge, Setters, JsonSchema, PartialEq)]
@@ -252,6 +268,8 @@ impl AgentDefinition {
             reasoning: Default::default(),
             max_tool_failure_per_turn: Default::default(),
             max_requests_per_turn: Default::default(),
+            budget: Default::default(),
+            fallbacks: Default::default(),
             provider: Default::default(),
             path: Default::default(),
         }
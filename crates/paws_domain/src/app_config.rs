@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use derive_more::From;
 use serde::{Deserialize, Serialize};
 
-use crate::{ModelId, ProviderId};
+use crate::{ModelId, PricingCatalogue, ProviderId};
 
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -21,6 +21,9 @@ pub struct AppConfig {
     pub provider: Option<ProviderId>,
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub model: HashMap<ProviderId, ModelId>,
+    /// Prices of models, taking precedence over those of the providers
+    #[serde(default, skip_serializing_if = "PricingCatalogue::is_empty")]
+    pub pricing: PricingCatalogue,
 }
 
 #[derive(Clone, Serialize, Deserialize, From, Debug, PartialEq)]
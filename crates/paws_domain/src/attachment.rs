@@ -135,7 +135,15 @@ impl FileTag {
         ));
 
         let parse_path = nom::branch::alt((
-            // Try Windows drive path first (letter:path)
+            // Try resource URIs first (scheme://anything), they may contain ':' and '#'
+            nom::combinator::recognize((
+                nom::bytes::complete::take_while_m_n(2, usize::MAX, |c: char| {
+                    c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.')
+                }),
+                tag("://"),
+                take_while1(|c: char| c != ']'),
+            )),
+            // Then Windows drive path (letter:path)
             nom::combinator::recognize((
                 nom::character::complete::satisfy(|c| c.is_ascii_alphabetic()),
                 nom::character::complete::char(':'),
@@ -163,6 +171,14 @@ impl FileTag {
     }
 }
 
+impl FileTag {
+    /// Returns true if the tag references a resource URI (eg:
+    /// `@[file:///notes.md]`) exposed by an MCP server instead of a path
+    pub fn is_resource(&self) -> bool {
+        self.path.contains("://")
+    }
+}
+
 impl AsRef<std::path::Path> for FileTag {
     fn as_ref(&self) -> &std::path::Path {
         std::path::Path::new(&self.path)
@@ -192,6 +208,30 @@ mod tests {
         assert_eq!(path_found.path, "/path/to/file.txt");
     }
 
+    #[test]
+    fn test_attachment_parse_all_resource_uri() {
+        let text =
+            String::from("Summarize @[postgres://db/tables/users#schema:v2] and @[/a.rs:1:2]");
+        let actual = Attachment::parse_all(text);
+        let expected = vec![
+            FileTag {
+                path: "postgres://db/tables/users#schema:v2".to_string(),
+                loc: None,
+                symbol: None,
+            },
+            FileTag {
+                path: "/a.rs".to_string(),
+                loc: Some(Location { start: Some(1), end: Some(2) }),
+                symbol: None,
+            },
+        ];
+        assert_eq!(actual, expected);
+        assert_eq!(
+            actual.iter().map(FileTag::is_resource).collect::<Vec<_>>(),
+            vec![true, false]
+        );
+    }
+
     #[test]
     fn test_attachment_parse_all_with_spaces() {
         let text = String::from("Check this file @[/path/with spaces/file.txt]");
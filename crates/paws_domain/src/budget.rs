@@ -0,0 +1,253 @@
+use std::fmt;
+
+use derive_setters::Setters;
+use merge::Merge;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::Usage;
+
+/// Spending limits that interrupt the orchestrator once reached. Cost limits
+/// are in USD and only apply when the provider reports the cost of a request.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Merge, Setters, JsonSchema, PartialEq)]
+#[setters(strip_option, into)]
+pub struct Budget {
+    /// Maximum cost of a single turn
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub max_cost_per_turn: Option<f64>,
+
+    /// Maximum number of tokens used by a single turn
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub max_tokens_per_turn: Option<usize>,
+
+    /// Maximum cost of a conversation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub max_cost_per_conversation: Option<f64>,
+
+    /// Maximum number of tokens used by a conversation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub max_tokens_per_conversation: Option<usize>,
+
+    /// Maximum cost per day across all conversations in the workspace
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub max_cost_per_day: Option<f64>,
+
+    /// Maximum number of tokens per day across all conversations in the
+    /// workspace
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub max_tokens_per_day: Option<usize>,
+}
+
+/// The period a budget limit applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum_macros::Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum BudgetScope {
+    Turn,
+    Conversation,
+    Day,
+}
+
+/// A budget limit that has been reached
+#[derive(Debug, Clone, PartialEq)]
+pub enum BudgetExceeded {
+    Cost {
+        scope: BudgetScope,
+        limit: f64,
+        spent: f64,
+    },
+    Tokens {
+        scope: BudgetScope,
+        limit: usize,
+        spent: usize,
+    },
+}
+
+impl BudgetExceeded {
+    pub fn scope(&self) -> BudgetScope {
+        match self {
+            BudgetExceeded::Cost { scope, .. } | BudgetExceeded::Tokens { scope, .. } => *scope,
+        }
+    }
+}
+
+impl fmt::Display for BudgetExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BudgetExceeded::Cost { scope, limit, spent } => {
+                write!(
+                    f,
+                    "{scope} cost budget of ${limit:.2} reached (${spent:.2} spent)"
+                )
+            }
+            BudgetExceeded::Tokens { scope, limit, spent } => {
+                write!(f, "{scope} token budget of {limit} reached ({spent} used)")
+            }
+        }
+    }
+}
+
+impl Budget {
+    /// Returns the first limit that the given usage has reached, checking the
+    /// narrowest scope first
+    pub fn check(&self, turn: &Usage, conversation: &Usage, day: &Usage) -> Option<BudgetExceeded> {
+        [
+            (
+                BudgetScope::Turn,
+                self.max_cost_per_turn,
+                self.max_tokens_per_turn,
+                turn,
+            ),
+            (
+                BudgetScope::Conversation,
+                self.max_cost_per_conversation,
+                self.max_tokens_per_conversation,
+                conversation,
+            ),
+            (
+                BudgetScope::Day,
+                self.max_cost_per_day,
+                self.max_tokens_per_day,
+                day,
+            ),
+        ]
+        .into_iter()
+        .find_map(|(scope, max_cost, max_tokens, usage)| {
+            let spent = usage.cost.unwrap_or_default();
+            let used = *usage.total_tokens;
+            match (max_cost, max_tokens) {
+                (Some(limit), _) if spent >= limit => {
+                    Some(BudgetExceeded::Cost { scope, limit, spent })
+                }
+                (_, Some(limit)) if used >= limit => {
+                    Some(BudgetExceeded::Tokens { scope, limit, spent: used })
+                }
+                _ => None,
+            }
+        })
+    }
+
+    /// Returns true if any conversation limit is configured, which requires
+    /// looking up the usage ledger of the conversation
+    pub fn has_conversation_limit(&self) -> bool {
+        self.max_cost_per_conversation.is_some() || self.max_tokens_per_conversation.is_some()
+    }
+
+    /// Returns true if any daily limit is configured, which requires looking
+    /// up the usage of the whole workspace
+    pub fn has_daily_limit(&self) -> bool {
+        self.max_cost_per_day.is_some() || self.max_tokens_per_day.is_some()
+    }
+
+    /// Raises the limit that was reached so that the same amount can be spent
+    /// again. Turn limits are left untouched since continuing starts a new
+    /// turn anyway.
+    pub fn extend(mut self, exceeded: &BudgetExceeded) -> Self {
+        match *exceeded {
+            BudgetExceeded::Cost { scope, limit, spent } => match scope {
+                BudgetScope::Turn => {}
+                BudgetScope::Conversation => self.max_cost_per_conversation = Some(spent + limit),
+                BudgetScope::Day => self.max_cost_per_day = Some(spent + limit),
+            },
+            BudgetExceeded::Tokens { scope, limit, spent } => match scope {
+                BudgetScope::Turn => {}
+                BudgetScope::Conversation => self.max_tokens_per_conversation = Some(spent + limit),
+                BudgetScope::Day => self.max_tokens_per_day = Some(spent + limit),
+            },
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::TokenCount;
+
+    fn usage(tokens: usize, cost: Option<f64>) -> Usage {
+        Usage {
+            total_tokens: TokenCount::Actual(tokens),
+            cost,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_check_within_budget() {
+        let fixture = Budget::default()
+            .max_cost_per_turn(1.0)
+            .max_tokens_per_conversation(1000usize);
+
+        let actual = fixture.check(&usage(10, Some(0.5)), &usage(500, None), &usage(0, None));
+
+        assert_eq!(actual, None);
+    }
+
+    #[test]
+    fn test_check_reports_narrowest_scope_first() {
+        let fixture = Budget::default()
+            .max_cost_per_turn(1.0)
+            .max_cost_per_day(2.0);
+
+        let actual = fixture.check(
+            &usage(10, Some(1.5)),
+            &usage(10, Some(1.5)),
+            &usage(10, Some(3.0)),
+        );
+
+        let expected =
+            Some(BudgetExceeded::Cost { scope: BudgetScope::Turn, limit: 1.0, spent: 1.5 });
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_check_tokens_per_day() {
+        let fixture = Budget::default().max_tokens_per_day(1000usize);
+
+        let actual = fixture.check(&usage(10, None), &usage(10, None), &usage(1200, None));
+
+        let expected =
+            Some(BudgetExceeded::Tokens { scope: BudgetScope::Day, limit: 1000, spent: 1200 });
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_check_ignores_unknown_cost() {
+        let fixture = Budget::default().max_cost_per_conversation(0.0001);
+
+        let actual = fixture.check(&usage(10, None), &usage(10, None), &usage(10, None));
+
+        assert_eq!(actual, None);
+    }
+
+    #[test]
+    fn test_extend_conversation_cost() {
+        let fixture = Budget::default().max_cost_per_conversation(5.0);
+
+        let actual = fixture.extend(&BudgetExceeded::Cost {
+            scope: BudgetScope::Conversation,
+            limit: 5.0,
+            spent: 5.5,
+        });
+
+        let expected = Budget::default().max_cost_per_conversation(10.5);
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_display() {
+        let fixture = BudgetExceeded::Cost { scope: BudgetScope::Day, limit: 20.0, spent: 20.126 };
+
+        let actual = fixture.to_string();
+
+        let expected = "day cost budget of $20.00 reached ($20.13 spent)";
+        assert_eq!(actual, expected);
+    }
+}
@@ -1,17 +1,31 @@
 use derive_setters::Setters;
 use serde::{Deserialize, Serialize};
 
-use crate::{ConversationId, Event};
+use crate::{AgentId, Budget, ConversationId, Event};
 
 #[derive(Debug, Serialize, Deserialize, Clone, Setters)]
 #[setters(into, strip_option)]
 pub struct ChatRequest {
     pub event: Event,
     pub conversation_id: ConversationId,
+
+    /// Overrides the configured spending limits for this request, used to
+    /// extend a budget that has been reached
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget: Option<Budget>,
+
+    /// Runs the request with this agent instead of the active one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent_id: Option<AgentId>,
 }
 
 impl ChatRequest {
     pub fn new(content: Event, conversation_id: ConversationId) -> Self {
-        Self { event: content, conversation_id }
+        Self {
+            event: content,
+            conversation_id,
+            budget: None,
+            agent_id: None,
+        }
     }
 }
@@ -3,7 +3,9 @@ use std::time::Duration;
 
 use chrono::Local;
 
-use crate::{ToolCallFull, ToolName, ToolResult};
+use crate::{
+    BudgetExceeded, FollowUpQuestion, FollowUpReply, RateLimit, ToolCallFull, ToolName, ToolResult,
+};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ChatResponseContent {
@@ -46,13 +48,29 @@ impl ChatResponseContent {
 /// events for all internal state changes.
 #[derive(Debug, Clone)]
 pub enum ChatResponse {
-    TaskMessage { content: ChatResponseContent },
-    TaskReasoning { content: String },
+    TaskMessage {
+        content: ChatResponseContent,
+    },
+    TaskReasoning {
+        content: String,
+    },
     TaskComplete,
     ToolCallStart(ToolCallFull),
     ToolCallEnd(ToolResult),
-    RetryAttempt { cause: Cause, duration: Duration },
-    Interrupt { reason: InterruptionReason },
+    RetryAttempt {
+        cause: Cause,
+        duration: Duration,
+        /// Remaining quota, when the provider reported its rate limits
+        rate_limit: Option<RateLimit>,
+    },
+    Interrupt {
+        reason: InterruptionReason,
+    },
+    /// The agent waits for an answer to the question, sent through `reply`
+    FollowUp {
+        question: FollowUpQuestion,
+        reply: FollowUpReply,
+    },
 }
 
 impl ChatResponse {
@@ -83,6 +101,9 @@ pub enum InterruptionReason {
     MaxRequestPerTurnLimitReached {
         limit: u64,
     },
+    BudgetLimitReached {
+        budget: BudgetExceeded,
+    },
 }
 
 #[derive(Clone)]
@@ -0,0 +1,62 @@
+use chrono::{DateTime, Utc};
+use derive_setters::Setters;
+use serde::{Deserialize, Serialize};
+
+use crate::Snapshot;
+
+/// Records the files touched during a single user turn so that the
+/// conversation and the workspace can be rewound to the point right before the
+/// turn started.
+#[derive(Debug, Clone, PartialEq, Setters, Serialize, Deserialize)]
+#[setters(into)]
+pub struct Checkpoint {
+    /// 1-based position of the turn's prompt among all the user prompts of the
+    /// conversation
+    pub turn: usize,
+
+    pub started_at: DateTime<Utc>,
+
+    /// Files modified during the turn, in the order they were modified
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<FileCheckpoint>,
+}
+
+impl Checkpoint {
+    pub fn new(turn: usize, started_at: DateTime<Utc>) -> Self {
+        Self { turn, started_at, files: Vec::new() }
+    }
+}
+
+/// A single modification of a file within a turn
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileCheckpoint {
+    pub path: String,
+
+    /// Snapshot of the file taken right before it was modified. `None` when
+    /// the file didn't exist yet.
+    pub snapshot: Option<Snapshot>,
+}
+
+impl FileCheckpoint {
+    pub fn new(path: impl Into<String>, snapshot: Option<Snapshot>) -> Self {
+        Self { path: path.into(), snapshot }
+    }
+}
+
+/// Contains information about a conversation that was rewound to an earlier
+/// turn
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RewindResult {
+    /// The turn the conversation was rewound to
+    pub turn: usize,
+    /// Number of messages removed from the context
+    pub removed_messages: usize,
+    /// Files that were restored to their state at the end of `turn`
+    pub restored_files: Vec<String>,
+}
+
+impl RewindResult {
+    pub fn new(turn: usize, removed_messages: usize, restored_files: Vec<String>) -> Self {
+        Self { turn, removed_messages, restored_files }
+    }
+}
@@ -0,0 +1,300 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Share of the use case similarity in the score of reranked search results
+const USE_CASE_WEIGHT: f32 = 0.4;
+
+/// A vector representation of a piece of text. Stored as base64 encoded
+/// little-endian floats, which is far more compact than a JSON array.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Embedding(pub Vec<f32>);
+
+impl Embedding {
+    /// Cosine similarity between two embeddings, 0 when either is empty or
+    /// their dimensions differ
+    pub fn similarity(&self, other: &Embedding) -> f32 {
+        if self.0.len() != other.0.len() || self.0.is_empty() {
+            return 0.0;
+        }
+        let dot: f32 = self.0.iter().zip(&other.0).map(|(a, b)| a * b).sum();
+        let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+        let norms = norm(&self.0) * norm(&other.0);
+        if norms == 0.0 { 0.0 } else { dot / norms }
+    }
+}
+
+impl Serialize for Embedding {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let bytes: Vec<u8> = self
+            .0
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect();
+        serializer.serialize_str(&STANDARD.encode(bytes))
+    }
+}
+
+impl<'de> Deserialize<'de> for Embedding {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        let bytes = STANDARD.decode(encoded).map_err(serde::de::Error::custom)?;
+        if bytes.len() % 4 != 0 {
+            return Err(serde::de::Error::custom(
+                "embedding length is not a multiple of 4",
+            ));
+        }
+        let values = bytes
+            .chunks_exact(4)
+            .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+            .collect();
+        Ok(Embedding(values))
+    }
+}
+
+/// A contiguous range of lines of a source file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CodeChunk {
+    /// 1-based first line of the chunk
+    pub start_line: usize,
+    /// 1-based last line of the chunk, inclusive
+    pub end_line: usize,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexedChunk {
+    #[serde(flatten)]
+    pub chunk: CodeChunk,
+    pub embedding: Embedding,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexedFile {
+    /// Hash of the content the chunks were computed from
+    pub hash: String,
+    pub chunks: Vec<IndexedChunk>,
+}
+
+/// Embeddings of every chunk of the workspace, keyed by path relative to the
+/// workspace root
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CodeIndex {
+    /// Identifies the embedding backend and model, embeddings of different
+    /// embedders can't be compared
+    pub embedder: String,
+    pub updated_at: DateTime<Utc>,
+    pub files: BTreeMap<String, IndexedFile>,
+}
+
+impl CodeIndex {
+    pub fn new(embedder: impl Into<String>) -> Self {
+        Self {
+            embedder: embedder.into(),
+            updated_at: Utc::now(),
+            files: BTreeMap::new(),
+        }
+    }
+
+    pub fn chunk_count(&self) -> usize {
+        self.files.values().map(|file| file.chunks.len()).sum()
+    }
+
+    /// Retrieves the `limit` chunks most similar to `query`, optionally
+    /// restricted to files with the given extension, then reranks them by
+    /// their similarity to `use_case` and keeps the best `top_k`
+    pub fn search(
+        &self,
+        query: &Embedding,
+        use_case: &Embedding,
+        limit: usize,
+        top_k: usize,
+        file_extension: Option<&str>,
+    ) -> Vec<SearchHit> {
+        let extension = file_extension.map(|ext| ext.trim_start_matches('.'));
+        let mut candidates: Vec<(&str, &IndexedChunk, f32)> = self
+            .files
+            .iter()
+            .filter(|(path, _)| {
+                extension.is_none_or(|ext| {
+                    std::path::Path::new(path)
+                        .extension()
+                        .is_some_and(|actual| actual.eq_ignore_ascii_case(ext))
+                })
+            })
+            .flat_map(|(path, file)| {
+                file.chunks.iter().map(move |indexed| {
+                    (path.as_str(), indexed, query.similarity(&indexed.embedding))
+                })
+            })
+            .collect();
+        candidates.sort_by(|a, b| b.2.total_cmp(&a.2));
+        candidates.truncate(limit);
+
+        let mut hits: Vec<SearchHit> = candidates
+            .into_iter()
+            .map(|(path, indexed, score)| SearchHit {
+                path: path.to_string(),
+                chunk: indexed.chunk.clone(),
+                score: (1.0 - USE_CASE_WEIGHT) * score
+                    + USE_CASE_WEIGHT * use_case.similarity(&indexed.embedding),
+            })
+            .collect();
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+        hits.truncate(top_k);
+        hits
+    }
+}
+
+/// A chunk matching a semantic search query
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchHit {
+    /// Path relative to the workspace root
+    pub path: String,
+    pub chunk: CodeChunk,
+    pub score: f32,
+}
+
+/// Files of the workspace that differ from the index
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IndexChanges {
+    pub added: usize,
+    pub modified: usize,
+    pub removed: usize,
+    pub unchanged: usize,
+}
+
+impl IndexChanges {
+    pub fn is_empty(&self) -> bool {
+        self.added == 0 && self.modified == 0 && self.removed == 0
+    }
+}
+
+/// State of the semantic search index of a workspace
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexStatus {
+    /// Where the index is stored
+    pub path: PathBuf,
+    pub embedder: String,
+    pub files: usize,
+    pub chunks: usize,
+    /// `None` when the workspace has never been indexed
+    pub updated_at: Option<DateTime<Utc>>,
+    /// Changes applied by a build, or still pending when reporting the status
+    pub changes: IndexChanges,
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn indexed(content: &str, embedding: Vec<f32>) -> IndexedChunk {
+        IndexedChunk {
+            chunk: CodeChunk { start_line: 1, end_line: 1, content: content.to_string() },
+            embedding: Embedding(embedding),
+        }
+    }
+
+    fn fixture() -> CodeIndex {
+        let mut index = CodeIndex::new("test");
+        index.files.insert(
+            "src/retry.rs".to_string(),
+            IndexedFile {
+                hash: "a".to_string(),
+                chunks: vec![
+                    indexed("retry", vec![1.0, 0.0]),
+                    indexed("backoff", vec![0.8, 0.6]),
+                ],
+            },
+        );
+        index.files.insert(
+            "web/login.ts".to_string(),
+            IndexedFile {
+                hash: "b".to_string(),
+                chunks: vec![indexed("login", vec![0.0, 1.0])],
+            },
+        );
+        index
+    }
+
+    #[test]
+    fn test_embedding_round_trip() {
+        let fixture = Embedding(vec![0.5, -1.25, 3.0]);
+
+        let json = serde_json::to_string(&fixture).unwrap();
+        let actual: Embedding = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(actual, fixture);
+    }
+
+    #[test]
+    fn test_similarity() {
+        let actual = Embedding(vec![1.0, 0.0]).similarity(&Embedding(vec![2.0, 0.0]));
+
+        assert_eq!(actual, 1.0);
+    }
+
+    #[test]
+    fn test_similarity_of_mismatched_dimensions() {
+        let actual = Embedding(vec![1.0, 0.0]).similarity(&Embedding(vec![1.0]));
+
+        assert_eq!(actual, 0.0);
+    }
+
+    #[test]
+    fn test_search_ranks_by_similarity() {
+        let actual = fixture()
+            .search(
+                &Embedding(vec![1.0, 0.1]),
+                &Embedding(vec![1.0, 0.0]),
+                10,
+                2,
+                None,
+            )
+            .into_iter()
+            .map(|hit| hit.chunk.content)
+            .collect::<Vec<_>>();
+
+        assert_eq!(actual, vec!["retry", "backoff"]);
+    }
+
+    #[test]
+    fn test_search_reranks_by_use_case() {
+        let actual = fixture()
+            .search(
+                &Embedding(vec![1.0, 0.0]),
+                &Embedding(vec![0.0, 1.0]),
+                2,
+                1,
+                None,
+            )
+            .into_iter()
+            .map(|hit| hit.chunk.content)
+            .collect::<Vec<_>>();
+
+        assert_eq!(actual, vec!["backoff"]);
+    }
+
+    #[test]
+    fn test_search_filters_by_extension() {
+        let actual = fixture()
+            .search(
+                &Embedding(vec![1.0, 0.0]),
+                &Embedding(vec![1.0, 0.0]),
+                10,
+                10,
+                Some(".ts"),
+            )
+            .into_iter()
+            .map(|hit| hit.path)
+            .collect::<Vec<_>>();
+
+        assert_eq!(actual, vec!["web/login.ts"]);
+    }
+}
@@ -36,6 +36,19 @@ pub struct Compact {
     #[merge(strategy = crate::merge::option)]
     pub token_threshold: Option<usize>,
 
+    /// Percentage of the active model's context window above which
+    /// compaction is triggered. Valid values are between 0.0 and 1.0. It is
+    /// evaluated against the model in use for every request, so the threshold
+    /// follows model switches. Works alongside token_threshold - whichever is
+    /// reached first triggers compaction.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_optional_percentage"
+    )]
+    #[merge(strategy = crate::merge::option)]
+    pub context_threshold: Option<f64>,
+
     /// Maximum number of conversation turns before triggering compaction
     #[serde(skip_serializing_if = "Option::is_none")]
     #[merge(strategy = crate::merge::option)]
@@ -62,6 +75,26 @@ pub struct Compact {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[merge(strategy = crate::merge::option)]
     pub on_turn_end: Option<bool>,
+
+    /// How the compacted messages are summarized. Defaults to a structured
+    /// summary of the tool calls.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub summary_strategy: Option<SummaryStrategy>,
+}
+
+/// Strategy used to summarize the messages evicted by compaction
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SummaryStrategy {
+    /// Lists the files read and modified, the commands run and the messages
+    /// exchanged, extracted mechanically from the conversation
+    #[default]
+    Structured,
+    /// Additionally asks the compaction model for a narrative of the
+    /// decisions, progress and open questions. Falls back to the structured
+    /// summary when the model fails to respond.
+    Llm,
 }
 
 fn deserialize_percentage<'de, D>(deserializer: D) -> Result<f64, D::Error>
@@ -79,6 +112,16 @@ where
     Ok(value)
 }
 
+fn deserialize_optional_percentage<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct Percentage(#[serde(deserialize_with = "deserialize_percentage")] f64);
+
+    Ok(Option::<Percentage>::deserialize(deserializer)?.map(|percentage| percentage.0))
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, PartialEq)]
 #[serde(transparent)]
 pub struct SummaryTag(String);
@@ -108,6 +151,7 @@ impl Compact {
         Self {
             max_tokens: None,
             token_threshold: None,
+            context_threshold: None,
             turn_threshold: None,
             message_threshold: None,
             summary_tag: None,
@@ -115,9 +159,25 @@ impl Compact {
             eviction_window: 0.2, // Default to 20% compaction
             retention_window: 0,
             on_turn_end: None,
+            summary_strategy: None,
         }
     }
 
+    /// Resolves `context_threshold` against the context window of the active
+    /// model, keeping the lower of the resulting token count and
+    /// `token_threshold`. Without a known context length the configuration is
+    /// returned unchanged.
+    pub fn for_context_window(mut self, context_length: Option<u64>) -> Self {
+        if let (Some(percentage), Some(context_length)) = (self.context_threshold, context_length) {
+            let threshold = (percentage * context_length as f64) as usize;
+            self.token_threshold = Some(
+                self.token_threshold
+                    .map_or(threshold, |token_threshold| token_threshold.min(threshold)),
+            );
+        }
+        self
+    }
+
     /// Determines if compaction should be triggered based on the current
     /// context
     pub fn should_compact(&self, context: &Context, token_count: usize) -> bool {
@@ -508,6 +568,49 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn test_for_context_window_resolves_percentage() {
+        let fixture = Compact::new().context_threshold(0.8);
+
+        let actual = fixture.for_context_window(Some(10_000)).token_threshold;
+
+        assert_eq!(actual, Some(8_000));
+    }
+
+    #[test]
+    fn test_for_context_window_keeps_lower_token_threshold() {
+        let fixture = Compact::new()
+            .token_threshold(5_000_usize)
+            .context_threshold(0.8);
+
+        let actual = fixture
+            .clone()
+            .for_context_window(Some(10_000))
+            .token_threshold;
+        assert_eq!(actual, Some(5_000));
+
+        let actual = fixture.for_context_window(Some(4_000)).token_threshold;
+        assert_eq!(actual, Some(3_200));
+    }
+
+    #[test]
+    fn test_for_context_window_unknown_context_length() {
+        let fixture = Compact::new()
+            .token_threshold(5_000_usize)
+            .context_threshold(0.5);
+
+        let actual = fixture.for_context_window(None).token_threshold;
+
+        assert_eq!(actual, Some(5_000));
+    }
+
+    #[test]
+    fn test_context_threshold_rejects_invalid_percentage() {
+        let actual = serde_json::from_str::<Compact>(r#"{"context_threshold": 1.5}"#);
+
+        assert!(actual.is_err());
+    }
+
     #[test]
     fn test_compact_model_none_falls_back_to_agent_model() {
         // Fixture
@@ -1,11 +1,13 @@
 use std::collections::HashMap;
 use std::ops::Deref;
+use std::path::Path;
 
 use derive_more::From;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    Context, ContextMessage, Role, TextMessage, ToolCallFull, ToolCallId, ToolCatalog, ToolResult,
+    Context, ContextMessage, Role, SearchQuery, TextMessage, ToolCallFull, ToolCallId, ToolCatalog,
+    ToolResult,
 };
 
 /// A simplified summary of a context, focusing on messages and their tool calls
@@ -164,6 +166,7 @@ pub enum SummaryTool {
     FileRemove { path: String },
     Shell { command: String },
     Search { pattern: String },
+    SemSearch { queries: Vec<SearchQuery> },
     Undo { path: String },
     Fetch { url: String },
     Followup { question: String },
@@ -242,8 +245,8 @@ impl From<&TextMessage> for Vec<SummaryMessage> {
 
         // Add tool call blocks if present
         if let Some(calls) = &text_msg.tool_calls {
-            blocks.extend(calls.iter().filter_map(|tool_call| {
-                extract_tool_info(tool_call).map(|call| {
+            blocks.extend(calls.iter().flat_map(|tool_call| {
+                extract_tool_info(tool_call).into_iter().map(|call| {
                     SummaryMessage::ToolCall(SummaryToolCall {
                         id: tool_call.call_id.clone(),
                         tool: call,
@@ -257,28 +260,45 @@ impl From<&TextMessage> for Vec<SummaryMessage> {
     }
 }
 
-/// Extracts tool information from a tool call
-fn extract_tool_info(call: &ToolCallFull) -> Option<SummaryTool> {
+/// Extracts tool information from a tool call. Tools touching several files
+/// yield one entry per file.
+fn extract_tool_info(call: &ToolCallFull) -> Vec<SummaryTool> {
     // Try to parse as a Tools enum variant
-    let tool = ToolCatalog::try_from(call.clone()).ok()?;
+    let Ok(tool) = ToolCatalog::try_from(call.clone()) else {
+        return vec![];
+    };
 
-    match tool {
+    let info = match tool {
         ToolCatalog::Read(input) => Some(SummaryTool::FileRead { path: input.path }),
         ToolCatalog::ReadImage(input) => Some(SummaryTool::FileRead { path: input.path }),
         ToolCatalog::Write(input) => Some(SummaryTool::FileUpdate { path: input.path }),
         ToolCatalog::Patch(input) => Some(SummaryTool::FileUpdate { path: input.path }),
+        ToolCatalog::MultiPatch(input) => {
+            // Paths are kept as written since the working directory is unknown
+            return input
+                .paths(Path::new(""))
+                .into_iter()
+                .map(|path| SummaryTool::FileUpdate { path: path.to_string_lossy().to_string() })
+                .collect();
+        }
         ToolCatalog::Remove(input) => Some(SummaryTool::FileRemove { path: input.path }),
         ToolCatalog::Shell(input) => Some(SummaryTool::Shell { command: input.command }),
+        // Job handles carry no information worth keeping in a summary
+        ToolCatalog::ShellRead(_) | ToolCatalog::ShellWait(_) | ToolCatalog::ShellKill(_) => None,
         ToolCatalog::Search(input) => input
             .file_pattern
             .or(input.regex)
             .map(|pattern| SummaryTool::Search { pattern }),
+        ToolCatalog::SemSearch(input) => Some(SummaryTool::SemSearch { queries: input.queries }),
+        ToolCatalog::Outline(input) => Some(SummaryTool::FileRead { path: input.path }),
+        ToolCatalog::FindSymbol(input) => Some(SummaryTool::Search { pattern: input.name }),
         ToolCatalog::Undo(input) => Some(SummaryTool::Undo { path: input.path }),
         ToolCatalog::Fetch(input) => Some(SummaryTool::Fetch { url: input.url }),
         ToolCatalog::Followup(input) => Some(SummaryTool::Followup { question: input.question }),
         ToolCatalog::Plan(input) => Some(SummaryTool::Plan { plan_name: input.plan_name }),
         ToolCatalog::Skill(input) => Some(SummaryTool::Skill { name: input.name }),
-    }
+    };
+    info.into_iter().collect()
 }
 
 #[cfg(test)]
@@ -778,7 +798,7 @@ mod tests {
 
         let actual = extract_tool_info(&fixture);
 
-        assert_eq!(actual, None);
+        assert_eq!(actual, vec![]);
     }
 
     #[test]
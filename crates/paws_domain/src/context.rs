@@ -518,9 +518,10 @@ impl Context {
         reasoning_details: Option<Vec<ReasoningFull>>,
         usage: Usage,
         tool_records: Vec<(ToolCallFull, ToolResult)>,
+        model: ModelId,
     ) -> Self {
         // Adding tool calls
-        let message: MessageEntry = ContextMessage::assistant(
+        let mut message = ContextMessage::assistant(
             content,
             reasoning_details,
             Some(
@@ -529,8 +530,12 @@ impl Context {
                     .map(|record| record.0.clone())
                     .collect::<Vec<_>>(),
             ),
-        )
-        .into();
+        );
+        // Records the model that served the message
+        if let ContextMessage::Text(text) = &mut message {
+            text.model = Some(model);
+        }
+        let message: MessageEntry = message.into();
 
         let tool_results = tool_records
             .iter()
@@ -602,6 +607,17 @@ impl Context {
         result
     }
 
+    /// Returns the indices of the messages that carry a user prompt, ie. the
+    /// messages that start a new turn.
+    pub fn prompt_indices(&self) -> Vec<usize> {
+        self.messages
+            .iter()
+            .enumerate()
+            .filter(|(_, msg)| msg.has_role(Role::User) && msg.as_value().is_some())
+            .map(|(index, _)| index)
+            .collect()
+    }
+
     /// Returns the total number of messages in the context
     pub fn total_messages(&self) -> usize {
         self.messages.len()
@@ -6,7 +6,7 @@ use derive_setters::Setters;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::{Context, Error, Metrics, Result, TokenCount};
+use crate::{Context, Error, FileCheckpoint, Metrics, Result, Role, TokenCount};
 
 #[derive(Debug, Default, Display, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(transparent)]
@@ -48,6 +48,20 @@ pub struct Conversation {
     pub metadata: MetaData,
 }
 
+/// A conversation found by a full-text search, along with an excerpt of the
+/// text that matched
+#[derive(Debug, Clone)]
+pub struct ConversationMatch {
+    pub conversation: Conversation,
+    pub snippet: String,
+}
+
+impl ConversationMatch {
+    pub fn new(conversation: Conversation, snippet: impl Into<String>) -> Self {
+        Self { conversation, snippet: snippet.into() }
+    }
+}
+
 #[derive(Debug, Setters, Serialize, Deserialize, Clone)]
 #[setters(into)]
 pub struct MetaData {
@@ -128,4 +142,176 @@ impl Conversation {
     pub fn accumulated_cost(&self) -> Option<f64> {
         self.accumulated_usage().and_then(|usage| usage.cost)
     }
+
+    /// Returns the text of the user and assistant messages, one message per
+    /// line, for indexing the conversation in a full-text search
+    pub fn searchable_text(&self) -> String {
+        self.context
+            .iter()
+            .flat_map(|ctx| ctx.messages.iter())
+            .filter(|entry| !entry.has_role(Role::System))
+            .filter_map(|entry| entry.content())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Rewinds the conversation to the end of the given turn by dropping the
+    /// messages and checkpoints of every later turn. Returns the file
+    /// modifications recorded after that turn, in the order they happened, so
+    /// that the workspace can be restored as well.
+    ///
+    /// # Errors
+    /// - If no checkpoint was recorded for the turn following `turn`
+    /// - If the prompt of the turn following `turn` was compacted
+    pub fn rewind(&mut self, turn: usize) -> Result<Vec<FileCheckpoint>> {
+        let checkpoints = &self.metrics.checkpoints;
+        let position = checkpoints
+            .iter()
+            .position(|checkpoint| checkpoint.turn == turn + 1)
+            .ok_or(Error::CheckpointNotFound(turn))?;
+        let latest = checkpoints
+            .last()
+            .map(|checkpoint| checkpoint.turn)
+            .unwrap_or_default();
+
+        let context = self
+            .context
+            .as_mut()
+            .ok_or(Error::CheckpointNotFound(turn))?;
+        let prompts = context.prompt_indices();
+
+        // Compaction summarises a contiguous range of messages that follows the
+        // first prompt, so the prompts that survive it are the first one and the
+        // most recent ones.
+        let evicted = latest.saturating_sub(prompts.len());
+        let index = match turn {
+            0 => prompts.first(),
+            turn if turn > evicted => prompts.get(turn - evicted),
+            _ => None,
+        }
+        .copied()
+        .ok_or(Error::CheckpointCompacted(turn))?;
+
+        context.messages.truncate(index);
+        Ok(self
+            .metrics
+            .checkpoints
+            .split_off(position)
+            .into_iter()
+            .flat_map(|checkpoint| checkpoint.files)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{Checkpoint, ContextMessage, EventValue, TextMessage};
+
+    fn prompt(content: &str) -> ContextMessage {
+        TextMessage::new(Role::User, content)
+            .raw_content(EventValue::text(content))
+            .into()
+    }
+
+    fn fixture(turns: usize) -> Conversation {
+        let mut context = Context::default().add_message(ContextMessage::system("system"));
+        let mut metrics = Metrics::default();
+        for turn in 1..=turns {
+            context = context
+                .add_message(prompt(&format!("prompt {turn}")))
+                .add_message(ContextMessage::assistant(
+                    format!("answer {turn}"),
+                    None,
+                    None,
+                ));
+            metrics = metrics
+                .begin_turn(turn - 1, Utc::now())
+                .record_snapshot(format!("/file_{turn}.rs"), None);
+        }
+        Conversation::generate().context(context).metrics(metrics)
+    }
+
+    fn contents(conversation: &Conversation) -> Vec<String> {
+        conversation
+            .context
+            .iter()
+            .flat_map(|context| context.messages.iter())
+            .filter_map(|message| message.content().map(str::to_string))
+            .collect()
+    }
+
+    #[test]
+    fn test_rewind_truncates_context_and_checkpoints() {
+        let mut fixture = fixture(3);
+
+        let actual = fixture.rewind(1).unwrap();
+
+        let expected = vec![
+            FileCheckpoint::new("/file_2.rs", None),
+            FileCheckpoint::new("/file_3.rs", None),
+        ];
+        assert_eq!(actual, expected);
+        assert_eq!(contents(&fixture), vec!["system", "prompt 1", "answer 1"]);
+        let turns = fixture
+            .metrics
+            .checkpoints
+            .iter()
+            .map(|checkpoint| checkpoint.turn)
+            .collect::<Vec<_>>();
+        assert_eq!(turns, vec![1]);
+    }
+
+    #[test]
+    fn test_rewind_to_turn_zero_keeps_system_prompt() {
+        let mut fixture = fixture(2);
+
+        fixture.rewind(0).unwrap();
+
+        assert_eq!(contents(&fixture), vec!["system"]);
+        assert_eq!(fixture.metrics.checkpoints, Vec::<Checkpoint>::new());
+    }
+
+    #[test]
+    fn test_rewind_to_latest_turn_fails() {
+        let mut fixture = fixture(2);
+
+        let actual = fixture.rewind(2).unwrap_err().to_string();
+
+        let expected = Error::CheckpointNotFound(2).to_string();
+        assert_eq!(actual, expected);
+        assert_eq!(fixture.metrics.checkpoints.len(), 2);
+    }
+
+    #[test]
+    fn test_rewind_after_compaction() {
+        let mut fixture = fixture(4);
+        // Simulate compaction of the second and third turns into a summary
+        let context = fixture.context.take().unwrap();
+        let mut messages = context.messages.clone();
+        messages.splice(3..7, [ContextMessage::user("summary", None).into()]);
+        fixture.context = Some(context.messages(messages));
+
+        let compacted = fixture.clone().rewind(2).unwrap_err().to_string();
+        let actual = fixture.rewind(3).unwrap();
+
+        assert_eq!(compacted, Error::CheckpointCompacted(2).to_string());
+        assert_eq!(actual, vec![FileCheckpoint::new("/file_4.rs", None)]);
+        assert_eq!(
+            contents(&fixture),
+            vec!["system", "prompt 1", "answer 1", "summary"]
+        );
+    }
+
+    #[test]
+    fn test_searchable_text_skips_system_prompt() {
+        let fixture = fixture(2);
+
+        let actual = fixture.searchable_text();
+
+        let expected = "prompt 1\nanswer 1\nprompt 2\nanswer 2";
+        assert_eq!(actual, expected);
+    }
 }
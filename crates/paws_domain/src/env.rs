@@ -6,7 +6,7 @@ use derive_setters::Setters;
 use serde::{Deserialize, Serialize};
 use url::Url;
 
-use crate::{HttpConfig, ModelId, ProviderId, RetryConfig};
+use crate::{HttpConfig, ModelId, ProviderId, RetryConfig, SandboxPolicy};
 
 const VERSION: &str = match option_env!("APP_VERSION") {
     Some(val) => val,
@@ -58,6 +58,9 @@ pub struct Environment {
     /// Maximum execution time in seconds for a single tool call.
     /// Controls how long a tool can run before being terminated.
     pub tool_timeout: u64,
+    /// Maximum number of independent tool calls executed concurrently.
+    /// Controlled by FORGE_MAX_PARALLEL_TOOL_CALLS environment variable.
+    pub max_parallel_tool_calls: usize,
     /// Whether to automatically open HTML dump files in the browser.
     /// Controlled by FORGE_DUMP_AUTO_OPEN environment variable.
     pub auto_open_dump: bool,
@@ -91,6 +94,24 @@ pub struct Environment {
     /// Controlled by FORGE_ENABLE_PERMISSIONS environment variable.
     /// When enabled, tools will check policies before execution.
     pub enable_permissions: bool,
+    /// Whether to reject writes and patches that leave a file with syntax
+    /// errors instead of only reporting them.
+    /// Controlled by FORGE_REJECT_SYNTAX_ERRORS environment variable.
+    pub reject_syntax_errors: bool,
+    /// Provider whose OpenAI-compatible embeddings endpoint is used to index
+    /// the codebase. If None, a local offline embedding is used.
+    /// Controlled by FORGE_EMBEDDING_PROVIDER environment variable.
+    #[dummy(default)]
+    pub embedding_provider: Option<ProviderId>,
+    /// Embedding model requested from the embedding provider.
+    /// Controlled by FORGE_EMBEDDING_MODEL environment variable.
+    #[dummy(default)]
+    pub embedding_model: Option<String>,
+    /// OS-level sandbox shell commands run in. If None, commands are not
+    /// confined. Enabled by the `--os-sandbox` flag or FORGE_OS_SANDBOX
+    /// environment variable.
+    #[dummy(default)]
+    pub sandbox: Option<SandboxPolicy>,
 }
 
 impl Environment {
@@ -152,6 +173,13 @@ impl Environment {
         self.base_path.join("cache")
     }
 
+    /// Returns the path of the semantic search index of the workspace
+    pub fn index_path(&self) -> PathBuf {
+        self.base_path
+            .join("index")
+            .join(format!("{}.json", self.workspace_hash()))
+    }
+
     /// Returns the global skills directory path (~/paws/skills)
     pub fn global_skills_path(&self) -> PathBuf {
         self.base_path.join("skills")
@@ -170,7 +198,7 @@ impl Environment {
     }
 }
 
-#[derive(Clone, Copy, Display)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
 pub struct WorkspaceHash(u64);
 impl WorkspaceHash {
     pub fn new(id: u64) -> Self {
@@ -289,6 +317,7 @@ fn test_command_path() {
         http: HttpConfig::default(),
         max_file_size: 104857600,
         tool_timeout: 300,
+        max_parallel_tool_calls: 8,
         auto_open_dump: false,
         debug_requests: None,
         custom_history_path: None,
@@ -300,6 +329,10 @@ fn test_command_path() {
         override_model: None,
         override_provider: None,
         enable_permissions: false,
+        reject_syntax_errors: false,
+        embedding_provider: None,
+        embedding_model: None,
+        sandbox: None,
     };
 
     let actual = fixture.command_path();
@@ -329,6 +362,7 @@ fn test_command_cwd_path() {
         http: HttpConfig::default(),
         max_file_size: 104857600,
         tool_timeout: 300,
+        max_parallel_tool_calls: 8,
         auto_open_dump: false,
         debug_requests: None,
         custom_history_path: None,
@@ -340,6 +374,10 @@ fn test_command_cwd_path() {
         override_model: None,
         override_provider: None,
         enable_permissions: false,
+        reject_syntax_errors: false,
+        embedding_provider: None,
+        embedding_model: None,
+        sandbox: None,
     };
 
     let actual = fixture.command_cwd_path();
@@ -369,6 +407,7 @@ fn test_command_cwd_path_independent_from_command_path() {
         http: HttpConfig::default(),
         max_file_size: 104857600,
         tool_timeout: 300,
+        max_parallel_tool_calls: 8,
         auto_open_dump: false,
         debug_requests: None,
         custom_history_path: None,
@@ -380,6 +419,10 @@ fn test_command_cwd_path_independent_from_command_path() {
         override_model: None,
         override_provider: None,
         enable_permissions: false,
+        reject_syntax_errors: false,
+        embedding_provider: None,
+        embedding_model: None,
+        sandbox: None,
     };
 
     let command_path = fixture.command_path();
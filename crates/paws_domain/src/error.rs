@@ -59,6 +59,14 @@ pub enum Error {
     #[error("Conversation with ID '{0}' not found")]
     ConversationNotFound(ConversationId),
 
+    #[error("No checkpoint found to rewind to turn {0}")]
+    #[from(skip)]
+    CheckpointNotFound(usize),
+
+    #[error("Can't rewind to turn {0} because its messages have been compacted")]
+    #[from(skip)]
+    CheckpointCompacted(usize),
+
     #[error("Missing description for agent: {0}")]
     #[from(skip)]
     MissingAgentDescription(AgentId),
@@ -95,6 +103,10 @@ pub enum Error {
     #[error("No default model configured for provider: {0}")]
     #[from(skip)]
     NoDefaultModel(ProviderId),
+
+    #[error("Invalid diff: {0}")]
+    #[from(skip)]
+    InvalidDiff(String),
 }
 
 pub type Result<A> = std::result::Result<A, Error>;
@@ -0,0 +1,168 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, ModelId, ProviderId};
+
+/// A provider and model to switch to when the ones in use keep failing
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct ModelFallback {
+    /// Provider serving the fallback model
+    pub provider: ProviderId,
+    /// Model to switch to
+    pub model: ModelId,
+}
+
+impl ModelFallback {
+    pub fn new(provider: ProviderId, model: impl Into<ModelId>) -> Self {
+        Self { provider, model: model.into() }
+    }
+}
+
+/// Why the orchestrator switched to a fallback model
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum_macros::Display)]
+pub enum FallbackReason {
+    #[strum(to_string = "retries exhausted")]
+    RetriesExhausted,
+    #[strum(to_string = "context too long")]
+    ContextTooLong,
+    #[strum(to_string = "model not found")]
+    ModelNotFound,
+    #[strum(to_string = "quota exceeded")]
+    QuotaExceeded,
+}
+
+const CONTEXT_TOO_LONG: [&str; 5] = [
+    "context_length_exceeded",
+    "maximum context length",
+    "context window",
+    "prompt is too long",
+    "input is too long",
+];
+
+const MODEL_NOT_FOUND: [&str; 3] = ["model_not_found", "model not found", "unknown model"];
+
+const QUOTA_EXCEEDED: [&str; 4] = [
+    "insufficient_quota",
+    "quota exceeded",
+    "exceeded your current quota",
+    "credit balance is too low",
+];
+
+impl FallbackReason {
+    /// Returns the reason to switch to a fallback model after the request
+    /// failed with the given error, `None` when switching would not help.
+    /// Retryable errors only reach here once every retry has failed.
+    pub fn classify(error: &anyhow::Error) -> Option<Self> {
+        let messages: Vec<String> = error
+            .chain()
+            .map(|cause| cause.to_string().to_lowercase())
+            .collect();
+        let mentions = |patterns: &[&str]| {
+            messages
+                .iter()
+                .any(|message| patterns.iter().any(|pattern| message.contains(pattern)))
+        };
+
+        if mentions(&CONTEXT_TOO_LONG) {
+            Some(Self::ContextTooLong)
+        } else if mentions(&MODEL_NOT_FOUND) {
+            Some(Self::ModelNotFound)
+        } else if mentions(&QUOTA_EXCEEDED) {
+            Some(Self::QuotaExceeded)
+        } else if matches!(error.downcast_ref::<Error>(), Some(Error::Retryable(_))) {
+            Some(Self::RetriesExhausted)
+        } else {
+            None
+        }
+    }
+
+    /// Whether retrying the same model can't recover from the failure, so the
+    /// fallback should be used right away
+    pub fn is_immediate(&self) -> bool {
+        matches!(self, Self::ModelNotFound | Self::QuotaExceeded)
+    }
+
+    /// Makes errors calling for an immediate switch non-retryable, so the
+    /// fallback model is used without waiting for every retry to fail
+    pub fn skip_retries(error: anyhow::Error) -> anyhow::Error {
+        if !Self::classify(&error).is_some_and(|reason| reason.is_immediate()) {
+            return error;
+        }
+        match error.downcast::<Error>() {
+            Ok(Error::Retryable(cause)) => cause,
+            Ok(error) => error.into(),
+            Err(error) => error,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        let fixture = [
+            anyhow::Error::from(Error::Retryable(anyhow::anyhow!("503 Service Unavailable"))),
+            anyhow::anyhow!("This model's maximum context length is 128000 tokens")
+                .context("Failed to complete the request"),
+            anyhow::anyhow!("{{\"error\":{{\"code\":\"model_not_found\"}}}}"),
+            Error::Retryable(anyhow::anyhow!("429: insufficient_quota")).into(),
+            anyhow::anyhow!("Invalid API key"),
+        ];
+
+        let actual: Vec<_> = fixture.iter().map(FallbackReason::classify).collect();
+
+        let expected = vec![
+            Some(FallbackReason::RetriesExhausted),
+            Some(FallbackReason::ContextTooLong),
+            Some(FallbackReason::ModelNotFound),
+            Some(FallbackReason::QuotaExceeded),
+            None,
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_skip_retries() {
+        let fixture = [
+            Error::Retryable(anyhow::anyhow!("429: insufficient_quota")).into(),
+            Error::Retryable(anyhow::anyhow!("Your credit balance is too low")).into(),
+            Error::Retryable(anyhow::anyhow!("404: model_not_found")).into(),
+            Error::Retryable(anyhow::anyhow!("503 Service Unavailable")).into(),
+        ];
+
+        let actual: Vec<_> = fixture
+            .into_iter()
+            .map(FallbackReason::skip_retries)
+            .map(|error| {
+                (
+                    matches!(error.downcast_ref::<Error>(), Some(Error::Retryable(_))),
+                    FallbackReason::classify(&error),
+                )
+            })
+            .collect();
+
+        let expected = vec![
+            (false, Some(FallbackReason::QuotaExceeded)),
+            (false, Some(FallbackReason::QuotaExceeded)),
+            (false, Some(FallbackReason::ModelNotFound)),
+            (true, Some(FallbackReason::RetriesExhausted)),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_fallback_from_yaml() {
+        let actual: Vec<ModelFallback> =
+            serde_yml::from_str("- provider: open_router\n  model: openai/gpt-4o\n").unwrap();
+
+        let expected = vec![ModelFallback::new(
+            ProviderId::OPEN_ROUTER,
+            ModelId::new("openai/gpt-4o"),
+        )];
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,322 @@
+use std::path::{Path, PathBuf};
+
+use crate::{Error, Result};
+
+/// A line of a unified diff hunk
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    /// Unchanged line surrounding the change
+    Context(String),
+    /// Line removed from the file
+    Remove(String),
+    /// Line added to the file
+    Add(String),
+}
+
+/// A single change to apply to a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Hunk {
+    /// A hunk of a unified diff
+    Diff {
+        /// The `@@ -a,b +c,d @@` line introducing the hunk
+        header: String,
+        /// 1-based line where the hunk starts in the original file, used to
+        /// pick between several matches
+        old_start: usize,
+        lines: Vec<DiffLine>,
+    },
+    /// Replaces the only occurrence of `search` with `replace`
+    Replace { search: String, replace: String },
+}
+
+impl Hunk {
+    /// Lines the hunk expects to find in the file
+    pub fn old_lines(&self) -> Vec<&str> {
+        match self {
+            Hunk::Diff { lines, .. } => lines
+                .iter()
+                .filter_map(|line| match line {
+                    DiffLine::Context(text) | DiffLine::Remove(text) => Some(text.as_str()),
+                    DiffLine::Add(_) => None,
+                })
+                .collect(),
+            Hunk::Replace { search, .. } => split_lines(search),
+        }
+    }
+
+    /// Short label identifying the hunk in reports
+    pub fn label(&self) -> String {
+        match self {
+            Hunk::Diff { header, .. } => header.clone(),
+            Hunk::Replace { search, .. } => {
+                let first = search.lines().find(|line| !line.trim().is_empty());
+                format!("replace `{}`", first.unwrap_or_default().trim())
+            }
+        }
+    }
+}
+
+/// Splits text into lines, ignoring the empty line after a trailing newline
+fn split_lines(text: &str) -> Vec<&str> {
+    let text = text.strip_suffix('\n').unwrap_or(text);
+    text.split('\n').collect()
+}
+
+/// The changes to apply to a single file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilePatch {
+    pub path: PathBuf,
+    /// Set when the diff creates the file
+    pub create: bool,
+    pub hunks: Vec<Hunk>,
+}
+
+impl FilePatch {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into(), create: false, hunks: Vec::new() }
+    }
+
+    /// Parses a unified diff, as produced by `git diff` or `diff -u`, into the
+    /// patches of the files it touches. Relative paths are resolved against
+    /// `cwd` after stripping the `a/` and `b/` prefixes used by git.
+    pub fn parse_unified_diff(diff: &str, cwd: &Path) -> Result<Vec<FilePatch>> {
+        let mut patches: Vec<FilePatch> = Vec::new();
+        let mut lines = diff.lines().peekable();
+        let mut old_path: Option<String> = None;
+
+        while let Some(line) = lines.next() {
+            if let Some(path) = line.strip_prefix("--- ") {
+                old_path = Some(header_path(path));
+            } else if let Some(path) = line.strip_prefix("+++ ") {
+                let old = old_path
+                    .take()
+                    .ok_or_else(|| invalid(format!("'{line}' is not preceded by a '---' line")))?;
+                let new = header_path(path);
+                if new == "/dev/null" {
+                    return Err(invalid(format!(
+                        "deleting '{old}' is not supported, use the remove tool instead"
+                    )));
+                }
+                let mut patch = FilePatch::new(resolve(&new, cwd));
+                patch.create = old == "/dev/null";
+                patches.push(patch);
+            } else if line.starts_with("@@") {
+                let patch = patches.last_mut().ok_or_else(|| {
+                    invalid(format!("hunk '{line}' appears before any file header"))
+                })?;
+                let (old_start, old_count, new_count) = parse_hunk_header(line)?;
+                let mut hunk_lines = Vec::new();
+                let (mut old_seen, mut new_seen) = (0, 0);
+                while old_seen < old_count || new_seen < new_count {
+                    let Some(line) = lines.next() else {
+                        break;
+                    };
+                    if let Some(text) = line.strip_prefix('-') {
+                        old_seen += 1;
+                        hunk_lines.push(DiffLine::Remove(text.to_string()));
+                    } else if let Some(text) = line.strip_prefix('+') {
+                        new_seen += 1;
+                        hunk_lines.push(DiffLine::Add(text.to_string()));
+                    } else if line.starts_with('\\') {
+                        // "\ No newline at end of file"
+                    } else {
+                        // Context lines start with a space, which editors and
+                        // models tend to strip from blank lines
+                        let text = line.strip_prefix(' ').unwrap_or(line);
+                        old_seen += 1;
+                        new_seen += 1;
+                        hunk_lines.push(DiffLine::Context(text.to_string()));
+                    }
+                }
+                while lines.peek().is_some_and(|line| line.starts_with('\\')) {
+                    lines.next();
+                }
+                patch.hunks.push(Hunk::Diff {
+                    header: line.trim().to_string(),
+                    old_start,
+                    lines: hunk_lines,
+                });
+            }
+        }
+
+        if patches.is_empty() {
+            return Err(invalid("no file headers ('---' and '+++' lines) found"));
+        }
+        if let Some(patch) = patches.iter().find(|patch| patch.hunks.is_empty()) {
+            return Err(invalid(format!(
+                "no hunks found for {}",
+                patch.path.display()
+            )));
+        }
+        Ok(patches)
+    }
+}
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::InvalidDiff(message.into())
+}
+
+/// Extracts the path from a `---`/`+++` line, dropping the timestamp that
+/// `diff -u` appends and the prefixes added by git
+fn header_path(value: &str) -> String {
+    let path = value.split('\t').next().unwrap_or(value).trim();
+    path.strip_prefix("a/")
+        .or_else(|| path.strip_prefix("b/"))
+        .unwrap_or(path)
+        .to_string()
+}
+
+fn resolve(path: &str, cwd: &Path) -> PathBuf {
+    let path = Path::new(path);
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        cwd.join(path)
+    }
+}
+
+/// Parses `@@ -a,b +c,d @@` into the start line of the original file and the
+/// number of old and new lines
+fn parse_hunk_header(header: &str) -> Result<(usize, usize, usize)> {
+    let malformed = || invalid(format!("malformed hunk header '{header}'"));
+    let ranges = header
+        .trim_start_matches('@')
+        .split("@@")
+        .next()
+        .ok_or_else(malformed)?;
+    let mut parts = ranges.split_whitespace();
+    let old = parts.next().and_then(|part| part.strip_prefix('-'));
+    let new = parts.next().and_then(|part| part.strip_prefix('+'));
+    let (Some(old), Some(new)) = (old, new) else {
+        return Err(malformed());
+    };
+    let range = |range: &str| -> Option<(usize, usize)> {
+        match range.split_once(',') {
+            Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+            None => Some((range.parse().ok()?, 1)),
+        }
+    };
+    let (old_start, old_count) = range(old).ok_or_else(malformed)?;
+    let (_, new_count) = range(new).ok_or_else(malformed)?;
+    Ok((old_start, old_count, new_count))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn context(text: &str) -> DiffLine {
+        DiffLine::Context(text.to_string())
+    }
+
+    fn remove(text: &str) -> DiffLine {
+        DiffLine::Remove(text.to_string())
+    }
+
+    fn add(text: &str) -> DiffLine {
+        DiffLine::Add(text.to_string())
+    }
+
+    #[test]
+    fn test_parse_git_diff_with_multiple_files() {
+        let fixture = "diff --git a/src/lib.rs b/src/lib.rs
+index 83db48f..bf269f4 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,3 +1,3 @@
+ fn a() {}
+-fn b() {}
++fn b() -> u8 { 1 }
+ fn c() {}
+@@ -10,2 +10,3 @@ impl Foo {
+ fn d() {}
++fn e() {}
+ fn f() {}
+--- /dev/null
++++ b/src/new.rs
+@@ -0,0 +1 @@
++pub struct New;
+";
+
+        let actual = FilePatch::parse_unified_diff(fixture, Path::new("/project")).unwrap();
+
+        let expected = vec![
+            FilePatch {
+                path: PathBuf::from("/project/src/lib.rs"),
+                create: false,
+                hunks: vec![
+                    Hunk::Diff {
+                        header: "@@ -1,3 +1,3 @@".to_string(),
+                        old_start: 1,
+                        lines: vec![
+                            context("fn a() {}"),
+                            remove("fn b() {}"),
+                            add("fn b() -> u8 { 1 }"),
+                            context("fn c() {}"),
+                        ],
+                    },
+                    Hunk::Diff {
+                        header: "@@ -10,2 +10,3 @@ impl Foo {".to_string(),
+                        old_start: 10,
+                        lines: vec![context("fn d() {}"), add("fn e() {}"), context("fn f() {}")],
+                    },
+                ],
+            },
+            FilePatch {
+                path: PathBuf::from("/project/src/new.rs"),
+                create: true,
+                hunks: vec![Hunk::Diff {
+                    header: "@@ -0,0 +1 @@".to_string(),
+                    old_start: 0,
+                    lines: vec![add("pub struct New;")],
+                }],
+            },
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_blank_context_line_without_leading_space() {
+        let fixture =
+            "--- /project/a.txt\n+++ /project/a.txt\n@@ -1,3 +1,3 @@\n one\n\n-two\n+three\n";
+
+        let actual = FilePatch::parse_unified_diff(fixture, Path::new("/cwd")).unwrap();
+
+        let expected = vec![context("one"), context(""), remove("two"), add("three")];
+        assert_eq!(
+            actual[0].hunks[0],
+            Hunk::Diff {
+                header: "@@ -1,3 +1,3 @@".to_string(),
+                old_start: 1,
+                lines: expected
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_deletion() {
+        let fixture = "--- a/old.rs\n+++ /dev/null\n@@ -1 +0,0 @@\n-gone\n";
+
+        let actual = FilePatch::parse_unified_diff(fixture, Path::new("/cwd"));
+
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_text_without_headers() {
+        let actual = FilePatch::parse_unified_diff("just some text", Path::new("/cwd"));
+
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn test_old_lines_of_replace_hunk() {
+        let fixture = Hunk::Replace { search: "a\nb\n".to_string(), replace: String::new() };
+
+        let actual = fixture.old_lines();
+
+        assert_eq!(actual, vec!["a", "b"]);
+    }
+}
@@ -0,0 +1,177 @@
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::oneshot;
+
+use crate::Followup;
+
+/// A question asked by the agent through the followup tool
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FollowUpQuestion {
+    pub question: String,
+    /// Options to choose from, the answer is free-form when empty
+    pub options: Vec<String>,
+    /// Whether several options can be selected
+    pub multiple: bool,
+}
+
+impl FollowUpQuestion {
+    pub fn new(question: impl Into<String>) -> Self {
+        Self {
+            question: question.into(),
+            options: Vec::new(),
+            multiple: false,
+        }
+    }
+
+    /// Describes the answer for the agent
+    pub fn feedback(&self, answer: FollowUpAnswer) -> String {
+        match answer {
+            FollowUpAnswer::Text(text) => text,
+            FollowUpAnswer::Selected(selected) if self.multiple => format!(
+                "User selected {} option(s): {}",
+                selected.len(),
+                selected.join(", ")
+            ),
+            FollowUpAnswer::Selected(selected) => {
+                format!("User selected: {}", selected.join(", "))
+            }
+        }
+    }
+}
+
+impl From<Followup> for FollowUpQuestion {
+    fn from(input: Followup) -> Self {
+        let options = [
+            input.option1,
+            input.option2,
+            input.option3,
+            input.option4,
+            input.option5,
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        Self {
+            question: input.question,
+            options,
+            multiple: input.multiple.unwrap_or_default(),
+        }
+    }
+}
+
+/// An answer to a [`FollowUpQuestion`], serialized as a string for free-form
+/// answers and as a list of strings for selected options
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FollowUpAnswer {
+    Text(String),
+    Selected(Vec<String>),
+}
+
+/// Hands the answer to a follow-up question back to the agent waiting for it.
+///
+/// Only the first answer is delivered. Dropping every clone without answering
+/// dismisses the question.
+#[derive(Clone)]
+pub struct FollowUpReply(Arc<Mutex<Option<oneshot::Sender<Option<FollowUpAnswer>>>>>);
+
+impl FollowUpReply {
+    /// Creates a reply along with the receiver the agent waits on
+    pub fn channel() -> (Self, oneshot::Receiver<Option<FollowUpAnswer>>) {
+        let (tx, rx) = oneshot::channel();
+        (Self(Arc::new(Mutex::new(Some(tx)))), rx)
+    }
+
+    /// Sends the answer, `None` dismisses the question. Returns `false` if the
+    /// question was already answered or nobody is waiting anymore.
+    pub fn send(&self, answer: Option<FollowUpAnswer>) -> bool {
+        let sender = self.0.lock().unwrap_or_else(|e| e.into_inner()).take();
+        sender.is_some_and(|sender| sender.send(answer).is_ok())
+    }
+}
+
+impl std::fmt::Debug for FollowUpReply {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("FollowUpReply")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_question_from_followup() {
+        let fixture = Followup {
+            question: "Which database?".to_string(),
+            option1: Some("Postgres".to_string()),
+            option3: Some("SQLite".to_string()),
+            ..Default::default()
+        };
+
+        let actual = FollowUpQuestion::from(fixture);
+
+        let expected = FollowUpQuestion {
+            question: "Which database?".to_string(),
+            options: vec!["Postgres".to_string(), "SQLite".to_string()],
+            multiple: false,
+        };
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_feedback() {
+        let single = FollowUpQuestion::new("Pick one");
+        let multiple = FollowUpQuestion { multiple: true, ..FollowUpQuestion::new("Pick many") };
+        let selected = FollowUpAnswer::Selected(vec!["a".to_string(), "b".to_string()]);
+
+        let actual = (
+            single.feedback(FollowUpAnswer::Text("Use b".to_string())),
+            single.feedback(FollowUpAnswer::Selected(vec!["a".to_string()])),
+            multiple.feedback(selected),
+        );
+
+        let expected = (
+            "Use b".to_string(),
+            "User selected: a".to_string(),
+            "User selected 2 option(s): a, b".to_string(),
+        );
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_answer_json() {
+        let actual: Vec<FollowUpAnswer> = serde_json::from_str(r#"["yes", ["a", "b"]]"#).unwrap();
+        let expected = vec![
+            FollowUpAnswer::Text("yes".to_string()),
+            FollowUpAnswer::Selected(vec!["a".to_string(), "b".to_string()]),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_reply_delivers_first_answer_only() {
+        let (fixture, rx) = FollowUpReply::channel();
+
+        let first = fixture.send(Some(FollowUpAnswer::Text("yes".to_string())));
+        let second = fixture.clone().send(None);
+
+        assert_eq!((first, second), (true, false));
+        assert_eq!(
+            rx.await.unwrap(),
+            Some(FollowUpAnswer::Text("yes".to_string()))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dropped_reply_dismisses_question() {
+        let (fixture, rx) = FollowUpReply::channel();
+
+        drop(fixture);
+
+        assert!(rx.await.is_err());
+    }
+}
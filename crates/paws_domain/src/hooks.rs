@@ -0,0 +1,336 @@
+use std::path::PathBuf;
+
+use derive_setters::Setters;
+use merge::Merge;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{AgentId, CommandOutput, ConversationId, ToolName};
+
+/// Exit code with which a hook vetoes the action that triggered it
+pub const HOOK_BLOCK_EXIT_CODE: i32 = 2;
+
+/// A command run when an agent lifecycle event occurs. The command receives
+/// the [`HookPayload`] of the event as JSON on stdin.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, Setters)]
+#[setters(strip_option, into)]
+pub struct Hook {
+    /// Shell command to run from the workspace directory
+    pub command: String,
+
+    /// Regular expression the full tool name has to match for tool events,
+    /// e.g. `write|patch`. Hooks without a matcher run for every tool.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matcher: Option<String>,
+
+    /// Seconds after which the command is killed, 60 when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+
+    /// Whether the action is blocked when the command can't be started or
+    /// times out. On by default for `pre_tool_use` hooks, off for the others.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fail_closed: Option<bool>,
+}
+
+impl Hook {
+    pub fn new(command: impl Into<String>) -> Self {
+        Self {
+            command: command.into(),
+            matcher: None,
+            timeout: None,
+            fail_closed: None,
+        }
+    }
+
+    /// Returns whether a hook that can't run blocks the action of the event
+    pub fn is_fail_closed(&self, event: HookEvent) -> bool {
+        self.fail_closed
+            .unwrap_or(matches!(event, HookEvent::PreToolUse))
+    }
+
+    /// Returns whether the hook runs for the tool. Events that are not about a
+    /// tool call run every hook.
+    pub fn matches(&self, tool_name: Option<&ToolName>) -> bool {
+        match (&self.matcher, tool_name) {
+            (Some(matcher), Some(tool_name)) => Regex::new(&format!("^(?:{matcher})$"))
+                .is_ok_and(|regex| regex.is_match(tool_name.as_str())),
+            _ => true,
+        }
+    }
+}
+
+/// Commands to run on agent lifecycle events, in the order they are listed
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema, Merge)]
+pub struct Hooks {
+    /// Run before the first request of a conversation
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[merge(strategy = merge::vec::append)]
+    pub session_start: Vec<Hook>,
+
+    /// Run when a prompt is submitted, before it is sent to the model
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[merge(strategy = merge::vec::append)]
+    pub user_prompt_submit: Vec<Hook>,
+
+    /// Run before a tool is called. These hooks can block the call or replace
+    /// its arguments.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[merge(strategy = merge::vec::append)]
+    pub pre_tool_use: Vec<Hook>,
+
+    /// Run after a tool is called. Blocking marks the call as failed and
+    /// sends the reason to the model.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[merge(strategy = merge::vec::append)]
+    pub post_tool_use: Vec<Hook>,
+
+    /// Run when the model completes its turn. Blocking sends the reason to
+    /// the model and continues the turn.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[merge(strategy = merge::vec::append)]
+    pub turn_complete: Vec<Hook>,
+
+    /// Run before the context is compacted. Blocking skips the compaction.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[merge(strategy = merge::vec::append)]
+    pub compaction: Vec<Hook>,
+}
+
+impl Hooks {
+    /// Returns the hooks configured for the event
+    pub fn get(&self, event: HookEvent) -> &[Hook] {
+        match event {
+            HookEvent::SessionStart => &self.session_start,
+            HookEvent::UserPromptSubmit => &self.user_prompt_submit,
+            HookEvent::PreToolUse => &self.pre_tool_use,
+            HookEvent::PostToolUse => &self.post_tool_use,
+            HookEvent::TurnComplete => &self.turn_complete,
+            HookEvent::Compaction => &self.compaction,
+        }
+    }
+}
+
+/// Agent lifecycle events hooks can be configured for
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, strum_macros::Display,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum HookEvent {
+    SessionStart,
+    UserPromptSubmit,
+    PreToolUse,
+    PostToolUse,
+    TurnComplete,
+    Compaction,
+}
+
+/// Details of an event, written as JSON to the stdin of its hooks
+#[derive(Debug, Clone, PartialEq, Serialize, Setters)]
+#[setters(strip_option, into)]
+pub struct HookPayload {
+    pub event: HookEvent,
+    pub cwd: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conversation_id: Option<ConversationId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agent_id: Option<AgentId>,
+    /// Prompt submitted by the user
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt: Option<String>,
+    /// Last message of the model, on turn completion
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_name: Option<ToolName>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_input: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_output: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_error: Option<bool>,
+}
+
+impl HookPayload {
+    pub fn new(event: HookEvent, cwd: impl Into<PathBuf>) -> Self {
+        Self {
+            event,
+            cwd: cwd.into(),
+            conversation_id: None,
+            agent_id: None,
+            prompt: None,
+            response: None,
+            tool_name: None,
+            tool_input: None,
+            tool_output: None,
+            is_error: None,
+        }
+    }
+}
+
+/// Decision a hook can print as JSON on stdout when exiting successfully
+#[derive(Debug, Default, Deserialize)]
+struct HookResponse {
+    #[serde(default)]
+    decision: Option<HookDecision>,
+    #[serde(default)]
+    reason: Option<String>,
+    #[serde(default)]
+    tool_input: Option<Value>,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum HookDecision {
+    Block,
+    Continue,
+}
+
+/// What to do with the action that triggered the hooks
+#[derive(Debug, Clone, PartialEq)]
+pub enum HookOutcome {
+    /// Proceed unchanged
+    Continue,
+    /// Veto the action for the given reason
+    Block(String),
+    /// Proceed with the given tool arguments
+    Modify(Value),
+}
+
+impl HookOutcome {
+    /// Interprets the output of a hook command. Exit code 2 blocks the action
+    /// with stderr as the reason. Exit code 0 continues, unless stdout is a
+    /// JSON object with `"decision": "block"` or replacement `tool_input`.
+    /// Returns `None` when the hook itself failed.
+    pub fn from_output(output: &CommandOutput) -> Option<Self> {
+        match output.exit_code {
+            Some(0) => {
+                let response: HookResponse = serde_json::from_str(output.stdout.trim())
+                    .ok()
+                    .unwrap_or_default();
+                Some(match response {
+                    HookResponse { decision: Some(HookDecision::Block), reason, .. } => {
+                        Self::Block(reason.unwrap_or_else(|| Self::default_reason(output)))
+                    }
+                    HookResponse { tool_input: Some(input), .. } => Self::Modify(input),
+                    _ => Self::Continue,
+                })
+            }
+            Some(HOOK_BLOCK_EXIT_CODE) => {
+                let reason = output.stderr.trim();
+                Some(Self::Block(if reason.is_empty() {
+                    Self::default_reason(output)
+                } else {
+                    reason.to_string()
+                }))
+            }
+            _ => None,
+        }
+    }
+
+    fn default_reason(output: &CommandOutput) -> String {
+        format!("Blocked by hook `{}`", output.command)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+
+    fn output(exit_code: i32, stdout: &str, stderr: &str) -> CommandOutput {
+        CommandOutput {
+            command: "check.sh".to_string(),
+            stdout: stdout.to_string(),
+            stderr: stderr.to_string(),
+            exit_code: Some(exit_code),
+        }
+    }
+
+    #[test]
+    fn test_hook_matches() {
+        let fixture = Hook::new("fmt.sh").matcher("write|patch");
+
+        let actual = (
+            fixture.matches(Some(&ToolName::new("write"))),
+            fixture.matches(Some(&ToolName::new("multi_patch"))),
+            fixture.matches(None),
+            Hook::new("log.sh").matches(Some(&ToolName::new("fetch"))),
+        );
+
+        assert_eq!(actual, (true, false, true, true));
+    }
+
+    #[test]
+    fn test_hook_is_fail_closed() {
+        let fixture = Hook::new("guard.sh");
+
+        let actual = (
+            fixture.is_fail_closed(HookEvent::PreToolUse),
+            fixture.is_fail_closed(HookEvent::PostToolUse),
+            fixture
+                .clone()
+                .fail_closed(false)
+                .is_fail_closed(HookEvent::PreToolUse),
+            fixture
+                .fail_closed(true)
+                .is_fail_closed(HookEvent::TurnComplete),
+        );
+
+        assert_eq!(actual, (true, false, false, true));
+    }
+
+    #[test]
+    fn test_outcome_from_output() {
+        let fixture = [
+            output(0, "formatted\n", ""),
+            output(0, r#"{"decision": "block", "reason": "no prod"}"#, ""),
+            output(0, r#"{"tool_input": {"command": "ls"}}"#, ""),
+            output(2, "", "touches production\n"),
+            output(2, "", ""),
+            output(1, "", "crashed"),
+        ];
+
+        let actual: Vec<_> = fixture.iter().map(HookOutcome::from_output).collect();
+
+        let expected = vec![
+            Some(HookOutcome::Continue),
+            Some(HookOutcome::Block("no prod".to_string())),
+            Some(HookOutcome::Modify(json!({"command": "ls"}))),
+            Some(HookOutcome::Block("touches production".to_string())),
+            Some(HookOutcome::Block("Blocked by hook `check.sh`".to_string())),
+            None,
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_hooks_from_yaml() {
+        let fixture = r#"
+pre_tool_use:
+  - command: ./scripts/guard.sh
+    matcher: shell
+    timeout: 5
+turn_complete:
+  - command: cargo check
+"#;
+
+        let actual: Hooks = serde_yml::from_str(fixture).unwrap();
+
+        let expected = Hooks {
+            pre_tool_use: vec![
+                Hook::new("./scripts/guard.sh")
+                    .matcher("shell")
+                    .timeout(5u64),
+            ],
+            turn_complete: vec![Hook::new("cargo check")],
+            ..Default::default()
+        };
+        assert_eq!(actual, expected);
+    }
+}
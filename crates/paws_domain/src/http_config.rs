@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use serde::{Deserialize, Serialize};
 use strum_macros::EnumString;
 
@@ -80,6 +82,38 @@ impl std::fmt::Display for TlsBackend {
     }
 }
 
+/// Whether provider traffic is written to a cassette or served from one
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, EnumString, fake::Dummy)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "lowercase")]
+pub enum CassetteMode {
+    /// Forwards requests to the providers and saves every exchange
+    Record,
+    /// Serves the saved exchanges without touching the network
+    Replay,
+}
+
+impl std::fmt::Display for CassetteMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CassetteMode::Record => write!(f, "record"),
+            CassetteMode::Replay => write!(f, "replay"),
+        }
+    }
+}
+
+/// A file of recorded HTTP exchanges used to reproduce sessions offline.
+///
+/// # Environment Variables
+/// - `FORGE_HTTP_CASSETTE`: Path of the cassette file
+/// - `FORGE_HTTP_CASSETTE_MODE`: "record" or "replay" (default: "replay")
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, fake::Dummy)]
+#[serde(rename_all = "camelCase")]
+pub struct CassetteConfig {
+    pub path: PathBuf,
+    pub mode: CassetteMode,
+}
+
 /// HTTP client configuration with support for timeouts, connection pooling,
 /// redirects, DNS resolution, TLS settings, and HTTP/2 configuration.
 ///
@@ -118,6 +152,8 @@ impl std::fmt::Display for TlsBackend {
 ///   false) - USE WITH CAUTION
 /// - `FORGE_HTTP_ROOT_CERT_PATHS`: Paths to root certificate files (PEM, CRT,
 ///   CER format), multiple paths separated by commas
+/// - `FORGE_HTTP_CASSETTE`, `FORGE_HTTP_CASSETTE_MODE`: Record or replay
+///   provider traffic, see [`CassetteConfig`]
 ///
 /// # Example
 /// ```
@@ -163,6 +199,10 @@ pub struct HttpConfig {
     /// Paths to root certificate files (PEM, CRT, CER format). Multiple paths
     /// can be separated by commas.
     pub root_cert_paths: Option<Vec<String>>,
+    /// Cassette recording or replaying every request. When `None`, requests
+    /// go straight to the network.
+    #[dummy(default)]
+    pub cassette: Option<CassetteConfig>,
 }
 
 impl Default for HttpConfig {
@@ -185,6 +225,7 @@ impl Default for HttpConfig {
             keep_alive_while_idle: true,
             accept_invalid_certs: false, // Default to false for security
             root_cert_paths: None,
+            cassette: None,
         }
     }
 }
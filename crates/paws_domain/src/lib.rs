@@ -3,8 +3,11 @@ mod agent_definition;
 mod app_config;
 mod attachment;
 mod auth;
+mod budget;
 mod chat_request;
 mod chat_response;
+mod checkpoint;
+mod code_index;
 mod compact;
 mod context;
 mod conversation;
@@ -13,13 +16,19 @@ mod data_gen;
 mod env;
 mod error;
 mod event;
+mod fallback;
 mod file;
 mod file_operation;
+mod file_patch;
+mod follow_up;
 mod group_by_key;
+mod hooks;
 mod http_config;
 mod image;
 mod max_tokens;
 mod mcp;
+mod mcp_prompt;
+mod mcp_resource;
 mod mcp_servers;
 mod merge;
 mod message;
@@ -29,11 +38,14 @@ mod model;
 
 mod point;
 mod policies;
+mod pricing;
 mod provider;
+mod rate_limit;
 mod reasoning;
 mod repo;
 mod result_stream_ext;
 mod retry_config;
+mod sandbox;
 mod session_metrics;
 mod shell;
 mod skill;
@@ -47,6 +59,7 @@ mod top_k;
 mod top_p;
 mod transformer;
 mod update;
+mod usage_report;
 mod workflow;
 
 mod xml;
@@ -54,8 +67,11 @@ mod xml;
 pub use agent::*;
 pub use agent_definition::*;
 pub use attachment::*;
+pub use budget::*;
 pub use chat_request::*;
 pub use chat_response::*;
+pub use checkpoint::*;
+pub use code_index::*;
 pub use compact::*;
 pub use context::*;
 pub use conversation::*;
@@ -64,13 +80,19 @@ pub use data_gen::*;
 pub use env::*;
 pub use error::*;
 pub use event::*;
+pub use fallback::*;
 pub use file::*;
 pub use file_operation::*;
+pub use file_patch::*;
+pub use follow_up::*;
 pub use group_by_key::*;
+pub use hooks::*;
 pub use http_config::*;
 pub use image::*;
 pub use max_tokens::*;
 pub use mcp::*;
+pub use mcp_prompt::*;
+pub use mcp_resource::*;
 pub use mcp_servers::*;
 pub use message::*;
 pub use message_pattern::*;
@@ -78,11 +100,14 @@ pub use migration::*;
 pub use model::*;
 pub use point::*;
 pub use policies::*;
+pub use pricing::*;
 pub use provider::*;
+pub use rate_limit::*;
 pub use reasoning::*;
 pub use repo::*;
 pub use result_stream_ext::*;
 pub use retry_config::*;
+pub use sandbox::*;
 pub use session_metrics::*;
 pub use shell::*;
 pub use skill::*;
@@ -96,6 +121,7 @@ pub use top_k::*;
 pub use top_p::*;
 pub use transformer::*;
 pub use update::*;
+pub use usage_report::*;
 pub use workflow::*;
 pub use xml::*;
 pub mod line_numbers;
@@ -0,0 +1,53 @@
+use derive_setters::Setters;
+use serde::{Deserialize, Serialize};
+
+use crate::Role;
+
+/// A prompt template exposed by an MCP server
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Setters)]
+#[serde(rename_all = "camelCase")]
+#[setters(strip_option, into)]
+pub struct McpPrompt {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub arguments: Vec<McpPromptArgument>,
+}
+
+impl McpPrompt {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self { name: name.into(), description: None, arguments: Vec::new() }
+    }
+}
+
+/// An argument used to render an MCP prompt
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Setters)]
+#[serde(rename_all = "camelCase")]
+#[setters(strip_option, into)]
+pub struct McpPromptArgument {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+}
+
+impl McpPromptArgument {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self { name: name.into(), description: None, required: false }
+    }
+}
+
+/// A message of a rendered MCP prompt
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct McpPromptMessage {
+    pub role: Role,
+    pub text: String,
+}
+
+impl McpPromptMessage {
+    pub fn new(role: Role, text: impl Into<String>) -> Self {
+        Self { role, text: text.into() }
+    }
+}
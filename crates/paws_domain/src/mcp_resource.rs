@@ -0,0 +1,125 @@
+use derive_setters::Setters;
+use serde::{Deserialize, Serialize};
+
+use crate::{AttachmentContent, Image, LineNumbers};
+
+/// A resource exposed by an MCP server that can be attached to a message
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Setters)]
+#[serde(rename_all = "camelCase")]
+#[setters(strip_option, into)]
+pub struct McpResource {
+    /// URI identifying the resource on the server, eg: `file:///logs/app.log`
+    pub uri: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+}
+
+impl McpResource {
+    pub fn new(uri: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            uri: uri.into(),
+            name: name.into(),
+            description: None,
+            mime_type: None,
+        }
+    }
+}
+
+/// Content returned by an MCP server when a resource is read
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum McpResourceContent {
+    Text {
+        uri: String,
+        mime_type: Option<String>,
+        text: String,
+    },
+    /// Binary content encoded as base64
+    Blob {
+        uri: String,
+        mime_type: Option<String>,
+        blob: String,
+    },
+}
+
+impl McpResourceContent {
+    pub fn uri(&self) -> &str {
+        match self {
+            McpResourceContent::Text { uri, .. } | McpResourceContent::Blob { uri, .. } => uri,
+        }
+    }
+
+    /// Converts the content into an attachment. Text is numbered the same way
+    /// as file attachments, images are attached as is and any other binary
+    /// content is replaced by a placeholder.
+    pub fn into_attachment_content(self) -> AttachmentContent {
+        match self {
+            McpResourceContent::Text { text, .. } => {
+                let total_lines = text.lines().count() as u64;
+                AttachmentContent::FileContent {
+                    content: text.to_numbered_from(1),
+                    start_line: 1,
+                    end_line: total_lines,
+                    total_lines,
+                }
+            }
+            McpResourceContent::Blob { mime_type: Some(mime_type), blob, .. }
+                if mime_type.starts_with("image/") =>
+            {
+                AttachmentContent::Image(Image::new_base64(blob, mime_type))
+            }
+            McpResourceContent::Blob { mime_type, .. } => AttachmentContent::FileContent {
+                content: format!(
+                    "[binary content ({}) omitted]",
+                    mime_type.as_deref().unwrap_or("application/octet-stream")
+                ),
+                start_line: 1,
+                end_line: 1,
+                total_lines: 1,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_text_into_attachment_content() {
+        let fixture = McpResourceContent::Text {
+            uri: "file:///notes.md".to_string(),
+            mime_type: Some("text/markdown".to_string()),
+            text: "first\nsecond".to_string(),
+        };
+
+        let actual = fixture.into_attachment_content();
+
+        let expected = AttachmentContent::FileContent {
+            content: "1:first\n2:second".to_string(),
+            start_line: 1,
+            end_line: 2,
+            total_lines: 2,
+        };
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_image_blob_into_attachment_content() {
+        let fixture = McpResourceContent::Blob {
+            uri: "file:///logo.png".to_string(),
+            mime_type: Some("image/png".to_string()),
+            blob: "aGVsbG8=".to_string(),
+        };
+
+        let actual = fixture.into_attachment_content();
+
+        let expected =
+            AttachmentContent::Image(Image::new_base64("aGVsbG8=".to_string(), "image/png"));
+        assert_eq!(actual, expected);
+    }
+}
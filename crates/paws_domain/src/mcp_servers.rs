@@ -2,7 +2,7 @@ use std::collections::{HashMap, hash_map};
 
 use serde::{Deserialize, Serialize};
 
-use crate::{ServerName, ToolDefinition};
+use crate::{McpPrompt, McpResource, ServerName, ToolDefinition};
 
 /// Cache for MCP tool definitions
 ///
@@ -18,6 +18,12 @@ pub struct McpServers {
     /// Failed MCP servers with their error messages
     #[serde(default)]
     failures: HashMap<ServerName, String>,
+    /// Resources exposed by the successfully loaded MCP servers
+    #[serde(default)]
+    resources: HashMap<ServerName, Vec<McpResource>>,
+    /// Prompts exposed by the successfully loaded MCP servers
+    #[serde(default)]
+    prompts: HashMap<ServerName, Vec<McpPrompt>>,
 }
 
 impl McpServers {
@@ -26,7 +32,12 @@ impl McpServers {
         servers: HashMap<ServerName, Vec<ToolDefinition>>,
         failures: HashMap<ServerName, String>,
     ) -> Self {
-        Self { servers, failures }
+        Self {
+            servers,
+            failures,
+            resources: Default::default(),
+            prompts: Default::default(),
+        }
     }
 
     /// Get the successful servers
@@ -38,6 +49,16 @@ impl McpServers {
     pub fn get_failures(&self) -> &HashMap<ServerName, String> {
         &self.failures
     }
+
+    /// Get the resources exposed by each server
+    pub fn get_resources(&self) -> &HashMap<ServerName, Vec<McpResource>> {
+        &self.resources
+    }
+
+    /// Get the prompts exposed by each server
+    pub fn get_prompts(&self) -> &HashMap<ServerName, Vec<McpPrompt>> {
+        &self.prompts
+    }
 }
 
 impl IntoIterator for McpServers {
@@ -4,8 +4,8 @@ use serde::{Deserialize, Serialize};
 use strum_macros::{EnumString, IntoStaticStr};
 
 use super::{ToolCall, ToolCallFull};
-use crate::TokenCount;
 use crate::reasoning::{Reasoning, ReasoningFull};
+use crate::{RateLimit, TokenCount};
 
 #[derive(Default, Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
 pub struct Usage {
@@ -13,6 +13,10 @@ pub struct Usage {
     pub completion_tokens: TokenCount,
     pub total_tokens: TokenCount,
     pub cached_tokens: TokenCount,
+    /// Prompt tokens written to the cache, reported by providers that bill
+    /// them separately
+    #[serde(default)]
+    pub cache_write_tokens: TokenCount,
     pub cost: Option<f64>,
 }
 
@@ -24,6 +28,7 @@ impl Usage {
         self.completion_tokens = self.completion_tokens + other.completion_tokens;
         self.total_tokens = self.total_tokens + other.total_tokens;
         self.cached_tokens = self.cached_tokens + other.cached_tokens;
+        self.cache_write_tokens = self.cache_write_tokens + other.cache_write_tokens;
         self.cost = match (self.cost, other.cost) {
             (Some(a), Some(b)) => Some(a + b),
             (Some(a), None) => Some(a),
@@ -46,6 +51,9 @@ pub struct ChatCompletionMessage {
     pub tool_calls: Vec<ToolCall>,
     pub finish_reason: Option<FinishReason>,
     pub usage: Option<Usage>,
+    /// Rate limits reported by the provider, set on the first message of a
+    /// response
+    pub rate_limit: Option<RateLimit>,
 }
 
 impl From<FinishReason> for ChatCompletionMessage {
@@ -190,6 +198,7 @@ mod tests {
             completion_tokens: TokenCount::Actual(50),
             total_tokens: TokenCount::Actual(150),
             cached_tokens: TokenCount::Actual(20),
+            cache_write_tokens: TokenCount::Actual(0),
             cost: Some(0.01),
         };
 
@@ -198,6 +207,7 @@ mod tests {
             completion_tokens: TokenCount::Actual(75),
             total_tokens: TokenCount::Actual(275),
             cached_tokens: TokenCount::Actual(30),
+            cache_write_tokens: TokenCount::Actual(0),
             cost: Some(0.02),
         };
 
@@ -208,6 +218,7 @@ mod tests {
             completion_tokens: TokenCount::Actual(125),
             total_tokens: TokenCount::Actual(425),
             cached_tokens: TokenCount::Actual(50),
+            cache_write_tokens: TokenCount::Actual(0),
             cost: Some(0.03),
         };
 
@@ -221,6 +232,7 @@ mod tests {
             completion_tokens: TokenCount::Approx(50),
             total_tokens: TokenCount::Actual(150),
             cached_tokens: TokenCount::Actual(20),
+            cache_write_tokens: TokenCount::Actual(0),
             cost: Some(0.01),
         };
 
@@ -229,6 +241,7 @@ mod tests {
             completion_tokens: TokenCount::Actual(75),
             total_tokens: TokenCount::Approx(275),
             cached_tokens: TokenCount::Approx(30),
+            cache_write_tokens: TokenCount::Actual(0),
             cost: Some(0.02),
         };
 
@@ -239,6 +252,7 @@ mod tests {
             completion_tokens: TokenCount::Approx(125),
             total_tokens: TokenCount::Approx(425),
             cached_tokens: TokenCount::Approx(50),
+            cache_write_tokens: TokenCount::Actual(0),
             cost: Some(0.03),
         };
 
@@ -252,6 +266,7 @@ mod tests {
             completion_tokens: TokenCount::Actual(50),
             total_tokens: TokenCount::Actual(150),
             cached_tokens: TokenCount::Actual(20),
+            cache_write_tokens: TokenCount::Actual(0),
             cost: Some(0.01),
         };
 
@@ -260,6 +275,7 @@ mod tests {
             completion_tokens: TokenCount::Actual(75),
             total_tokens: TokenCount::Actual(275),
             cached_tokens: TokenCount::Actual(30),
+            cache_write_tokens: TokenCount::Actual(0),
             cost: None,
         };
 
@@ -270,6 +286,7 @@ mod tests {
             completion_tokens: TokenCount::Actual(125),
             total_tokens: TokenCount::Actual(425),
             cached_tokens: TokenCount::Actual(50),
+            cache_write_tokens: TokenCount::Actual(0),
             cost: Some(0.01),
         };
 
@@ -283,6 +300,7 @@ mod tests {
             completion_tokens: TokenCount::Actual(50),
             total_tokens: TokenCount::Actual(150),
             cached_tokens: TokenCount::Actual(20),
+            cache_write_tokens: TokenCount::Actual(0),
             cost: None,
         };
 
@@ -291,6 +309,7 @@ mod tests {
             completion_tokens: TokenCount::Actual(75),
             total_tokens: TokenCount::Actual(275),
             cached_tokens: TokenCount::Actual(30),
+            cache_write_tokens: TokenCount::Actual(0),
             cost: None,
         };
 
@@ -301,6 +320,7 @@ mod tests {
             completion_tokens: TokenCount::Actual(125),
             total_tokens: TokenCount::Actual(425),
             cached_tokens: TokenCount::Actual(50),
+            cache_write_tokens: TokenCount::Actual(0),
             cost: None,
         };
 
@@ -316,6 +336,7 @@ mod tests {
             completion_tokens: TokenCount::Actual(75),
             total_tokens: TokenCount::Actual(275),
             cached_tokens: TokenCount::Actual(30),
+            cache_write_tokens: TokenCount::Actual(0),
             cost: Some(0.05),
         };
 
@@ -326,6 +347,7 @@ mod tests {
             completion_tokens: TokenCount::Actual(75),
             total_tokens: TokenCount::Actual(275),
             cached_tokens: TokenCount::Actual(30),
+            cache_write_tokens: TokenCount::Actual(0),
             cost: Some(0.05),
         };
 
@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ChatCompletionMessage, ModelId, Usage};
+
+/// Prices of a model in USD per million tokens
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelPricing {
+    /// Price of prompt tokens
+    pub input: f64,
+    /// Price of completion tokens
+    pub output: f64,
+    /// Price of prompt tokens read from the cache, the input price when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_read: Option<f64>,
+    /// Price of prompt tokens written to the cache, the input price when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_write: Option<f64>,
+}
+
+impl ModelPricing {
+    pub fn new(input: f64, output: f64) -> Self {
+        Self { input, output, cache_read: None, cache_write: None }
+    }
+
+    /// Cost of the usage in USD
+    pub fn cost(&self, usage: &Usage) -> f64 {
+        let prompt = *usage.prompt_tokens;
+        let cache_read = (*usage.cached_tokens).min(prompt);
+        let cache_write = (*usage.cache_write_tokens).min(prompt - cache_read);
+        let input = prompt - cache_read - cache_write;
+
+        let cost = input as f64 * self.input
+            + cache_read as f64 * self.cache_read.unwrap_or(self.input)
+            + cache_write as f64 * self.cache_write.unwrap_or(self.input)
+            + *usage.completion_tokens as f64 * self.output;
+        cost / 1_000_000.0
+    }
+
+    /// Fills the cost of the message usage when the provider did not report it
+    pub fn apply(&self, mut message: ChatCompletionMessage) -> ChatCompletionMessage {
+        if let Some(usage) = message.usage.as_mut()
+            && usage.cost.is_none()
+        {
+            usage.cost = Some(self.cost(usage));
+        }
+        message
+    }
+}
+
+/// Prices of models keyed by model id. The `*` entry applies to every model
+/// without an entry of its own.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct PricingCatalogue(HashMap<ModelId, ModelPricing>);
+
+impl PricingCatalogue {
+    /// Returns the prices of the model
+    pub fn get(&self, model: &ModelId) -> Option<&ModelPricing> {
+        self.0.get(model).or_else(|| self.0.get(&ModelId::new("*")))
+    }
+
+    /// Adds the prices of the other catalogue, replacing those of the same
+    /// models
+    pub fn merge(&mut self, other: PricingCatalogue) {
+        self.0.extend(other.0);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl FromIterator<(ModelId, ModelPricing)> for PricingCatalogue {
+    fn from_iter<T: IntoIterator<Item = (ModelId, ModelPricing)>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::TokenCount;
+
+    fn sonnet() -> ModelPricing {
+        ModelPricing {
+            cache_read: Some(0.3),
+            cache_write: Some(3.75),
+            ..ModelPricing::new(3.0, 15.0)
+        }
+    }
+
+    #[test]
+    fn test_cost() {
+        let fixture = Usage {
+            prompt_tokens: TokenCount::Actual(1_000_000),
+            completion_tokens: TokenCount::Actual(100_000),
+            total_tokens: TokenCount::Actual(1_100_000),
+            cached_tokens: TokenCount::Actual(600_000),
+            cache_write_tokens: TokenCount::Actual(200_000),
+            cost: None,
+        };
+
+        let actual = sonnet().cost(&fixture);
+
+        // 200k input + 600k cache reads + 200k cache writes + 100k output
+        let expected = 0.6 + 0.18 + 0.75 + 1.5;
+        assert!((actual - expected).abs() < 1e-9, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn test_cost_without_cache_prices() {
+        let fixture = Usage {
+            prompt_tokens: TokenCount::Actual(2_000),
+            completion_tokens: TokenCount::Actual(1_000),
+            cached_tokens: TokenCount::Actual(1_000),
+            ..Default::default()
+        };
+
+        let actual = ModelPricing::new(1.0, 4.0).cost(&fixture);
+
+        assert!((actual - 0.006).abs() < 1e-12, "{actual}");
+    }
+
+    #[test]
+    fn test_apply_keeps_reported_cost() {
+        let usage = Usage {
+            prompt_tokens: TokenCount::Actual(1_000_000),
+            ..Default::default()
+        };
+        let fixture = [
+            ChatCompletionMessage::default().usage(usage),
+            ChatCompletionMessage::default().usage(Usage { cost: Some(0.5), ..usage }),
+            ChatCompletionMessage::default(),
+        ];
+
+        let actual: Vec<_> = fixture
+            .map(|message| sonnet().apply(message).usage.and_then(|usage| usage.cost))
+            .to_vec();
+
+        assert_eq!(actual, vec![Some(3.0), Some(0.5), None]);
+    }
+
+    #[test]
+    fn test_catalogue_get() {
+        let mut fixture: PricingCatalogue =
+            serde_json::from_str(r#"{"*": {"input": 0, "output": 0}}"#).unwrap();
+        fixture.merge(PricingCatalogue::from_iter([(
+            ModelId::new("claude-sonnet-4-5"),
+            sonnet(),
+        )]));
+
+        let actual = (
+            fixture.get(&ModelId::new("claude-sonnet-4-5")).copied(),
+            fixture.get(&ModelId::new("llama3")).copied(),
+            PricingCatalogue::default()
+                .get(&ModelId::new("llama3"))
+                .copied(),
+        );
+
+        let expected = (Some(sonnet()), Some(ModelPricing::new(0.0, 0.0)), None);
+        assert_eq!(actual, expected);
+    }
+}
@@ -7,7 +7,7 @@ use serde::{Deserialize, Serialize};
 use strum_macros::{Display, EnumString};
 use url::Url;
 
-use crate::{ApiKey, AuthCredential, AuthDetails, Model, Template};
+use crate::{ApiKey, AuthCredential, AuthDetails, Model, PricingCatalogue, Template};
 
 /// Distinguishes between different categories of providers
 #[derive(
@@ -65,6 +65,11 @@ impl ProviderId {
     pub const IO_INTELLIGENCE: ProviderId = ProviderId(Cow::Borrowed("io_intelligence"));
     pub const BEDROCK: ProviderId = ProviderId(Cow::Borrowed("bedrock"));
 
+    /// Returns the id as written in configuration files, e.g. `open_router`
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
     /// Returns all built-in provider IDs
     ///
     /// This includes all providers defined as constants in this implementation.
@@ -163,8 +168,10 @@ impl From<String> for ProviderId {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ProviderResponse {
     OpenAI,
+    OpenAIResponses,
     Anthropic,
     Bedrock,
+    Gemini,
 }
 
 /// Represents the source of models for a provider
@@ -188,6 +195,10 @@ pub struct Provider<T> {
     #[serde(default)]
     pub url_params: Vec<crate::URLParam>,
     pub credential: Option<AuthCredential>,
+    /// Prices of the models, used to compute the cost of completions when the
+    /// provider does not report it
+    #[serde(default, skip_serializing_if = "PricingCatalogue::is_empty")]
+    pub pricing: PricingCatalogue,
 }
 
 impl<T> Provider<T> {
@@ -312,6 +323,7 @@ mod test_helpers {
             models: Some(ModelSource::Url(
                 Url::parse("https://api.z.ai/api/paas/v4/models").unwrap(),
             )),
+            pricing: Default::default(),
         }
     }
 
@@ -328,6 +340,7 @@ mod test_helpers {
             models: Some(ModelSource::Url(
                 Url::parse("https://api.z.ai/api/paas/v4/models").unwrap(),
             )),
+            pricing: Default::default(),
         }
     }
 
@@ -344,6 +357,7 @@ mod test_helpers {
             models: Some(ModelSource::Url(
                 Url::parse("https://api.openai.com/v1/models").unwrap(),
             )),
+            pricing: Default::default(),
         }
     }
 
@@ -360,6 +374,7 @@ mod test_helpers {
             models: Some(ModelSource::Url(
                 Url::parse("https://api.x.ai/v1/models").unwrap(),
             )),
+            pricing: Default::default(),
         }
     }
 
@@ -400,6 +415,7 @@ mod test_helpers {
                 .collect(),
             credential: make_credential(ProviderId::VERTEX_AI, key),
             models: Some(ModelSource::Url(Url::parse(&model_url).unwrap())),
+            pricing: Default::default(),
         }
     }
 
@@ -417,6 +433,7 @@ mod test_helpers {
             models: Some(ModelSource::Url(
                 Url::parse("https://api.intelligence.io.solutions/api/v1/models").unwrap(),
             )),
+            pricing: Default::default(),
         }
     }
 
@@ -448,6 +465,7 @@ mod test_helpers {
                 .collect(),
             credential: make_credential(ProviderId::AZURE, key),
             models: Some(ModelSource::Url(Url::parse(&model_url).unwrap())),
+            pricing: Default::default(),
         }
     }
 }
@@ -502,6 +520,7 @@ mod tests {
             models: Some(ModelSource::Url(
                 Url::from_str("https://api.intelligence.io.solutions/api/v1/models").unwrap(),
             )),
+            pricing: Default::default(),
         };
         assert_eq!(actual, expected);
     }
@@ -525,6 +544,7 @@ mod tests {
             models: Some(ModelSource::Url(
                 Url::from_str("https://api.x.ai/v1/models").unwrap(),
             )),
+            pricing: Default::default(),
         };
         assert_eq!(actual, expected);
     }
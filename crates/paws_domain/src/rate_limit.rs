@@ -0,0 +1,370 @@
+use std::fmt;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+use crate::Error;
+
+/// Rate limits reported by a provider in the headers of a response
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RateLimit {
+    /// How long the provider asked to wait before the next request
+    pub retry_after: Option<Duration>,
+    /// Limit on the number of requests
+    pub requests: Option<RateLimitWindow>,
+    /// Limit on the number of tokens
+    pub tokens: Option<RateLimitWindow>,
+}
+
+/// Quota of a rate limit over its current window
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RateLimitWindow {
+    pub limit: Option<u64>,
+    pub remaining: Option<u64>,
+    /// Time until the quota is replenished
+    pub reset_after: Option<Duration>,
+}
+
+impl RateLimitWindow {
+    fn is_exhausted(&self) -> bool {
+        self.remaining == Some(0)
+    }
+}
+
+impl RateLimit {
+    /// Reads the `Retry-After`, `x-ratelimit-*` (OpenAI and compatible
+    /// providers) and `anthropic-ratelimit-*` headers, `None` when none of
+    /// them is present. Header names are expected in lowercase.
+    pub fn parse<'a>(
+        headers: impl IntoIterator<Item = (&'a str, &'a str)>,
+        now: DateTime<Utc>,
+    ) -> Option<Self> {
+        let mut rate_limit = Self::default();
+        let mut retry_after_ms = None;
+        for (name, value) in headers {
+            let value = value.trim();
+            let (window, field) = match name {
+                "retry-after-ms" => {
+                    retry_after_ms = value.parse().ok().map(Duration::from_millis);
+                    continue;
+                }
+                "retry-after" => {
+                    rate_limit.retry_after = parse_reset(value, now);
+                    continue;
+                }
+                "x-ratelimit-limit-requests" | "anthropic-ratelimit-requests-limit" => {
+                    (&mut rate_limit.requests, Field::Limit)
+                }
+                "x-ratelimit-remaining-requests" | "anthropic-ratelimit-requests-remaining" => {
+                    (&mut rate_limit.requests, Field::Remaining)
+                }
+                "x-ratelimit-reset-requests" | "anthropic-ratelimit-requests-reset" => {
+                    (&mut rate_limit.requests, Field::Reset)
+                }
+                "x-ratelimit-limit-tokens" | "anthropic-ratelimit-tokens-limit" => {
+                    (&mut rate_limit.tokens, Field::Limit)
+                }
+                "x-ratelimit-remaining-tokens" | "anthropic-ratelimit-tokens-remaining" => {
+                    (&mut rate_limit.tokens, Field::Remaining)
+                }
+                "x-ratelimit-reset-tokens" | "anthropic-ratelimit-tokens-reset" => {
+                    (&mut rate_limit.tokens, Field::Reset)
+                }
+                _ => continue,
+            };
+            let window = window.get_or_insert_default();
+            match field {
+                Field::Limit => window.limit = value.parse().ok(),
+                Field::Remaining => window.remaining = value.parse().ok(),
+                Field::Reset => window.reset_after = parse_reset(value, now),
+            }
+        }
+        // The millisecond variant is more precise
+        if retry_after_ms.is_some() {
+            rate_limit.retry_after = retry_after_ms;
+        }
+
+        (rate_limit != Self::default()).then_some(rate_limit)
+    }
+
+    /// How long to wait before the next request: the delay asked by the
+    /// provider, or else the time until every exhausted quota resets
+    pub fn delay(&self) -> Option<Duration> {
+        self.retry_after.or_else(|| {
+            [self.requests, self.tokens]
+                .into_iter()
+                .flatten()
+                .filter(RateLimitWindow::is_exhausted)
+                .filter_map(|window| window.reset_after)
+                .max()
+        })
+    }
+
+    /// How long to hold back new requests once fewer than `reserve` requests
+    /// are left in the window, or the token quota is exhausted, so that the
+    /// requests already in flight don't run into the limit
+    pub fn hold_back(&self, reserve: u64) -> Option<Duration> {
+        let requests = self.requests.filter(|window| {
+            window
+                .remaining
+                .is_some_and(|remaining| remaining < reserve)
+        });
+        let tokens = self.tokens.filter(RateLimitWindow::is_exhausted);
+        [requests, tokens]
+            .into_iter()
+            .flatten()
+            .filter_map(|window| window.reset_after)
+            .max()
+    }
+
+    /// Finds the rate limit attached to an error, looking through retryable
+    /// errors
+    pub fn find(error: &anyhow::Error) -> Option<&RateLimit> {
+        error
+            .downcast_ref::<RateLimit>()
+            .or_else(|| match error.downcast_ref::<Error>() {
+                Some(Error::Retryable(error)) => Self::find(error),
+                _ => None,
+            })
+    }
+}
+
+enum Field {
+    Limit,
+    Remaining,
+    Reset,
+}
+
+/// Parses a reset time given in seconds, as a duration such as `6m0s` or
+/// `20ms`, or as an RFC 3339 or HTTP date
+fn parse_reset(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    if let Ok(seconds) = value.parse::<f64>() {
+        return Duration::try_from_secs_f64(seconds).ok();
+    }
+    if let Some(duration) = parse_duration(value) {
+        return Some(duration);
+    }
+    let date = DateTime::parse_from_rfc3339(value)
+        .or_else(|_| DateTime::parse_from_rfc2822(value))
+        .ok()?;
+    // A date in the past means the quota was already replenished
+    Some(
+        (date.with_timezone(&Utc) - now)
+            .to_std()
+            .unwrap_or_default(),
+    )
+}
+
+/// Parses durations made of `h`, `m`, `s` and `ms` components, e.g. `1m30.5s`
+fn parse_duration(value: &str) -> Option<Duration> {
+    let mut rest = value;
+    let mut total = 0.0;
+    while !rest.is_empty() {
+        let number_end = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .filter(|end| *end > 0)?;
+        let (number, tail) = rest.split_at(number_end);
+        let unit_end = tail
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(tail.len());
+        let (unit, tail) = tail.split_at(unit_end);
+        let seconds = match unit {
+            "h" => 3600.0,
+            "m" => 60.0,
+            "s" => 1.0,
+            "ms" => 0.001,
+            _ => return None,
+        };
+        total += number.parse::<f64>().ok()? * seconds;
+        rest = tail;
+    }
+    Duration::try_from_secs_f64(total).ok()
+}
+
+impl fmt::Display for RateLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(retry_after) = self.retry_after {
+            parts.push(format!("retry after {retry_after:?}"));
+        }
+        for (name, window) in [("requests", self.requests), ("tokens", self.tokens)] {
+            let Some(RateLimitWindow { remaining: Some(remaining), limit, .. }) = window else {
+                continue;
+            };
+            parts.push(match limit {
+                Some(limit) => format!("{remaining}/{limit} {name} left"),
+                None => format!("{remaining} {name} left"),
+            });
+        }
+        write!(f, "Rate limit: {}", parts.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_parse_openai_headers() {
+        let fixture = [
+            ("x-ratelimit-limit-requests", "500"),
+            ("x-ratelimit-remaining-requests", "0"),
+            ("x-ratelimit-reset-requests", "1m30.5s"),
+            ("x-ratelimit-limit-tokens", "30000"),
+            ("x-ratelimit-remaining-tokens", "29000"),
+            ("x-ratelimit-reset-tokens", "20ms"),
+            ("content-type", "application/json"),
+        ];
+
+        let actual = RateLimit::parse(fixture, now());
+
+        let expected = Some(RateLimit {
+            retry_after: None,
+            requests: Some(RateLimitWindow {
+                limit: Some(500),
+                remaining: Some(0),
+                reset_after: Some(Duration::from_millis(90_500)),
+            }),
+            tokens: Some(RateLimitWindow {
+                limit: Some(30000),
+                remaining: Some(29000),
+                reset_after: Some(Duration::from_millis(20)),
+            }),
+        });
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_anthropic_headers() {
+        let fixture = [
+            ("retry-after", "12"),
+            ("anthropic-ratelimit-tokens-limit", "80000"),
+            ("anthropic-ratelimit-tokens-remaining", "0"),
+            ("anthropic-ratelimit-tokens-reset", "2025-01-01T12:00:45Z"),
+        ];
+
+        let actual = RateLimit::parse(fixture, now());
+
+        let expected = Some(RateLimit {
+            retry_after: Some(Duration::from_secs(12)),
+            requests: None,
+            tokens: Some(RateLimitWindow {
+                limit: Some(80000),
+                remaining: Some(0),
+                reset_after: Some(Duration::from_secs(45)),
+            }),
+        });
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let actual = [
+            RateLimit::parse([("retry-after", "Wed, 01 Jan 2025 12:00:30 GMT")], now()),
+            RateLimit::parse([("retry-after", "2"), ("retry-after-ms", "1500")], now()),
+            RateLimit::parse([("content-type", "text/plain")], now()),
+        ]
+        .map(|rate_limit| rate_limit.and_then(|rate_limit| rate_limit.retry_after));
+
+        let expected = [
+            Some(Duration::from_secs(30)),
+            Some(Duration::from_millis(1500)),
+            None,
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_delay() {
+        let exhausted = |reset_after| RateLimitWindow {
+            limit: Some(10),
+            remaining: Some(0),
+            reset_after: Some(Duration::from_secs(reset_after)),
+        };
+        let available = RateLimitWindow { remaining: Some(5), ..exhausted(60) };
+
+        let actual = [
+            RateLimit {
+                retry_after: Some(Duration::from_secs(3)),
+                ..Default::default()
+            },
+            RateLimit {
+                requests: Some(exhausted(10)),
+                tokens: Some(exhausted(20)),
+                ..Default::default()
+            },
+            RateLimit { requests: Some(available), ..Default::default() },
+        ]
+        .map(|rate_limit| rate_limit.delay());
+
+        let expected = [
+            Some(Duration::from_secs(3)),
+            Some(Duration::from_secs(20)),
+            None,
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_hold_back() {
+        let window = |remaining, reset_after| RateLimitWindow {
+            limit: Some(100),
+            remaining: Some(remaining),
+            reset_after: Some(Duration::from_secs(reset_after)),
+        };
+
+        let actual = [
+            RateLimit { requests: Some(window(3, 10)), ..Default::default() },
+            RateLimit { requests: Some(window(4, 10)), ..Default::default() },
+            RateLimit {
+                requests: Some(window(50, 10)),
+                tokens: Some(window(0, 20)),
+                ..Default::default()
+            },
+            RateLimit { tokens: Some(window(3, 20)), ..Default::default() },
+        ]
+        .map(|rate_limit| rate_limit.hold_back(4));
+
+        let expected = [
+            Some(Duration::from_secs(10)),
+            None,
+            Some(Duration::from_secs(20)),
+            None,
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_find_through_retryable_error() {
+        let rate_limit = RateLimit {
+            retry_after: Some(Duration::from_secs(3)),
+            requests: Some(RateLimitWindow {
+                limit: Some(50),
+                remaining: Some(0),
+                reset_after: None,
+            }),
+            tokens: None,
+        };
+        let fixture: anyhow::Error = Error::Retryable(
+            anyhow::anyhow!("Invalid status code")
+                .context(rate_limit.clone())
+                .context("429 Too Many Requests"),
+        )
+        .into();
+
+        let actual = RateLimit::find(&fixture);
+
+        assert_eq!(actual, Some(&rate_limit));
+        assert_eq!(
+            rate_limit.to_string(),
+            "Rate limit: retry after 3s, 0/50 requests left"
+        );
+    }
+}
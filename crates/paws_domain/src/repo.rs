@@ -1,11 +1,12 @@
 use std::path::Path;
 
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use url::Url;
 
 use crate::{
-    AnyProvider, AppConfig, AuthCredential, Conversation, ConversationId, MigrationResult,
-    Provider, ProviderId, Skill, Snapshot,
+    AnyProvider, AppConfig, AuthCredential, Conversation, ConversationId, ConversationMatch,
+    MigrationResult, ModelId, Provider, ProviderId, Skill, Snapshot, Usage, UsageReportEntry,
 };
 
 /// Repository for managing file snapshots
@@ -31,6 +32,44 @@ pub trait SnapshotRepository: Send + Sync {
     /// # Errors
     /// Returns an error if no snapshot exists or restoration fails
     async fn undo_snapshot(&self, file_path: &Path) -> Result<()>;
+
+    /// Reads the content stored in the given snapshot
+    ///
+    /// # Arguments
+    /// * `snapshot` - The snapshot to read
+    ///
+    /// # Errors
+    /// Returns an error if the snapshot exists but can't be read. Returns
+    /// `None` if the snapshot no longer exists, eg: it was consumed by an undo.
+    async fn read_snapshot(&self, snapshot: &Snapshot) -> Result<Option<Vec<u8>>>;
+
+    /// Lists the snapshots available for the given file path, oldest first
+    ///
+    /// # Arguments
+    /// * `file_path` - Path to the file whose history is listed
+    ///
+    /// # Errors
+    /// Returns an error if the snapshot directory can't be read
+    async fn list_snapshots(&self, file_path: &Path) -> Result<Vec<Snapshot>>;
+
+    /// Restores the file of the given snapshot to the snapshot's content. The
+    /// current content is snapshotted first so that the restore can be undone.
+    ///
+    /// # Arguments
+    /// * `snapshot` - The snapshot to restore
+    ///
+    /// # Errors
+    /// Returns an error if the snapshot no longer exists or restoration fails
+    async fn restore_snapshot(&self, snapshot: &Snapshot) -> Result<()>;
+
+    /// Reverts the most recent undo of the given file path
+    ///
+    /// # Arguments
+    /// * `file_path` - Path to the file to redo
+    ///
+    /// # Errors
+    /// Returns an error if there is nothing to redo or restoration fails
+    async fn redo_snapshot(&self, file_path: &Path) -> Result<()>;
 }
 
 /// Repository for managing conversation persistence
@@ -78,6 +117,20 @@ pub trait ConversationRepository: Send + Sync {
     /// Returns an error if the operation fails
     async fn get_last_conversation(&self) -> Result<Option<Conversation>>;
 
+    /// Searches conversation titles and message text, best matches first
+    ///
+    /// # Arguments
+    /// * `query` - Words to search for, each matching as a prefix
+    /// * `limit` - Optional maximum number of matches to retrieve
+    ///
+    /// # Errors
+    /// Returns an error if the operation fails
+    async fn search_conversations(
+        &self,
+        query: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<ConversationMatch>>;
+
     /// Permanently deletes a conversation
     ///
     /// # Arguments
@@ -86,6 +139,54 @@ pub trait ConversationRepository: Send + Sync {
     /// # Errors
     /// Returns an error if the operation fails
     async fn delete_conversation(&self, conversation_id: &ConversationId) -> Result<()>;
+
+    /// Appends the usage of a completion to the workspace usage ledger
+    ///
+    /// # Arguments
+    /// * `conversation_id` - The conversation the completion belongs to
+    /// * `usage` - The tokens and cost of the completion
+    /// * `model` - The model that made the completion
+    /// * `provider` - The provider serving the model
+    ///
+    /// # Errors
+    /// Returns an error if the operation fails
+    async fn record_usage(
+        &self,
+        conversation_id: &ConversationId,
+        usage: &Usage,
+        model: &ModelId,
+        provider: &ProviderId,
+    ) -> Result<()>;
+
+    /// Returns the usage of all conversations in the workspace recorded since
+    /// the given instant
+    ///
+    /// # Arguments
+    /// * `since` - Start of the period to sum up
+    ///
+    /// # Errors
+    /// Returns an error if the operation fails
+    async fn get_usage_since(&self, since: DateTime<Utc>) -> Result<Usage>;
+
+    /// Returns the usage recorded for a conversation over its whole lifetime,
+    /// regardless of later compaction or rewinds of its context
+    ///
+    /// # Arguments
+    /// * `conversation_id` - The conversation to sum up
+    ///
+    /// # Errors
+    /// Returns an error if the operation fails
+    async fn get_conversation_usage(&self, conversation_id: &ConversationId) -> Result<Usage>;
+
+    /// Returns the usage of every workspace recorded since the given instant,
+    /// summed per day, workspace, provider and model
+    ///
+    /// # Arguments
+    /// * `since` - Start of the period to report
+    ///
+    /// # Errors
+    /// Returns an error if the operation fails
+    async fn get_usage_report(&self, since: DateTime<Utc>) -> Result<Vec<UsageReportEntry>>;
 }
 
 #[async_trait::async_trait]
@@ -273,6 +273,7 @@ mod tests {
                     completion_tokens: TokenCount::Actual(5),
                     total_tokens: TokenCount::Actual(15),
                     cached_tokens: TokenCount::Actual(0),
+                    cache_write_tokens: TokenCount::Actual(0),
                     cost: None,
                 })),
             Ok(ChatCompletionMessage::default()
@@ -282,6 +283,7 @@ mod tests {
                     completion_tokens: TokenCount::Actual(10),
                     total_tokens: TokenCount::Actual(20),
                     cached_tokens: TokenCount::Actual(0),
+                    cache_write_tokens: TokenCount::Actual(0),
                     cost: None,
                 })),
         ];
@@ -301,6 +303,7 @@ mod tests {
                 completion_tokens: TokenCount::Actual(10),
                 total_tokens: TokenCount::Actual(20),
                 cached_tokens: TokenCount::Actual(0),
+                cache_write_tokens: TokenCount::Actual(0),
                 cost: None,
             },
             reasoning: None,
@@ -506,6 +509,7 @@ mod tests {
                 completion_tokens: TokenCount::Actual(15),
                 total_tokens: TokenCount::Actual(20),
                 cached_tokens: TokenCount::Actual(0),
+                cache_write_tokens: TokenCount::Actual(0),
                 cost: None,
             })),
         ];
@@ -522,6 +526,7 @@ mod tests {
             completion_tokens: TokenCount::Actual(15),
             total_tokens: TokenCount::Actual(20),
             cached_tokens: TokenCount::Actual(0),
+            cache_write_tokens: TokenCount::Actual(0),
             cost: None,
         };
         assert_eq!(actual.usage, expected_final_usage);
@@ -546,6 +551,7 @@ mod tests {
                     completion_tokens: TokenCount::Actual(15),
                     total_tokens: TokenCount::Actual(20),
                     cached_tokens: TokenCount::Actual(0),
+                    cache_write_tokens: TokenCount::Actual(0),
                     cost: None,
                 })),
         ];
@@ -566,6 +572,7 @@ mod tests {
                 completion_tokens: TokenCount::Actual(15),
                 total_tokens: TokenCount::Actual(20),
                 cached_tokens: TokenCount::Actual(0),
+                cache_write_tokens: TokenCount::Actual(0),
                 cost: None,
             },
             reasoning: None,
@@ -588,6 +595,7 @@ mod tests {
                 completion_tokens: TokenCount::Actual(15),
                 total_tokens: TokenCount::Actual(20),
                 cached_tokens: TokenCount::Actual(0),
+                cache_write_tokens: TokenCount::Actual(0),
                 cost: None,
             })),
         ];
@@ -607,6 +615,7 @@ mod tests {
                 completion_tokens: TokenCount::Actual(15),
                 total_tokens: TokenCount::Actual(20),
                 cached_tokens: TokenCount::Actual(0),
+                cache_write_tokens: TokenCount::Actual(0),
                 cost: None,
             },
             reasoning: None,
@@ -728,6 +737,7 @@ mod tests {
                 completion_tokens: TokenCount::Actual(20),
                 total_tokens: TokenCount::Actual(25),
                 cached_tokens: TokenCount::Actual(0),
+                cache_write_tokens: TokenCount::Actual(0),
                 cost: None,
             })),
         ];
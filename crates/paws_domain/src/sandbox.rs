@@ -0,0 +1,194 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::CommandOutput;
+
+/// Error message of file operations Landlock refuses with `EACCES`
+const PATH_DENIAL_MARKER: &str = "Permission denied";
+
+/// Error messages of network operations, reported when the sandbox blocks the
+/// network. Seccomp refuses sockets with `EPERM`.
+const NETWORK_DENIAL_MARKERS: &[&str] = &[
+    "Operation not permitted",
+    "Could not resolve host",
+    "Temporary failure in name resolution",
+    "Network is unreachable",
+    "Address family not supported",
+];
+
+/// Maximum number of denied operations reported for a single command
+const MAX_DENIALS: usize = 10;
+
+/// Limits of the OS-level sandbox shell commands run in. Commands can write
+/// to the workspace and read system directories in addition to the paths
+/// listed here.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, fake::Dummy)]
+#[serde(rename_all = "camelCase")]
+pub struct SandboxPolicy {
+    /// Additional paths commands can read
+    pub read_only: Vec<PathBuf>,
+    /// Additional paths commands can read and write
+    pub writable: Vec<PathBuf>,
+    /// Whether commands can open network connections
+    pub network: bool,
+}
+
+impl SandboxPolicy {
+    /// Returns the lines of the error output of the command that look like
+    /// operations the sandbox denied: permission errors on paths outside the
+    /// writable roots, and network errors when the network is blocked
+    pub fn denials(&self, output: &CommandOutput, workspace: &Path) -> Vec<String> {
+        let network_markers = if self.network {
+            &[][..]
+        } else {
+            NETWORK_DENIAL_MARKERS
+        };
+        let mut roots = vec![workspace.to_path_buf(), std::env::temp_dir()];
+        roots.extend(self.writable.iter().cloned());
+
+        output
+            .stderr
+            .lines()
+            .map(str::trim)
+            .filter(|line| {
+                network_markers.iter().any(|marker| line.contains(marker))
+                    || (line.contains(PATH_DENIAL_MARKER)
+                        && paths(line).any(|path| !roots.iter().any(|root| path.starts_with(root))))
+            })
+            .take(MAX_DENIALS)
+            .map(String::from)
+            .collect()
+    }
+
+    /// Describes what commands can access when run from the workspace
+    pub fn describe(&self, workspace: &Path) -> String {
+        let display = |paths: &[PathBuf]| {
+            paths
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        let mut writable = vec![workspace.display().to_string(), "the temp directory".into()];
+        writable.extend(display(&self.writable));
+        let mut readable = vec!["those paths".to_string(), "system directories".into()];
+        readable.extend(display(&self.read_only));
+
+        let mut description = format!(
+            "Shell commands run in a sandbox. They can only write to {}, and only read {}.",
+            join(&writable),
+            join(&readable)
+        );
+        if !self.network {
+            description.push_str(" Network access is blocked.");
+        }
+        description
+    }
+}
+
+/// Absolute paths mentioned in an error message, e.g. in
+/// `touch: cannot touch '/etc/motd': Permission denied`
+fn paths(line: &str) -> impl Iterator<Item = &Path> {
+    line.split(|c: char| c.is_whitespace() || "'\"`‘’“”".contains(c))
+        .map(|word| word.trim_end_matches([':', ',', ';']))
+        .filter(|word| word.starts_with('/'))
+        .map(Path::new)
+}
+
+/// Joins the items into an English list
+fn join(items: &[String]) -> String {
+    match items {
+        [] => String::new(),
+        [item] => item.clone(),
+        [init @ .., last] => format!("{} and {last}", init.join(", ")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn output(stderr: &str) -> CommandOutput {
+        CommandOutput {
+            command: "make".to_string(),
+            stdout: "".to_string(),
+            stderr: stderr.to_string(),
+            exit_code: Some(1),
+        }
+    }
+
+    #[test]
+    fn test_denials() {
+        let fixture = output(
+            "touch: cannot touch '/etc/hosts': Permission denied\n\
+             compiling\n\
+             curl: (6) Could not resolve host: example.com\n\
+             ping: socket: Operation not permitted\n",
+        );
+
+        let actual = (
+            SandboxPolicy::default().denials(&fixture, Path::new("/work")),
+            SandboxPolicy { network: true, ..Default::default() }
+                .denials(&fixture, Path::new("/work")),
+        );
+
+        let expected = (
+            vec![
+                "touch: cannot touch '/etc/hosts': Permission denied".to_string(),
+                "curl: (6) Could not resolve host: example.com".to_string(),
+                "ping: socket: Operation not permitted".to_string(),
+            ],
+            vec!["touch: cannot touch '/etc/hosts': Permission denied".to_string()],
+        );
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_denials_ignore_permission_errors_within_roots() {
+        let fixture = output(
+            "git@github.com: Permission denied (publickey).\n\
+             bash: ./deploy.sh: Permission denied\n\
+             bash: /work/deploy.sh: Permission denied\n\
+             cp: cannot create regular file '/home/user/.cache/x': Permission denied\n\
+             mkdir: cannot create directory ‘/opt/tool’: Permission denied\n",
+        );
+        let policy = SandboxPolicy {
+            writable: vec![PathBuf::from("/home/user/.cache")],
+            network: true,
+            ..Default::default()
+        };
+
+        let actual = policy.denials(&fixture, Path::new("/work"));
+
+        let expected = vec!["mkdir: cannot create directory ‘/opt/tool’: Permission denied"];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_denials_are_capped() {
+        let fixture = output(&"rm: cannot remove '/etc/x': Permission denied\n".repeat(20));
+
+        let actual = SandboxPolicy::default()
+            .denials(&fixture, Path::new("/work"))
+            .len();
+
+        assert_eq!(actual, MAX_DENIALS);
+    }
+
+    #[test]
+    fn test_describe() {
+        let fixture = SandboxPolicy {
+            read_only: vec![PathBuf::from("/home/user/.cargo")],
+            writable: vec![PathBuf::from("/home/user/.cache")],
+            network: false,
+        };
+
+        let actual = fixture.describe(Path::new("/work"));
+
+        let expected = "Shell commands run in a sandbox. They can only write to /work, the temp directory and /home/user/.cache, and only read those paths, system directories and /home/user/.cargo. Network access is blocked.";
+        assert_eq!(actual, expected);
+    }
+}
@@ -6,6 +6,7 @@ use derive_setters::Setters;
 use serde::{Deserialize, Serialize};
 
 pub use crate::file_operation::FileOperation;
+use crate::{Checkpoint, FileCheckpoint, Snapshot};
 
 #[derive(Debug, Clone, Default, Setters, Serialize, Deserialize)]
 #[setters(into, strip_option)]
@@ -16,6 +17,10 @@ pub struct Metrics {
     /// Holds the last file operation for each file
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub file_operations: HashMap<String, FileOperation>,
+
+    /// One checkpoint per user turn, ordered by turn
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub checkpoints: Vec<Checkpoint>,
 }
 
 impl Metrics {
@@ -26,7 +31,26 @@ impl Metrics {
         self
     }
 
-    /// Gets the session duration if tracking has started
+    /// Starts the checkpoint of a new user turn. `prompts` is the number of
+    /// user prompts in the context before the turn's prompt was added.
+    pub fn begin_turn(mut self, prompts: usize, started_at: DateTime<Utc>) -> Self {
+        let turn = self
+            .checkpoints
+            .last()
+            .map(|checkpoint| checkpoint.turn + 1)
+            .unwrap_or(prompts + 1);
+        self.checkpoints.push(Checkpoint::new(turn, started_at));
+        self
+    }
+
+    /// Records a file modification in the checkpoint of the current turn
+    pub fn record_snapshot(mut self, path: String, snapshot: Option<Snapshot>) -> Self {
+        if let Some(checkpoint) = self.checkpoints.last_mut() {
+            checkpoint.files.push(FileCheckpoint::new(path, snapshot));
+        }
+        self
+    }
+
     /// Gets the session duration if tracking has started
     pub fn duration(&self, now: DateTime<Utc>) -> Option<Duration> {
         self.started_at
@@ -147,4 +171,37 @@ mod tests {
         assert_eq!(operation.lines_removed, 0);
         assert_eq!(operation.content_hash, Some("hash1".to_string()));
     }
+
+    #[test]
+    fn test_begin_turn_continues_from_last_checkpoint() {
+        let started_at = Utc::now();
+        let actual = Metrics::default()
+            .begin_turn(2, started_at)
+            .begin_turn(0, started_at)
+            .checkpoints
+            .into_iter()
+            .map(|checkpoint| checkpoint.turn)
+            .collect::<Vec<_>>();
+
+        let expected = vec![3, 4];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_record_snapshot_into_current_turn() {
+        let started_at = Utc::now();
+        let actual = Metrics::default()
+            .record_snapshot("ignored.rs".to_string(), None)
+            .begin_turn(0, started_at)
+            .record_snapshot("a.rs".to_string(), None)
+            .begin_turn(1, started_at)
+            .record_snapshot("b.rs".to_string(), None)
+            .checkpoints;
+
+        let expected = vec![
+            Checkpoint::new(1, started_at).files(vec![FileCheckpoint::new("a.rs", None)]),
+            Checkpoint::new(2, started_at).files(vec![FileCheckpoint::new("b.rs", None)]),
+        ];
+        assert_eq!(actual, expected);
+    }
 }
@@ -1,5 +1,8 @@
+use derive_more::Display;
+use serde::{Deserialize, Serialize};
+
 /// Output from a command execution
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct CommandOutput {
     pub command: String,
     pub stdout: String,
@@ -12,3 +15,24 @@ impl CommandOutput {
         self.exit_code.is_none_or(|code| code >= 0)
     }
 }
+
+/// Handle of a command running in the background
+#[derive(Debug, Display, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(transparent)]
+pub struct JobId(u64);
+
+impl JobId {
+    pub fn new(id: u64) -> Self {
+        Self(id)
+    }
+}
+
+/// State of a background job along with the output it produced since the
+/// previous read
+#[derive(Debug, Clone, PartialEq)]
+pub struct JobOutput {
+    pub job_id: JobId,
+    /// Incremental output; `exit_code` is only set once the job has exited
+    pub output: CommandOutput,
+    pub running: bool,
+}
@@ -6,6 +6,9 @@ use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+/// Format of the timestamp used to name snapshot files
+const TIME_FORMAT: &str = "%Y-%m-%d_%H-%M-%S-%9f";
+
 /// A newtype for snapshot IDs, internally using UUID
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct SnapshotId(Uuid);
@@ -49,7 +52,7 @@ impl From<Uuid> for SnapshotId {
 ///
 /// Contains details about when the snapshot was created,
 /// the original file path, the snapshot location, and file size.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Snapshot {
     /// Unique ID for the file
     pub id: SnapshotId,
@@ -59,6 +62,11 @@ pub struct Snapshot {
 
     /// Original file path that is being processed
     pub path: String,
+
+    /// Whether the file didn't exist when the snapshot was taken, restoring
+    /// it removes the file
+    #[serde(default)]
+    pub removed: bool,
 }
 
 impl Snapshot {
@@ -81,6 +89,7 @@ impl Snapshot {
             id: SnapshotId::new(),
             timestamp,
             path: path.display().to_string(),
+            removed: false,
         })
     }
 
@@ -91,16 +100,37 @@ impl Snapshot {
         format!("{:x}", hasher.finish())
     }
 
-    /// Create a snapshot filename from a path and timestamp
+    /// Returns the time at which the snapshot was created
+    pub fn created_at(&self) -> chrono::DateTime<chrono::Utc> {
+        chrono::DateTime::<chrono::Utc>::from(UNIX_EPOCH + self.timestamp)
+    }
+
+    /// Create a snapshot file stem from the timestamp.
+    /// Format: YYYY-MM-DD_HH-MM-SS-nnnnnnnnn (including nanoseconds)
+    pub fn file_stem(&self) -> String {
+        self.created_at().format(TIME_FORMAT).to_string()
+    }
+
+    /// Recreates the snapshot of `path` stored in a file with the given stem.
+    /// Returns `None` if the stem wasn't created by [`Snapshot::file_stem`].
+    pub fn from_file_stem(path: impl Into<String>, stem: &str) -> Option<Self> {
+        let datetime = chrono::NaiveDateTime::parse_from_str(stem, TIME_FORMAT)
+            .ok()?
+            .and_utc();
+        let timestamp = Duration::from_nanos(datetime.timestamp_nanos_opt()?.try_into().ok()?);
+        Some(Self {
+            id: SnapshotId::new(),
+            timestamp,
+            path: path.into(),
+            removed: false,
+        })
+    }
+
+    /// Create a snapshot filename from a path and timestamp. Snapshots of a
+    /// removed file are stored as empty `.removed` markers.
     pub fn snapshot_path(&self, cwd: Option<PathBuf>) -> PathBuf {
-        // Convert Duration to SystemTime then to a formatted string
-        let datetime = UNIX_EPOCH + self.timestamp;
-        // Format: YYYY-MM-DD_HH-MM-SS-nnnnnnnnn (including nanoseconds)
-        let formatted_time = chrono::DateTime::<chrono::Utc>::from(datetime)
-            .format("%Y-%m-%d_%H-%M-%S-%9f")
-            .to_string();
-
-        let filename = format!("{formatted_time}.snap");
+        let extension = if self.removed { "removed" } else { "snap" };
+        let filename = format!("{}.{extension}", self.file_stem());
         let path = PathBuf::from(self.path_hash()).join(PathBuf::from(filename));
         if let Some(cwd) = cwd {
             cwd.join(path)
@@ -126,6 +156,22 @@ mod tests {
         assert_eq!(snapshot.path, nonexistent_path.display().to_string());
     }
 
+    #[test]
+    fn test_from_file_stem_roundtrip() {
+        let fixture = Snapshot::create(PathBuf::from("/project/main.rs")).unwrap();
+
+        let actual = Snapshot::from_file_stem(fixture.path.clone(), &fixture.file_stem()).unwrap();
+
+        assert_eq!(actual.timestamp, fixture.timestamp);
+        assert_eq!(actual.snapshot_path(None), fixture.snapshot_path(None));
+    }
+
+    #[test]
+    fn test_from_file_stem_invalid() {
+        let actual = Snapshot::from_file_stem("/project/main.rs", "not-a-timestamp");
+        assert_eq!(actual, None);
+    }
+
     #[test]
     fn test_create_with_nonexistent_relative_path() {
         // Test with a non-existent relative path
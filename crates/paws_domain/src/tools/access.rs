@@ -0,0 +1,95 @@
+use std::path::PathBuf;
+
+/// Describes how a tool call interacts with the workspace. Used to decide
+/// which tool calls from a single response can be executed concurrently.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ToolAccess {
+    /// Only observes state. When a path is known, it is the file or directory
+    /// being observed.
+    Read(Option<PathBuf>),
+
+    /// Mutates the file at the given path and nothing else.
+    Write(PathBuf),
+
+    /// Side effects can't be determined upfront (shell commands, user
+    /// interaction, agents, MCP tools). Must run on its own.
+    Exclusive,
+}
+
+impl ToolAccess {
+    /// Returns true if both accesses can't be executed at the same time
+    /// without changing the outcome of either of them.
+    pub fn conflicts_with(&self, other: &ToolAccess) -> bool {
+        match (self, other) {
+            (ToolAccess::Exclusive, _) | (_, ToolAccess::Exclusive) => true,
+            (ToolAccess::Read(_), ToolAccess::Read(_)) => false,
+            (ToolAccess::Write(a), ToolAccess::Write(b)) => a == b,
+            (ToolAccess::Write(write), ToolAccess::Read(read))
+            | (ToolAccess::Read(read), ToolAccess::Write(write)) => {
+                // Reads without a path (eg: fetch) never touch the workspace. A read of a
+                // directory (eg: search) observes every file underneath it.
+                read.as_ref().is_some_and(|read| write.starts_with(read))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn read(path: &str) -> ToolAccess {
+        ToolAccess::Read(Some(PathBuf::from(path)))
+    }
+
+    fn write(path: &str) -> ToolAccess {
+        ToolAccess::Write(PathBuf::from(path))
+    }
+
+    #[test]
+    fn test_reads_never_conflict() {
+        let actual = read("/a.rs").conflicts_with(&read("/a.rs"));
+        assert_eq!(actual, false);
+    }
+
+    #[test]
+    fn test_writes_to_different_files_do_not_conflict() {
+        let actual = write("/a.rs").conflicts_with(&write("/b.rs"));
+        assert_eq!(actual, false);
+    }
+
+    #[test]
+    fn test_writes_to_same_file_conflict() {
+        let actual = write("/a.rs").conflicts_with(&write("/a.rs"));
+        assert_eq!(actual, true);
+    }
+
+    #[test]
+    fn test_write_conflicts_with_read_of_parent_directory() {
+        let actual = read("/src").conflicts_with(&write("/src/a.rs"));
+        assert_eq!(actual, true);
+    }
+
+    #[test]
+    fn test_write_does_not_conflict_with_unrelated_read() {
+        let actual = write("/src/a.rs").conflicts_with(&read("/docs"));
+        assert_eq!(actual, false);
+    }
+
+    #[test]
+    fn test_write_does_not_conflict_with_pathless_read() {
+        let actual = write("/src/a.rs").conflicts_with(&ToolAccess::Read(None));
+        assert_eq!(actual, false);
+    }
+
+    #[test]
+    fn test_exclusive_conflicts_with_everything() {
+        let fixture = [read("/a.rs"), write("/a.rs"), ToolAccess::Read(None)];
+        for access in fixture {
+            assert_eq!(ToolAccess::Exclusive.conflicts_with(&access), true);
+            assert_eq!(access.conflicts_with(&ToolAccess::Exclusive), true);
+        }
+    }
+}
@@ -2,19 +2,28 @@ use std::sync::{Arc, Mutex};
 
 use derive_setters::Setters;
 
-use crate::{ArcSender, ChatResponse, ChatResponseContent, Metrics, TitleFormat};
+use crate::{
+    ArcSender, ChatResponse, ChatResponseContent, ConversationId, FollowUpAnswer, FollowUpQuestion,
+    FollowUpReply, Metrics, TitleFormat,
+};
 
 /// Provides additional context for tool calls.
 #[derive(Debug, Clone, Setters)]
 pub struct ToolCallContext {
     sender: Option<ArcSender>,
     metrics: Arc<Mutex<Metrics>>,
+    /// Conversation the tool call belongs to, if any
+    pub conversation_id: Option<ConversationId>,
 }
 
 impl ToolCallContext {
     /// Creates a new ToolCallContext with default values
     pub fn new(metrics: Metrics) -> Self {
-        Self { sender: None, metrics: Arc::new(Mutex::new(metrics)) }
+        Self {
+            sender: None,
+            metrics: Arc::new(Mutex::new(metrics)),
+            conversation_id: None,
+        }
     }
 
     /// Send a message through the sender if available
@@ -36,6 +45,21 @@ impl ToolCallContext {
         self.send(title.into()).await
     }
 
+    /// Whether responses reach a front-end, which can answer follow-up
+    /// questions
+    pub fn has_sender(&self) -> bool {
+        self.sender.is_some()
+    }
+
+    /// Asks the front-end a follow-up question and waits for the answer,
+    /// `None` when it is dismissed
+    pub async fn ask(&self, question: FollowUpQuestion) -> anyhow::Result<Option<FollowUpAnswer>> {
+        let (reply, answer) = FollowUpReply::channel();
+        self.send(ChatResponse::FollowUp { question, reply })
+            .await?;
+        Ok(answer.await.ok().flatten())
+    }
+
     /// Execute a closure with access to the metrics
     pub fn with_metrics<F, R>(&self, f: F) -> anyhow::Result<R>
     where
@@ -51,6 +75,8 @@ impl ToolCallContext {
 
 #[cfg(test)]
 mod tests {
+    use pretty_assertions::assert_eq;
+
     use super::*;
 
     #[test]
@@ -68,4 +94,23 @@ mod tests {
         let context = ToolCallContext::new(metrics);
         assert!(context.sender.is_none());
     }
+
+    #[tokio::test]
+    async fn test_ask_waits_for_reply() {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        let context = ToolCallContext::new(Metrics::default()).sender(Some(tx));
+        tokio::spawn(async move {
+            if let Some(Ok(ChatResponse::FollowUp { reply, .. })) = rx.recv().await {
+                reply.send(Some(FollowUpAnswer::Text("yes".to_string())));
+            }
+        });
+
+        let actual = context
+            .ask(FollowUpQuestion::new("Proceed?"))
+            .await
+            .unwrap();
+
+        let expected = Some(FollowUpAnswer::Text("yes".to_string()));
+        assert_eq!(actual, expected);
+    }
 }
@@ -28,7 +28,8 @@ impl ToolCallId {
         &self.0
     }
 
-    fn generate() -> Self {
+    /// Generates a unique id for providers that do not assign call ids
+    pub fn generate() -> Self {
         let id = format!("paws_call_id_{}", uuid::Uuid::new_v4());
         ToolCallId(id)
     }
@@ -13,7 +13,10 @@ use serde_json::Map;
 use strum::IntoEnumIterator;
 use strum_macros::{AsRefStr, Display, EnumDiscriminants, EnumIter};
 
-use crate::{ToolCallArguments, ToolCallFull, ToolDefinition, ToolDescription, ToolName};
+use crate::{
+    FilePatch, Hunk, ToolAccess, ToolCallArguments, ToolCallFull, ToolDefinition, ToolDescription,
+    ToolName,
+};
 
 /// Enum representing all possible tool input types.
 ///
@@ -42,10 +45,17 @@ pub enum ToolCatalog {
     ReadImage(ReadImage),
     Write(FSWrite),
     Search(FSSearch),
+    SemSearch(SemanticSearch),
+    Outline(FSOutline),
+    FindSymbol(FindSymbol),
     Remove(FSRemove),
     Patch(FSPatch),
+    MultiPatch(FSMultiPatch),
     Undo(FSUndo),
     Shell(Shell),
+    ShellRead(ShellRead),
+    ShellWait(ShellWait),
+    ShellKill(ShellKill),
     Fetch(NetFetch),
     Followup(Followup),
     Plan(PlanCreate),
@@ -172,6 +182,81 @@ pub struct FSSearch {
     pub file_pattern: Option<String>,
 }
 
+/// Semantic code search over a local index of the workspace. Use this when you
+/// need to find code locations, understand implementations, or explore
+/// functionality described in natural language rather than by exact names.
+/// Understands queries like "retry logic" (finds backoff) or "validation"
+/// (finds checking/sanitization). Returns file:line locations with code
+/// context, ranked by relevance. Use multiple varied queries (2-3) for best
+/// coverage. For exact string matching (TODO comments, specific function
+/// names), use regex search instead. Fails if the workspace has not been
+/// indexed with `paws index build`.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema, ToolDescription, PartialEq)]
+pub struct SemanticSearch {
+    /// List of search queries to execute. Using multiple queries (2-3) with
+    /// varied phrasings improves results - each query captures different
+    /// aspects of what you're looking for. Example: for authentication, try
+    /// "user login verification", "token generation", "OAuth flow".
+    pub queries: Vec<SearchQuery>,
+
+    /// Optional file extension filter (e.g., ".rs", ".ts", ".py"). If
+    /// provided, only files with this extension will be included in the
+    /// search results.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_extension: Option<String>,
+}
+
+/// A query and the use case used to rerank its results
+#[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct SearchQuery {
+    /// Describe WHAT the code does or its purpose, including domain-specific
+    /// terms. Good: "retry mechanism with exponential backoff", "OAuth token
+    /// refresh flow". Bad: generic terms like "retry" or "auth".
+    pub query: String,
+
+    /// A short natural-language description of what you are trying to find,
+    /// used to rerank the results of the query. Express a single focused
+    /// information need in 1-2 sentences, not the query verbatim.
+    pub use_case: String,
+}
+
+impl SearchQuery {
+    pub fn new(query: impl Into<String>, use_case: impl Into<String>) -> Self {
+        Self { query: query.into(), use_case: use_case.into() }
+    }
+}
+
+/// Lists the structure of a source file or of every source file in a
+/// directory: functions, methods, types, traits, impls, classes and modules
+/// with their signatures and line ranges, nested by scope. Use this instead of
+/// reading whole files to find where something is defined or to get an
+/// overview of a module; then read only the line range you need. Directories
+/// are walked recursively, respecting `.gitignore`. Supports Rust, TypeScript,
+/// JavaScript, Python and Go. Requires absolute paths.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema, ToolDescription, PartialEq)]
+pub struct FSOutline {
+    /// The absolute path of the file or directory to outline.
+    pub path: String,
+}
+
+/// Finds where a symbol is defined and referenced by its exact name, using the
+/// syntax tree instead of text matching so that comments and strings are
+/// ignored. Returns the definitions with their signatures and line ranges, and
+/// the lines referencing the symbol. Searches recursively, respecting
+/// `.gitignore`. Supports Rust, TypeScript, JavaScript, Python and Go. Use
+/// regex search for other languages or partial names.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema, ToolDescription, PartialEq)]
+pub struct FindSymbol {
+    /// The exact name of the function, type, method or variable to find,
+    /// without any qualifying path (e.g. `PawsServices`, `execute`).
+    pub name: String,
+
+    /// The absolute path of the directory or file to search in. Defaults to
+    /// the current working directory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
 /// Request to remove a file at the specified path. Use this when you need to
 /// delete an existing file. The path must be absolute. This operation cannot
 /// be undone, so use it carefully.
@@ -272,6 +357,93 @@ pub struct FSPatch {
     pub content: String,
 }
 
+/// Applies several edits to one or more files in a single atomic operation.
+/// Accepts either a standard unified diff (as produced by `git diff` or `diff
+/// -u`) in `diff`, or a list of search/replace `edits`. Context lines are
+/// matched fuzzily, tolerating whitespace and indentation differences and
+/// hunk line numbers that are slightly off. Every file is snapshotted before
+/// it is modified, and if any hunk fails to apply no file is changed. Reports
+/// the outcome of every hunk. Prefer this tool over repeated `patch` calls
+/// when making related changes across several places or files. Deleting files
+/// through a diff is not supported, use `remove` instead.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema, ToolDescription, PartialEq)]
+pub struct FSMultiPatch {
+    /// A unified diff with `---`/`+++` file headers and `@@` hunks. Paths may
+    /// be absolute or relative to the working directory, with or without the
+    /// `a/` and `b/` prefixes. Use `--- /dev/null` to create a new file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diff: Option<String>,
+
+    /// Search/replace edits applied in order. Each search text must match
+    /// exactly one location in its file.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub edits: Vec<PatchEdit>,
+}
+
+/// A single search/replace edit of a `multi_patch` call
+#[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct PatchEdit {
+    /// The absolute path of the file to modify
+    pub path: String,
+
+    /// The text to find, including enough surrounding lines to be unique
+    pub search: String,
+
+    /// The text to replace it with
+    pub replace: String,
+}
+
+impl FSMultiPatch {
+    /// Converts the diff and the edits into per-file patches, keeping the
+    /// order in which files first appear. Relative paths are resolved against
+    /// `cwd`.
+    pub fn file_patches(&self, cwd: &Path) -> crate::Result<Vec<FilePatch>> {
+        let mut patches = match &self.diff {
+            Some(diff) if !diff.trim().is_empty() => FilePatch::parse_unified_diff(diff, cwd)?,
+            _ => Vec::new(),
+        };
+        for edit in &self.edits {
+            let path = cwd.join(&edit.path);
+            let hunk = Hunk::Replace { search: edit.search.clone(), replace: edit.replace.clone() };
+            match patches.iter_mut().find(|patch| patch.path == path) {
+                Some(patch) => patch.hunks.push(hunk),
+                None => {
+                    let mut patch = FilePatch::new(path);
+                    patch.hunks.push(hunk);
+                    patches.push(patch);
+                }
+            }
+        }
+
+        // Diffs touching the same file twice are merged so that every file is
+        // read and written once
+        let mut merged: Vec<FilePatch> = Vec::new();
+        for patch in patches {
+            match merged
+                .iter_mut()
+                .find(|existing| existing.path == patch.path)
+            {
+                Some(existing) => existing.hunks.extend(patch.hunks),
+                None => merged.push(patch),
+            }
+        }
+        if merged.is_empty() {
+            return Err(crate::Error::InvalidDiff(
+                "either `diff` or `edits` must be provided".to_string(),
+            ));
+        }
+        Ok(merged)
+    }
+
+    /// Paths of the files touched by the patch, resolved against `cwd`.
+    /// Invalid diffs contribute no paths.
+    pub fn paths(&self, cwd: &Path) -> Vec<PathBuf> {
+        self.file_patches(cwd)
+            .map(|patches| patches.into_iter().map(|patch| patch.path).collect())
+            .unwrap_or_default()
+    }
+}
+
 /// Reverts the most recent file operation (create/modify/delete) on a specific
 /// file. Use this tool when you need to recover from incorrect file changes or
 /// if a revert is requested by the user.
@@ -308,6 +480,46 @@ pub struct Shell {
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub env: Option<Vec<String>>,
+
+    /// Run the command in the background and return a job id immediately
+    /// instead of waiting for it to exit. Use for dev servers, watchers and
+    /// long running test suites, then check on the job with `shell_read`,
+    /// `shell_wait` or `shell_kill`. ANSI escape codes are always stripped from
+    /// the output of background jobs.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub background: bool,
+}
+
+/// Reads the output a background shell job has produced since the previous
+/// read, without waiting. Reports whether the job is still running and its
+/// exit code once it has exited, after which the job can't be read again.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema, ToolDescription, PartialEq)]
+pub struct ShellRead {
+    /// The id of the job returned by the shell tool in background mode.
+    pub job_id: u64,
+}
+
+/// Waits for a background shell job to exit, up to the given timeout, then
+/// returns the output produced since the previous read. A job that is still
+/// running after the timeout is left running.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema, ToolDescription, PartialEq)]
+pub struct ShellWait {
+    /// The id of the job returned by the shell tool in background mode.
+    pub job_id: u64,
+
+    /// Maximum number of seconds to wait for the job to exit, capped by the
+    /// tool timeout.
+    pub timeout_secs: u64,
+}
+
+/// Kills a background shell job, along with every process it started, and
+/// returns the output it produced since the previous read. Use this to stop dev
+/// servers and watchers once they are no longer needed.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema, ToolDescription, PartialEq)]
+pub struct ShellKill {
+    /// The id of the job returned by the shell tool in background mode.
+    pub job_id: u64,
 }
 
 /// Input type for the net fetch tool
@@ -477,10 +689,17 @@ impl ToolDescription for ToolCatalog {
     fn description(&self) -> String {
         match self {
             ToolCatalog::Patch(v) => v.description(),
+            ToolCatalog::MultiPatch(v) => v.description(),
             ToolCatalog::Shell(v) => v.description(),
+            ToolCatalog::ShellRead(v) => v.description(),
+            ToolCatalog::ShellWait(v) => v.description(),
+            ToolCatalog::ShellKill(v) => v.description(),
             ToolCatalog::Followup(v) => v.description(),
             ToolCatalog::Fetch(v) => v.description(),
             ToolCatalog::Search(v) => v.description(),
+            ToolCatalog::SemSearch(v) => v.description(),
+            ToolCatalog::Outline(v) => v.description(),
+            ToolCatalog::FindSymbol(v) => v.description(),
 
             ToolCatalog::Read(v) => v.description(),
             ToolCatalog::ReadImage(v) => v.description(),
@@ -514,10 +733,17 @@ impl ToolCatalog {
             .into_generator();
         match self {
             ToolCatalog::Patch(_) => r#gen.into_root_schema_for::<FSPatch>(),
+            ToolCatalog::MultiPatch(_) => r#gen.into_root_schema_for::<FSMultiPatch>(),
             ToolCatalog::Shell(_) => r#gen.into_root_schema_for::<Shell>(),
+            ToolCatalog::ShellRead(_) => r#gen.into_root_schema_for::<ShellRead>(),
+            ToolCatalog::ShellWait(_) => r#gen.into_root_schema_for::<ShellWait>(),
+            ToolCatalog::ShellKill(_) => r#gen.into_root_schema_for::<ShellKill>(),
             ToolCatalog::Followup(_) => r#gen.into_root_schema_for::<Followup>(),
             ToolCatalog::Fetch(_) => r#gen.into_root_schema_for::<NetFetch>(),
             ToolCatalog::Search(_) => r#gen.into_root_schema_for::<FSSearch>(),
+            ToolCatalog::SemSearch(_) => r#gen.into_root_schema_for::<SemanticSearch>(),
+            ToolCatalog::Outline(_) => r#gen.into_root_schema_for::<FSOutline>(),
+            ToolCatalog::FindSymbol(_) => r#gen.into_root_schema_for::<FindSymbol>(),
 
             ToolCatalog::Read(_) => r#gen.into_root_schema_for::<FSRead>(),
             ToolCatalog::ReadImage(_) => r#gen.into_root_schema_for::<ReadImage>(),
@@ -544,13 +770,42 @@ impl ToolCatalog {
             .any(|v| v.to_string().to_case(Case::Snake).eq(tool_name.as_str()))
     }
 
-    /// Convert a tool input to its corresponding domain operation for policy
-    /// checking. Returns None for tools that don't require permission
-    /// checks.
-    pub fn to_policy_operation(
-        &self,
-        cwd: PathBuf,
-    ) -> Option<crate::policies::PermissionOperation> {
+    /// Classifies how the tool interacts with the workspace so that
+    /// independent calls can be scheduled concurrently. Relative paths are
+    /// resolved against `cwd`.
+    pub fn access(&self, cwd: &Path) -> ToolAccess {
+        let resolve = |path: &str| cwd.join(path);
+        match self {
+            ToolCatalog::Read(input) => ToolAccess::Read(Some(resolve(&input.path))),
+            ToolCatalog::ReadImage(input) => ToolAccess::Read(Some(resolve(&input.path))),
+            ToolCatalog::Search(input) => ToolAccess::Read(Some(resolve(&input.path))),
+            ToolCatalog::SemSearch(_) => ToolAccess::Read(Some(cwd.to_path_buf())),
+            ToolCatalog::Outline(input) => ToolAccess::Read(Some(resolve(&input.path))),
+            ToolCatalog::FindSymbol(input) => ToolAccess::Read(Some(
+                input.path.as_deref().map_or(cwd.to_path_buf(), resolve),
+            )),
+            ToolCatalog::Fetch(_) | ToolCatalog::Skill(_) => ToolAccess::Read(None),
+            ToolCatalog::Write(input) => ToolAccess::Write(resolve(&input.path)),
+            ToolCatalog::Patch(input) => ToolAccess::Write(resolve(&input.path)),
+            ToolCatalog::MultiPatch(input) => match input.paths(cwd).as_slice() {
+                [path] => ToolAccess::Write(path.clone()),
+                _ => ToolAccess::Exclusive,
+            },
+            ToolCatalog::Remove(input) => ToolAccess::Write(resolve(&input.path)),
+            ToolCatalog::Undo(input) => ToolAccess::Write(resolve(&input.path)),
+            ToolCatalog::Shell(_)
+            | ToolCatalog::ShellRead(_)
+            | ToolCatalog::ShellWait(_)
+            | ToolCatalog::ShellKill(_)
+            | ToolCatalog::Followup(_)
+            | ToolCatalog::Plan(_) => ToolAccess::Exclusive,
+        }
+    }
+
+    /// Convert a tool input to its corresponding domain operations for policy
+    /// checking. Tools touching several files yield one operation per file,
+    /// and tools that don't require permission checks yield none.
+    pub fn to_policy_operations(&self, cwd: PathBuf) -> Vec<crate::policies::PermissionOperation> {
         let cwd_path = cwd.clone();
         let display_path_for = |path: &str| {
             format!(
@@ -559,7 +814,7 @@ impl ToolCatalog {
             )
         };
 
-        match self {
+        let operation = match self {
             ToolCatalog::Read(input) => Some(crate::policies::PermissionOperation::Read {
                 path: std::path::PathBuf::from(&input.path),
                 cwd,
@@ -599,6 +854,36 @@ impl ToolCatalog {
                     message,
                 })
             }
+            ToolCatalog::SemSearch(input) => {
+                let queries = input
+                    .queries
+                    .iter()
+                    .map(|query| format!("'{}'", query.query))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                Some(crate::policies::PermissionOperation::Read {
+                    path: cwd.clone(),
+                    cwd,
+                    message: format!("Semantic search for: {queries}"),
+                })
+            }
+            ToolCatalog::Outline(input) => Some(crate::policies::PermissionOperation::Read {
+                path: std::path::PathBuf::from(&input.path),
+                cwd,
+                message: format!("Outline: {}", display_path_for(&input.path)),
+            }),
+            ToolCatalog::FindSymbol(input) => {
+                let path = input
+                    .path
+                    .as_ref()
+                    .map_or(cwd.clone(), std::path::PathBuf::from);
+                let message = format!(
+                    "Find symbol '{}' in: {}",
+                    input.name,
+                    display_path_for(&path.to_string_lossy())
+                );
+                Some(crate::policies::PermissionOperation::Read { path, cwd, message })
+            }
             ToolCatalog::Remove(input) => Some(crate::policies::PermissionOperation::Write {
                 path: std::path::PathBuf::from(&input.path),
                 cwd,
@@ -609,6 +894,21 @@ impl ToolCatalog {
                 cwd,
                 message: format!("Modify file: {}", display_path_for(&input.path)),
             }),
+            ToolCatalog::MultiPatch(input) => {
+                return input
+                    .paths(&cwd)
+                    .into_iter()
+                    .map(|path| {
+                        let message =
+                            format!("Modify file: {}", display_path_for(&path.to_string_lossy()));
+                        crate::policies::PermissionOperation::Write {
+                            path,
+                            cwd: cwd.clone(),
+                            message,
+                        }
+                    })
+                    .collect();
+            }
             ToolCatalog::Shell(input) => Some(crate::policies::PermissionOperation::Execute {
                 command: input.command.clone(),
                 cwd,
@@ -620,11 +920,15 @@ impl ToolCatalog {
                 message: format!("Fetch content from URL: {}", input.url),
             }),
             // Operations that don't require permission checks
-            ToolCatalog::Undo(_)
+            ToolCatalog::ShellRead(_)
+            | ToolCatalog::ShellWait(_)
+            | ToolCatalog::ShellKill(_)
+            | ToolCatalog::Undo(_)
             | ToolCatalog::Followup(_)
             | ToolCatalog::Plan(_)
             | ToolCatalog::Skill(_) => None,
-        }
+        };
+        operation.into_iter().collect()
     }
 
     /// Creates a Read tool call with the specified path
@@ -830,6 +1134,118 @@ mod tests {
         insta::assert_snapshot!(tools);
     }
 
+    #[test]
+    fn test_access_resolves_relative_paths() {
+        use std::path::{Path, PathBuf};
+
+        use crate::{FSRead, ToolAccess};
+
+        let fixture =
+            ToolCatalog::Read(FSRead { path: "src/main.rs".to_string(), ..Default::default() });
+
+        let actual = fixture.access(Path::new("/project"));
+        let expected = ToolAccess::Read(Some(PathBuf::from("/project/src/main.rs")));
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_access_shell_is_exclusive() {
+        use std::path::Path;
+
+        use crate::ToolAccess;
+
+        let fixture =
+            ToolCatalog::try_from(ToolCatalog::tool_call_shell("ls", "/project")).unwrap();
+
+        let actual = fixture.access(Path::new("/project"));
+        let expected = ToolAccess::Exclusive;
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_access_multi_patch_of_single_file() {
+        use std::path::{Path, PathBuf};
+
+        use crate::{FSMultiPatch, PatchEdit, ToolAccess};
+
+        let fixture = ToolCatalog::MultiPatch(FSMultiPatch {
+            diff: None,
+            edits: vec![
+                PatchEdit {
+                    path: "a.rs".to_string(),
+                    search: "a".to_string(),
+                    replace: "b".to_string(),
+                },
+                PatchEdit {
+                    path: "a.rs".to_string(),
+                    search: "c".to_string(),
+                    replace: "d".to_string(),
+                },
+            ],
+        });
+
+        let actual = fixture.access(Path::new("/project"));
+        let expected = ToolAccess::Write(PathBuf::from("/project/a.rs"));
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_multi_patch_policy_operation_per_file() {
+        use std::path::PathBuf;
+
+        use crate::policies::PermissionOperation;
+        use crate::{FSMultiPatch, PatchEdit};
+
+        let fixture = ToolCatalog::MultiPatch(FSMultiPatch {
+            diff: Some(
+                "--- a/a.rs\n+++ b/a.rs\n@@ -1 +1 @@\n-a\n+b\n--- /dev/null\n+++ b/b.rs\n@@ -0,0 +1 @@\n+c\n"
+                    .to_string(),
+            ),
+            edits: vec![PatchEdit {
+                path: "/project/a.rs".to_string(),
+                search: "x".to_string(),
+                replace: "y".to_string(),
+            }],
+        });
+
+        let actual = fixture
+            .to_policy_operations(PathBuf::from("/project"))
+            .into_iter()
+            .map(|operation| match operation {
+                PermissionOperation::Write { path, .. } => path,
+                _ => panic!("Expected Write operation"),
+            })
+            .collect::<Vec<_>>();
+
+        let expected = vec![
+            PathBuf::from("/project/a.rs"),
+            PathBuf::from("/project/b.rs"),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_find_symbol_defaults_to_cwd() {
+        use std::path::PathBuf;
+
+        use crate::FindSymbol;
+        use crate::policies::PermissionOperation;
+
+        let fixture =
+            ToolCatalog::FindSymbol(FindSymbol { name: "PawsServices".to_string(), path: None });
+
+        let actual = fixture
+            .to_policy_operations(PathBuf::from("/project"))
+            .remove(0);
+
+        let expected = PermissionOperation::Read {
+            path: PathBuf::from("/project"),
+            cwd: PathBuf::from("/project"),
+            message: "Find symbol 'PawsServices' in: `.`".to_string(),
+        };
+        assert_eq!(actual, expected);
+    }
+
     #[test]
     fn test_fs_search_message_with_regex() {
         use std::path::PathBuf;
@@ -846,8 +1262,8 @@ mod tests {
         });
 
         let operation = search_with_regex
-            .to_policy_operation(PathBuf::from("/test/cwd"))
-            .unwrap();
+            .to_policy_operations(PathBuf::from("/test/cwd"))
+            .remove(0);
 
         match operation {
             PermissionOperation::Read { message, .. } => {
@@ -876,8 +1292,8 @@ mod tests {
         });
 
         let operation = search_without_regex
-            .to_policy_operation(PathBuf::from("/test/cwd"))
-            .unwrap();
+            .to_policy_operations(PathBuf::from("/test/cwd"))
+            .remove(0);
 
         match operation {
             PermissionOperation::Read { message, .. } => {
@@ -903,8 +1319,8 @@ mod tests {
         });
 
         let operation = search_with_pattern
-            .to_policy_operation(PathBuf::from("/test/cwd"))
-            .unwrap();
+            .to_policy_operations(PathBuf::from("/test/cwd"))
+            .remove(0);
 
         match operation {
             PermissionOperation::Read { message, .. } => {
@@ -933,8 +1349,8 @@ mod tests {
         });
 
         let operation = search_with_both
-            .to_policy_operation(PathBuf::from("/test/cwd"))
-            .unwrap();
+            .to_policy_operations(PathBuf::from("/test/cwd"))
+            .remove(0);
 
         match operation {
             PermissionOperation::Read { message, .. } => {
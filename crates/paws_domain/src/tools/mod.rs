@@ -1,9 +1,11 @@
 pub mod call;
 pub mod definition;
 
+mod access;
 mod catalog;
 mod result;
 
+pub use access::*;
 pub use call::*;
 pub use catalog::*;
 pub use definition::*;
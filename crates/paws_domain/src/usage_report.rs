@@ -0,0 +1,18 @@
+use chrono::NaiveDate;
+
+use crate::{ModelId, ProviderId, Usage, WorkspaceHash};
+
+/// Usage of a model in a workspace over one day
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsageReportEntry {
+    /// Day the completions were made, in UTC
+    pub date: NaiveDate,
+    pub workspace_id: WorkspaceHash,
+    /// Provider serving the model, `None` for completions recorded before
+    /// providers were tracked
+    pub provider: Option<ProviderId>,
+    /// Model that made the completions, `None` for completions recorded
+    /// before models were tracked
+    pub model: Option<ModelId>,
+    pub usage: Usage,
+}
@@ -6,7 +6,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::temperature::Temperature;
 use crate::update::Update;
-use crate::{Compact, MaxTokens, TopK, TopP};
+use crate::{Budget, Compact, Hooks, MaxTokens, ModelFallback, TopK, TopP};
 
 /// Configuration for a workflow that contains all settings
 /// required to initialize a workflow.
@@ -110,6 +110,28 @@ pub struct Workflow {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[merge(strategy = crate::merge::option)]
     pub max_requests_per_turn: Option<usize>,
+
+    /// Spending limits for all agents. Limits set by an agent take priority
+    /// over the ones set here.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub budget: Option<Budget>,
+
+    /// Providers and models to switch to, in order, for agents that don't
+    /// define their own fallbacks
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub fallbacks: Option<Vec<ModelFallback>>,
+
+    /// Commands to run on agent lifecycle events, such as before and after
+    /// tool calls, for all agents
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub hooks: Option<Hooks>,
+
     /// Configuration for automatic context compaction for all agents
     /// If specified, this will be applied to all agents in the workflow
     /// If not specified, each agent's individual setting will be used
@@ -159,6 +181,9 @@ impl Workflow {
             templates: None,
             max_tool_failure_per_turn: None,
             max_requests_per_turn: None,
+            budget: None,
+            fallbacks: None,
+            hooks: None,
             compact: None,
             commands: vec![],
         }
@@ -0,0 +1,470 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::Context;
+use axum::body::Body;
+use axum::extract::{Request, State};
+use axum::response::Response;
+use bytes::Bytes;
+use paws_domain::{CassetteConfig, CassetteMode};
+use reqwest::header::{HOST, HeaderMap, HeaderValue};
+use reqwest::{Client, Method, StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Mutex, OnceCell, mpsc};
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::{debug, warn};
+
+/// Header carrying the URL a request sent to the stand-in server was meant
+/// for
+const UPSTREAM_HEADER: &str = "x-paws-cassette-upstream";
+
+/// Headers describing the connection rather than the exchange
+const HOP_HEADERS: [&str; 4] = [
+    "connection",
+    "content-length",
+    "keep-alive",
+    "transfer-encoding",
+];
+
+/// Recorded HTTP exchanges, in the order the responses completed
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    /// Requested URL with credentials passed as query parameters redacted
+    pub url: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub body: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// Response body, the raw event stream for server-sent events
+    pub body: String,
+}
+
+impl Cassette {
+    pub async fn load(path: &Path) -> anyhow::Result<Self> {
+        let content = tokio::fs::read_to_string(path)
+            .await
+            .with_context(|| format!("Failed to read cassette '{}'", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse cassette '{}'", path.display()))
+    }
+
+    async fn save(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(path, serde_json::to_string_pretty(self)?)
+            .await
+            .with_context(|| format!("Failed to write cassette '{}'", path.display()))
+    }
+
+    /// Index of the first unused interaction answering `request`. An
+    /// identical body is preferred so concurrent requests get their own
+    /// responses, otherwise the first one for the same endpoint is used since
+    /// prompts embed details such as the current date.
+    fn find(&self, used: &[bool], request: &RecordedRequest) -> Option<usize> {
+        let candidates = || {
+            self.interactions
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| !used[*index])
+                .filter(|(_, interaction)| {
+                    interaction.request.method == request.method
+                        && interaction.request.url == request.url
+                })
+        };
+        candidates()
+            .find(|(_, interaction)| interaction.request.body == request.body)
+            .or_else(|| candidates().next())
+            .map(|(index, _)| index)
+    }
+}
+
+/// Replaces the value of query parameters holding credentials, such as API
+/// keys, so they are neither written to cassettes nor needed to replay them
+fn redact(url: &Url) -> String {
+    if url.query().is_none() {
+        return url.to_string();
+    }
+    let mut redacted = url.clone();
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(name, value)| {
+            let lowercase = name.to_lowercase();
+            let value = if lowercase.contains("key") || lowercase.contains("token") {
+                "REDACTED".to_string()
+            } else {
+                value.into_owned()
+            };
+            (name.into_owned(), value)
+        })
+        .collect();
+    redacted.query_pairs_mut().clear().extend_pairs(pairs);
+    redacted.to_string()
+}
+
+struct Tape {
+    config: CassetteConfig,
+    client: Client,
+    cassette: Mutex<(Cassette, Vec<bool>)>,
+}
+
+impl Tape {
+    async fn replay(&self, request: &RecordedRequest) -> anyhow::Result<Response> {
+        let mut state = self.cassette.lock().await;
+        let (cassette, used) = &mut *state;
+        let Some(index) = cassette.find(used, request) else {
+            warn!(method = %request.method, url = %request.url, "No recorded response");
+            return Ok(Response::builder()
+                .status(StatusCode::NOT_FOUND)
+                .body(Body::from(format!(
+                    "No recorded response for {} {} in cassette '{}'",
+                    request.method,
+                    request.url,
+                    self.config.path.display()
+                )))?);
+        };
+        used[index] = true;
+
+        let recorded = &cassette.interactions[index].response;
+        let mut response = Response::builder().status(recorded.status);
+        for (name, value) in &recorded.headers {
+            response = response.header(name, value);
+        }
+        Ok(response.body(Body::from(recorded.body.clone()))?)
+    }
+
+    async fn record(
+        self: Arc<Self>,
+        request: RecordedRequest,
+        mut headers: HeaderMap,
+        body: Bytes,
+        upstream: Url,
+    ) -> anyhow::Result<Response> {
+        headers.remove(UPSTREAM_HEADER);
+        headers.remove(HOST);
+        for name in HOP_HEADERS {
+            headers.remove(name);
+        }
+        let method = Method::from_bytes(request.method.as_bytes())?;
+        let mut upstream = self
+            .client
+            .request(method, upstream)
+            .headers(headers)
+            .body(body)
+            .send()
+            .await?;
+
+        let status = upstream.status();
+        let mut response = Response::builder().status(status);
+        let mut recorded_headers = BTreeMap::new();
+        for (name, value) in upstream.headers() {
+            if HOP_HEADERS.contains(&name.as_str()) {
+                continue;
+            }
+            response = response.header(name, value);
+            if let Ok(value) = value.to_str() {
+                recorded_headers.insert(name.to_string(), value.to_string());
+            }
+        }
+
+        // Chunks are forwarded as they arrive so streams stay interactive, the
+        // exchange is saved once the response is complete
+        let (tx, rx) = mpsc::channel::<Result<Bytes, reqwest::Error>>(64);
+        tokio::spawn(async move {
+            let mut body = Vec::new();
+            loop {
+                match upstream.chunk().await {
+                    Ok(Some(chunk)) => {
+                        body.extend_from_slice(&chunk);
+                        // The rest is still recorded when the client disconnects
+                        let _ = tx.send(Ok(chunk)).await;
+                    }
+                    Ok(None) => break,
+                    Err(error) => {
+                        warn!(url = %request.url, error = %error, "Response not recorded");
+                        let _ = tx.send(Err(error)).await;
+                        return;
+                    }
+                }
+            }
+            let interaction = Interaction {
+                request,
+                response: RecordedResponse {
+                    status: status.as_u16(),
+                    headers: recorded_headers,
+                    body: String::from_utf8_lossy(&body).into_owned(),
+                },
+            };
+            if let Err(error) = self.append(interaction).await {
+                warn!(error = ?error, "Failed to save the cassette");
+            }
+        });
+
+        Ok(response.body(Body::from_stream(ReceiverStream::new(rx)))?)
+    }
+
+    async fn append(&self, interaction: Interaction) -> anyhow::Result<()> {
+        let mut state = self.cassette.lock().await;
+        state.0.interactions.push(interaction);
+        state.0.save(&self.config.path).await
+    }
+}
+
+async fn handle(State(tape): State<Arc<Tape>>, request: Request) -> Response {
+    match serve(tape, request).await {
+        Ok(response) => response,
+        Err(error) => Response::builder()
+            .status(StatusCode::BAD_GATEWAY)
+            .body(Body::from(format!("{error:#}")))
+            .unwrap_or_default(),
+    }
+}
+
+async fn serve(tape: Arc<Tape>, request: Request) -> anyhow::Result<Response> {
+    let (parts, body) = request.into_parts();
+    let upstream: Url = parts
+        .headers
+        .get(UPSTREAM_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .context("Request has no upstream URL")?
+        .parse()?;
+    let body = axum::body::to_bytes(body, usize::MAX).await?;
+    let request = RecordedRequest {
+        method: parts.method.to_string(),
+        url: redact(&upstream),
+        body: String::from_utf8_lossy(&body).into_owned(),
+    };
+
+    match tape.config.mode {
+        CassetteMode::Replay => tape.replay(&request).await,
+        CassetteMode::Record => tape.record(request, parts.headers, body, upstream).await,
+    }
+}
+
+/// A local stand-in server all requests are routed through while a cassette
+/// is in use. When recording it forwards them to their destination and saves
+/// the exchanges, when replaying it answers them from the cassette without
+/// touching the network. It starts on the first request.
+pub struct CassetteServer {
+    config: CassetteConfig,
+    client: Client,
+    address: OnceCell<Url>,
+}
+
+impl CassetteServer {
+    pub fn new(config: CassetteConfig, client: Client) -> Self {
+        Self { config, client, address: OnceCell::new() }
+    }
+
+    /// Returns the URL `url` is served from and marks `headers` with the
+    /// original destination
+    pub async fn route(&self, url: &Url, headers: &mut HeaderMap) -> anyhow::Result<Url> {
+        let address = self.address.get_or_try_init(|| self.start()).await?;
+        headers.insert(UPSTREAM_HEADER, HeaderValue::from_str(url.as_str())?);
+
+        let mut routed = address.clone();
+        routed.set_path(url.path());
+        routed.set_query(url.query());
+        Ok(routed)
+    }
+
+    async fn start(&self) -> anyhow::Result<Url> {
+        let cassette = match self.config.mode {
+            CassetteMode::Replay => Cassette::load(&self.config.path).await?,
+            CassetteMode::Record => Cassette::default(),
+        };
+        let used = vec![false; cassette.interactions.len()];
+        let tape = Arc::new(Tape {
+            config: self.config.clone(),
+            client: self.client.clone(),
+            cassette: Mutex::new((cassette, used)),
+        });
+
+        let listener = tokio::net::TcpListener::bind(("127.0.0.1", 0))
+            .await
+            .context("Failed to start the cassette server")?;
+        let address = listener.local_addr()?;
+        let router = axum::Router::new().fallback(handle).with_state(tape);
+        tokio::spawn(async move {
+            if let Err(error) = axum::serve(listener, router).await {
+                warn!(error = %error, "Cassette server stopped");
+            }
+        });
+        debug!(%address, mode = %self.config.mode, "Cassette server started");
+        Ok(Url::parse(&format!("http://{address}"))?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn interaction(url: &str, body: &str, response: &str) -> Interaction {
+        Interaction {
+            request: RecordedRequest {
+                method: "POST".to_string(),
+                url: url.to_string(),
+                body: body.to_string(),
+            },
+            response: RecordedResponse {
+                status: 200,
+                headers: BTreeMap::from([(
+                    "content-type".to_string(),
+                    "text/event-stream".to_string(),
+                )]),
+                body: response.to_string(),
+            },
+        }
+    }
+
+    fn request(url: &str, body: &str) -> RecordedRequest {
+        RecordedRequest {
+            method: "POST".to_string(),
+            url: url.to_string(),
+            body: body.to_string(),
+        }
+    }
+
+    /// Serves `response` as an event stream for every request
+    async fn upstream(response: &'static str) -> (Url, tokio::task::JoinHandle<()>) {
+        let router = axum::Router::new().fallback(move || async move {
+            Response::builder()
+                .header("content-type", "text/event-stream")
+                .body(Body::from(response))
+                .unwrap()
+        });
+        let listener = tokio::net::TcpListener::bind(("127.0.0.1", 0))
+            .await
+            .unwrap();
+        let address = listener.local_addr().unwrap();
+        let handle = tokio::spawn(async move {
+            axum::serve(listener, router).await.unwrap();
+        });
+        (Url::parse(&format!("http://{address}")).unwrap(), handle)
+    }
+
+    async fn send(server: &CassetteServer, url: &Url, body: &str) -> (u16, String) {
+        let mut headers = HeaderMap::new();
+        let routed = server.route(url, &mut headers).await.unwrap();
+        let response = Client::new()
+            .post(routed)
+            .headers(headers)
+            .body(body.to_string())
+            .send()
+            .await
+            .unwrap();
+        (response.status().as_u16(), response.text().await.unwrap())
+    }
+
+    #[test]
+    fn test_find_prefers_identical_body() {
+        let fixture = Cassette {
+            interactions: vec![
+                interaction("https://api.test/chat", "first", "one"),
+                interaction("https://api.test/chat", "second", "two"),
+                interaction("https://api.test/models", "", "models"),
+            ],
+        };
+
+        let actual = fixture.find(
+            &[false, false, false],
+            &request("https://api.test/chat", "second"),
+        );
+        assert_eq!(actual, Some(1));
+
+        let actual = fixture.find(
+            &[false, false, false],
+            &request("https://api.test/chat", "changed"),
+        );
+        assert_eq!(actual, Some(0));
+
+        let actual = fixture.find(
+            &[true, true, false],
+            &request("https://api.test/chat", "first"),
+        );
+        assert_eq!(actual, None);
+    }
+
+    #[test]
+    fn test_redact_credentials() {
+        let fixture = Url::parse("https://api.test/v1/models?key=secret&alt=sse").unwrap();
+
+        let actual = redact(&fixture);
+
+        let expected = "https://api.test/v1/models?key=REDACTED&alt=sse";
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_record_then_replay_offline() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cassettes/session.json");
+        let stream = "data: {\"delta\":\"Hello\"}\n\ndata: [DONE]\n\n";
+        let (address, upstream) = upstream(stream).await;
+        let url = address.join("/v1/chat/completions").unwrap();
+
+        let recorder = CassetteServer::new(
+            CassetteConfig { path: path.clone(), mode: CassetteMode::Record },
+            Client::new(),
+        );
+        let actual = send(&recorder, &url, "{\"model\":\"test\"}").await;
+        assert_eq!(actual, (200, stream.to_string()));
+
+        // The exchange is saved once the response completes
+        let mut cassette = Cassette::default();
+        for _ in 0..50 {
+            if let Ok(loaded) = Cassette::load(&path).await {
+                cassette = loaded;
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        let actual = &cassette.interactions;
+        assert_eq!(actual.len(), 1);
+        assert_eq!(
+            actual[0].request,
+            request(url.as_str(), "{\"model\":\"test\"}")
+        );
+        assert_eq!(actual[0].response.status, 200);
+        assert_eq!(
+            actual[0].response.headers["content-type"],
+            "text/event-stream"
+        );
+        assert_eq!(actual[0].response.body, stream);
+
+        upstream.abort();
+        let player = CassetteServer::new(
+            CassetteConfig { path, mode: CassetteMode::Replay },
+            Client::new(),
+        );
+        let actual = send(&player, &url, "{\"model\":\"other\"}").await;
+        assert_eq!(actual, (200, stream.to_string()));
+
+        // Every recorded response is served once
+        let (status, _) = send(&player, &url, "{\"model\":\"test\"}").await;
+        assert_eq!(status, 404);
+    }
+}
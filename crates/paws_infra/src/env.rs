@@ -3,12 +3,16 @@ use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 use paws_app::EnvironmentInfra;
-use paws_domain::{Environment, ModelId, ProviderId, RetryConfig, TlsBackend, TlsVersion};
+use paws_domain::{
+    CassetteConfig, CassetteMode, Environment, ModelId, ProviderId, RetryConfig, SandboxPolicy,
+    TlsBackend, TlsVersion,
+};
 use reqwest::Url;
 
 #[derive(Clone)]
 pub struct PawsEnvironmentInfra {
     restricted: bool,
+    os_sandbox: bool,
     cwd: PathBuf,
 }
 
@@ -18,10 +22,12 @@ impl PawsEnvironmentInfra {
     /// # Arguments
     /// * `restricted` - If true, use restricted shell mode (rbash) If false,
     ///   use unrestricted shell mode (sh/bash)
+    /// * `os_sandbox` - If true, confine shell commands with an OS-level
+    ///   sandbox even when FORGE_OS_SANDBOX is not set
     /// * `cwd` - Required working directory path
-    pub fn new(restricted: bool, cwd: PathBuf) -> Self {
+    pub fn new(restricted: bool, os_sandbox: bool, cwd: PathBuf) -> Self {
         Self::dot_env(&cwd);
-        Self { restricted, cwd }
+        Self { restricted, os_sandbox, cwd }
     }
 
     /// Get path to appropriate shell based on platform and mode
@@ -78,6 +84,8 @@ impl PawsEnvironmentInfra {
             stdout_max_prefix_length: 200,
             stdout_max_suffix_length: 200,
             tool_timeout: parse_env::<u64>("FORGE_TOOL_TIMEOUT").unwrap_or(300),
+            max_parallel_tool_calls: parse_env::<usize>("FORGE_MAX_PARALLEL_TOOL_CALLS")
+                .unwrap_or(8),
             auto_open_dump: parse_env::<bool>("FORGE_DUMP_AUTO_OPEN").unwrap_or(false),
             debug_requests: parse_env::<String>("FORGE_DEBUG_REQUESTS").map(PathBuf::from),
             stdout_max_line_length: parse_env::<usize>("FORGE_STDOUT_MAX_LINE_LENGTH")
@@ -93,6 +101,11 @@ impl PawsEnvironmentInfra {
             override_model,
             override_provider,
             enable_permissions,
+            reject_syntax_errors: parse_env::<bool>("FORGE_REJECT_SYNTAX_ERRORS").unwrap_or(false),
+            embedding_provider: parse_env::<String>("FORGE_EMBEDDING_PROVIDER")
+                .and_then(|s| ProviderId::from_str(&s).ok()),
+            embedding_model: parse_env::<String>("FORGE_EMBEDDING_MODEL"),
+            sandbox: resolve_sandbox_policy(self.os_sandbox),
         }
     }
 
@@ -170,6 +183,7 @@ impl_from_env_str_via_from_str! {
     String,
     paws_domain::TlsBackend,
     paws_domain::TlsVersion,
+    paws_domain::CassetteMode,
 }
 
 /// Parse environment variable using custom FromEnvStr trait
@@ -210,6 +224,36 @@ fn resolve_retry_config() -> RetryConfig {
     config
 }
 
+/// Resolves the OS-level sandbox policy, if the sandbox is enabled by the flag
+/// or environment variables
+fn resolve_sandbox_policy(enabled: bool) -> Option<SandboxPolicy> {
+    if !enabled && !parse_env::<bool>("FORGE_OS_SANDBOX").unwrap_or(false) {
+        return None;
+    }
+
+    Some(SandboxPolicy {
+        read_only: parse_env_paths("FORGE_OS_SANDBOX_READ_ONLY"),
+        writable: parse_env_paths("FORGE_OS_SANDBOX_WRITABLE"),
+        network: parse_env::<bool>("FORGE_OS_SANDBOX_NETWORK").unwrap_or(true),
+    })
+}
+
+/// Parses a list of paths separated like `PATH`, expanding a leading `~` to
+/// the home directory
+fn parse_env_paths(name: &str) -> Vec<PathBuf> {
+    let Some(value) = std::env::var_os(name) else {
+        return vec![];
+    };
+
+    std::env::split_paths(&value)
+        .filter(|path| !path.as_os_str().is_empty())
+        .map(|path| match (path.strip_prefix("~"), dirs::home_dir()) {
+            (Ok(rest), Some(home)) => home.join(rest),
+            _ => path,
+        })
+        .collect()
+}
+
 fn resolve_http_config() -> paws_domain::HttpConfig {
     let mut config = paws_domain::HttpConfig::default();
 
@@ -272,6 +316,11 @@ fn resolve_http_config() -> paws_domain::HttpConfig {
             config.root_cert_paths = Some(paths);
         }
     }
+    if let Some(path) = parse_env::<String>("FORGE_HTTP_CASSETTE") {
+        let mode =
+            parse_env::<CassetteMode>("FORGE_HTTP_CASSETTE_MODE").unwrap_or(CassetteMode::Replay);
+        config.cassette = Some(CassetteConfig { path: PathBuf::from(path), mode });
+    }
 
     config
 }
@@ -335,6 +384,8 @@ mod tests {
             "FORGE_HTTP_KEEP_ALIVE_WHILE_IDLE",
             "FORGE_HTTP_ACCEPT_INVALID_CERTS",
             "FORGE_HTTP_ROOT_CERT_PATHS",
+            "FORGE_HTTP_CASSETTE",
+            "FORGE_HTTP_CASSETTE_MODE",
         ];
 
         for var in &http_env_vars {
@@ -476,6 +527,31 @@ mod tests {
         clean_http_env_vars();
     }
 
+    #[test]
+    #[serial]
+    fn test_http_config_cassette() {
+        clean_http_env_vars();
+        assert_eq!(resolve_http_config().cassette, None);
+
+        unsafe {
+            env::set_var("FORGE_HTTP_CASSETTE", "/tmp/session.json");
+        }
+        let actual = resolve_http_config().cassette;
+        let expected = Some(CassetteConfig {
+            path: PathBuf::from("/tmp/session.json"),
+            mode: CassetteMode::Replay,
+        });
+        assert_eq!(actual, expected);
+
+        unsafe {
+            env::set_var("FORGE_HTTP_CASSETTE_MODE", "record");
+        }
+        let actual = resolve_http_config().cassette.map(|cassette| cassette.mode);
+        assert_eq!(actual, Some(CassetteMode::Record));
+
+        clean_http_env_vars();
+    }
+
     #[test]
     #[serial]
     fn test_http_config_keep_alive_special_cases() {
@@ -501,7 +577,7 @@ mod tests {
         }
 
         // Test default value
-        let paws_env = PawsEnvironmentInfra::new(false, PathBuf::from("/tmp"));
+        let paws_env = PawsEnvironmentInfra::new(false, false, PathBuf::from("/tmp"));
         let environment = paws_env.get_environment();
         let expected_default = (10.0_f64 * 1024.0).ceil() as usize;
         assert_eq!(environment.max_search_result_bytes, expected_default);
@@ -536,7 +612,7 @@ mod tests {
     #[serial]
     fn test_auto_open_dump_env_var() {
         let cwd = tempdir().unwrap().path().to_path_buf();
-        let infra = PawsEnvironmentInfra::new(false, cwd);
+        let infra = PawsEnvironmentInfra::new(false, false, cwd);
 
         // Test default value when env var is not set
         {
@@ -624,7 +700,7 @@ mod tests {
     #[serial]
     fn test_tool_timeout_env_var() {
         let cwd = tempdir().unwrap().path().to_path_buf();
-        let infra = PawsEnvironmentInfra::new(false, cwd);
+        let infra = PawsEnvironmentInfra::new(false, false, cwd);
 
         // Test Default value when env var is not set
         {
@@ -664,7 +740,7 @@ mod tests {
     #[serial]
     fn test_max_image_size_env_var() {
         let cwd = tempfile::tempdir().unwrap();
-        let infra = PawsEnvironmentInfra::new(false, cwd.path().to_path_buf());
+        let infra = PawsEnvironmentInfra::new(false, false, cwd.path().to_path_buf());
 
         // Test default value (256 KiB)
         unsafe {
@@ -695,7 +771,7 @@ mod tests {
     #[test]
     fn test_max_conversations_env_var() {
         let cwd = tempfile::tempdir().unwrap();
-        let infra = PawsEnvironmentInfra::new(false, cwd.path().to_path_buf());
+        let infra = PawsEnvironmentInfra::new(false, false, cwd.path().to_path_buf());
 
         // Test default value
         unsafe {
@@ -723,6 +799,51 @@ mod tests {
         }
     }
 
+    #[test]
+    #[serial]
+    fn test_sandbox_env_vars() {
+        let cwd = tempfile::tempdir().unwrap();
+        let vars = [
+            "FORGE_OS_SANDBOX",
+            "FORGE_OS_SANDBOX_READ_ONLY",
+            "FORGE_OS_SANDBOX_WRITABLE",
+            "FORGE_OS_SANDBOX_NETWORK",
+        ];
+        unsafe {
+            vars.iter().for_each(|var| env::remove_var(var));
+        }
+
+        // Disabled unless the flag or FORGE_OS_SANDBOX enables it
+        let disabled = PawsEnvironmentInfra::new(false, false, cwd.path().to_path_buf());
+        assert_eq!(disabled.get_environment().sandbox, None);
+        let actual = PawsEnvironmentInfra::new(false, true, cwd.path().to_path_buf())
+            .get_environment()
+            .sandbox;
+        let expected = Some(SandboxPolicy { network: true, ..Default::default() });
+        assert_eq!(actual, expected);
+
+        unsafe {
+            env::set_var("FORGE_OS_SANDBOX", "true");
+            env::set_var("FORGE_OS_SANDBOX_READ_ONLY", "/opt/sdk:~/.cargo");
+            env::set_var("FORGE_OS_SANDBOX_WRITABLE", "/var/cache/build");
+            env::set_var("FORGE_OS_SANDBOX_NETWORK", "false");
+        }
+        let actual = disabled.get_environment().sandbox;
+        let expected = Some(SandboxPolicy {
+            read_only: vec![
+                PathBuf::from("/opt/sdk"),
+                dirs::home_dir().unwrap().join(".cargo"),
+            ],
+            writable: vec![PathBuf::from("/var/cache/build")],
+            network: false,
+        });
+        assert_eq!(actual, expected);
+
+        unsafe {
+            vars.iter().for_each(|var| env::remove_var(var));
+        }
+    }
+
     #[test]
     #[serial]
     fn test_multiline_env_vars() {
@@ -1,26 +1,56 @@
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 
 use paws_app::CommandInfra;
-use paws_domain::{CommandOutput, Environment};
-use tokio::io::AsyncReadExt;
+use paws_domain::{CommandOutput, ConversationId, Environment, JobId, JobOutput};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::process::Command;
 use tokio::sync::Mutex;
 
+use crate::jobs::JobRegistry;
+use crate::sandbox;
+
+/// Time left for reading a job's output when waiting on it is cut short by
+/// the tool timeout
+const WAIT_TIMEOUT_MARGIN: Duration = Duration::from_secs(1);
+
 /// Service for executing shell commands
 #[derive(Clone, Debug)]
 pub struct PawsCommandExecutorService {
     restricted: bool,
     env: Environment,
 
-    // Mutex to ensure that only one command is executed at a time
+    // The console carries paws' own protocol, so commands neither read from
+    // stdin nor stream their output to it
+    headless: bool,
+
+    // Mutex to ensure that only one command streams its output to the console
+    // at a time
     ready: Arc<Mutex<()>>,
+
+    // Commands started in the background, killed once the last clone of the
+    // service is dropped
+    jobs: JobRegistry,
 }
 
 impl PawsCommandExecutorService {
     pub fn new(restricted: bool, env: Environment) -> Self {
-        Self { restricted, env, ready: Arc::new(Mutex::new(())) }
+        Self {
+            restricted,
+            env,
+            headless: false,
+            ready: Arc::new(Mutex::new(())),
+            jobs: JobRegistry::default(),
+        }
+    }
+
+    /// Runs every command silently with a null stdin, for front-ends that
+    /// speak a protocol over stdin and stdout
+    pub fn headless(mut self, headless: bool) -> Self {
+        self.headless = headless;
+        self
     }
 
     fn prepare_command(
@@ -28,14 +58,30 @@ impl PawsCommandExecutorService {
         command_str: &str,
         working_dir: &Path,
         env_vars: Option<Vec<String>>,
-    ) -> Command {
-        // Create a basic command
-        let is_windows = cfg!(target_os = "windows");
-        let shell = if self.restricted && !is_windows {
+    ) -> anyhow::Result<Command> {
+        let shell = if self.restricted && !cfg!(target_os = "windows") {
             "rbash"
         } else {
             self.env.shell.as_str()
         };
+        let mut command = Self::prepare_shell_command(shell, command_str, working_dir, env_vars);
+        if self.headless {
+            command.stdin(std::process::Stdio::null());
+        }
+        if let Some(policy) = &self.env.sandbox {
+            sandbox::confine(&mut command, policy, &self.env.cwd, shell)?;
+        }
+        Ok(command)
+    }
+
+    fn prepare_shell_command(
+        shell: &str,
+        command_str: &str,
+        working_dir: &Path,
+        env_vars: Option<Vec<String>>,
+    ) -> Command {
+        // Create a basic command
+        let is_windows = cfg!(target_os = "windows");
         let mut command = Command::new(shell);
 
         // Core color settings for general commands
@@ -99,9 +145,16 @@ impl PawsCommandExecutorService {
         silent: bool,
         env_vars: Option<Vec<String>>,
     ) -> anyhow::Result<CommandOutput> {
-        let ready = self.ready.lock().await;
+        let silent = silent || self.headless;
+
+        // Silent commands don't write to the console and can run concurrently
+        let ready = if silent {
+            None
+        } else {
+            Some(self.ready.lock().await)
+        };
 
-        let mut prepared_command = self.prepare_command(&command, working_dir, env_vars);
+        let mut prepared_command = self.prepare_command(&command, working_dir, env_vars)?;
 
         // Spawn the command
         let mut child = prepared_command.spawn()?;
@@ -180,7 +233,7 @@ impl CommandInfra for PawsCommandExecutorService {
         working_dir: PathBuf,
         env_vars: Option<Vec<String>>,
     ) -> anyhow::Result<std::process::ExitStatus> {
-        let mut prepared_command = self.prepare_command(command, &working_dir, env_vars);
+        let mut prepared_command = self.prepare_command(command, &working_dir, env_vars)?;
 
         // overwrite the stdin, stdout and stderr to inherit
         prepared_command
@@ -190,6 +243,98 @@ impl CommandInfra for PawsCommandExecutorService {
 
         Ok(prepared_command.spawn()?.wait().await?)
     }
+
+    async fn execute_command_with_input(
+        &self,
+        command: String,
+        working_dir: PathBuf,
+        input: String,
+    ) -> anyhow::Result<CommandOutput> {
+        // Configured by the user, so the restrictions on agent commands don't apply
+        let mut prepared_command =
+            Self::prepare_shell_command(&self.env.shell, &command, &working_dir, None);
+        prepared_command.stdin(std::process::Stdio::piped());
+
+        let mut child = prepared_command.spawn()?;
+        let mut stdin_pipe = child.stdin.take();
+        let mut stdout_pipe = child.stdout.take();
+        let mut stderr_pipe = child.stderr.take();
+
+        let write_input = async {
+            if let Some(mut stdin) = stdin_pipe.take() {
+                // Commands are free to exit without reading their input
+                match stdin.write_all(input.as_bytes()).await {
+                    Err(error) if error.kind() != io::ErrorKind::BrokenPipe => return Err(error),
+                    _ => {}
+                }
+            }
+            Ok(())
+        };
+
+        let (status, _, stdout_buffer, stderr_buffer) = tokio::try_join!(
+            child.wait(),
+            write_input,
+            stream(&mut stdout_pipe, io::sink()),
+            stream(&mut stderr_pipe, io::sink())
+        )?;
+
+        Ok(CommandOutput {
+            stdout: String::from_utf8_lossy(&stdout_buffer).into_owned(),
+            stderr: String::from_utf8_lossy(&stderr_buffer).into_owned(),
+            exit_code: status.code(),
+            command,
+        })
+    }
+
+    async fn spawn_job(
+        &self,
+        conversation_id: ConversationId,
+        command: String,
+        working_dir: PathBuf,
+        env_vars: Option<Vec<String>>,
+    ) -> anyhow::Result<JobOutput> {
+        let mut prepared_command = self.prepare_command(&command, &working_dir, env_vars)?;
+
+        // Background jobs must not compete with the user for the terminal
+        prepared_command.stdin(std::process::Stdio::null());
+
+        // Lead a process group so that killing the job kills everything it started
+        #[cfg(unix)]
+        prepared_command.process_group(0);
+
+        let child = prepared_command.spawn()?;
+        Ok(self.jobs.insert(conversation_id, command, child))
+    }
+
+    async fn read_job(
+        &self,
+        conversation_id: ConversationId,
+        job_id: JobId,
+    ) -> anyhow::Result<JobOutput> {
+        self.jobs.read(conversation_id, job_id)
+    }
+
+    async fn wait_job(
+        &self,
+        conversation_id: ConversationId,
+        job_id: JobId,
+        timeout: Duration,
+    ) -> anyhow::Result<JobOutput> {
+        // Return before the tool call itself times out
+        let max_timeout =
+            Duration::from_secs(self.env.tool_timeout).saturating_sub(WAIT_TIMEOUT_MARGIN);
+        self.jobs
+            .wait(conversation_id, job_id, timeout.min(max_timeout))
+            .await
+    }
+
+    async fn kill_job(
+        &self,
+        conversation_id: ConversationId,
+        job_id: JobId,
+    ) -> anyhow::Result<JobOutput> {
+        self.jobs.kill(conversation_id, job_id).await
+    }
 }
 
 #[cfg(test)]
@@ -241,6 +386,54 @@ mod tests {
         assert_eq!(actual.stderr, expected.stderr);
         assert_eq!(actual.success(), expected.success());
     }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_headless_command_has_no_stdin() {
+        let fixture = PawsCommandExecutorService::new(false, test_env()).headless(true);
+
+        let actual = fixture
+            .execute_command("cat".to_string(), PathBuf::from("."), false, None)
+            .await
+            .unwrap();
+
+        assert!(actual.success());
+        assert_eq!(actual.stdout, "");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_command_with_input() {
+        // Restrictions don't apply to commands with input
+        let fixture = PawsCommandExecutorService::new(true, test_env());
+
+        let actual = fixture
+            .execute_command_with_input(
+                "cat; echo done >&2; exit 2".to_string(),
+                PathBuf::from("."),
+                r#"{"event":"pre_tool_use"}"#.to_string(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(actual.stdout, r#"{"event":"pre_tool_use"}"#);
+        assert_eq!(actual.stderr, "done\n");
+        assert_eq!(actual.exit_code, Some(2));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_command_with_unread_input() {
+        let fixture = PawsCommandExecutorService::new(false, test_env());
+
+        let actual = fixture
+            .execute_command_with_input("true".to_string(), PathBuf::from("."), "x".repeat(1 << 20))
+            .await
+            .unwrap();
+
+        assert!(actual.success());
+    }
+
     #[tokio::test]
     async fn test_command_executor_with_env_vars_success() {
         // Set up test environment variables
@@ -384,4 +577,320 @@ mod tests {
         assert_eq!(actual.stderr, expected.stderr);
         assert_eq!(actual.success(), expected.success());
     }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_background_job_wait_returns_output_and_exit_code() {
+        let fixture = PawsCommandExecutorService::new(false, test_env());
+        let conversation_id = ConversationId::generate();
+
+        let job = fixture
+            .spawn_job(
+                conversation_id,
+                "echo hello; echo oops >&2; exit 3".to_string(),
+                PathBuf::from("."),
+                None,
+            )
+            .await
+            .unwrap();
+        let actual = fixture
+            .wait_job(conversation_id, job.job_id, Duration::from_secs(10))
+            .await
+            .unwrap();
+
+        let expected = JobOutput {
+            job_id: job.job_id,
+            output: CommandOutput {
+                command: "echo hello; echo oops >&2; exit 3".to_string(),
+                stdout: "hello\n".to_string(),
+                stderr: "oops\n".to_string(),
+                exit_code: Some(3),
+            },
+            running: false,
+        };
+        assert_eq!(actual, expected);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_background_job_read_is_incremental() {
+        let fixture = PawsCommandExecutorService::new(false, test_env());
+        let conversation_id = ConversationId::generate();
+
+        let job = fixture
+            .spawn_job(
+                conversation_id,
+                "echo first; sleep 0.5; echo second".to_string(),
+                PathBuf::from("."),
+                None,
+            )
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        let first = fixture.read_job(conversation_id, job.job_id).await.unwrap();
+        let second = fixture
+            .wait_job(conversation_id, job.job_id, Duration::from_secs(10))
+            .await
+            .unwrap();
+
+        assert_eq!(first.output.stdout, "first\n");
+        assert!(first.running);
+        assert_eq!(second.output.stdout, "second\n");
+        assert!(!second.running);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_background_job_kill() {
+        let fixture = PawsCommandExecutorService::new(false, test_env());
+        let conversation_id = ConversationId::generate();
+
+        let job = fixture
+            .spawn_job(
+                conversation_id,
+                "sleep 30".to_string(),
+                PathBuf::from("."),
+                None,
+            )
+            .await
+            .unwrap();
+        let waited = fixture
+            .wait_job(conversation_id, job.job_id, Duration::from_millis(100))
+            .await
+            .unwrap();
+        let actual = fixture.kill_job(conversation_id, job.job_id).await.unwrap();
+
+        assert!(waited.running);
+        assert!(!actual.running);
+        assert_eq!(actual.output.exit_code, None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_background_job_kill_kills_started_processes() {
+        let fixture = PawsCommandExecutorService::new(false, test_env());
+        let conversation_id = ConversationId::generate();
+
+        let job = fixture
+            .spawn_job(
+                conversation_id,
+                "sleep 30 & echo $!; wait".to_string(),
+                PathBuf::from("."),
+                None,
+            )
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        let pid = fixture
+            .read_job(conversation_id, job.job_id)
+            .await
+            .unwrap()
+            .output
+            .stdout
+            .trim()
+            .to_string();
+        fixture.kill_job(conversation_id, job.job_id).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(250)).await;
+
+        // The orphaned process is gone, or a zombie when nothing reaps it
+        let actual = std::fs::read_to_string(format!("/proc/{pid}/stat"))
+            .map(|stat| stat.contains(") Z "))
+            .unwrap_or(true);
+        assert!(actual, "process {pid} survived the kill");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_background_job_processes_are_killed_with_the_registry() {
+        let fixture = PawsCommandExecutorService::new(false, test_env());
+        let conversation_id = ConversationId::generate();
+
+        let job = fixture
+            .spawn_job(
+                conversation_id,
+                "sleep 30 & echo $!; wait".to_string(),
+                PathBuf::from("."),
+                None,
+            )
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        let pid = fixture
+            .read_job(conversation_id, job.job_id)
+            .await
+            .unwrap()
+            .output
+            .stdout
+            .trim()
+            .to_string();
+        drop(fixture);
+        // Blocks the runtime, so only a synchronous kill can have happened
+        std::thread::sleep(Duration::from_millis(250));
+
+        // The orphaned process is gone, or a zombie when nothing reaps it
+        let actual = std::fs::read_to_string(format!("/proc/{pid}/stat"))
+            .map(|stat| stat.contains(") Z "))
+            .unwrap_or(true);
+        assert!(actual, "process {pid} survived the registry");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_background_job_is_removed_after_final_read() {
+        let fixture = PawsCommandExecutorService::new(false, test_env());
+        let conversation_id = ConversationId::generate();
+
+        let job = fixture
+            .spawn_job(
+                conversation_id,
+                "true".to_string(),
+                PathBuf::from("."),
+                None,
+            )
+            .await
+            .unwrap();
+        let waited = fixture
+            .wait_job(conversation_id, job.job_id, Duration::from_secs(10))
+            .await
+            .unwrap();
+        let actual = fixture.read_job(conversation_id, job.job_id).await;
+
+        assert!(!waited.running);
+        assert!(actual.is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_background_job_wait_is_limited_by_tool_timeout() {
+        let fixture = PawsCommandExecutorService::new(false, test_env().tool_timeout(2u64));
+        let conversation_id = ConversationId::generate();
+
+        let job = fixture
+            .spawn_job(
+                conversation_id,
+                "sleep 30".to_string(),
+                PathBuf::from("."),
+                None,
+            )
+            .await
+            .unwrap();
+        let actual = tokio::time::timeout(
+            Duration::from_secs(5),
+            fixture.wait_job(conversation_id, job.job_id, Duration::from_secs(60)),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        fixture.kill_job(conversation_id, job.job_id).await.unwrap();
+
+        assert!(actual.running);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_background_job_is_scoped_to_conversation() {
+        let fixture = PawsCommandExecutorService::new(false, test_env());
+
+        let job = fixture
+            .spawn_job(
+                ConversationId::generate(),
+                "true".to_string(),
+                PathBuf::from("."),
+                None,
+            )
+            .await
+            .unwrap();
+        let actual = fixture
+            .read_job(ConversationId::generate(), job.job_id)
+            .await;
+
+        assert!(actual.is_err());
+    }
+
+    /// Runs the command in a sandbox confined to `workspace`, or returns None
+    /// when the kernel doesn't support Landlock
+    #[cfg(target_os = "linux")]
+    async fn execute_sandboxed(
+        command: &str,
+        workspace: &Path,
+        policy: paws_domain::SandboxPolicy,
+    ) -> Option<CommandOutput> {
+        let env = test_env().cwd(workspace.to_path_buf()).sandbox(policy);
+        let fixture = PawsCommandExecutorService::new(false, env);
+
+        match fixture
+            .execute_command(command.to_string(), workspace.to_path_buf(), true, None)
+            .await
+        {
+            Ok(output) => Some(output),
+            Err(error) if error.to_string().contains("Landlock is not supported") => None,
+            Err(error) => panic!("{error:?}"),
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_sandbox_confines_writes_to_workspace() {
+        // Created outside the temp directory, which the sandbox always allows
+        let workspace = tempfile::tempdir_in(env!("CARGO_MANIFEST_DIR")).unwrap();
+        let outside = tempfile::tempdir_in(env!("CARGO_MANIFEST_DIR")).unwrap();
+        let command = format!(
+            "echo inside > inside.txt && echo outside > {}/outside.txt",
+            outside.path().display()
+        );
+
+        let Some(actual) = execute_sandboxed(&command, workspace.path(), Default::default()).await
+        else {
+            return;
+        };
+
+        assert_eq!(actual.exit_code, Some(1));
+        assert!(actual.stderr.contains("Permission denied"), "{actual:?}");
+        assert!(workspace.path().join("inside.txt").exists());
+        assert!(!outside.path().join("outside.txt").exists());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_sandbox_allows_writable_paths() {
+        let workspace = tempfile::tempdir_in(env!("CARGO_MANIFEST_DIR")).unwrap();
+        let cache = tempfile::tempdir_in(env!("CARGO_MANIFEST_DIR")).unwrap();
+        let policy = paws_domain::SandboxPolicy {
+            writable: vec![cache.path().to_path_buf()],
+            ..Default::default()
+        };
+        let command = format!("echo cached > {}/cached.txt", cache.path().display());
+
+        let Some(actual) = execute_sandboxed(&command, workspace.path(), policy).await else {
+            return;
+        };
+
+        assert_eq!(actual.exit_code, Some(0), "{actual:?}");
+        assert!(cache.path().join("cached.txt").exists());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_sandbox_blocks_network() {
+        let workspace = tempfile::tempdir_in(env!("CARGO_MANIFEST_DIR")).unwrap();
+        let command = "exec 3<>/dev/tcp/127.0.0.1/9";
+
+        let Some(blocked) = execute_sandboxed(command, workspace.path(), Default::default()).await
+        else {
+            return;
+        };
+        let policy = paws_domain::SandboxPolicy { network: true, ..Default::default() };
+        let allowed = execute_sandboxed(command, workspace.path(), policy)
+            .await
+            .unwrap();
+
+        assert!(
+            blocked.stderr.contains("Operation not permitted"),
+            "{blocked:?}"
+        );
+        assert!(
+            !allowed.stderr.contains("Operation not permitted"),
+            "{allowed:?}"
+        );
+    }
 }
@@ -4,14 +4,19 @@ use std::time::Duration;
 
 use anyhow::Context;
 use bytes::Bytes;
-use paws_app::HttpInfra;
-use paws_domain::{Environment, TlsBackend, TlsVersion};
-use reqwest::header::{AUTHORIZATION, HeaderMap, HeaderValue};
+use chrono::Utc;
+use eventsource_stream::Eventsource;
+use futures::{StreamExt, stream};
+use paws_app::{EventStream, HttpInfra};
+use paws_domain::{Environment, RateLimit, TlsBackend, TlsVersion};
+use reqwest::header::{AUTHORIZATION, CONTENT_TYPE, HeaderMap, HeaderValue};
 use reqwest::redirect::Policy;
 use reqwest::{Certificate, Client, Response, StatusCode, Url};
-use reqwest_eventsource::{EventSource, RequestBuilderExt};
+use reqwest_eventsource::{Error as EventError, Event};
 use tracing::{debug, warn};
 
+use crate::cassette::CassetteServer;
+
 const VERSION: &str = match option_env!("APP_VERSION") {
     None => env!("CARGO_PKG_VERSION"),
     Some(v) => v,
@@ -21,6 +26,7 @@ pub struct PawsHttpInfra<F> {
     client: Client,
     env: Environment,
     file: Arc<F>,
+    cassette: Option<CassetteServer>,
 }
 
 fn to_reqwest_tls(tls: TlsVersion) -> reqwest::tls::Version {
@@ -99,33 +105,54 @@ impl<F> PawsHttpInfra<F> {
             TlsBackend::Default => {}
         }
 
-        Self { env, client: client.build().unwrap(), file: file_writer }
+        let client = client.build().unwrap();
+        let cassette = env
+            .http
+            .cassette
+            .clone()
+            .map(|config| CassetteServer::new(config, client.clone()));
+        Self { env, client, file: file_writer, cassette }
     }
 
     async fn get(&self, url: &Url, headers: Option<HeaderMap>) -> anyhow::Result<Response> {
-        self.execute_request("GET", url, |client| {
-            client.get(url.clone()).headers(self.headers(headers))
-        })
-        .await
+        let (target, headers) = self.route(url, self.headers(headers)).await?;
+        self.execute_request("GET", url, |client| client.get(target).headers(headers))
+            .await
     }
 
-    async fn post(&self, url: &Url, body: Bytes) -> anyhow::Result<Response> {
+    async fn post(
+        &self,
+        url: &Url,
+        headers: Option<HeaderMap>,
+        body: Bytes,
+    ) -> anyhow::Result<Response> {
+        let (target, headers) = self.route(url, self.headers(headers)).await?;
         self.execute_request("POST", url, |client| {
-            client
-                .post(url.clone())
-                .headers(self.headers(None))
-                .body(body)
+            client.post(target).headers(headers).body(body)
         })
         .await
     }
 
     async fn delete(&self, url: &Url) -> anyhow::Result<Response> {
+        let (target, headers) = self.route(url, self.headers(None)).await?;
         self.execute_request("DELETE", url, |client| {
-            client.delete(url.clone()).headers(self.headers(None))
+            client.delete(target).headers(headers)
         })
         .await
     }
 
+    /// Routes requests through the cassette server while recording or
+    /// replaying provider traffic
+    async fn route(&self, url: &Url, mut headers: HeaderMap) -> anyhow::Result<(Url, HeaderMap)> {
+        match &self.cassette {
+            Some(cassette) => {
+                let target = cassette.route(url, &mut headers).await?;
+                Ok((target, headers))
+            }
+            None => Ok((url.clone(), headers)),
+        }
+    }
+
     /// Generic helper method to execute HTTP requests with consistent error
     /// handling
     async fn execute_request<B>(
@@ -202,7 +229,7 @@ impl<F: paws_app::FileWriterInfra + 'static> PawsHttpInfra<F> {
         url: &Url,
         headers: Option<HeaderMap>,
         body: Bytes,
-    ) -> anyhow::Result<EventSource> {
+    ) -> anyhow::Result<EventStream> {
         let mut request_headers = self.headers(headers);
         request_headers.insert("Content-Type", HeaderValue::from_static("application/json"));
 
@@ -216,13 +243,63 @@ impl<F: paws_app::FileWriterInfra + 'static> PawsHttpInfra<F> {
             });
         }
 
-        self.client
-            .post(url.clone())
+        let (target, request_headers) = self.route(url, request_headers).await?;
+        let response = self
+            .client
+            .post(target)
             .headers(request_headers)
             .body(body)
-            .eventsource()
-            .with_context(|| format_http_context(None, "POST (EventSource)", url))
+            .send()
+            .await;
+        Ok(event_stream(response))
+    }
+}
+
+/// Streams the server-sent events of a response. Failures are reported
+/// in-stream, the way `reqwest_eventsource` does, so that providers handle
+/// them as before.
+fn event_stream(response: reqwest::Result<Response>) -> EventStream {
+    let response = match response {
+        Ok(response) => response,
+        Err(error) => {
+            return EventStream::new(None, stream::iter([Err(EventError::Transport(error))]));
+        }
+    };
+    if !response.status().is_success() {
+        let status = response.status();
+        return EventStream::new(
+            None,
+            stream::iter([Err(EventError::InvalidStatusCode(status, response))]),
+        );
+    }
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .cloned()
+        .unwrap_or(HeaderValue::from_static(""));
+    let is_event_stream = content_type
+        .to_str()
+        .is_ok_and(|value| value.starts_with("text/event-stream"));
+    if !is_event_stream {
+        return EventStream::new(
+            None,
+            stream::iter([Err(EventError::InvalidContentType(content_type, response))]),
+        );
     }
+
+    let headers = response
+        .headers()
+        .iter()
+        .filter_map(|(name, value)| Some((name.as_str(), value.to_str().ok()?)));
+    let rate_limit = RateLimit::parse(headers, Utc::now());
+    let events = response
+        .bytes_stream()
+        .eventsource()
+        .map(|event| event.map(Event::Message).map_err(EventError::from));
+    EventStream::new(
+        rate_limit,
+        stream::once(async { Ok(Event::Open) }).chain(events),
+    )
 }
 
 /// Helper function to format HTTP request/response context for logging and
@@ -241,8 +318,13 @@ impl<F: paws_app::FileWriterInfra + 'static> HttpInfra for PawsHttpInfra<F> {
         self.get(url, headers).await
     }
 
-    async fn http_post(&self, url: &Url, body: Bytes) -> anyhow::Result<Response> {
-        self.post(url, body).await
+    async fn http_post(
+        &self,
+        url: &Url,
+        headers: Option<HeaderMap>,
+        body: Bytes,
+    ) -> anyhow::Result<Response> {
+        self.post(url, headers, body).await
     }
 
     async fn http_delete(&self, url: &Url) -> anyhow::Result<Response> {
@@ -254,7 +336,7 @@ impl<F: paws_app::FileWriterInfra + 'static> HttpInfra for PawsHttpInfra<F> {
         url: &Url,
         headers: Option<HeaderMap>,
         body: Bytes,
-    ) -> anyhow::Result<EventSource> {
+    ) -> anyhow::Result<EventStream> {
         self.eventsource(url, headers, body).await
     }
 }
@@ -398,4 +480,55 @@ mod tests {
         assert_eq!(writes[0].0, debug_path);
         assert_eq!(writes[0].1, body);
     }
+
+    #[tokio::test]
+    async fn test_eventsource_replays_cassette() {
+        use futures::StreamExt;
+        use paws_domain::{CassetteConfig, CassetteMode};
+        use reqwest_eventsource::Event;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.json");
+        let cassette = serde_json::json!({
+            "interactions": [{
+                "request": {
+                    "method": "POST",
+                    "url": "https://api.test.com/messages"
+                },
+                "response": {
+                    "status": 200,
+                    "headers": {
+                        "content-type": "text/event-stream",
+                        "x-ratelimit-remaining-requests": "7"
+                    },
+                    "body": "data: first\n\ndata: second\n\n"
+                }
+            }]
+        });
+        std::fs::write(&path, cassette.to_string()).unwrap();
+        let mut env = create_test_env(None);
+        env.http.cassette = Some(CassetteConfig { path, mode: CassetteMode::Replay });
+        let http = PawsHttpInfra::new(env, Arc::new(MockFileWriter::new()));
+
+        let url = Url::parse("https://api.test.com/messages").unwrap();
+        let mut source = http
+            .eventsource(&url, None, Bytes::from("{}"))
+            .await
+            .unwrap();
+        let mut actual = Vec::new();
+        while let Some(event) = source.events.next().await {
+            match event {
+                Ok(Event::Message(message)) => actual.push(message.data),
+                Ok(Event::Open) => {}
+                Err(_) => break,
+            }
+        }
+
+        let expected = vec!["first".to_string(), "second".to_string()];
+        assert_eq!(actual, expected);
+        let actual = source.rate_limit.and_then(|rate_limit| rate_limit.requests);
+        let expected =
+            Some(paws_domain::RateLimitWindow { remaining: Some(7), ..Default::default() });
+        assert_eq!(actual, expected);
+    }
 }
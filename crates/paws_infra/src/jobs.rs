@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+use anyhow::Context as _;
+use paws_domain::{CommandOutput, ConversationId, JobId, JobOutput};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::process::Child;
+use tokio::sync::{oneshot, watch};
+
+/// How long to keep draining the pipes after a job exits. Processes forked by
+/// the job can hold the pipes open indefinitely, so EOF is not awaited.
+const DRAIN_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// How long to wait for a killed job to be reaped
+const KILL_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Exit state of a job: `None` while running, `Some(exit_code)` once exited
+type ExitState = Option<Option<i32>>;
+
+type Buffer = Arc<Mutex<Vec<u8>>>;
+
+/// A command running in the background
+#[derive(Debug)]
+struct Job {
+    command: String,
+    stdout: Buffer,
+    stderr: Buffer,
+    exit: watch::Receiver<ExitState>,
+    /// Sending or dropping it makes the watcher task kill the job
+    kill: Option<oneshot::Sender<()>>,
+    /// Process group of the job, shared with every process it started
+    pgid: Option<u32>,
+}
+
+impl Job {
+    /// Returns the job state along with the output produced since the
+    /// previous read
+    fn read(&self, job_id: JobId) -> JobOutput {
+        let exit = *self.exit.borrow();
+        let running = exit.is_none();
+        JobOutput {
+            job_id,
+            output: CommandOutput {
+                command: self.command.clone(),
+                stdout: drain(&self.stdout, running),
+                stderr: drain(&self.stderr, running),
+                exit_code: exit.flatten(),
+            },
+            running,
+        }
+    }
+}
+
+/// Jobs grouped by the conversation that started them
+#[derive(Debug, Default)]
+struct Jobs(HashMap<ConversationId, HashMap<JobId, Job>>);
+
+impl Deref for Jobs {
+    type Target = HashMap<ConversationId, HashMap<JobId, Job>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Jobs {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl Drop for Jobs {
+    /// Kills the process group of every job. The watcher tasks can't be relied
+    /// on for this, a shutting down runtime drops them without polling them
+    /// again, and jobs don't receive the terminal's signals since they lead
+    /// their own process group.
+    fn drop(&mut self) {
+        for job in self.0.values().flat_map(HashMap::values) {
+            kill_group(job.pgid);
+        }
+    }
+}
+
+/// Background jobs grouped by the conversation that started them
+#[derive(Debug, Clone, Default)]
+pub struct JobRegistry {
+    next_id: Arc<AtomicU64>,
+    jobs: Arc<Mutex<Jobs>>,
+}
+
+impl JobRegistry {
+    /// Tracks an already spawned child process as a job of `conversation_id`.
+    /// The child is expected to lead its own process group, so that killing
+    /// the job also kills the processes it started.
+    pub fn insert(
+        &self,
+        conversation_id: ConversationId,
+        command: String,
+        mut child: Child,
+    ) -> JobOutput {
+        let job_id = JobId::new(self.next_id.fetch_add(1, Ordering::Relaxed) + 1);
+        let stdout = Buffer::default();
+        let stderr = Buffer::default();
+        let (exit_tx, exit_rx) = watch::channel(None);
+        let (kill_tx, kill_rx) = oneshot::channel::<()>();
+        let pgid = child.id();
+
+        let stdout_task = tokio::spawn(collect(child.stdout.take(), stdout.clone()));
+        let stderr_task = tokio::spawn(collect(child.stderr.take(), stderr.clone()));
+
+        tokio::spawn(async move {
+            let status = tokio::select! {
+                status = child.wait() => status.ok(),
+                // Resolves on an explicit kill and when the job is dropped
+                _ = kill_rx => {
+                    kill_group(pgid);
+                    let _ = child.kill().await;
+                    child.wait().await.ok()
+                }
+            };
+            let _ = tokio::time::timeout(DRAIN_TIMEOUT, async {
+                let _ = tokio::join!(stdout_task, stderr_task);
+            })
+            .await;
+            let _ = exit_tx.send(Some(status.and_then(|status| status.code())));
+        });
+
+        let job = Job {
+            command,
+            stdout,
+            stderr,
+            exit: exit_rx,
+            kill: Some(kill_tx),
+            pgid,
+        };
+        let output = job.read(job_id);
+        self.lock()
+            .entry(conversation_id)
+            .or_default()
+            .insert(job_id, job);
+        output
+    }
+
+    /// Returns the output produced since the previous read. Exited jobs are
+    /// forgotten once their remaining output has been read.
+    pub fn read(
+        &self,
+        conversation_id: ConversationId,
+        job_id: JobId,
+    ) -> anyhow::Result<JobOutput> {
+        let output = self.with_job(conversation_id, job_id, |job| job.read(job_id))?;
+        if !output.running {
+            let mut jobs = self.lock();
+            if let Some(conversation_jobs) = jobs.get_mut(&conversation_id) {
+                conversation_jobs.remove(&job_id);
+                if conversation_jobs.is_empty() {
+                    jobs.remove(&conversation_id);
+                }
+            }
+        }
+        Ok(output)
+    }
+
+    pub async fn wait(
+        &self,
+        conversation_id: ConversationId,
+        job_id: JobId,
+        timeout: Duration,
+    ) -> anyhow::Result<JobOutput> {
+        let mut exit = self.with_job(conversation_id, job_id, |job| job.exit.clone())?;
+        let _ = tokio::time::timeout(timeout, exit.wait_for(Option::is_some)).await;
+        self.read(conversation_id, job_id)
+    }
+
+    pub async fn kill(
+        &self,
+        conversation_id: ConversationId,
+        job_id: JobId,
+    ) -> anyhow::Result<JobOutput> {
+        let (kill, pgid, mut exit) = self.with_job_mut(conversation_id, job_id, |job| {
+            (job.kill.take(), job.pgid, job.exit.clone())
+        })?;
+        // Processes left behind by a job that already exited are killed too
+        kill_group(pgid);
+        if let Some(kill) = kill {
+            let _ = kill.send(());
+        }
+        tokio::time::timeout(KILL_TIMEOUT, exit.wait_for(Option::is_some))
+            .await
+            .with_context(|| format!("Timed out waiting for job {job_id} to exit"))?
+            .ok();
+        self.read(conversation_id, job_id)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Jobs> {
+        self.jobs.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn with_job<T>(
+        &self,
+        conversation_id: ConversationId,
+        job_id: JobId,
+        f: impl FnOnce(&Job) -> T,
+    ) -> anyhow::Result<T> {
+        self.with_job_mut(conversation_id, job_id, |job| f(job))
+    }
+
+    fn with_job_mut<T>(
+        &self,
+        conversation_id: ConversationId,
+        job_id: JobId,
+        f: impl FnOnce(&mut Job) -> T,
+    ) -> anyhow::Result<T> {
+        let mut jobs = self.lock();
+        let job = jobs
+            .get_mut(&conversation_id)
+            .and_then(|jobs| jobs.get_mut(&job_id))
+            .with_context(|| format!("No background job with id {job_id}"))?;
+        Ok(f(job))
+    }
+}
+
+/// Kills every process of the job's process group
+fn kill_group(pgid: Option<u32>) {
+    #[cfg(unix)]
+    if let Some(pgid) = pgid.and_then(|pgid| libc::pid_t::try_from(pgid).ok()) {
+        // SAFETY: killpg has no memory safety requirements, a group that no
+        // longer exists is reported through the return value
+        unsafe {
+            libc::killpg(pgid, libc::SIGKILL);
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = pgid;
+}
+
+/// Appends everything read from `pipe` to `buffer` until EOF
+async fn collect<A: AsyncRead + Unpin>(pipe: Option<A>, buffer: Buffer) {
+    let Some(mut pipe) = pipe else {
+        return;
+    };
+    let mut buff = [0; 1024];
+    while let Ok(n) = pipe.read(&mut buff).await {
+        if n == 0 {
+            break;
+        }
+        buffer
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .extend_from_slice(&buff[..n]);
+    }
+}
+
+/// Takes the unread content out of `buffer`. While the job is running an
+/// incomplete trailing UTF-8 sequence is left for the next read.
+fn drain(buffer: &Buffer, running: bool) -> String {
+    let mut buffer = buffer.lock().unwrap_or_else(PoisonError::into_inner);
+    let end = match std::str::from_utf8(&buffer) {
+        Err(error) if running && error.error_len().is_none() => error.valid_up_to(),
+        _ => buffer.len(),
+    };
+    let bytes: Vec<u8> = buffer.drain(..end).collect();
+    String::from_utf8_lossy(&bytes).into_owned()
+}
@@ -1,6 +1,7 @@
 pub mod executor;
 
 mod auth;
+mod cassette;
 mod env;
 mod error;
 mod fs_create_dirs;
@@ -11,10 +12,12 @@ mod fs_remove;
 mod fs_write;
 mod http;
 mod inquire;
+mod jobs;
 mod kv_storage;
 mod mcp_client;
 mod mcp_server;
 mod paws_infra;
+mod sandbox;
 mod walker;
 
 pub use executor::PawsCommandExecutorService;
@@ -7,8 +7,15 @@ use std::sync::{Arc, OnceLock, RwLock};
 use backon::{ExponentialBuilder, Retryable};
 use http::{HeaderName, HeaderValue, header};
 use paws_app::McpClientInfra;
-use paws_domain::{Image, McpHttpServer, McpServerConfig, ToolDefinition, ToolName, ToolOutput};
-use rmcp::model::{CallToolRequestParam, ClientInfo, Implementation, InitializeRequestParam};
+use paws_domain::{
+    Image, McpHttpServer, McpPrompt, McpPromptArgument, McpPromptMessage, McpResource,
+    McpResourceContent, McpServerConfig, Role, ToolDefinition, ToolName, ToolOutput,
+};
+use rmcp::model::{
+    CallToolRequestParam, ClientInfo, GetPromptRequestParam, Implementation,
+    InitializeRequestParam, PromptMessageContent, PromptMessageRole, ReadResourceRequestParam,
+    ResourceContents,
+};
 use rmcp::service::RunningService;
 use rmcp::transport::sse_client::SseClientConfig;
 use rmcp::transport::streamable_http_client::StreamableHttpClientTransportConfig;
@@ -210,6 +217,124 @@ impl PawsMcpClient {
             .is_error(result.is_error.unwrap_or_default()))
     }
 
+    async fn list_resources(&self) -> anyhow::Result<Vec<McpResource>> {
+        let client = self.connect().await?;
+        let supported = client
+            .peer_info()
+            .is_some_and(|info| info.capabilities.resources.is_some());
+        if !supported {
+            return Ok(Vec::new());
+        }
+
+        let resources = client.list_all_resources().await?;
+        Ok(resources
+            .into_iter()
+            .map(|resource| {
+                let resource = resource.raw;
+                let mut output = McpResource::new(resource.uri, resource.name);
+                output.description = resource.description;
+                output.mime_type = resource.mime_type;
+                output
+            })
+            .collect())
+    }
+
+    async fn read_resource(&self, uri: &str) -> anyhow::Result<Vec<McpResourceContent>> {
+        let client = self.connect().await?;
+        let result = client
+            .read_resource(ReadResourceRequestParam { uri: uri.to_string() })
+            .await?;
+
+        Ok(result
+            .contents
+            .into_iter()
+            .map(|content| match content {
+                ResourceContents::TextResourceContents { uri, mime_type, text, .. } => {
+                    McpResourceContent::Text { uri, mime_type, text }
+                }
+                ResourceContents::BlobResourceContents { uri, mime_type, blob, .. } => {
+                    McpResourceContent::Blob { uri, mime_type, blob }
+                }
+            })
+            .collect())
+    }
+
+    async fn list_prompts(&self) -> anyhow::Result<Vec<McpPrompt>> {
+        let client = self.connect().await?;
+        let supported = client
+            .peer_info()
+            .is_some_and(|info| info.capabilities.prompts.is_some());
+        if !supported {
+            return Ok(Vec::new());
+        }
+
+        let prompts = client.list_all_prompts().await?;
+        Ok(prompts
+            .into_iter()
+            .map(|prompt| {
+                let arguments = prompt
+                    .arguments
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|argument| {
+                        let mut output = McpPromptArgument::new(argument.name);
+                        output.description = argument.description;
+                        output.required = argument.required.unwrap_or_default();
+                        output
+                    })
+                    .collect::<Vec<_>>();
+
+                let mut output = McpPrompt::new(prompt.name).arguments(arguments);
+                output.description = prompt.description;
+                output
+            })
+            .collect())
+    }
+
+    async fn get_prompt(
+        &self,
+        name: &str,
+        arguments: &BTreeMap<String, String>,
+    ) -> anyhow::Result<Vec<McpPromptMessage>> {
+        let client = self.connect().await?;
+        let result = client
+            .get_prompt(GetPromptRequestParam {
+                name: name.to_string(),
+                arguments: Some(
+                    arguments
+                        .iter()
+                        .map(|(key, value)| (key.clone(), Value::String(value.clone())))
+                        .collect(),
+                ),
+            })
+            .await?;
+
+        result
+            .messages
+            .into_iter()
+            .map(|message| {
+                let role = match message.role {
+                    PromptMessageRole::User => Role::User,
+                    PromptMessageRole::Assistant => Role::Assistant,
+                };
+                let text = match message.content {
+                    PromptMessageContent::Text { text } => text,
+                    PromptMessageContent::Resource { resource } => match resource.raw.resource {
+                        ResourceContents::TextResourceContents { text, .. } => text,
+                        ResourceContents::BlobResourceContents { .. } => {
+                            return Err(Error::UnsupportedMcpResponse("Blob").into());
+                        }
+                    },
+                    PromptMessageContent::ResourceLink { link } => link.raw.uri,
+                    PromptMessageContent::Image { .. } => {
+                        return Err(Error::UnsupportedMcpResponse("Image").into());
+                    }
+                };
+                Ok(McpPromptMessage::new(role, text))
+            })
+            .collect()
+    }
+
     async fn attempt_with_retry<T, F>(&self, call: impl Fn() -> F) -> anyhow::Result<T>
     where
         F: Future<Output = anyhow::Result<T>>,
@@ -250,6 +375,27 @@ impl McpClientInfra for PawsMcpClient {
         self.attempt_with_retry(|| self.call(tool_name, &input))
             .await
     }
+
+    async fn list_resources(&self) -> anyhow::Result<Vec<McpResource>> {
+        self.attempt_with_retry(|| self.list_resources()).await
+    }
+
+    async fn read_resource(&self, uri: &str) -> anyhow::Result<Vec<McpResourceContent>> {
+        self.attempt_with_retry(|| self.read_resource(uri)).await
+    }
+
+    async fn list_prompts(&self) -> anyhow::Result<Vec<McpPrompt>> {
+        self.attempt_with_retry(|| self.list_prompts()).await
+    }
+
+    async fn get_prompt(
+        &self,
+        name: &str,
+        arguments: BTreeMap<String, String>,
+    ) -> anyhow::Result<Vec<McpPromptMessage>> {
+        self.attempt_with_retry(|| self.get_prompt(name, &arguments))
+            .await
+    }
 }
 
 /// Resolves mustache templates in McpHttpServer headers using Handlebars
@@ -2,20 +2,20 @@ use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::process::ExitStatus;
 use std::sync::Arc;
+use std::time::Duration;
 
 use bytes::Bytes;
 use paws_app::{
-    CommandInfra, DirectoryReaderInfra, EnvironmentInfra, FileDirectoryInfra, FileInfoInfra,
-    FileReaderInfra, FileRemoverInfra, FileWriterInfra, HttpInfra, McpServerInfra, StrategyFactory,
-    UserInfra, WalkerInfra,
+    CommandInfra, DirectoryReaderInfra, EnvironmentInfra, EventStream, FileDirectoryInfra,
+    FileInfoInfra, FileReaderInfra, FileRemoverInfra, FileWriterInfra, HttpInfra, McpServerInfra,
+    StrategyFactory, UserInfra, WalkerInfra,
 };
 use paws_domain::{
-    AuthMethod, CommandOutput, Environment, FileInfo as FileInfoData, McpServerConfig, ProviderId,
-    URLParam,
+    AuthMethod, CommandOutput, ConversationId, Environment, FileInfo as FileInfoData, JobId,
+    JobOutput, McpServerConfig, ProviderId, URLParam,
 };
 use reqwest::header::HeaderMap;
 use reqwest::{Response, Url};
-use reqwest_eventsource::EventSource;
 
 use crate::auth::{AnyAuthStrategy, PawsAuthStrategyFactory};
 use crate::env::PawsEnvironmentInfra;
@@ -52,8 +52,8 @@ pub struct PawsInfra {
 }
 
 impl PawsInfra {
-    pub fn new(restricted: bool, cwd: PathBuf) -> Self {
-        let environment_service = Arc::new(PawsEnvironmentInfra::new(restricted, cwd));
+    pub fn new(restricted: bool, os_sandbox: bool, cwd: PathBuf) -> Self {
+        let environment_service = Arc::new(PawsEnvironmentInfra::new(restricted, os_sandbox, cwd));
         let env = environment_service.get_environment();
 
         let file_write_service = Arc::new(PawsFileWriteService::new());
@@ -81,6 +81,18 @@ impl PawsInfra {
             http_service,
         }
     }
+
+    /// Keeps shell commands off the console, see
+    /// [`PawsCommandExecutorService::headless`]
+    pub fn headless(mut self, headless: bool) -> Self {
+        self.command_executor_service = Arc::new(
+            self.command_executor_service
+                .as_ref()
+                .clone()
+                .headless(headless),
+        );
+        self
+    }
 }
 
 impl EnvironmentInfra for PawsInfra {
@@ -188,6 +200,60 @@ impl CommandInfra for PawsInfra {
             .execute_command_raw(command, working_dir, env_vars)
             .await
     }
+
+    async fn execute_command_with_input(
+        &self,
+        command: String,
+        working_dir: PathBuf,
+        input: String,
+    ) -> anyhow::Result<CommandOutput> {
+        self.command_executor_service
+            .execute_command_with_input(command, working_dir, input)
+            .await
+    }
+
+    async fn spawn_job(
+        &self,
+        conversation_id: ConversationId,
+        command: String,
+        working_dir: PathBuf,
+        env_vars: Option<Vec<String>>,
+    ) -> anyhow::Result<JobOutput> {
+        self.command_executor_service
+            .spawn_job(conversation_id, command, working_dir, env_vars)
+            .await
+    }
+
+    async fn read_job(
+        &self,
+        conversation_id: ConversationId,
+        job_id: JobId,
+    ) -> anyhow::Result<JobOutput> {
+        self.command_executor_service
+            .read_job(conversation_id, job_id)
+            .await
+    }
+
+    async fn wait_job(
+        &self,
+        conversation_id: ConversationId,
+        job_id: JobId,
+        timeout: Duration,
+    ) -> anyhow::Result<JobOutput> {
+        self.command_executor_service
+            .wait_job(conversation_id, job_id, timeout)
+            .await
+    }
+
+    async fn kill_job(
+        &self,
+        conversation_id: ConversationId,
+        job_id: JobId,
+    ) -> anyhow::Result<JobOutput> {
+        self.command_executor_service
+            .kill_job(conversation_id, job_id)
+            .await
+    }
 }
 
 #[async_trait::async_trait]
@@ -239,8 +305,13 @@ impl HttpInfra for PawsInfra {
         self.http_service.http_get(url, headers).await
     }
 
-    async fn http_post(&self, url: &Url, body: Bytes) -> anyhow::Result<Response> {
-        self.http_service.http_post(url, body).await
+    async fn http_post(
+        &self,
+        url: &Url,
+        headers: Option<HeaderMap>,
+        body: Bytes,
+    ) -> anyhow::Result<Response> {
+        self.http_service.http_post(url, headers, body).await
     }
 
     async fn http_delete(&self, url: &Url) -> anyhow::Result<Response> {
@@ -251,7 +322,7 @@ impl HttpInfra for PawsInfra {
         url: &Url,
         headers: Option<HeaderMap>,
         body: Bytes,
-    ) -> anyhow::Result<EventSource> {
+    ) -> anyhow::Result<EventStream> {
         self.http_service.http_eventsource(url, headers, body).await
     }
 }
@@ -0,0 +1,121 @@
+use std::path::Path;
+
+use paws_domain::SandboxPolicy;
+use tokio::process::Command;
+
+/// System directories commands can read and execute programs from
+#[cfg(target_os = "linux")]
+const SYSTEM_PATHS: &[&str] = &[
+    "/bin", "/sbin", "/usr", "/lib", "/lib32", "/lib64", "/etc", "/opt", "/proc", "/sys", "/run",
+    "/nix", "/snap",
+];
+
+/// Confines the command to the sandbox policy. Writes are limited to the
+/// workspace, the temp directory and the writable paths of the policy with
+/// Landlock, and sockets other than Unix sockets are refused with seccomp
+/// when the policy blocks the network. The restrictions are applied in the
+/// child right before it executes the shell, so paws itself is not confined.
+#[cfg(target_os = "linux")]
+pub fn confine(
+    command: &mut Command,
+    policy: &SandboxPolicy,
+    workspace: &Path,
+    shell: &str,
+) -> anyhow::Result<()> {
+    use std::path::PathBuf;
+
+    use anyhow::Context;
+    use landlock::{
+        ABI, Access, AccessFs, CompatLevel, Compatible, Ruleset, RulesetAttr, RulesetCreatedAttr,
+        path_beneath_rules,
+    };
+
+    let mut read_only: Vec<PathBuf> = SYSTEM_PATHS.iter().map(PathBuf::from).collect();
+    read_only.extend(Path::new(shell).parent().map(Path::to_path_buf));
+    if let Some(paths) = std::env::var_os("PATH") {
+        read_only.extend(std::env::split_paths(&paths).filter(|path| path.is_absolute()));
+    }
+    read_only.extend(policy.read_only.iter().cloned());
+
+    let mut writable = vec![
+        workspace.to_path_buf(),
+        std::env::temp_dir(),
+        PathBuf::from("/dev"),
+    ];
+    writable.extend(policy.writable.iter().cloned());
+
+    // Fail instead of running unconfined commands on kernels without Landlock
+    let ruleset = Ruleset::default()
+        .set_compatibility(CompatLevel::HardRequirement)
+        .handle_access(AccessFs::from_all(ABI::V1))
+        .context("Landlock is not supported by this kernel")?
+        .set_compatibility(CompatLevel::BestEffort)
+        .handle_access(AccessFs::from_all(ABI::V5))?
+        .create()?
+        .add_rules(path_beneath_rules(&read_only, AccessFs::from_read(ABI::V5)))?
+        .add_rules(path_beneath_rules(&writable, AccessFs::from_all(ABI::V5)))?;
+    let filter = if policy.network {
+        None
+    } else {
+        Some(network_filter()?)
+    };
+
+    let mut ruleset = Some(ruleset);
+    // SAFETY: The closure runs between fork and exec, where it only makes
+    // system calls and does not allocate.
+    unsafe {
+        command.pre_exec(move || {
+            let denied = || std::io::Error::from_raw_os_error(libc::EPERM);
+            if let Some(ruleset) = ruleset.take() {
+                ruleset.restrict_self().map_err(|_| denied())?;
+            }
+            if let Some(filter) = &filter {
+                seccompiler::apply_filter(filter).map_err(|_| denied())?;
+            }
+            Ok(())
+        });
+    }
+
+    Ok(())
+}
+
+/// Builds a seccomp filter failing the creation of sockets other than Unix
+/// sockets with `EPERM`
+#[cfg(target_os = "linux")]
+fn network_filter() -> anyhow::Result<seccompiler::BpfProgram> {
+    use std::collections::BTreeMap;
+
+    use seccompiler::{
+        SeccompAction, SeccompCmpArgLen, SeccompCmpOp, SeccompCondition, SeccompFilter, SeccompRule,
+    };
+
+    let not_unix = SeccompCondition::new(
+        0,
+        SeccompCmpArgLen::Dword,
+        SeccompCmpOp::Ne,
+        libc::AF_UNIX as u64,
+    )?;
+    let rules = BTreeMap::from([
+        (libc::SYS_socket, vec![SeccompRule::new(vec![not_unix])?]),
+        // io_uring can open sockets without going through `socket`
+        (libc::SYS_io_uring_setup, vec![]),
+    ]);
+    let filter = SeccompFilter::new(
+        rules,
+        SeccompAction::Allow,
+        SeccompAction::Errno(libc::EPERM as u32),
+        std::env::consts::ARCH.try_into()?,
+    )?;
+
+    Ok(filter.try_into()?)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn confine(
+    _command: &mut Command,
+    _policy: &SandboxPolicy,
+    _workspace: &Path,
+    _shell: &str,
+) -> anyhow::Result<()> {
+    anyhow::bail!("The OS-level sandbox is only supported on Linux")
+}
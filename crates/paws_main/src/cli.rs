@@ -5,11 +5,14 @@
 //! remains compatible. The plugin at `shell-plugin/paws.plugin.zsh` implements
 //! shell completion and command shortcuts that depend on the CLI structure.
 
+use std::net::SocketAddr;
 use std::path::PathBuf;
 
 use clap::{Parser, Subcommand, ValueEnum};
 use paws_domain::{AgentId, ConversationId, ProviderId};
 
+use crate::follow_up::FollowUpPolicy;
+
 #[derive(Parser)]
 #[command(version = env!("CARGO_PKG_VERSION"))]
 pub struct Cli {
@@ -21,6 +24,22 @@ pub struct Cli {
     #[arg(long, short = 'p', allow_hyphen_values = true)]
     pub prompt: Option<String>,
 
+    /// Output format of non-interactive runs.
+    ///
+    /// `stream-json` prints every event as a line of JSON as it happens and
+    /// `json` prints a single JSON document once the task ends. Both keep
+    /// stdout free of anything else and set the exit code from the reason the
+    /// task stopped.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub output_format: OutputFormat,
+
+    /// How to answer the agent's follow-up questions: `prompt`, `first` to
+    /// pick the first option, `fail` to end the run, `-` to read one JSON
+    /// answer per line from stdin, or the path to a JSON array of answers.
+    /// Defaults to `prompt` in a terminal and `fail` otherwise.
+    #[arg(long, value_name = "POLICY")]
+    pub follow_up: Option<FollowUpPolicy>,
+
     /// Piped input from stdin (populated internally)
     ///
     /// This field is automatically populated when content is piped to paws
@@ -58,6 +77,11 @@ pub struct Cli {
     #[arg(long, default_value_t = false, short = 'r')]
     pub restricted: bool,
 
+    /// Confine shell commands to the workspace with an OS-level sandbox
+    /// (Linux only).
+    #[arg(long, default_value_t = false)]
+    pub os_sandbox: bool,
+
     /// Agent ID to use for this session.
     #[arg(long, alias = "aid")]
     pub agent: Option<AgentId>,
@@ -83,6 +107,35 @@ impl Cli {
     pub fn is_interactive(&self) -> bool {
         self.prompt.is_none() && self.piped_input.is_none() && self.subcommands.is_none()
     }
+
+    /// Checks if stdin may be consumed as the prompt.
+    ///
+    /// Returns false for `paws mcp serve`, where stdin carries the MCP
+    /// protocol instead, for `paws serve`, which takes its prompts over HTTP,
+    /// and when follow-up answers are read from stdin.
+    pub fn accepts_piped_input(&self) -> bool {
+        self.follow_up != Some(FollowUpPolicy::Stdin)
+            && !matches!(
+                &self.subcommands,
+                Some(TopLevelCommand::Mcp(McpCommandGroup {
+                    command: McpCommand::Serve(_),
+                    ..
+                })) | Some(TopLevelCommand::Serve(_))
+            )
+    }
+
+    /// Whether stdin and stdout carry a protocol or machine-readable output,
+    /// which shell commands must not read from or write to
+    pub fn is_headless(&self) -> bool {
+        self.output_format != OutputFormat::Text
+            || matches!(
+                &self.subcommands,
+                Some(TopLevelCommand::Mcp(McpCommandGroup {
+                    command: McpCommand::Serve(_),
+                    ..
+                }))
+            )
+    }
 }
 
 #[derive(Subcommand, Debug, Clone)]
@@ -121,9 +174,30 @@ pub enum TopLevelCommand {
     #[command(alias = "session")]
     Conversation(ConversationCommandGroup),
 
+    /// Report token usage and cost per day, model, provider and workspace.
+    Usage {
+        /// Number of days to report, today included.
+        #[arg(long, default_value_t = 30, value_parser = clap::value_parser!(u32).range(1..))]
+        days: u32,
+
+        /// Output in machine-readable format.
+        #[arg(long)]
+        porcelain: bool,
+    },
+
+    /// Browse and restore the history of files modified by the agent.
+    #[command(alias = "snap")]
+    Snapshot(SnapshotCommandGroup),
+
+    /// Manage the semantic search index of the workspace.
+    Index(IndexCommandGroup),
+
     /// Manage Model Context Protocol servers.
     Mcp(McpCommandGroup),
 
+    /// Serve the API over HTTP for web and editor front-ends.
+    Serve(ServeArgs),
+
     /// Suggest shell commands from natural language.
     Suggest {
         /// Natural language description of the desired command.
@@ -286,6 +360,39 @@ pub enum McpCommand {
 
     /// Reload servers and rebuild caches.
     Reload,
+
+    /// Serve paws tools and agents to other MCP clients.
+    Serve(McpServeArgs),
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct McpServeArgs {
+    /// Serve over streamable HTTP instead of stdio, on 127.0.0.1:7879 unless
+    /// another address is given. Only local clients are accepted.
+    #[arg(
+        long,
+        value_name = "ADDR",
+        num_args = 0..=1,
+        default_missing_value = "127.0.0.1:7879"
+    )]
+    pub http: Option<SocketAddr>,
+
+    /// Bearer token HTTP clients must send, a random one is generated when
+    /// omitted.
+    #[arg(long, requires = "http")]
+    pub token: Option<String>,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct ServeArgs {
+    /// Address to listen on.
+    #[arg(long, value_name = "ADDR", default_value = "127.0.0.1:7878")]
+    pub addr: SocketAddr,
+
+    /// Bearer token clients must send, a random one is generated when
+    /// omitted.
+    #[arg(long)]
+    pub token: Option<String>,
 }
 
 #[derive(Parser, Debug, Clone)]
@@ -315,6 +422,19 @@ pub struct McpShowArgs {
     pub name: String,
 }
 
+/// Output format of non-interactive runs.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+#[clap(rename_all = "kebab-case")]
+pub enum OutputFormat {
+    /// Rendered markdown for humans.
+    #[default]
+    Text,
+    /// A single JSON document with every event and the final usage.
+    Json,
+    /// Newline-delimited JSON events, ending with the final usage.
+    StreamJson,
+}
+
 /// Configuration scope for settings.
 #[derive(Copy, Clone, Debug, ValueEnum, Default)]
 pub enum Scope {
@@ -407,6 +527,16 @@ pub enum ConversationCommand {
         porcelain: bool,
     },
 
+    /// Search conversation titles and messages.
+    Search {
+        /// Words to search for, matched as prefixes.
+        query: String,
+
+        /// Output in machine-readable format.
+        #[arg(long)]
+        porcelain: bool,
+    },
+
     /// Print conversation history.
     #[command(alias = "pr")]
     Print {
@@ -433,6 +563,16 @@ pub enum ConversationCommand {
         id: ConversationId,
     },
 
+    /// Rewind conversation and modified files to an earlier turn.
+    Rewind {
+        /// Conversation ID to rewind.
+        id: ConversationId,
+
+        /// Turn to rewind to. Defaults to the turn before the last one.
+        #[arg(long)]
+        to: Option<usize>,
+    },
+
     /// Retry last command without modifying context.
     Retry {
         /// Conversation ID to retry.
@@ -484,6 +624,75 @@ pub enum ConversationCommand {
     },
 }
 
+/// Command group for file snapshot management.
+#[derive(Parser, Debug, Clone)]
+pub struct SnapshotCommandGroup {
+    #[command(subcommand)]
+    pub command: SnapshotCommand,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum SnapshotCommand {
+    /// List the snapshots of a file, most recent first.
+    List {
+        /// File whose snapshots are listed.
+        path: PathBuf,
+
+        /// Output in machine-readable format.
+        #[arg(long)]
+        porcelain: bool,
+    },
+
+    /// Show the changes between a snapshot and the current file.
+    Show {
+        /// File whose snapshot is shown.
+        path: PathBuf,
+
+        /// Snapshot version as numbered by `list`, 1 being the most recent.
+        #[arg(long, default_value_t = 1)]
+        version: usize,
+    },
+
+    /// Restore a file to one of its snapshots.
+    Restore {
+        /// File to restore.
+        path: PathBuf,
+
+        /// Snapshot version as numbered by `list`, 1 being the most recent.
+        #[arg(long, default_value_t = 1)]
+        version: usize,
+    },
+
+    /// Revert the most recent undo of a file.
+    Redo {
+        /// File to redo.
+        path: PathBuf,
+    },
+}
+
+/// Command group for semantic search index management.
+#[derive(Parser, Debug, Clone)]
+pub struct IndexCommandGroup {
+    #[command(subcommand)]
+    pub command: IndexCommand,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum IndexCommand {
+    /// Index the files that changed since the last build.
+    Build,
+
+    /// Show the state of the index and the files pending indexing.
+    Status {
+        /// Output in machine-readable format.
+        #[arg(long)]
+        porcelain: bool,
+    },
+
+    /// Delete the index of the workspace.
+    Clear,
+}
+
 /// Command group for provider authentication management.
 #[derive(Parser, Debug, Clone)]
 pub struct ProviderCommandGroup {
@@ -587,6 +796,16 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn test_os_sandbox_flag() {
+        let fixture = ["paws", "--os-sandbox", "--restricted"];
+
+        let actual = Cli::parse_from(fixture);
+
+        assert_eq!((actual.os_sandbox, actual.restricted), (true, true));
+        assert_eq!(Cli::parse_from(["paws"]).os_sandbox, false);
+    }
+
     #[test]
     fn test_config_set_with_model() {
         let fixture = Cli::parse_from([
@@ -819,6 +1038,93 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_conversation_rewind_with_turn() {
+        let fixture = Cli::parse_from([
+            "paws",
+            "conversation",
+            "rewind",
+            "550e8400-e29b-41d4-a716-446655440003",
+            "--to",
+            "2",
+        ]);
+        let actual = match fixture.subcommands {
+            Some(TopLevelCommand::Conversation(conversation)) => match conversation.command {
+                ConversationCommand::Rewind { id, to } => Some((id, to)),
+                _ => None,
+            },
+            _ => None,
+        };
+        let expected = Some((
+            ConversationId::parse("550e8400-e29b-41d4-a716-446655440003").unwrap(),
+            Some(2),
+        ));
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_snapshot_list_with_porcelain() {
+        let fixture = Cli::parse_from(["paws", "snapshot", "list", "src/main.rs", "--porcelain"]);
+        let actual = match fixture.subcommands {
+            Some(TopLevelCommand::Snapshot(snapshot)) => match snapshot.command {
+                SnapshotCommand::List { path, porcelain } => Some((path, porcelain)),
+                _ => None,
+            },
+            _ => None,
+        };
+        let expected = Some((PathBuf::from("src/main.rs"), true));
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_index_status_with_porcelain() {
+        let fixture = Cli::parse_from(["paws", "index", "status", "--porcelain"]);
+        let actual = match fixture.subcommands {
+            Some(TopLevelCommand::Index(index)) => match index.command {
+                IndexCommand::Status { porcelain } => Some(porcelain),
+                _ => None,
+            },
+            _ => None,
+        };
+        let expected = Some(true);
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_snapshot_show_defaults_to_latest_version() {
+        let fixture = Cli::parse_from(["paws", "snapshot", "show", "src/main.rs"]);
+        let actual = match fixture.subcommands {
+            Some(TopLevelCommand::Snapshot(snapshot)) => match snapshot.command {
+                SnapshotCommand::Show { version, .. } => Some(version),
+                _ => None,
+            },
+            _ => None,
+        };
+        let expected = Some(1);
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_snapshot_restore_with_version() {
+        let fixture = Cli::parse_from([
+            "paws",
+            "snapshot",
+            "restore",
+            "src/main.rs",
+            "--version",
+            "3",
+        ]);
+        let actual = match fixture.subcommands {
+            Some(TopLevelCommand::Snapshot(snapshot)) => match snapshot.command {
+                SnapshotCommand::Restore { path, version } => Some((path, version)),
+                _ => None,
+            },
+            _ => None,
+        };
+        let expected = Some((PathBuf::from("src/main.rs"), 3));
+        assert_eq!(actual, expected);
+    }
+
     #[test]
     fn test_conversation_last_with_id() {
         let fixture = Cli::parse_from([
@@ -1004,6 +1310,121 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn test_mcp_serve_with_http() {
+        let fixture = Cli::parse_from(["paws", "mcp", "serve", "--http", "127.0.0.1:8080"]);
+        let actual = match fixture.subcommands {
+            Some(TopLevelCommand::Mcp(McpCommandGroup {
+                command: McpCommand::Serve(args),
+                ..
+            })) => args.http,
+            _ => None,
+        };
+        let expected = Some("127.0.0.1:8080".parse().unwrap());
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_mcp_serve_with_http_defaults_to_loopback() {
+        let fixture = Cli::parse_from(["paws", "mcp", "serve", "--http", "--token", "secret"]);
+        let actual = match fixture.subcommands {
+            Some(TopLevelCommand::Mcp(McpCommandGroup {
+                command: McpCommand::Serve(args),
+                ..
+            })) => Some((args.http, args.token)),
+            _ => None,
+        };
+        let expected = Some((
+            Some("127.0.0.1:7879".parse().unwrap()),
+            Some("secret".to_string()),
+        ));
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_mcp_serve_is_headless() {
+        let fixture = Cli::parse_from(["paws", "mcp", "serve"]);
+        let actual = (
+            fixture.is_headless(),
+            Cli::parse_from(["paws"]).is_headless(),
+        );
+        let expected = (true, false);
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_mcp_serve_does_not_accept_piped_input() {
+        let fixture = Cli::parse_from(["paws", "mcp", "serve"]);
+        let actual = fixture.accepts_piped_input();
+        let expected = false;
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_follow_up_from_stdin_does_not_accept_piped_input() {
+        let fixture = Cli::parse_from(["paws", "-p", "plan it", "--follow-up", "-"]);
+        let actual = (fixture.follow_up.clone(), fixture.accepts_piped_input());
+        let expected = (Some(FollowUpPolicy::Stdin), false);
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_serve_args() {
+        let fixture = Cli::parse_from(["paws", "serve", "--token", "secret"]);
+        let actual = match fixture.subcommands {
+            Some(TopLevelCommand::Serve(args)) => Some((args.addr, args.token)),
+            _ => None,
+        };
+        let expected = Some((
+            "127.0.0.1:7878".parse().unwrap(),
+            Some("secret".to_string()),
+        ));
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_usage_command() {
+        let actual = [
+            Cli::parse_from(["paws", "usage"]),
+            Cli::parse_from(["paws", "usage", "--days", "7", "--porcelain"]),
+        ]
+        .map(|fixture| match fixture.subcommands {
+            Some(TopLevelCommand::Usage { days, porcelain }) => Some((days, porcelain)),
+            _ => None,
+        });
+        let expected = [Some((30, false)), Some((7, true))];
+        assert_eq!(actual, expected);
+        assert!(Cli::try_parse_from(["paws", "usage", "--days", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_serve_does_not_accept_piped_input() {
+        let fixture = Cli::parse_from(["paws", "serve"]);
+        let actual = fixture.accepts_piped_input();
+        let expected = false;
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_conversation_search_with_porcelain() {
+        let fixture = Cli::parse_from([
+            "paws",
+            "conversation",
+            "search",
+            "fix migration",
+            "--porcelain",
+        ]);
+        let actual = match fixture.subcommands {
+            Some(TopLevelCommand::Conversation(conversation)) => match conversation.command {
+                ConversationCommand::Search { query, porcelain } => Some((query, porcelain)),
+                _ => None,
+            },
+            _ => None,
+        };
+        let expected = Some(("fix migration".to_string(), true));
+        assert_eq!(actual, expected);
+    }
+
     #[test]
     fn test_conversation_list_with_porcelain() {
         let fixture = Cli::parse_from(["paws", "conversation", "list", "--porcelain"]);
@@ -1284,4 +1705,21 @@ mod tests {
         let fixture = Cli::parse_from(["paws", "-p", "--something"]);
         assert_eq!(fixture.prompt, Some("--something".to_string()));
     }
+
+    #[test]
+    fn test_output_format() {
+        let fixture = Cli::parse_from(["paws", "-p", "hi", "--output-format", "stream-json"]);
+        assert_eq!(fixture.output_format, OutputFormat::StreamJson);
+
+        let fixture = Cli::parse_from(["paws", "-p", "hi"]);
+        assert_eq!(fixture.output_format, OutputFormat::Text);
+    }
+
+    #[test]
+    fn test_json_output_is_headless() {
+        let fixture = Cli::parse_from(["paws", "-p", "hi", "--output-format", "json"]);
+        let actual = fixture.is_headless();
+        let expected = true;
+        assert_eq!(actual, expected);
+    }
 }
@@ -13,6 +13,7 @@ use crate::model::PawsCommandManager;
 pub struct InputCompleter {
     walker: Walker,
     command: CommandCompleter,
+    command_manager: Arc<PawsCommandManager>,
     fuzzy_matcher: Matcher,
 }
 
@@ -21,7 +22,8 @@ impl InputCompleter {
         let walker = Walker::max_all().cwd(cwd).skip_binary(true);
         Self {
             walker,
-            command: CommandCompleter::new(command_manager),
+            command: CommandCompleter::new(command_manager.clone()),
+            command_manager,
             fuzzy_matcher: Matcher::new(Config::DEFAULT.match_paths()),
         }
     }
@@ -70,6 +72,25 @@ impl Completer for InputCompleter {
                 })
                 .collect();
 
+            // Resources exposed by MCP servers are attached by their URI
+            for resource in self.command_manager.mcp_resources() {
+                let mut haystack_buf = Vec::new();
+                let haystack = Utf32Str::new(&resource.uri, &mut haystack_buf);
+                if let Some(score) = pattern.score(haystack, &mut self.fuzzy_matcher) {
+                    scored_matches.push((
+                        score,
+                        Suggestion {
+                            description: Some(resource.name),
+                            value: format!("[{}]", resource.uri),
+                            style: None,
+                            extra: None,
+                            span: query.span,
+                            append_whitespace: true,
+                        },
+                    ));
+                }
+            }
+
             // Sort by fuzzy match score (higher is better)
             scored_matches.sort_by(|a, b| b.0.cmp(&a.0));
 
@@ -362,4 +383,35 @@ mod tests {
             "Should find li'b with literal matching"
         );
     }
+
+    #[test]
+    fn test_mcp_resource_completion() {
+        let temp_dir = TempDir::new().unwrap();
+        let command_manager = Arc::new(PawsCommandManager::default());
+        let fixture = paws_api::McpServers::default().resources(
+            [(
+                paws_api::ServerName::from("docs".to_string()),
+                vec![paws_api::McpResource::new(
+                    "file:///docs/readme.md",
+                    "Readme",
+                )],
+            )]
+            .into_iter()
+            .collect::<std::collections::HashMap<_, _>>(),
+        );
+        command_manager.register_mcp(&fixture);
+        let mut completer = InputCompleter::new(temp_dir.path().to_path_buf(), command_manager);
+
+        let actual = completer
+            .complete("@readme", 7)
+            .into_iter()
+            .map(|suggestion| (suggestion.value, suggestion.description))
+            .collect::<Vec<_>>();
+
+        let expected = vec![(
+            "[file:///docs/readme.md]".to_string(),
+            Some("Readme".to_string()),
+        )];
+        assert_eq!(actual, expected);
+    }
 }
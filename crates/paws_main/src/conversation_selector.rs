@@ -1,93 +1,192 @@
 use std::fmt::Display;
+use std::future::Future;
 
 use anyhow::Result;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use colored::Colorize;
-use paws_api::Conversation;
+use paws_api::{Conversation, ConversationMatch};
 use paws_common::select::PawsSelect;
 
 use crate::display_constants::markers;
 
+/// Entry of the selector that switches to searching the message text
+const SEARCH_ENTRY: &str = "🔍 Search messages...";
+
+#[derive(Clone)]
+enum SelectorItem {
+    Conversation(String, Box<Conversation>),
+    Search,
+}
+
+impl Display for SelectorItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SelectorItem::Conversation(display, _) => display.fmt(f),
+            SelectorItem::Search => SEARCH_ENTRY.fmt(f),
+        }
+    }
+}
+
 /// Logic for selecting conversations from a list
 pub struct ConversationSelector;
 
 impl ConversationSelector {
-    /// Select a conversation from the provided list
+    /// Select a conversation from the provided list, or search the message
+    /// text of all conversations with `search` and select from its matches
     ///
     /// Returns the selected conversation ID, or None if no selection was made
-    pub async fn select_conversation(
+    pub async fn select_conversation<S, Fut>(
         conversations: &[Conversation],
-    ) -> Result<Option<Conversation>> {
+        search: S,
+    ) -> Result<Option<Conversation>>
+    where
+        S: FnOnce(String) -> Fut,
+        Fut: Future<Output = Result<Vec<ConversationMatch>>>,
+    {
         if conversations.is_empty() {
             return Ok(None);
         }
 
         // Select conversations that have some title
-        let conversation_iter = conversations.iter().filter(|c| c.title.is_some());
+        let conversations = conversations
+            .iter()
+            .filter(|c| c.title.is_some())
+            .map(|c| (c.clone(), None))
+            .collect::<Vec<_>>();
+        let mut items = Self::items(conversations);
+        items.push(SelectorItem::Search);
 
-        // First, calculate all formatted dates to find the maximum length
-        let now = Utc::now();
-        let dates = conversation_iter.clone().map(|c| {
-            let date = c.metadata.updated_at.unwrap_or(c.metadata.created_at);
-            let duration = now.signed_duration_since(date);
-            let duration =
-                std::time::Duration::from_secs((duration.num_minutes() * 60).max(0) as u64);
-            if duration.is_zero() {
-                "now".to_string()
-            } else {
-                let duration = humantime::format_duration(duration);
-                format!("{duration} ago")
-            }
-        });
-
-        let titles = conversation_iter.clone().map(|c| {
-            c.title
-                .as_ref()
-                .map(|title| {
-                    const MAX_TITLE: usize = 57;
-                    if title.len() > MAX_TITLE {
-                        format!("{}...", title.chars().take(MAX_TITLE).collect::<String>())
-                    } else {
-                        title.to_owned()
-                    }
-                })
-                .unwrap_or_else(|| format!("{} [{}]", markers::EMPTY, c.id).to_string())
-        });
-
-        let max_title_length: usize = titles.clone().map(|s| s.len()).max().unwrap_or(0);
-
-        #[derive(Clone)]
-        struct ConversationItem((String, Conversation));
-        impl Display for ConversationItem {
-            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                self.0.0.fmt(f)
-            }
+        match Self::select(
+            "Select the conversation to resume:",
+            "Type a name or use arrow keys to navigate and Enter to select",
+            items,
+        )
+        .await?
+        {
+            Some(SelectorItem::Conversation(_, conversation)) => Ok(Some(*conversation)),
+            Some(SelectorItem::Search) => Self::search_conversation(search).await,
+            None => Ok(None),
         }
+    }
 
-        let conversations = dates
-            .zip(titles)
-            .map(|(date, title)| format!("{:<max_title_length$} {}", title.bold(), date.dimmed()))
-            .zip(conversation_iter.cloned())
-            .map(ConversationItem)
-            .collect::<Vec<_>>();
-
-        if let Some(selected) = tokio::task::spawn_blocking(|| {
-            PawsSelect::select("Select the conversation to resume:", conversations)
-                .with_help_message("Type a name or use arrow keys to navigate and Enter to select")
+    /// Prompts for a query and selects one of the conversations whose title or
+    /// messages match it
+    async fn search_conversation<S, Fut>(search: S) -> Result<Option<Conversation>>
+    where
+        S: FnOnce(String) -> Fut,
+        Fut: Future<Output = Result<Vec<ConversationMatch>>>,
+    {
+        let query = tokio::task::spawn_blocking(|| {
+            PawsSelect::input("Search messages:")
+                .allow_empty(true)
                 .prompt()
         })
-        .await??
+        .await??;
+        let Some(query) = query.filter(|query| !query.trim().is_empty()) else {
+            return Ok(None);
+        };
+
+        let matches = search(query.clone()).await?;
+        if matches.is_empty() {
+            anyhow::bail!("No conversations match '{query}'");
+        }
+
+        let matches = matches
+            .into_iter()
+            .map(|found| (found.conversation, Some(found.snippet)))
+            .collect();
+        match Self::select(
+            "Select the conversation to resume:",
+            "Type to narrow the matches or use arrow keys to navigate and Enter to select",
+            Self::items(matches),
+        )
+        .await?
         {
-            Ok(Some(selected.0.1))
+            Some(SelectorItem::Conversation(_, conversation)) => Ok(Some(*conversation)),
+            _ => Ok(None),
+        }
+    }
+
+    async fn select(
+        message: &'static str,
+        help: &'static str,
+        items: Vec<SelectorItem>,
+    ) -> Result<Option<SelectorItem>> {
+        tokio::task::spawn_blocking(move || {
+            PawsSelect::select(message, items)
+                .with_help_message(help)
+                .prompt()
+        })
+        .await?
+    }
+
+    /// Formats each conversation as its title, the optional snippet and the
+    /// time of its last update, with the titles aligned
+    fn items(conversations: Vec<(Conversation, Option<String>)>) -> Vec<SelectorItem> {
+        // First, calculate all formatted dates to find the maximum length
+        let now = Utc::now();
+        let dates = conversations.iter().map(|(c, _)| Self::time_ago(c, now));
+
+        let titles = conversations
+            .iter()
+            .map(|(c, _)| {
+                c.title
+                    .as_ref()
+                    .map(|title| truncate(title, 57))
+                    .unwrap_or_else(|| format!("{} [{}]", markers::EMPTY, c.id).to_string())
+            })
+            .collect::<Vec<_>>();
+
+        let max_title_length: usize = titles.iter().map(|s| s.len()).max().unwrap_or(0);
+
+        dates
+            .zip(titles)
+            .zip(conversations.iter())
+            .map(|((date, title), (conversation, snippet))| {
+                let display = match snippet {
+                    Some(snippet) => format!(
+                        "{:<max_title_length$} {} {}",
+                        title.bold(),
+                        truncate(&snippet.replace('\n', " "), 60),
+                        date.dimmed()
+                    ),
+                    None => format!("{:<max_title_length$} {}", title.bold(), date.dimmed()),
+                };
+                SelectorItem::Conversation(display, Box::new(conversation.clone()))
+            })
+            .collect()
+    }
+
+    /// Describes when the conversation was last updated, to the minute, e.g.
+    /// `1h 5m ago`
+    pub fn time_ago(conversation: &Conversation, now: DateTime<Utc>) -> String {
+        let date = conversation
+            .metadata
+            .updated_at
+            .unwrap_or(conversation.metadata.created_at);
+        let duration = now.signed_duration_since(date);
+        let duration = std::time::Duration::from_secs((duration.num_minutes() * 60).max(0) as u64);
+        if duration.is_zero() {
+            "now".to_string()
         } else {
-            Ok(None)
+            let duration = humantime::format_duration(duration);
+            format!("{duration} ago")
         }
     }
 }
 
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() > max {
+        format!("{}...", text.chars().take(max).collect::<String>())
+    } else {
+        text.to_owned()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use chrono::Utc;
+    use console::strip_ansi_codes;
     use paws_api::Conversation;
     use paws_domain::{ConversationId, MetaData, Metrics};
     use pretty_assertions::assert_eq;
@@ -108,9 +207,10 @@ mod tests {
     #[tokio::test]
     async fn test_select_conversation_empty_list() {
         let conversations = vec![];
-        let result = ConversationSelector::select_conversation(&conversations)
-            .await
-            .unwrap();
+        let result =
+            ConversationSelector::select_conversation(&conversations, |_| async { Ok(vec![]) })
+                .await
+                .unwrap();
         assert!(result.is_none());
     }
 
@@ -141,4 +241,32 @@ mod tests {
 
         assert_eq!(conversations.len(), 2);
     }
+
+    #[test]
+    fn test_items_include_search_snippet() {
+        let fixture = vec![(
+            create_test_conversation("550e8400-e29b-41d4-a716-446655440004", Some("Migration")),
+            Some("the migration\nfailed".to_string()),
+        )];
+
+        let actual = ConversationSelector::items(fixture)
+            .iter()
+            .map(|item| strip_ansi_codes(&item.to_string()).to_string())
+            .collect::<Vec<_>>();
+
+        let expected = vec!["Migration the migration failed now".to_string()];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_time_ago() {
+        let fixture = create_test_conversation("550e8400-e29b-41d4-a716-446655440005", None);
+        let updated_at = fixture.metadata.updated_at.unwrap();
+
+        let actual = [chrono::Duration::seconds(30), chrono::Duration::minutes(65)]
+            .map(|elapsed| ConversationSelector::time_ago(&fixture, updated_at + elapsed));
+
+        let expected = ["now".to_string(), "1h 5m ago".to_string()];
+        assert_eq!(actual, expected);
+    }
 }
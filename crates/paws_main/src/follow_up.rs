@@ -0,0 +1,231 @@
+use std::collections::VecDeque;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::Context;
+use paws_common::select::PawsSelect;
+use paws_domain::{FollowUpAnswer, FollowUpQuestion};
+use tokio::io::{AsyncBufReadExt, BufReader, Lines, Stdin};
+
+/// How the follow-up questions of the agent are answered
+#[derive(Debug, Clone, PartialEq)]
+pub enum FollowUpPolicy {
+    /// Asks in the terminal
+    Prompt,
+    /// Picks the first option, dismissing free-form questions
+    First,
+    /// Ends the run with an error
+    Fail,
+    /// Reads one JSON answer per line from stdin as each question is asked
+    Stdin,
+    /// Takes the answers in order from a JSON array in the file
+    File(PathBuf),
+}
+
+impl FollowUpPolicy {
+    /// Prompts when a terminal is attached and fails otherwise, so runs
+    /// without one never wait for an answer
+    pub fn detect() -> Self {
+        if std::io::stdin().is_terminal() {
+            Self::Prompt
+        } else {
+            Self::Fail
+        }
+    }
+}
+
+impl FromStr for FollowUpPolicy {
+    type Err = String;
+
+    /// Anything other than a policy name must be an existing file, so a
+    /// misspelled policy is rejected instead of being read as a path
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "prompt" => Self::Prompt,
+            "first" => Self::First,
+            "fail" => Self::Fail,
+            "-" => Self::Stdin,
+            path if Path::new(path).is_file() => Self::File(PathBuf::from(path)),
+            other => {
+                return Err(format!(
+                    "unknown policy `{other}`, expected `prompt`, `first`, `fail`, `-` or the path to an existing answers file"
+                ));
+            }
+        })
+    }
+}
+
+/// Answers follow-up questions according to a [`FollowUpPolicy`]
+pub struct FollowUpAnswers {
+    policy: FollowUpPolicy,
+    /// Answers read from the file, loaded on the first question
+    queued: Option<VecDeque<FollowUpAnswer>>,
+    stdin: Option<Lines<BufReader<Stdin>>>,
+}
+
+impl FollowUpAnswers {
+    pub fn new(policy: FollowUpPolicy) -> Self {
+        Self { policy, queued: None, stdin: None }
+    }
+
+    /// Returns the answer, `None` when the question is dismissed
+    pub async fn answer(
+        &mut self,
+        question: &FollowUpQuestion,
+    ) -> anyhow::Result<Option<FollowUpAnswer>> {
+        match &self.policy {
+            FollowUpPolicy::Prompt => prompt(question),
+            FollowUpPolicy::First => Ok(first(question)),
+            FollowUpPolicy::Fail => anyhow::bail!(
+                "The agent asked a follow-up question: {}\nPass --follow-up to answer it",
+                question.question
+            ),
+            FollowUpPolicy::Stdin => {
+                let stdin = self
+                    .stdin
+                    .get_or_insert_with(|| BufReader::new(tokio::io::stdin()).lines());
+                // The question is dismissed when stdin is closed
+                let Some(line) = stdin.next_line().await? else {
+                    return Ok(None);
+                };
+                serde_json::from_str(&line)
+                    .with_context(|| format!("Invalid follow-up answer on stdin: {line}"))
+            }
+            FollowUpPolicy::File(path) => {
+                if self.queued.is_none() {
+                    let content = tokio::fs::read_to_string(path).await.with_context(|| {
+                        format!("Failed to read follow-up answers from {}", path.display())
+                    })?;
+                    let answers: VecDeque<FollowUpAnswer> = serde_json::from_str(&content)
+                        .with_context(|| {
+                            format!("{} must contain a JSON array of answers", path.display())
+                        })?;
+                    self.queued = Some(answers);
+                }
+                let answer = self.queued.as_mut().and_then(VecDeque::pop_front);
+                answer.map(Some).with_context(|| {
+                    format!(
+                        "No answer left in {} for the follow-up question: {}",
+                        path.display(),
+                        question.question
+                    )
+                })
+            }
+        }
+    }
+}
+
+fn first(question: &FollowUpQuestion) -> Option<FollowUpAnswer> {
+    question
+        .options
+        .first()
+        .map(|option| FollowUpAnswer::Selected(vec![option.clone()]))
+}
+
+fn prompt(question: &FollowUpQuestion) -> anyhow::Result<Option<FollowUpAnswer>> {
+    let message = question.question.as_str();
+    let options = question.options.clone();
+    Ok(match (options.is_empty(), question.multiple) {
+        (true, _) => PawsSelect::input(message)
+            .allow_empty(true)
+            .prompt()?
+            .map(FollowUpAnswer::Text),
+        (false, true) => PawsSelect::multi_select(message, options)
+            .prompt()?
+            .map(FollowUpAnswer::Selected),
+        (false, false) => PawsSelect::select_owned(message, options)
+            .prompt()?
+            .map(|selected| FollowUpAnswer::Selected(vec![selected])),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn question() -> FollowUpQuestion {
+        FollowUpQuestion {
+            options: vec!["Postgres".to_string(), "SQLite".to_string()],
+            ..FollowUpQuestion::new("Which database?")
+        }
+    }
+
+    #[test]
+    fn test_policy_from_str() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("answers.json");
+        std::fs::write(&path, "[]").unwrap();
+
+        let actual = ["prompt", "first", "fail", "-", path.to_str().unwrap()]
+            .map(|value| FollowUpPolicy::from_str(value).unwrap());
+        let expected = [
+            FollowUpPolicy::Prompt,
+            FollowUpPolicy::First,
+            FollowUpPolicy::Fail,
+            FollowUpPolicy::Stdin,
+            FollowUpPolicy::File(path),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_policy_from_str_rejects_unknown_value() {
+        let actual = FollowUpPolicy::from_str("frist").unwrap_err();
+        assert!(actual.contains("unknown policy `frist`"));
+    }
+
+    #[tokio::test]
+    async fn test_first_picks_first_option() {
+        let mut fixture = FollowUpAnswers::new(FollowUpPolicy::First);
+
+        let actual = (
+            fixture.answer(&question()).await.unwrap(),
+            fixture
+                .answer(&FollowUpQuestion::new("Anything else?"))
+                .await
+                .unwrap(),
+        );
+
+        let expected = (
+            Some(FollowUpAnswer::Selected(vec!["Postgres".to_string()])),
+            None,
+        );
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_fail_returns_error() {
+        let mut fixture = FollowUpAnswers::new(FollowUpPolicy::Fail);
+
+        let actual = fixture.answer(&question()).await.unwrap_err().to_string();
+
+        assert!(actual.contains("Which database?"));
+    }
+
+    #[tokio::test]
+    async fn test_file_answers_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("answers.json");
+        std::fs::write(&path, r#"["SQLite", "Keep it simple"]"#).unwrap();
+        let mut fixture = FollowUpAnswers::new(FollowUpPolicy::File(path));
+
+        let actual = (
+            fixture.answer(&question()).await.unwrap(),
+            fixture
+                .answer(&FollowUpQuestion::new("Anything else?"))
+                .await
+                .unwrap(),
+        );
+        let exhausted = fixture.answer(&question()).await;
+
+        let expected = (
+            Some(FollowUpAnswer::Text("SQLite".to_string())),
+            Some(FollowUpAnswer::Text("Keep it simple".to_string())),
+        );
+        assert_eq!(actual, expected);
+        assert!(exhausted.is_err());
+    }
+}
@@ -4,7 +4,9 @@ use std::time::Duration;
 
 use colored::Colorize;
 use num_format::{Locale, ToFormattedString};
-use paws_api::{Conversation, Environment, LoginInfo, Metrics, Role, Usage, UserUsage};
+use paws_api::{
+    Conversation, Environment, LoginInfo, Metrics, Role, Usage, UsageReportEntry, UserUsage,
+};
 use paws_app::utils::truncate_key;
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -385,6 +387,13 @@ impl From<&Environment> for Info {
                     .map(|paths| paths.join(", "))
                     .unwrap_or_else(|| markers::EMPTY.to_string()),
             )
+            .add_key_value(
+                "Cassette",
+                env.http
+                    .cassette
+                    .as_ref()
+                    .map(|cassette| format!("{} ({})", cassette.path.display(), cassette.mode)),
+            )
             .add_title("API CONFIGURATION")
             .add_key_value("Paws API URL", env.paws_api_url.to_string())
             .add_title("TOOL CONFIGURATION")
@@ -495,6 +504,22 @@ impl From<&Usage> for Info {
     }
 }
 
+impl From<&[UsageReportEntry]> for Info {
+    fn from(entries: &[UsageReportEntry]) -> Self {
+        entries.iter().fold(Info::new(), |info, entry| {
+            let usage = &entry.usage;
+            info.add_title(entry.date)
+                .add_key_value("Workspace", entry.workspace_id.to_string())
+                .add_key_value("Provider", entry.provider.as_ref().map(ToString::to_string))
+                .add_key_value("Model", entry.model.as_ref().map(ToString::to_string))
+                .add_key_value("Input Tokens", usage.prompt_tokens.to_string())
+                .add_key_value("Cached Tokens", usage.cached_tokens.to_string())
+                .add_key_value("Output Tokens", usage.completion_tokens.to_string())
+                .add_key_value("Cost", usage.cost.map(|cost| format!("${cost:.4}")))
+        })
+    }
+}
+
 fn calculate_cache_percentage(usage: &Usage) -> u8 {
     let total = *usage.prompt_tokens; // Use prompt tokens as the base for cache percentage
     let cached = *usage.cached_tokens;
@@ -1254,6 +1279,39 @@ mod tests {
         assert!(!expected_display.contains("no_changes.rs"));
     }
 
+    #[test]
+    fn test_usage_report_info() {
+        use paws_api::{ModelId, ProviderId, TokenCount, Usage, UsageReportEntry, WorkspaceHash};
+
+        let fixture = [UsageReportEntry {
+            date: chrono::NaiveDate::from_ymd_opt(2025, 10, 28).unwrap(),
+            workspace_id: WorkspaceHash::new(42),
+            provider: Some(ProviderId::ANTHROPIC),
+            model: Some(ModelId::new("claude-sonnet-4-5")),
+            usage: Usage {
+                prompt_tokens: TokenCount::Actual(1200),
+                completion_tokens: TokenCount::Actual(300),
+                cost: Some(0.0081),
+                ..Default::default()
+            },
+        }];
+
+        let actual =
+            crate::porcelain::Porcelain::from(&super::Info::from(fixture.as_slice())).into_body();
+
+        let expected = vec![vec![
+            Some("2025-10-28".to_string()),
+            Some("42".to_string()),
+            Some("Anthropic".to_string()),
+            Some("claude-sonnet-4-5".to_string()),
+            Some("1200".to_string()),
+            Some("0".to_string()),
+            Some("300".to_string()),
+            Some("$0.0081".to_string()),
+        ]];
+        assert_eq!(actual, expected);
+    }
+
     #[test]
     fn test_metrics_info_all_zero_changes_shows_no_changes() {
         use paws_api::Metrics;
@@ -4,12 +4,16 @@ mod completer;
 mod conversation_selector;
 mod display_constants;
 mod editor;
+mod follow_up;
 mod info;
 mod input;
+mod mcp_serve;
 mod model;
+mod output;
 mod porcelain;
 mod prompt;
 mod sandbox;
+mod server;
 mod state;
 mod title_display;
 mod tools_display;
@@ -1,6 +1,7 @@
 use std::io::Read;
 use std::panic;
 use std::path::PathBuf;
+use std::process::ExitCode;
 
 use anyhow::Result;
 use clap::Parser;
@@ -9,7 +10,7 @@ use paws_domain::TitleFormat;
 use paws_main::{Cli, Sandbox, TitleDisplayExt, UI};
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> Result<ExitCode> {
     // Set up panic hook for better error display
     panic::set_hook(Box::new(|panic_info| {
         let message = if let Some(s) = panic_info.payload().downcast_ref::<&str>() {
@@ -29,7 +30,7 @@ async fn main() -> Result<()> {
     let mut cli = Cli::parse();
 
     // Check if there's piped input
-    if !atty::is(atty::Stream::Stdin) {
+    if cli.accepts_piped_input() && !atty::is(atty::Stream::Stdin) {
         let mut stdin_content = String::new();
         std::io::stdin().read_to_string(&mut stdin_content)?;
         let trimmed_content = stdin_content.trim();
@@ -53,12 +54,14 @@ async fn main() -> Result<()> {
         (_, _) => std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
     };
 
-    // Initialize the PawsAPI with the restricted mode if specified
+    // Initialize the PawsAPI with the restricted and sandbox modes if specified
     let restricted = cli.restricted;
-    let mut ui = UI::init(cli, move || PawsAPI::init(restricted, cwd.clone()))?;
-    ui.run().await;
-
-    Ok(())
+    let os_sandbox = cli.os_sandbox;
+    let headless = cli.is_headless();
+    let mut ui = UI::init(cli, move || {
+        PawsAPI::init(restricted, os_sandbox, headless, cwd.clone())
+    })?;
+    Ok(ui.run().await)
 }
 
 #[cfg(test)]
@@ -0,0 +1,387 @@
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+use anyhow::Context;
+use axum::extract::{Request, State};
+use axum::http::header::{AUTHORIZATION, HOST, ORIGIN};
+use axum::http::{HeaderMap, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use paws_api::API;
+use paws_app::ToolResolver;
+use paws_domain::{
+    ToolCallArguments, ToolCallFull, ToolCatalog, ToolDefinition, ToolOutput, ToolValue,
+};
+use rmcp::model::{
+    CallToolRequestParam, CallToolResult, Content, ErrorData, Implementation, ListToolsResult,
+    PaginatedRequestParam, ServerCapabilities, ServerInfo, Tool,
+};
+use rmcp::service::RequestContext;
+use rmcp::transport::streamable_http_server::StreamableHttpService;
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp::{RoleServer, ServerHandler, ServiceExt};
+use strum::IntoEnumIterator;
+use url::{Host, Url};
+
+use crate::server::token_matches;
+
+/// Path under which the streamable HTTP transport is mounted
+const HTTP_PATH: &str = "/mcp";
+
+/// Exposes the paws system tools and agents to other MCP clients.
+///
+/// Tools are served on behalf of the active agent: only the tools it may use
+/// are listed, and calls go through the same checks, hooks and workflow
+/// policies as in a conversation.
+pub struct McpServe<A> {
+    api: Arc<A>,
+}
+
+impl<A> Clone for McpServe<A> {
+    fn clone(&self) -> Self {
+        Self { api: self.api.clone() }
+    }
+}
+
+impl<A: API + 'static> McpServe<A> {
+    pub fn new(api: Arc<A>) -> Self {
+        Self { api }
+    }
+
+    /// Serves over stdin and stdout until the client disconnects
+    pub async fn serve_stdio(self) -> anyhow::Result<()> {
+        let service = self
+            .serve(rmcp::transport::stdio())
+            .await
+            .context("Failed to start the MCP server")?;
+        service.waiting().await?;
+        Ok(())
+    }
+
+    /// Serves over streamable HTTP on `addr` until interrupted. Requests must
+    /// carry `Authorization: Bearer <token>` and come from a local origin.
+    pub async fn serve_http(self, addr: SocketAddr, token: String) -> anyhow::Result<()> {
+        let service = StreamableHttpService::new(
+            move || Ok(self.clone()),
+            LocalSessionManager::default().into(),
+            Default::default(),
+        );
+        let router = HttpGuard::new(addr, token)
+            .protect(axum::Router::new().nest_service(HTTP_PATH, service));
+        let listener = tokio::net::TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("Failed to bind the MCP server to {addr}"))?;
+        tracing::info!(%addr, "Serving MCP over HTTP");
+        axum::serve(listener, router)
+            .with_graceful_shutdown(async {
+                let _ = tokio::signal::ctrl_c().await;
+            })
+            .await?;
+        Ok(())
+    }
+
+    async fn tools(&self) -> anyhow::Result<Vec<Tool>> {
+        let agents = self.api.get_agents().await?;
+        let active_agent_id = self.api.get_active_agent().await.unwrap_or_default();
+        let Some(active_agent) = agents.iter().find(|agent| agent.id == active_agent_id) else {
+            anyhow::bail!("Agent {active_agent_id} is not available");
+        };
+        ToolCatalog::iter()
+            .map(|tool| tool.definition())
+            .chain(agents.iter().cloned().map(ToolDefinition::from))
+            .filter(|definition| ToolResolver::is_allowed(active_agent, &definition.name))
+            .map(to_mcp_tool)
+            .collect()
+    }
+}
+
+/// Restricts the HTTP transport to local clients holding the token. The tools
+/// run commands and write files, so a request from another host, or from a
+/// browser page reaching the port through DNS rebinding, must never get
+/// through.
+#[derive(Debug)]
+struct HttpGuard {
+    addr: SocketAddr,
+    token: String,
+}
+
+impl HttpGuard {
+    fn new(addr: SocketAddr, token: impl Into<String>) -> Self {
+        Self { addr, token: token.into() }
+    }
+
+    fn protect(self, router: axum::Router) -> axum::Router {
+        router.layer(axum::middleware::from_fn_with_state(
+            Arc::new(self),
+            authorize,
+        ))
+    }
+
+    /// Returns why the request is rejected, if it is
+    fn check(&self, headers: &HeaderMap) -> Result<(), (StatusCode, &'static str)> {
+        let header = |name| headers.get(name).and_then(|value| value.to_str().ok());
+
+        let host = header(HOST).and_then(|host| Url::parse(&format!("http://{host}")).ok());
+        if !host.is_some_and(|host| self.is_local(&host)) {
+            return Err((StatusCode::FORBIDDEN, "Host is not local"));
+        }
+        // Browsers send the origin of the page, other clients usually don't
+        if let Some(origin) = header(ORIGIN)
+            && !Url::parse(origin).is_ok_and(|origin| self.is_local(&origin))
+        {
+            return Err((StatusCode::FORBIDDEN, "Origin is not local"));
+        }
+
+        let token = header(AUTHORIZATION).and_then(|value| value.strip_prefix("Bearer "));
+        if !token.is_some_and(|token| token_matches(token, &self.token)) {
+            return Err((StatusCode::UNAUTHORIZED, "Missing or invalid bearer token"));
+        }
+        Ok(())
+    }
+
+    /// Whether the URL names this machine, either as a loopback host or as the
+    /// address the server was explicitly bound to
+    fn is_local(&self, url: &Url) -> bool {
+        let ip = match url.host() {
+            Some(Host::Domain(domain)) => return domain.eq_ignore_ascii_case("localhost"),
+            Some(Host::Ipv4(ip)) => IpAddr::V4(ip),
+            Some(Host::Ipv6(ip)) => IpAddr::V6(ip),
+            None => return false,
+        };
+        ip.is_loopback() || (!self.addr.ip().is_unspecified() && ip == self.addr.ip())
+    }
+}
+
+async fn authorize(State(guard): State<Arc<HttpGuard>>, request: Request, next: Next) -> Response {
+    match guard.check(request.headers()) {
+        Ok(()) => next.run(request).await,
+        Err((status, message)) => (status, message).into_response(),
+    }
+}
+
+impl<A: API + 'static> ServerHandler for McpServe<A> {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            server_info: Implementation {
+                name: "paws".to_string(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                ..Default::default()
+            },
+            instructions: Some(
+                "Provides the paws file, search, shell and fetch tools, and delegates tasks \
+                 to paws agents."
+                    .to_string(),
+            ),
+            ..Default::default()
+        }
+    }
+
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, ErrorData> {
+        let tools = self
+            .tools()
+            .await
+            .map_err(|error| ErrorData::internal_error(error.to_string(), None))?;
+        Ok(ListToolsResult::with_all_items(tools))
+    }
+
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let arguments = serde_json::Value::Object(request.arguments.unwrap_or_default());
+        let call =
+            ToolCallFull::new(request.name.as_ref()).arguments(ToolCallArguments::from(arguments));
+        let result = self
+            .api
+            .call_tool(call)
+            .await
+            .map_err(|error| ErrorData::internal_error(error.to_string(), None))?;
+        Ok(to_mcp_result(result.output))
+    }
+}
+
+fn to_mcp_tool(definition: ToolDefinition) -> anyhow::Result<Tool> {
+    let schema = match serde_json::to_value(&definition.input_schema)? {
+        serde_json::Value::Object(schema) => schema,
+        _ => anyhow::bail!("Invalid input schema for tool {}", definition.name),
+    };
+    Ok(Tool::new(
+        definition.name.to_string(),
+        definition.description,
+        schema,
+    ))
+}
+
+fn to_mcp_result(output: ToolOutput) -> CallToolResult {
+    let content = output
+        .values
+        .into_iter()
+        .filter_map(|value| match value {
+            ToolValue::Text(text) => Some(Content::text(text)),
+            ToolValue::AI { value, .. } => Some(Content::text(value)),
+            ToolValue::Image(image) => Some(Content::image(image.data(), image.mime_type())),
+            ToolValue::Empty => None,
+        })
+        .collect();
+
+    if output.is_error {
+        CallToolResult::error(content)
+    } else {
+        CallToolResult::success(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use paws_domain::Image;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_to_mcp_tool_uses_object_schema() {
+        let fixture = ToolCatalog::iter().next().unwrap().definition();
+
+        let actual = to_mcp_tool(fixture.clone()).unwrap();
+
+        assert_eq!(actual.name, fixture.name.to_string());
+        assert_eq!(
+            actual.description.as_deref(),
+            Some(fixture.description.as_str())
+        );
+        assert_eq!(
+            actual.input_schema.get("type"),
+            Some(&serde_json::json!("object"))
+        );
+    }
+
+    #[test]
+    fn test_to_mcp_result() {
+        let fixture = ToolOutput {
+            is_error: false,
+            values: vec![
+                ToolValue::Text("hello".to_string()),
+                ToolValue::Empty,
+                ToolValue::Image(Image::new_base64("aGk=".to_string(), "image/png")),
+            ],
+        };
+
+        let actual = to_mcp_result(fixture);
+
+        let expected = CallToolResult::success(vec![
+            Content::text("hello"),
+            Content::image("aGk=", "image/png"),
+        ]);
+        assert_eq!(actual, expected);
+    }
+
+    fn headers(fixture: &[(&'static str, &str)]) -> HeaderMap {
+        fixture
+            .iter()
+            .map(|(name, value)| (name.parse().unwrap(), value.parse().unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn test_http_guard_check() {
+        let guard = HttpGuard::new("127.0.0.1:7879".parse().unwrap(), "secret");
+        let authorized = ("authorization", "Bearer secret");
+
+        let actual = [
+            guard.check(&headers(&[("host", "127.0.0.1:7879"), authorized])),
+            guard.check(&headers(&[("host", "localhost:7879"), authorized])),
+            guard.check(&headers(&[("host", "[::1]:7879"), authorized])),
+            guard.check(&headers(&[
+                ("host", "localhost:7879"),
+                ("origin", "http://localhost:3000"),
+                authorized,
+            ])),
+            guard.check(&headers(&[("host", "127.0.0.1:7879")])),
+            guard.check(&headers(&[
+                ("host", "127.0.0.1:7879"),
+                ("authorization", "Bearer guess"),
+            ])),
+            guard.check(&headers(&[("host", "attacker.example:7879"), authorized])),
+            guard.check(&headers(&[
+                ("host", "127.0.0.1:7879"),
+                ("origin", "https://attacker.example"),
+                authorized,
+            ])),
+            guard.check(&headers(&[authorized])),
+        ];
+
+        let unauthorized = Err((StatusCode::UNAUTHORIZED, "Missing or invalid bearer token"));
+        let expected = [
+            Ok(()),
+            Ok(()),
+            Ok(()),
+            Ok(()),
+            unauthorized,
+            unauthorized,
+            Err((StatusCode::FORBIDDEN, "Host is not local")),
+            Err((StatusCode::FORBIDDEN, "Origin is not local")),
+            Err((StatusCode::FORBIDDEN, "Host is not local")),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_http_guard_accepts_bound_address() {
+        let guard = HttpGuard::new("192.168.1.5:7879".parse().unwrap(), "secret");
+
+        let actual = [
+            guard.check(&headers(&[
+                ("host", "192.168.1.5:7879"),
+                ("authorization", "Bearer secret"),
+            ])),
+            guard.check(&headers(&[
+                ("host", "192.168.1.6:7879"),
+                ("authorization", "Bearer secret"),
+            ])),
+        ];
+
+        let expected = [Ok(()), Err((StatusCode::FORBIDDEN, "Host is not local"))];
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_http_rejects_unauthenticated_request() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let router = HttpGuard::new(addr, "secret").protect(
+            axum::Router::new().route(HTTP_PATH, axum::routing::post(|| async { "called" })),
+        );
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let request = format!(
+            "POST {HTTP_PATH} HTTP/1.1\r\nHost: {addr}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+
+        let actual = response.lines().next().unwrap();
+        let expected = "HTTP/1.1 401 Unauthorized";
+        assert_eq!(actual, expected);
+        assert!(!response.contains("called"));
+    }
+
+    #[test]
+    fn test_to_mcp_result_error() {
+        let fixture = ToolOutput::text("boom").is_error(true);
+
+        let actual = to_mcp_result(fixture).is_error;
+
+        let expected = Some(true);
+        assert_eq!(actual, expected);
+    }
+}
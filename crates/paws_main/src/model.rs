@@ -1,8 +1,11 @@
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::Display;
 use std::sync::{Arc, Mutex};
 
 use colored::Colorize;
-use paws_api::{Agent, AnyProvider, Model, ProviderId, Template};
+use paws_api::{
+    Agent, AnyProvider, McpPrompt, McpResource, McpServers, Model, ProviderId, ServerName, Template,
+};
 use paws_domain::UserCommand;
 use strum::{EnumProperty, IntoEnumIterator};
 use strum_macros::{EnumIter, EnumProperty};
@@ -131,12 +134,20 @@ pub struct PawsCommand {
 #[derive(Debug)]
 pub struct PawsCommandManager {
     commands: Arc<Mutex<Vec<PawsCommand>>>,
+    /// MCP prompts keyed by the name of the command that runs them
+    mcp_prompts: Arc<Mutex<HashMap<String, (ServerName, McpPrompt)>>>,
+    /// MCP resources that can be attached to a message
+    mcp_resources: Arc<Mutex<Vec<McpResource>>>,
 }
 
 impl Default for PawsCommandManager {
     fn default() -> Self {
         let commands = Self::default_commands();
-        PawsCommandManager { commands: Arc::new(Mutex::new(commands)) }
+        PawsCommandManager {
+            commands: Arc::new(Mutex::new(commands)),
+            mcp_prompts: Default::default(),
+            mcp_resources: Default::default(),
+        }
     }
 }
 
@@ -187,6 +198,7 @@ impl PawsCommandManager {
             .filter(|command| !matches!(command, SlashCommand::Custom(_)))
             .filter(|command| !matches!(command, SlashCommand::Shell(_)))
             .filter(|command| !matches!(command, SlashCommand::AgentSwitch(_)))
+            .filter(|command| !matches!(command, SlashCommand::McpPrompt { .. }))
             .map(|command| PawsCommand {
                 name: command.name().to_string(),
                 description: command.usage().to_string(),
@@ -258,6 +270,90 @@ impl PawsCommandManager {
         result
     }
 
+    /// Registers the prompts exposed by MCP servers as `mcp-<server>-<prompt>`
+    /// commands and keeps track of their resources for completion.
+    pub fn register_mcp(&self, servers: &McpServers) {
+        let mut guard = self.commands.lock().unwrap();
+        let mut prompts = self.mcp_prompts.lock().unwrap();
+
+        // Remove existing MCP prompt commands
+        guard.retain(|cmd| !prompts.contains_key(&cmd.name));
+        prompts.clear();
+
+        for (server, server_prompts) in servers.get_prompts() {
+            for prompt in server_prompts {
+                let command_name = format!(
+                    "mcp-{}-{}",
+                    Self::sanitize_agent_id(server),
+                    Self::sanitize_agent_id(&prompt.name)
+                );
+                let description = prompt
+                    .description
+                    .clone()
+                    .unwrap_or_else(|| format!("Run the {} prompt of {server}", prompt.name));
+
+                guard.push(PawsCommand {
+                    name: command_name.clone(),
+                    description: format!("🔌 {description}"),
+                    value: None,
+                });
+                prompts.insert(command_name, (server.clone(), prompt.clone()));
+            }
+        }
+
+        // Sort commands for consistent completion behavior
+        guard.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut resources = servers
+            .get_resources()
+            .values()
+            .flatten()
+            .cloned()
+            .collect::<Vec<_>>();
+        resources.sort_by(|a, b| a.uri.cmp(&b.uri));
+        *self.mcp_resources.lock().unwrap() = resources;
+    }
+
+    /// Lists the resources exposed by MCP servers.
+    pub fn mcp_resources(&self) -> Vec<McpResource> {
+        self.mcp_resources.lock().unwrap().clone()
+    }
+
+    /// Maps the parameters of an MCP prompt command to the prompt arguments,
+    /// in the order the prompt declares them. The last argument receives all
+    /// the remaining parameters so that it can contain spaces.
+    fn mcp_prompt_arguments(
+        command: &str,
+        prompt: &McpPrompt,
+        parameters: &[&str],
+    ) -> anyhow::Result<BTreeMap<String, String>> {
+        let mut arguments = BTreeMap::new();
+        let count = prompt.arguments.len();
+
+        for (index, argument) in prompt.arguments.iter().enumerate() {
+            let value = if index + 1 == count {
+                parameters.get(index..).map(|rest| rest.join(" "))
+            } else {
+                parameters.get(index).map(|value| value.to_string())
+            };
+
+            match value.filter(|value| !value.is_empty()) {
+                Some(value) => {
+                    arguments.insert(argument.name.clone(), value);
+                }
+                None if argument.required => {
+                    return Err(anyhow::anyhow!(
+                        "Missing required argument '{}' for {command}",
+                        argument.name
+                    ));
+                }
+                None => {}
+            }
+        }
+
+        Ok(arguments)
+    }
+
     /// Finds a command by name.
     fn find(&self, command: &str) -> Option<PawsCommand> {
         self.commands
@@ -332,6 +428,16 @@ impl PawsCommandManager {
         // TODO: Can leverage Clap to parse commands and provide correct error messages
         match command {
             "/compact" => Ok(SlashCommand::Compact),
+            "/rewind" => {
+                let turn = parameters
+                    .first()
+                    .map(|turn| {
+                        turn.parse::<usize>()
+                            .map_err(|_| anyhow::anyhow!("Invalid turn: {turn}"))
+                    })
+                    .transpose()?;
+                Ok(SlashCommand::Rewind { turn })
+            }
             "/new" => Ok(SlashCommand::New),
             "/info" => Ok(SlashCommand::Info),
             "/env" => Ok(SlashCommand::Env),
@@ -371,8 +477,19 @@ impl PawsCommandManager {
                         return Err(anyhow::anyhow!("{command} is not a valid agent command"));
                     }
 
-                    // Handle custom workflow commands
+                    // Handle prompts exposed by MCP servers
                     let command_name = command.strip_prefix('/').unwrap_or(command);
+                    let mcp_prompt = self.mcp_prompts.lock().unwrap().get(command_name).cloned();
+                    if let Some((server, prompt)) = mcp_prompt {
+                        let arguments = Self::mcp_prompt_arguments(command, &prompt, &parameters)?;
+                        return Ok(SlashCommand::McpPrompt {
+                            server: server.to_string(),
+                            name: prompt.name,
+                            arguments,
+                        });
+                    }
+
+                    // Handle custom workflow commands
                     if let Some(command) = self.find(command_name) {
                         let template = Template::new(
                             self.extract_command_value(&command, &parts[1..])
@@ -406,6 +523,10 @@ pub enum SlashCommand {
     /// '/compact' command.
     #[strum(props(usage = "Compact the conversation context"))]
     Compact,
+    /// Rewind the conversation and the files modified since to an earlier
+    /// turn. This can be triggered with the '/rewind [turn]' command.
+    #[strum(props(usage = "Rewind the conversation and modified files to an earlier turn"))]
+    Rewind { turn: Option<usize> },
     /// Start a new conversation while preserving history.
     /// This can be triggered with the '/new' command.
     #[strum(props(usage = "Start a new conversation"))]
@@ -465,6 +586,14 @@ pub enum SlashCommand {
     Tools,
     /// Handles custom command defined in workflow file.
     Custom(UserCommand),
+    /// Runs a prompt exposed by an MCP server.
+    /// This can be triggered with the '/mcp-<server>-<prompt>' commands.
+    #[strum(props(usage = "Run a prompt exposed by an MCP server"))]
+    McpPrompt {
+        server: String,
+        name: String,
+        arguments: BTreeMap<String, String>,
+    },
     /// Executes a native shell command.
     /// This can be triggered with commands starting with '!' character.
     #[strum(props(usage = "Execute a native shell command"))]
@@ -502,6 +631,7 @@ impl SlashCommand {
     pub fn name(&self) -> &str {
         match self {
             SlashCommand::Compact => "compact",
+            SlashCommand::Rewind { .. } => "rewind",
             SlashCommand::New => "new",
             SlashCommand::Message(_) => "message",
             SlashCommand::Update => "update",
@@ -519,6 +649,7 @@ impl SlashCommand {
             SlashCommand::Provider => "provider",
             SlashCommand::Tools => "tools",
             SlashCommand::Custom(event) => &event.name,
+            SlashCommand::McpPrompt { name, .. } => name,
             SlashCommand::Shell(_) => "!shell",
             SlashCommand::Agent => "agent",
             SlashCommand::Login => "login",
@@ -573,6 +704,7 @@ mod tests {
                 description: String::from("Test command"),
                 value: Some(String::from("default_value")),
             }])),
+            ..Default::default()
         };
         let command = PawsCommand {
             name: String::from("/test"),
@@ -597,6 +729,7 @@ mod tests {
                 description: String::from("Test command"),
                 value: Some(String::from("default_value")),
             }])),
+            ..Default::default()
         };
         let command = PawsCommand {
             name: String::from("/test"),
@@ -621,6 +754,7 @@ mod tests {
                 description: String::from("Test command"),
                 value: Some(String::from("default_value")),
             }])),
+            ..Default::default()
         };
         let command = PawsCommand {
             name: String::from("/test"),
@@ -645,6 +779,7 @@ mod tests {
                 description: String::from("Test command"),
                 value: None,
             }])),
+            ..Default::default()
         };
         let command = PawsCommand {
             name: String::from("/test"),
@@ -669,6 +804,7 @@ mod tests {
                 description: String::from("Test command"),
                 value: Some(String::from("default_value")),
             }])),
+            ..Default::default()
         };
         let command = PawsCommand {
             name: String::from("/test"),
@@ -804,6 +940,96 @@ mod tests {
         assert!(!PawsCommandManager::is_reserved_command("custom"));
     }
 
+    fn fixture_mcp_servers() -> McpServers {
+        use paws_api::McpPromptArgument;
+
+        let prompt = McpPrompt::new("review_pr")
+            .description("Review a pull request")
+            .arguments(vec![
+                McpPromptArgument::new("number").required(true),
+                McpPromptArgument::new("focus"),
+            ]);
+        McpServers::default()
+            .prompts(HashMap::from([(
+                ServerName::from("github".to_string()),
+                vec![prompt],
+            )]))
+            .resources(HashMap::from([(
+                ServerName::from("docs".to_string()),
+                vec![McpResource::new("file:///docs/readme.md", "readme")],
+            )]))
+    }
+
+    #[test]
+    fn test_register_mcp_prompts() {
+        let fixture = PawsCommandManager::default();
+
+        fixture.register_mcp(&fixture_mcp_servers());
+
+        let actual = fixture
+            .list()
+            .into_iter()
+            .find(|cmd| cmd.name.starts_with("mcp-"));
+        let expected = Some(PawsCommand {
+            name: "mcp-github-review-pr".to_string(),
+            description: "🔌 Review a pull request".to_string(),
+            value: None,
+        });
+        assert_eq!(actual, expected);
+        assert_eq!(
+            fixture.mcp_resources(),
+            vec![McpResource::new("file:///docs/readme.md", "readme")]
+        );
+    }
+
+    #[test]
+    fn test_parse_mcp_prompt_command() {
+        let fixture = PawsCommandManager::default();
+        fixture.register_mcp(&fixture_mcp_servers());
+
+        let actual = fixture
+            .parse("/mcp-github-review-pr 42 error handling")
+            .unwrap();
+
+        let expected = SlashCommand::McpPrompt {
+            server: "github".to_string(),
+            name: "review_pr".to_string(),
+            arguments: BTreeMap::from([
+                ("number".to_string(), "42".to_string()),
+                ("focus".to_string(), "error handling".to_string()),
+            ]),
+        };
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_mcp_prompt_command_missing_required_argument() {
+        let fixture = PawsCommandManager::default();
+        fixture.register_mcp(&fixture_mcp_servers());
+
+        let actual = fixture
+            .parse("/mcp-github-review-pr")
+            .unwrap_err()
+            .to_string();
+
+        let expected = "Missing required argument 'number' for /mcp-github-review-pr";
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_register_mcp_replaces_previous_prompts() {
+        let fixture = PawsCommandManager::default();
+        fixture.register_mcp(&fixture_mcp_servers());
+
+        fixture.register_mcp(&McpServers::default());
+
+        let actual = fixture
+            .list()
+            .iter()
+            .any(|cmd| cmd.name.starts_with("mcp-"));
+        assert_eq!(actual, false);
+    }
+
     #[test]
     fn test_register_agent_commands() {
         use paws_api::Agent;
@@ -1004,6 +1230,7 @@ mod tests {
             models: Some(ModelSource::Url(
                 Url::parse("https://api.openai.com/v1/models").unwrap(),
             )),
+            pricing: Default::default(),
         });
         let formatted = format!("{}", CliProvider(fixture));
         let actual = strip_ansi_codes(&formatted);
@@ -1024,6 +1251,7 @@ mod tests {
             models: Some(ModelSource::Url(
                 Url::parse("https://openrouter.ai/api/v1/models").unwrap(),
             )),
+            pricing: Default::default(),
         });
         let formatted = format!("{}", CliProvider(fixture));
         let actual = strip_ansi_codes(&formatted);
@@ -1044,6 +1272,7 @@ mod tests {
             models: Some(ModelSource::Url(
                 Url::parse("http://localhost:8080/models").unwrap(),
             )),
+            pricing: Default::default(),
         });
         let formatted = format!("{}", CliProvider(fixture));
         let actual = strip_ansi_codes(&formatted);
@@ -1064,6 +1293,7 @@ mod tests {
             models: Some(ModelSource::Url(Template::new(
                 "https://api.anthropic.com/v1/models",
             ))),
+            pricing: Default::default(),
         });
         let formatted = format!("{}", CliProvider(fixture));
         let actual = strip_ansi_codes(&formatted);
@@ -1084,6 +1314,7 @@ mod tests {
             models: Some(ModelSource::Url(
                 Url::parse("http://192.168.1.1:8080/models").unwrap(),
             )),
+            pricing: Default::default(),
         });
         let formatted = format!("{}", CliProvider(fixture));
         let actual = strip_ansi_codes(&formatted);
@@ -1126,6 +1357,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_rewind_command() {
+        let fixture = PawsCommandManager::default();
+
+        let actual = (
+            fixture.parse("/rewind").unwrap(),
+            fixture.parse("/rewind 2").unwrap(),
+        );
+
+        let expected = (
+            SlashCommand::Rewind { turn: None },
+            SlashCommand::Rewind { turn: Some(2) },
+        );
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_rewind_command_invalid_turn() {
+        let fixture = PawsCommandManager::default();
+
+        let actual = fixture.parse("/rewind last").unwrap_err().to_string();
+
+        let expected = "Invalid turn: last";
+        assert_eq!(actual, expected);
+    }
+
     #[test]
     fn test_parse_dump_command_json() {
         // Setup
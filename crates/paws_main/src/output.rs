@@ -0,0 +1,507 @@
+//! Machine-readable output of non-interactive runs.
+//!
+//! Every [`ChatResponse`] is mapped to an [`OutputEvent`], serialized as a
+//! JSON object whose `type` field names the event. Message and reasoning
+//! events carry chunks as they are streamed by the model, consumers
+//! concatenate them. The last event is always a `result` with the final
+//! status, usage and cost. The schema only grows new optional fields or event
+//! types, existing ones are never renamed.
+
+use std::io::Write;
+use std::process::ExitCode;
+
+use paws_domain::{
+    Category, ChatResponse, ChatResponseContent, ConversationId, InterruptionReason, RateLimit,
+    ToolValue, Usage,
+};
+use serde::Serialize;
+
+use crate::cli::OutputFormat;
+
+/// Exit code of a run that failed with an error
+const EXIT_ERROR: u8 = 1;
+/// Exit code of a run interrupted after the maximum requests per turn
+const EXIT_MAX_REQUESTS: u8 = 2;
+/// Exit code of a run interrupted after the maximum tool failures per turn
+const EXIT_MAX_TOOL_FAILURES: u8 = 3;
+/// Exit code of a run interrupted by a budget limit
+const EXIT_BUDGET: u8 = 4;
+/// Exit code of a run cancelled with Ctrl+C, following the shell convention
+const EXIT_CANCELLED: u8 = 130;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OutputEvent {
+    /// A status line, such as a tool being executed
+    Title {
+        category: &'static str,
+        title: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        subtitle: Option<String>,
+    },
+    /// Plain text shown as is
+    Text {
+        content: String,
+    },
+    /// A chunk of the model's answer, in markdown
+    Markdown {
+        content: String,
+    },
+    /// A chunk of the model's reasoning
+    Reasoning {
+        content: String,
+    },
+    ToolCallStart {
+        call_id: Option<String>,
+        name: String,
+        arguments: serde_json::Value,
+    },
+    ToolCallEnd {
+        call_id: Option<String>,
+        name: String,
+        is_error: bool,
+        /// Text returned by the tool, images are left out
+        output: String,
+    },
+    /// A failed request is retried after `delay_ms`
+    Retry {
+        cause: String,
+        delay_ms: u64,
+        /// Remaining quota, when the provider reported its rate limits
+        #[serde(skip_serializing_if = "Option::is_none")]
+        rate_limit: Option<OutputRateLimit>,
+    },
+    /// The agent waits for an answer, given according to `--follow-up`
+    FollowUp {
+        question: String,
+        options: Vec<String>,
+        multiple: bool,
+    },
+    /// The task stopped before completion, see [`Status`] for the reasons
+    Interrupt {
+        reason: Status,
+        message: String,
+    },
+    TaskComplete,
+    Error {
+        message: String,
+    },
+    /// Always the last event
+    Result {
+        status: Status,
+        exit_code: u8,
+        conversation_id: Option<String>,
+        usage: Option<OutputUsage>,
+        /// Cost in USD, when reported by the provider
+        cost: Option<f64>,
+    },
+}
+
+/// Why a run ended, each with its own exit code
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    #[default]
+    Completed,
+    Error,
+    MaxRequestsPerTurn,
+    MaxToolFailuresPerTurn,
+    Budget,
+    Cancelled,
+}
+
+impl Status {
+    pub fn exit_code(self) -> u8 {
+        match self {
+            Status::Completed => 0,
+            Status::Error => EXIT_ERROR,
+            Status::MaxRequestsPerTurn => EXIT_MAX_REQUESTS,
+            Status::MaxToolFailuresPerTurn => EXIT_MAX_TOOL_FAILURES,
+            Status::Budget => EXIT_BUDGET,
+            Status::Cancelled => EXIT_CANCELLED,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OutputRateLimit {
+    pub requests_limit: Option<u64>,
+    pub requests_remaining: Option<u64>,
+    pub tokens_limit: Option<u64>,
+    pub tokens_remaining: Option<u64>,
+}
+
+impl From<RateLimit> for OutputRateLimit {
+    fn from(rate_limit: RateLimit) -> Self {
+        let requests = rate_limit.requests.unwrap_or_default();
+        let tokens = rate_limit.tokens.unwrap_or_default();
+        Self {
+            requests_limit: requests.limit,
+            requests_remaining: requests.remaining,
+            tokens_limit: tokens.limit,
+            tokens_remaining: tokens.remaining,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OutputUsage {
+    pub prompt_tokens: usize,
+    pub completion_tokens: usize,
+    pub cached_tokens: usize,
+    pub total_tokens: usize,
+}
+
+impl From<&Usage> for OutputUsage {
+    fn from(usage: &Usage) -> Self {
+        Self {
+            prompt_tokens: *usage.prompt_tokens,
+            completion_tokens: *usage.completion_tokens,
+            cached_tokens: *usage.cached_tokens,
+            total_tokens: *usage.total_tokens,
+        }
+    }
+}
+
+fn category(category: &Category) -> &'static str {
+    match category {
+        Category::Action => "action",
+        Category::Info => "info",
+        Category::Debug => "debug",
+        Category::Error => "error",
+        Category::Completion => "completion",
+        Category::Warning => "warning",
+    }
+}
+
+fn interruption(reason: InterruptionReason) -> (Status, String) {
+    match reason {
+        InterruptionReason::MaxRequestPerTurnLimitReached { limit } => (
+            Status::MaxRequestsPerTurn,
+            format!("Maximum request ({limit}) per turn achieved"),
+        ),
+        InterruptionReason::MaxToolFailurePerTurnLimitReached { limit, .. } => (
+            Status::MaxToolFailuresPerTurn,
+            format!("Maximum tool failure limit ({limit}) reached for this turn"),
+        ),
+        InterruptionReason::BudgetLimitReached { budget } => {
+            (Status::Budget, format!("Budget limit reached: {budget}"))
+        }
+    }
+}
+
+impl OutputEvent {
+    /// The closing event of a run
+    pub fn result(
+        status: Status,
+        conversation_id: Option<ConversationId>,
+        usage: Option<Usage>,
+    ) -> Self {
+        OutputEvent::Result {
+            status,
+            exit_code: status.exit_code(),
+            conversation_id: conversation_id.map(|id| id.into_string()),
+            usage: usage.as_ref().map(OutputUsage::from),
+            cost: usage.and_then(|usage| usage.cost),
+        }
+    }
+}
+
+impl From<ChatResponse> for OutputEvent {
+    fn from(response: ChatResponse) -> Self {
+        match response {
+            ChatResponse::TaskMessage { content } => match content {
+                ChatResponseContent::Title(title) => OutputEvent::Title {
+                    category: category(&title.category),
+                    title: title.title,
+                    subtitle: title.sub_title,
+                },
+                ChatResponseContent::PlainText(content) => OutputEvent::Text { content },
+                ChatResponseContent::Markdown(content) => OutputEvent::Markdown { content },
+            },
+            ChatResponse::TaskReasoning { content } => OutputEvent::Reasoning { content },
+            ChatResponse::TaskComplete => OutputEvent::TaskComplete,
+            ChatResponse::ToolCallStart(call) => OutputEvent::ToolCallStart {
+                call_id: call.call_id.map(|id| id.as_str().to_string()),
+                name: call.name.to_string(),
+                arguments: serde_json::to_value(&call.arguments).unwrap_or_default(),
+            },
+            ChatResponse::ToolCallEnd(result) => OutputEvent::ToolCallEnd {
+                call_id: result.call_id.map(|id| id.as_str().to_string()),
+                name: result.name.to_string(),
+                is_error: result.output.is_error,
+                output: result
+                    .output
+                    .values
+                    .iter()
+                    .filter_map(ToolValue::as_str)
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            },
+            ChatResponse::RetryAttempt { cause, duration, rate_limit } => OutputEvent::Retry {
+                cause: cause.into_string(),
+                delay_ms: duration.as_millis() as u64,
+                rate_limit: rate_limit.map(Into::into),
+            },
+            ChatResponse::Interrupt { reason } => {
+                let (reason, message) = interruption(reason);
+                OutputEvent::Interrupt { reason, message }
+            }
+            ChatResponse::FollowUp { question, .. } => OutputEvent::FollowUp {
+                question: question.question,
+                options: question.options,
+                multiple: question.multiple,
+            },
+        }
+    }
+}
+
+/// Writes the events of a non-interactive run in a machine-readable format
+pub struct HeadlessOutput<W> {
+    format: OutputFormat,
+    writer: W,
+    events: Vec<OutputEvent>,
+    status: Status,
+}
+
+impl<W: Write> HeadlessOutput<W> {
+    /// Returns `None` for the text format, which is rendered for humans
+    pub fn new(format: OutputFormat, writer: W) -> Option<Self> {
+        (format != OutputFormat::Text).then(|| Self {
+            format,
+            writer,
+            events: Vec::new(),
+            status: Status::default(),
+        })
+    }
+
+    pub fn push(&mut self, response: ChatResponse) -> anyhow::Result<()> {
+        if response.is_empty() {
+            return Ok(());
+        }
+        let event = OutputEvent::from(response);
+        if let OutputEvent::Interrupt { reason, .. } = &event {
+            self.status = *reason;
+        }
+        self.emit(event)
+    }
+
+    pub fn fail(&mut self, error: &anyhow::Error) -> anyhow::Result<()> {
+        self.status = Status::Error;
+        self.emit(OutputEvent::Error { message: format!("{error:#}") })
+    }
+
+    pub fn cancel(&mut self) {
+        self.status = Status::Cancelled;
+    }
+
+    /// Writes the result and returns the exit code of the run
+    pub fn finish(
+        mut self,
+        conversation_id: Option<ConversationId>,
+        usage: Option<Usage>,
+    ) -> anyhow::Result<ExitCode> {
+        let exit_code = self.status.exit_code();
+        self.emit(OutputEvent::result(self.status, conversation_id, usage))?;
+
+        if self.format == OutputFormat::Json {
+            let events = std::mem::take(&mut self.events);
+            serde_json::to_writer(&mut self.writer, &serde_json::json!({ "events": events }))?;
+            writeln!(self.writer)?;
+            self.writer.flush()?;
+        }
+        Ok(ExitCode::from(exit_code))
+    }
+
+    fn emit(&mut self, event: OutputEvent) -> anyhow::Result<()> {
+        match self.format {
+            OutputFormat::StreamJson => {
+                serde_json::to_writer(&mut self.writer, &event)?;
+                writeln!(self.writer)?;
+                self.writer.flush()?;
+            }
+            OutputFormat::Json | OutputFormat::Text => self.events.push(event),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use paws_domain::{
+        BudgetExceeded, BudgetScope, RateLimitWindow, TokenCount, ToolCallFull, ToolCallId,
+        ToolOutput, ToolResult,
+    };
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+
+    fn lines(output: &[u8]) -> Vec<serde_json::Value> {
+        String::from_utf8_lossy(output)
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    fn usage() -> Usage {
+        Usage {
+            prompt_tokens: TokenCount::Actual(100),
+            completion_tokens: TokenCount::Actual(20),
+            total_tokens: TokenCount::Actual(120),
+            cached_tokens: TokenCount::Actual(50),
+            cache_write_tokens: TokenCount::Actual(0),
+            cost: Some(0.25),
+        }
+    }
+
+    #[test]
+    fn test_stream_json_events() {
+        let mut output = Vec::new();
+        let mut fixture = HeadlessOutput::new(OutputFormat::StreamJson, &mut output).unwrap();
+
+        fixture
+            .push(ChatResponse::TaskReasoning { content: "Looking".to_string() })
+            .unwrap();
+        fixture
+            .push(ChatResponse::ToolCallStart(
+                ToolCallFull::new("read")
+                    .call_id(ToolCallId::new("call_1"))
+                    .arguments(paws_domain::ToolCallArguments::from_json(
+                        r#"{"path":"a.rs"}"#,
+                    )),
+            ))
+            .unwrap();
+        fixture
+            .push(ChatResponse::ToolCallEnd(
+                ToolResult::new("read")
+                    .call_id(ToolCallId::new("call_1"))
+                    .output(Ok(ToolOutput::text("fn main() {}"))),
+            ))
+            .unwrap();
+        fixture
+            .push(ChatResponse::RetryAttempt {
+                cause: (&anyhow::anyhow!("Overloaded")).into(),
+                duration: Duration::from_millis(500),
+                rate_limit: Some(RateLimit {
+                    requests: Some(RateLimitWindow {
+                        limit: Some(60),
+                        remaining: Some(0),
+                        reset_after: None,
+                    }),
+                    ..Default::default()
+                }),
+            })
+            .unwrap();
+        fixture
+            .push(ChatResponse::TaskMessage {
+                content: ChatResponseContent::Markdown("Done".to_string()),
+            })
+            .unwrap();
+        fixture.push(ChatResponse::TaskComplete).unwrap();
+        let exit_code = fixture
+            .finish(Some(ConversationId::generate()), Some(usage()))
+            .unwrap();
+
+        let mut actual = lines(&output);
+        actual[6]["conversation_id"] = json!("id");
+        // The cause carries a backtrace when RUST_BACKTRACE is set
+        let cause = actual[3]["cause"].as_str().unwrap().lines().next().unwrap();
+        actual[3]["cause"] = json!(cause);
+        let expected = vec![
+            json!({"type": "reasoning", "content": "Looking"}),
+            json!({"type": "tool_call_start", "call_id": "call_1", "name": "read", "arguments": {"path": "a.rs"}}),
+            json!({"type": "tool_call_end", "call_id": "call_1", "name": "read", "is_error": false, "output": "fn main() {}"}),
+            json!({
+                "type": "retry",
+                "cause": "Overloaded",
+                "delay_ms": 500,
+                "rate_limit": {"requests_limit": 60, "requests_remaining": 0, "tokens_limit": null, "tokens_remaining": null}
+            }),
+            json!({"type": "markdown", "content": "Done"}),
+            json!({"type": "task_complete"}),
+            json!({
+                "type": "result",
+                "status": "completed",
+                "exit_code": 0,
+                "conversation_id": "id",
+                "usage": {"prompt_tokens": 100, "completion_tokens": 20, "cached_tokens": 50, "total_tokens": 120},
+                "cost": 0.25
+            }),
+        ];
+        assert_eq!(actual, expected);
+        assert_eq!(exit_code, ExitCode::SUCCESS);
+    }
+
+    #[test]
+    fn test_json_prints_single_document() {
+        let mut output = Vec::new();
+        let mut fixture = HeadlessOutput::new(OutputFormat::Json, &mut output).unwrap();
+
+        fixture
+            .push(ChatResponse::Interrupt {
+                reason: InterruptionReason::BudgetLimitReached {
+                    budget: BudgetExceeded::Tokens {
+                        scope: BudgetScope::Turn,
+                        limit: 100,
+                        spent: 120,
+                    },
+                },
+            })
+            .unwrap();
+        fixture.finish(None, None).unwrap();
+
+        let actual = lines(&output);
+        let expected = vec![json!({"events": [
+            {
+                "type": "interrupt",
+                "reason": "budget",
+                "message": "Budget limit reached: turn token budget of 100 reached (120 used)"
+            },
+            {
+                "type": "result",
+                "status": "budget",
+                "exit_code": 4,
+                "conversation_id": null,
+                "usage": null,
+                "cost": null
+            }
+        ]})];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_exit_code_reflects_interruption() {
+        let fixture = [
+            (
+                InterruptionReason::MaxRequestPerTurnLimitReached { limit: 10 },
+                Status::MaxRequestsPerTurn,
+            ),
+            (
+                InterruptionReason::MaxToolFailurePerTurnLimitReached {
+                    limit: 3,
+                    errors: Default::default(),
+                },
+                Status::MaxToolFailuresPerTurn,
+            ),
+        ];
+
+        for (reason, expected) in fixture {
+            let mut fixture = HeadlessOutput::new(OutputFormat::Json, Vec::new()).unwrap();
+            fixture.push(ChatResponse::Interrupt { reason }).unwrap();
+            assert_eq!(fixture.status, expected);
+        }
+        assert_eq!(Status::Error.exit_code(), 1);
+        assert_eq!(Status::MaxRequestsPerTurn.exit_code(), 2);
+        assert_eq!(Status::MaxToolFailuresPerTurn.exit_code(), 3);
+        assert_eq!(Status::Cancelled.exit_code(), 130);
+    }
+
+    #[test]
+    fn test_text_format_is_not_headless() {
+        let actual = HeadlessOutput::new(OutputFormat::Text, Vec::new()).is_none();
+        assert!(actual);
+    }
+}
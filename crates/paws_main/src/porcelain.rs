@@ -255,6 +255,15 @@ impl Porcelain {
         Porcelain(rows)
     }
 
+    /// Renames the header (first row) of the specified column
+    pub fn rename_header(self, c: usize, name: &str) -> Self {
+        let mut rows = self.0;
+        if let Some(header) = rows.first_mut().and_then(|row| row.get_mut(c)) {
+            *header = Some(name.to_owned());
+        }
+        Porcelain(rows)
+    }
+
     pub fn into_long(self) -> Self {
         if self.0.is_empty() {
             return self;
@@ -502,6 +511,22 @@ mod tests {
         assert_eq!(actual, expected)
     }
 
+    #[test]
+    fn test_rename_header() {
+        let fixture = Porcelain(vec![
+            vec![Some("id".into()), Some("name".into())],
+            vec![Some("user1".into()), Some("Alice".into())],
+        ]);
+
+        let actual = fixture.rename_header(0, "user").into_rows();
+
+        let expected = vec![
+            vec![Some("user".into()), Some("name".into())],
+            vec![Some("user1".into()), Some("Alice".into())],
+        ];
+        assert_eq!(actual, expected)
+    }
+
     #[test]
     fn test_drop_cols() {
         let fixture = Porcelain(vec![
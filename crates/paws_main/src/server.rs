@@ -0,0 +1,559 @@
+//! HTTP interface to the [`API`] for web and editor front-ends.
+//!
+//! Every request must carry `Authorization: Bearer <token>`. Responses are
+//! JSON, errors are `{"error": "..."}` with a matching status code.
+//! `POST /v1/chat` streams the run as server-sent events whose data is an
+//! [`OutputEvent`], the same schema as `--output-format stream-json`, closing
+//! with a `result` event.
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Context;
+use axum::Json;
+use axum::extract::{Path, Query, Request, State};
+use axum::http::StatusCode;
+use axum::http::header::AUTHORIZATION;
+use axum::middleware::Next;
+use axum::response::sse::{Event as SseEvent, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use paws_api::API;
+use paws_domain::{
+    Agent, AgentId, AnyProvider, ChatRequest, ChatResponse, CompactionResult, Conversation,
+    ConversationId, Event, FollowUpAnswer, FollowUpReply, Model, ModelId, ProviderId,
+};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::{Stream, StreamExt};
+
+use crate::output::{OutputEvent, Status};
+
+/// Serves the [`API`] over HTTP until interrupted
+pub struct ApiServer<A> {
+    api: Arc<A>,
+    token: String,
+    /// Conversations with a chat in progress, along with the follow-up
+    /// question waiting for an answer. A conversation runs one chat at a time.
+    active: Mutex<HashMap<ConversationId, Option<FollowUpReply>>>,
+}
+
+impl<A: API + 'static> ApiServer<A> {
+    pub fn new(api: Arc<A>, token: impl Into<String>) -> Self {
+        Self { api, token: token.into(), active: Mutex::default() }
+    }
+
+    pub async fn serve(self, addr: SocketAddr) -> anyhow::Result<()> {
+        let listener = tokio::net::TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("Failed to bind the server to {addr}"))?;
+        tracing::info!(%addr, "Serving the API over HTTP");
+        axum::serve(listener, self.router())
+            .with_graceful_shutdown(async {
+                let _ = tokio::signal::ctrl_c().await;
+            })
+            .await?;
+        Ok(())
+    }
+
+    fn router(self) -> axum::Router {
+        let server = Arc::new(self);
+        axum::Router::new()
+            .route("/v1/models", get(models::<A>))
+            .route("/v1/agents", get(agents::<A>))
+            .route("/v1/providers", get(providers::<A>))
+            .route("/v1/conversations", get(conversations::<A>))
+            .route(
+                "/v1/conversations/{id}",
+                get(conversation::<A>).delete(delete_conversation::<A>),
+            )
+            .route(
+                "/v1/conversations/{id}/compact",
+                post(compact_conversation::<A>),
+            )
+            .route(
+                "/v1/conversations/{id}/follow-up",
+                post(answer_follow_up::<A>),
+            )
+            .route("/v1/chat", post(chat::<A>))
+            .layer(axum::middleware::from_fn_with_state(
+                server.clone(),
+                authorize::<A>,
+            ))
+            .with_state(server)
+    }
+
+    /// Marks the conversation as running a chat until the returned guard is
+    /// dropped, `None` if it already is
+    fn claim(self: &Arc<Self>, id: ConversationId) -> Option<ChatGuard<A>> {
+        let mut active = self.active.lock().unwrap_or_else(|e| e.into_inner());
+        if active.contains_key(&id) {
+            return None;
+        }
+        active.insert(id, None);
+        Some(ChatGuard { server: self.clone(), id })
+    }
+
+    /// Takes the follow-up question of the conversation waiting for an answer
+    fn take_follow_up(&self, id: &ConversationId) -> Option<FollowUpReply> {
+        let mut active = self.active.lock().unwrap_or_else(|e| e.into_inner());
+        active.get_mut(id).and_then(Option::take)
+    }
+}
+
+struct ChatGuard<A> {
+    server: Arc<ApiServer<A>>,
+    id: ConversationId,
+}
+
+impl<A> ChatGuard<A> {
+    fn follow_up(&self, reply: FollowUpReply) {
+        let mut active = self.server.active.lock().unwrap_or_else(|e| e.into_inner());
+        active.insert(self.id, Some(reply));
+    }
+}
+
+impl<A> Drop for ChatGuard<A> {
+    fn drop(&mut self) {
+        let mut active = self.server.active.lock().unwrap_or_else(|e| e.into_inner());
+        active.remove(&self.id);
+    }
+}
+
+struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self { status, message: message.into() }
+    }
+
+    fn not_found(id: &ConversationId) -> Self {
+        Self::new(
+            StatusCode::NOT_FOUND,
+            format!("Conversation {id} not found"),
+        )
+    }
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(error: anyhow::Error) -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, format!("{error:#}"))
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = Json(serde_json::json!({ "error": self.message }));
+        (self.status, body).into_response()
+    }
+}
+
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+/// Compares in constant time, so the token can't be guessed byte by byte from
+/// response timings
+pub(crate) fn token_matches(actual: &str, expected: &str) -> bool {
+    actual.len() == expected.len()
+        && actual
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+async fn authorize<A>(
+    State(server): State<Arc<ApiServer<A>>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let token = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    if token.is_some_and(|token| token_matches(token, &server.token)) {
+        next.run(request).await
+    } else {
+        ApiError::new(StatusCode::UNAUTHORIZED, "Missing or invalid bearer token").into_response()
+    }
+}
+
+/// An agent as listed by `GET /v1/agents`
+#[derive(Debug, PartialEq, Serialize)]
+struct AgentInfo {
+    id: AgentId,
+    title: Option<String>,
+    description: Option<String>,
+    provider: ProviderId,
+    model: ModelId,
+}
+
+impl From<Agent> for AgentInfo {
+    fn from(agent: Agent) -> Self {
+        Self {
+            id: agent.id,
+            title: agent.title,
+            description: agent.description,
+            provider: agent.provider,
+            model: agent.model,
+        }
+    }
+}
+
+/// A provider as listed by `GET /v1/providers`, credentials are never exposed
+#[derive(Debug, PartialEq, Serialize)]
+struct ProviderInfo {
+    id: ProviderId,
+    url: Option<String>,
+    configured: bool,
+}
+
+impl From<AnyProvider> for ProviderInfo {
+    fn from(provider: AnyProvider) -> Self {
+        Self {
+            id: provider.id(),
+            url: provider.url().map(|url| url.to_string()),
+            configured: provider.is_configured(),
+        }
+    }
+}
+
+/// A conversation as listed by `GET /v1/conversations`, without its messages
+#[derive(Debug, PartialEq, Serialize)]
+struct ConversationInfo {
+    id: ConversationId,
+    title: Option<String>,
+    created_at: chrono::DateTime<chrono::Utc>,
+    updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl From<Conversation> for ConversationInfo {
+    fn from(conversation: Conversation) -> Self {
+        Self {
+            id: conversation.id,
+            title: conversation.title,
+            created_at: conversation.metadata.created_at,
+            updated_at: conversation.metadata.updated_at,
+        }
+    }
+}
+
+async fn models<A: API>(State(server): State<Arc<ApiServer<A>>>) -> ApiResult<Vec<Model>> {
+    Ok(Json(server.api.get_models().await?))
+}
+
+async fn agents<A: API>(State(server): State<Arc<ApiServer<A>>>) -> ApiResult<Vec<AgentInfo>> {
+    let agents = server.api.get_agents().await?;
+    Ok(Json(agents.into_iter().map(AgentInfo::from).collect()))
+}
+
+async fn providers<A: API>(
+    State(server): State<Arc<ApiServer<A>>>,
+) -> ApiResult<Vec<ProviderInfo>> {
+    let providers = server.api.get_providers().await?;
+    Ok(Json(
+        providers.into_iter().map(ProviderInfo::from).collect(),
+    ))
+}
+
+#[derive(Deserialize)]
+struct ListQuery {
+    limit: Option<usize>,
+}
+
+async fn conversations<A: API>(
+    State(server): State<Arc<ApiServer<A>>>,
+    Query(query): Query<ListQuery>,
+) -> ApiResult<Vec<ConversationInfo>> {
+    let conversations = server.api.get_conversations(query.limit).await?;
+    Ok(Json(
+        conversations
+            .into_iter()
+            .map(ConversationInfo::from)
+            .collect(),
+    ))
+}
+
+async fn conversation<A: API>(
+    State(server): State<Arc<ApiServer<A>>>,
+    Path(id): Path<ConversationId>,
+) -> ApiResult<Conversation> {
+    let conversation = server.api.conversation(&id).await?;
+    Ok(Json(conversation.ok_or_else(|| ApiError::not_found(&id))?))
+}
+
+async fn delete_conversation<A: API>(
+    State(server): State<Arc<ApiServer<A>>>,
+    Path(id): Path<ConversationId>,
+) -> Result<StatusCode, ApiError> {
+    if server.api.conversation(&id).await?.is_none() {
+        return Err(ApiError::not_found(&id));
+    }
+    server.api.delete_conversation(&id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn compact_conversation<A: API>(
+    State(server): State<Arc<ApiServer<A>>>,
+    Path(id): Path<ConversationId>,
+) -> ApiResult<CompactionResult> {
+    if server.api.conversation(&id).await?.is_none() {
+        return Err(ApiError::not_found(&id));
+    }
+    Ok(Json(server.api.compact_conversation(&id).await?))
+}
+
+/// Body of `POST /v1/chat`
+#[derive(Debug, PartialEq, Deserialize)]
+struct ChatBody {
+    prompt: String,
+    /// Resumes this conversation, a new one is started when omitted
+    #[serde(default)]
+    conversation_id: Option<ConversationId>,
+    /// Runs the prompt with this agent instead of the active one
+    #[serde(default)]
+    agent_id: Option<AgentId>,
+}
+
+async fn chat<A: API + 'static>(
+    State(server): State<Arc<ApiServer<A>>>,
+    Json(body): Json<ChatBody>,
+) -> Result<Sse<impl Stream<Item = Result<SseEvent, axum::Error>>>, ApiError> {
+    let api = server.api.clone();
+    let id = match body.conversation_id {
+        Some(id) => {
+            if api.conversation(&id).await?.is_none() {
+                return Err(ApiError::not_found(&id));
+            }
+            id
+        }
+        None => {
+            let conversation = Conversation::generate();
+            let id = conversation.id;
+            api.upsert_conversation(conversation).await?;
+            id
+        }
+    };
+    let guard = server.claim(id).ok_or_else(|| {
+        ApiError::new(
+            StatusCode::CONFLICT,
+            format!("Conversation {id} is already running a chat"),
+        )
+    })?;
+
+    let mut request = ChatRequest::new(Event::new(body.prompt), id);
+    request.agent_id = body.agent_id;
+    let mut stream = api.chat(request).await?;
+
+    let (tx, rx) = mpsc::channel(32);
+    tokio::spawn(async move {
+        let status = relay(&mut stream, &tx, |reply| guard.follow_up(reply)).await;
+        drop(guard);
+        // Dropping the stream aborts the chat when the client disconnected
+        drop(stream);
+        let usage = api
+            .conversation(&id)
+            .await
+            .ok()
+            .flatten()
+            .and_then(|conversation| conversation.accumulated_usage());
+        let _ = tx.send(OutputEvent::result(status, Some(id), usage)).await;
+    });
+
+    let events = ReceiverStream::new(rx).map(|event| SseEvent::default().json_data(event));
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+/// Answers the follow-up question the chat of the conversation waits on. The
+/// body is the answer, a string or a list of selected options, `null`
+/// dismisses the question.
+async fn answer_follow_up<A: API + 'static>(
+    State(server): State<Arc<ApiServer<A>>>,
+    Path(id): Path<ConversationId>,
+    Json(answer): Json<Option<FollowUpAnswer>>,
+) -> Result<StatusCode, ApiError> {
+    let reply = server.take_follow_up(&id).ok_or_else(|| {
+        ApiError::new(
+            StatusCode::CONFLICT,
+            format!("Conversation {id} has no follow-up question waiting for an answer"),
+        )
+    })?;
+    if reply.send(answer) {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(ApiError::new(
+            StatusCode::CONFLICT,
+            format!("The chat of conversation {id} has ended"),
+        ))
+    }
+}
+
+/// Forwards the chat responses as events and returns how the run ended.
+/// Follow-up questions are handed to `on_follow_up` to be answered.
+async fn relay<S>(
+    stream: &mut S,
+    tx: &mpsc::Sender<OutputEvent>,
+    mut on_follow_up: impl FnMut(FollowUpReply),
+) -> Status
+where
+    S: Stream<Item = anyhow::Result<ChatResponse>> + Unpin,
+{
+    let mut status = Status::Completed;
+    while let Some(response) = stream.next().await {
+        let event = match response {
+            Ok(response) if response.is_empty() => continue,
+            Ok(ChatResponse::FollowUp { question, reply }) => {
+                on_follow_up(reply.clone());
+                OutputEvent::from(ChatResponse::FollowUp { question, reply })
+            }
+            Ok(response) => OutputEvent::from(response),
+            Err(error) => {
+                status = Status::Error;
+                OutputEvent::Error { message: format!("{error:#}") }
+            }
+        };
+        if let OutputEvent::Interrupt { reason, .. } = &event {
+            status = *reason;
+        }
+        if tx.send(event).await.is_err() {
+            return Status::Cancelled;
+        }
+        if status == Status::Error {
+            break;
+        }
+    }
+    status
+}
+
+#[cfg(test)]
+mod tests {
+    use paws_domain::{BudgetExceeded, BudgetScope, FollowUpQuestion, InterruptionReason};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    async fn relay_all(responses: Vec<anyhow::Result<ChatResponse>>) -> (Status, Vec<OutputEvent>) {
+        let (tx, mut rx) = mpsc::channel(responses.len() + 1);
+        let status = relay(&mut tokio_stream::iter(responses), &tx, |_| {}).await;
+        drop(tx);
+        let mut events = Vec::new();
+        while let Some(event) = rx.recv().await {
+            events.push(event);
+        }
+        (status, events)
+    }
+
+    #[test]
+    fn test_token_matches() {
+        let actual = (
+            token_matches("secret", "secret"),
+            token_matches("secreT", "secret"),
+            token_matches("secret2", "secret"),
+            token_matches("", "secret"),
+        );
+        let expected = (true, false, false, false);
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_chat_body_defaults() {
+        let actual: ChatBody = serde_json::from_str(r#"{"prompt": "hi"}"#).unwrap();
+        let expected = ChatBody {
+            prompt: "hi".to_string(),
+            conversation_id: None,
+            agent_id: None,
+        };
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_relay_skips_empty_responses() {
+        let (actual, events) = relay_all(vec![
+            Ok(ChatResponse::TaskReasoning { content: String::new() }),
+            Ok(ChatResponse::TaskReasoning { content: "Thinking".to_string() }),
+            Ok(ChatResponse::TaskComplete),
+        ])
+        .await;
+
+        assert_eq!(actual, Status::Completed);
+        assert_eq!(
+            events,
+            vec![
+                OutputEvent::Reasoning { content: "Thinking".to_string() },
+                OutputEvent::TaskComplete,
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_relay_reports_interruption() {
+        let (actual, _) = relay_all(vec![Ok(ChatResponse::Interrupt {
+            reason: InterruptionReason::BudgetLimitReached {
+                budget: BudgetExceeded::Tokens { scope: BudgetScope::Turn, limit: 100, spent: 120 },
+            },
+        })])
+        .await;
+
+        assert_eq!(actual, Status::Budget);
+    }
+
+    #[tokio::test]
+    async fn test_relay_stops_on_error() {
+        let (actual, events) = relay_all(vec![
+            Err(anyhow::anyhow!("Provider unavailable")),
+            Ok(ChatResponse::TaskComplete),
+        ])
+        .await;
+
+        assert_eq!(actual, Status::Error);
+        assert_eq!(
+            events,
+            vec![OutputEvent::Error { message: "Provider unavailable".to_string() }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_relay_hands_follow_up_for_answer() {
+        let (reply, answer) = FollowUpReply::channel();
+        let (tx, mut rx) = mpsc::channel(1);
+        let question = FollowUpQuestion::new("Proceed?");
+        let responses = vec![Ok(ChatResponse::FollowUp { question, reply })];
+
+        let status = relay(&mut tokio_stream::iter(responses), &tx, |reply| {
+            reply.send(Some(FollowUpAnswer::Text("yes".to_string())));
+        })
+        .await;
+
+        let expected = OutputEvent::FollowUp {
+            question: "Proceed?".to_string(),
+            options: vec![],
+            multiple: false,
+        };
+        assert_eq!(status, Status::Completed);
+        assert_eq!(rx.recv().await, Some(expected));
+        assert_eq!(
+            answer.await.unwrap(),
+            Some(FollowUpAnswer::Text("yes".to_string()))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_relay_cancels_when_client_disconnects() {
+        let (tx, rx) = mpsc::channel(1);
+        drop(rx);
+
+        let actual = relay(
+            &mut tokio_stream::iter(vec![Ok(ChatResponse::TaskComplete)]),
+            &tx,
+            |_| {},
+        )
+        .await;
+
+        assert_eq!(actual, Status::Cancelled);
+    }
+}
@@ -1,7 +1,7 @@
 use std::path::PathBuf;
 
 use derive_setters::Setters;
-use paws_api::{ConversationId, Environment};
+use paws_api::{Budget, ConversationId, Environment};
 
 //TODO: UIState and PawsPrompt seem like the same thing and can be merged
 /// State information for the UI
@@ -10,10 +10,16 @@ use paws_api::{ConversationId, Environment};
 pub struct UIState {
     pub cwd: PathBuf,
     pub conversation_id: Option<ConversationId>,
+    /// Limits raised by the user after a budget was reached
+    pub budget: Option<Budget>,
 }
 
 impl UIState {
     pub fn new(env: Environment) -> Self {
-        Self { cwd: env.cwd, conversation_id: Default::default() }
+        Self {
+            cwd: env.cwd,
+            conversation_id: Default::default(),
+            budget: Default::default(),
+        }
     }
 }
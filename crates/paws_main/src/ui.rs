@@ -1,5 +1,7 @@
 use std::collections::HashMap;
 use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
@@ -9,18 +11,20 @@ use colored::Colorize;
 use convert_case::{Case, Casing};
 use merge::Merge;
 use paws_api::{
-    API, AgentId, AnyProvider, ApiKeyRequest, AuthContextRequest, AuthContextResponse, ChatRequest,
-    ChatResponse, CodeRequest, Conversation, ConversationId, DeviceCodeRequest, Event,
-    InterruptionReason, Model, ModelId, Provider, ProviderId, TextMessage, UserPrompt, Workflow,
+    API, AgentId, AnyProvider, ApiKeyRequest, AuthContextRequest, AuthContextResponse,
+    BudgetExceeded, ChatRequest, ChatResponse, CodeRequest, Conversation, ConversationId,
+    DeviceCodeRequest, Event, InterruptionReason, Model, ModelId, Provider, ProviderId, ServerName,
+    TextMessage, Usage, UserPrompt, Workflow,
 };
 use paws_app::ToolResolver;
 use paws_app::utils::{format_display_path, truncate_key};
-use paws_common::display::MarkdownWriter;
+use paws_common::display::{DiffFormat, MarkdownWriter};
 use paws_common::fs::PawsFS;
 use paws_common::select::PawsSelect;
 use paws_common::spinner::SpinnerManager;
 use paws_domain::{
-    AuthMethod, ChatResponseContent, ContextMessage, Role, TitleFormat, UserCommand,
+    AuthMethod, ChatResponseContent, ContextMessage, FollowUpQuestion, FollowUpReply, Role,
+    Snapshot, TitleFormat, UserCommand,
 };
 use tokio_stream::StreamExt;
 use tracing::debug;
@@ -28,15 +32,20 @@ use url::Url;
 
 use crate::banner;
 use crate::cli::{
-    Cli, ConversationCommand, ExtensionCommand, ListCommand, McpCommand, TopLevelCommand,
+    Cli, ConversationCommand, ExtensionCommand, IndexCommand, ListCommand, McpCommand,
+    SnapshotCommand, TopLevelCommand,
 };
 use crate::conversation_selector::ConversationSelector;
 use crate::display_constants::{CommandType, headers, markers, status};
+use crate::follow_up::{FollowUpAnswers, FollowUpPolicy};
 use crate::info::Info;
 use crate::input::Console;
+use crate::mcp_serve::McpServe;
 use crate::model::{CliModel, CliProvider, PawsCommandManager, SlashCommand};
+use crate::output::HeadlessOutput;
 use crate::porcelain::Porcelain;
 use crate::prompt::PawsPrompt;
+use crate::server::ApiServer;
 use crate::state::UIState;
 use crate::title_display::TitleDisplayExt;
 use crate::tools_display::format_tools;
@@ -95,6 +104,10 @@ pub struct UI<A, F: Fn() -> A> {
     spinner: SpinnerManager,
     ctrl_c_rx: tokio::sync::broadcast::Receiver<()>,
     thinking_start: Option<std::time::Instant>,
+    /// Machine-readable output replacing the rendered one, see
+    /// `--output-format`
+    output: Option<HeadlessOutput<std::io::Stdout>>,
+    follow_up: FollowUpAnswers,
     #[allow(dead_code)] // The guard is kept alive by being held in the struct
     _guard: paws_services::log::Guard,
 }
@@ -210,7 +223,13 @@ impl<A: API + 'static, F: Fn() -> A + Send + Sync> UI<A, F> {
         let api = Arc::new(f());
         let env = api.environment();
         let command = Arc::new(PawsCommandManager::default());
+        let output = HeadlessOutput::new(cli.output_format, std::io::stdout());
+        let follow_up =
+            FollowUpAnswers::new(cli.follow_up.clone().unwrap_or_else(FollowUpPolicy::detect));
         let mut spinner = SpinnerManager::new();
+        if output.is_some() {
+            spinner = spinner.quiet();
+        }
         let ctrl_c_rx = spinner.init()?;
         Ok(Self {
             state: Default::default(),
@@ -223,6 +242,8 @@ impl<A: API + 'static, F: Fn() -> A + Send + Sync> UI<A, F> {
             ctrl_c_rx,
             markdown: MarkdownWriter::new(),
             thinking_start: None,
+            output,
+            follow_up,
             _guard: paws_services::log::init_tracing(env.log_path())?,
         })
     }
@@ -253,11 +274,12 @@ impl<A: API + 'static, F: Fn() -> A + Send + Sync> UI<A, F> {
         Ok(command)
     }
 
-    pub async fn run(&mut self) {
+    pub async fn run(&mut self) -> ExitCode {
         match self.run_inner().await {
             Ok(_) => {}
             Err(error) => {
                 tracing::error!(error = ?error);
+                self.on_headless_error(&error);
 
                 // Display the full error chain for better debugging
                 let mut error_message = error.to_string();
@@ -271,12 +293,45 @@ impl<A: API + 'static, F: Fn() -> A + Send + Sync> UI<A, F> {
                     self.writeln_to_stderr(TitleFormat::error(error_message).display().to_string());
             }
         }
+
+        let Some(output) = self.output.take() else {
+            return ExitCode::SUCCESS;
+        };
+        let conversation_id = self.state.conversation_id;
+        let usage = match conversation_id {
+            Some(id) => self
+                .api
+                .conversation(&id)
+                .await
+                .ok()
+                .flatten()
+                .and_then(|conversation| conversation.accumulated_usage()),
+            None => None,
+        };
+        output
+            .finish(conversation_id, usage)
+            .unwrap_or_else(|error| {
+                tracing::error!(error = ?error, "Failed to write the output");
+                ExitCode::FAILURE
+            })
+    }
+
+    /// Reports `error` in the machine-readable output, if any
+    fn on_headless_error(&mut self, error: &anyhow::Error) {
+        if let Some(output) = self.output.as_mut()
+            && let Err(error) = output.fail(error)
+        {
+            tracing::error!(error = ?error, "Failed to write the output");
+        }
     }
 
     async fn run_inner(&mut self) -> Result<()> {
         if let Some(cmd) = self.cli.subcommands.clone() {
             return self.handle_subcommands(cmd).await;
         }
+        if self.output.is_some() && self.cli.is_interactive() {
+            anyhow::bail!("--output-format requires a prompt, pass one with --prompt or stdin");
+        }
 
         // Display the banner in dimmed colors since we're in interactive mode
         self.display_banner()?;
@@ -310,6 +365,9 @@ impl<A: API + 'static, F: Fn() -> A + Send + Sync> UI<A, F> {
                     tokio::select! {
                         _ = tokio::signal::ctrl_c() => {
                             tracing::info!("User interrupted operation with Ctrl+C");
+                            if let Some(output) = self.output.as_mut() {
+                                output.cancel();
+                            }
                         }
                         _ = ctrl_c_rx.recv() => {
                             tracing::info!("User interrupted operation with Ctrl+C (spinner)");
@@ -322,6 +380,7 @@ impl<A: API + 'static, F: Fn() -> A + Send + Sync> UI<A, F> {
                                 },
                                 Err(error) => {
                                     tracing::error!(error = ?error);
+                                    self.on_headless_error(&error);
                                     self.spinner.stop(None)?;
                                     self.writeln_to_stderr(TitleFormat::error(format!("{error:?}")).display().to_string())?;
                                     if !is_interactive { return Ok(()) }
@@ -332,6 +391,7 @@ impl<A: API + 'static, F: Fn() -> A + Send + Sync> UI<A, F> {
                 }
                 Err(error) => {
                     tracing::error!(error = ?error);
+                    self.on_headless_error(&error);
                     self.spinner.stop(None)?;
                     self.writeln_to_stderr(
                         TitleFormat::error(error.to_string()).display().to_string(),
@@ -491,12 +551,36 @@ impl<A: API + 'static, F: Fn() -> A + Send + Sync> UI<A, F> {
                         self.writeln_title(TitleFormat::error(error))?;
                     }
                 }
+                McpCommand::Serve(args) => {
+                    let server = McpServe::new(self.api.clone());
+                    match args.http {
+                        Some(addr) => {
+                            let token = args
+                                .token
+                                .unwrap_or_else(|| uuid::Uuid::new_v4().simple().to_string());
+                            eprintln!("Serving MCP on http://{addr}/mcp with token {token}");
+                            server.serve_http(addr, token).await?
+                        }
+                        None => server.serve_stdio().await?,
+                    }
+                }
                 McpCommand::Reload => {
                     self.spinner.start(Some("Reloading MCPs"))?;
                     self.api.reload_mcp().await?;
                     self.writeln_title(TitleFormat::info("MCP reloaded"))?;
                 }
             },
+            TopLevelCommand::Serve(args) => {
+                let token = args
+                    .token
+                    .unwrap_or_else(|| uuid::Uuid::new_v4().simple().to_string());
+                // stdout stays free for whatever launched the server
+                eprintln!("Serving on http://{} with token {token}", args.addr);
+                ApiServer::new(self.api.clone(), token)
+                    .serve(args.addr)
+                    .await?;
+                return Ok(());
+            }
             TopLevelCommand::Info { porcelain, conversation_id } => {
                 // Make sure to init model
                 self.on_new().await?;
@@ -508,6 +592,10 @@ impl<A: API + 'static, F: Fn() -> A + Send + Sync> UI<A, F> {
                 self.on_env().await?;
                 return Ok(());
             }
+            TopLevelCommand::Usage { days, porcelain } => {
+                self.on_usage_report(days, porcelain).await?;
+                return Ok(());
+            }
             TopLevelCommand::Banner => {
                 banner::display(true)?;
                 return Ok(());
@@ -525,6 +613,14 @@ impl<A: API + 'static, F: Fn() -> A + Send + Sync> UI<A, F> {
                 self.handle_conversation_command(conversation_group).await?;
                 return Ok(());
             }
+            TopLevelCommand::Snapshot(snapshot_group) => {
+                self.handle_snapshot_command(snapshot_group).await?;
+                return Ok(());
+            }
+            TopLevelCommand::Index(index_group) => {
+                self.handle_index_command(index_group).await?;
+                return Ok(());
+            }
             TopLevelCommand::Suggest { prompt } => {
                 self.on_cmd(UserPrompt::from(prompt)).await?;
                 return Ok(());
@@ -582,6 +678,9 @@ impl<A: API + 'static, F: Fn() -> A + Send + Sync> UI<A, F> {
             ConversationCommand::List { porcelain } => {
                 self.on_show_conversations(porcelain).await?;
             }
+            ConversationCommand::Search { query, porcelain } => {
+                self.on_search_conversations(&query, porcelain).await?;
+            }
             ConversationCommand::New => {
                 self.handle_generate_conversation_id().await?;
             }
@@ -607,6 +706,17 @@ impl<A: API + 'static, F: Fn() -> A + Send + Sync> UI<A, F> {
 
                 self.state.conversation_id = original_id;
             }
+            ConversationCommand::Rewind { id, to } => {
+                self.validate_conversation_exists(&id).await?;
+
+                let original_id = self.state.conversation_id;
+                self.state.conversation_id = Some(id);
+
+                self.spinner.start(Some("Rewinding"))?;
+                self.on_rewind(to).await?;
+
+                self.state.conversation_id = original_id;
+            }
             ConversationCommand::Delete { id } => {
                 let conversation_id =
                     ConversationId::parse(&id).context(format!("Invalid conversation ID: {id}"))?;
@@ -666,6 +776,206 @@ impl<A: API + 'static, F: Fn() -> A + Send + Sync> UI<A, F> {
         Ok(())
     }
 
+    async fn handle_snapshot_command(
+        &mut self,
+        snapshot_group: crate::cli::SnapshotCommandGroup,
+    ) -> anyhow::Result<()> {
+        let cwd = self.api.environment().cwd;
+        match snapshot_group.command {
+            SnapshotCommand::List { path, porcelain } => {
+                self.on_show_snapshots(cwd.join(path), porcelain).await?;
+            }
+            SnapshotCommand::Show { path, version } => {
+                let path = cwd.join(path);
+                let snapshot = self.find_snapshot(&path, version).await?;
+                let before = self.read_snapshot_content(&snapshot).await?;
+                let after = Self::read_current_content(&path).await?;
+
+                let diff = DiffFormat::format(&before, &after);
+                if diff.diff().is_empty() {
+                    self.writeln_title(TitleFormat::info(format!(
+                        "Version {version} matches the current file"
+                    )))?;
+                } else {
+                    self.writeln(diff.diff())?;
+                }
+            }
+            SnapshotCommand::Restore { path, version } => {
+                let path = cwd.join(path);
+                let snapshot = self.find_snapshot(&path, version).await?;
+                self.api.restore_snapshot(&snapshot).await?;
+                self.writeln_title(
+                    TitleFormat::action(format!(
+                        "Restored {} to version {version}",
+                        format_display_path(&path, &cwd)
+                    ))
+                    .sub_title(Self::format_snapshot_time(&snapshot)),
+                )?;
+            }
+            SnapshotCommand::Redo { path } => {
+                let path = cwd.join(path);
+                self.api.redo_snapshot(&path).await?;
+                self.writeln_title(TitleFormat::action(format!(
+                    "Redone last undo of {}",
+                    format_display_path(&path, &cwd)
+                )))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn handle_index_command(
+        &mut self,
+        index_group: crate::cli::IndexCommandGroup,
+    ) -> anyhow::Result<()> {
+        match index_group.command {
+            IndexCommand::Build => {
+                self.spinner.start(Some("Indexing"))?;
+                let status = self.api.build_index().await?;
+                self.spinner.stop(None)?;
+
+                let changes = &status.changes;
+                self.writeln_title(
+                    TitleFormat::action(format!(
+                        "Indexed {} files into {} chunks",
+                        status.files, status.chunks
+                    ))
+                    .sub_title(format!(
+                        "{} added, {} modified, {} removed",
+                        changes.added, changes.modified, changes.removed
+                    )),
+                )?;
+            }
+            IndexCommand::Status { porcelain } => {
+                self.spinner.start(Some("Scanning"))?;
+                let status = self.api.index_status().await?;
+                self.spinner.stop(None)?;
+
+                let updated_at = status.updated_at.map_or("never".to_string(), |time| {
+                    time.with_timezone(&chrono::Local)
+                        .format("%Y-%m-%d %H:%M:%S")
+                        .to_string()
+                });
+                let changes = &status.changes;
+                let info = Info::new()
+                    .add_title("INDEX")
+                    .add_key_value("Path", status.path.display().to_string())
+                    .add_key_value("Embedder", &status.embedder)
+                    .add_key_value("Updated", updated_at)
+                    .add_key_value("Files", status.files.to_string())
+                    .add_key_value("Chunks", status.chunks.to_string())
+                    .add_title("PENDING")
+                    .add_key_value("Added", changes.added.to_string())
+                    .add_key_value("Modified", changes.modified.to_string())
+                    .add_key_value("Removed", changes.removed.to_string());
+
+                if porcelain {
+                    self.writeln(Porcelain::from(&info))?;
+                } else {
+                    self.writeln(info)?;
+                }
+            }
+            IndexCommand::Clear => {
+                if self.api.clear_index().await? {
+                    self.writeln_title(TitleFormat::action("Cleared the index"))?;
+                } else {
+                    self.writeln_title(TitleFormat::info("The workspace is not indexed"))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Finds the snapshot of `path` with the given version, versions are
+    /// numbered from the most recent snapshot starting at 1
+    async fn find_snapshot(&self, path: &Path, version: usize) -> anyhow::Result<Snapshot> {
+        let mut snapshots = self.api.list_snapshots(path).await?;
+        let count = snapshots.len();
+        version
+            .checked_sub(1)
+            .and_then(|index| count.checked_sub(index + 1))
+            .map(|index| snapshots.swap_remove(index))
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Snapshot version {version} not found for {}, {count} versions available",
+                    path.display()
+                )
+            })
+    }
+
+    async fn read_snapshot_content(&self, snapshot: &Snapshot) -> anyhow::Result<String> {
+        let content = self
+            .api
+            .read_snapshot(snapshot)
+            .await?
+            .context(format!("Snapshot of {} no longer exists", snapshot.path))?;
+        Ok(String::from_utf8_lossy(&content).into_owned())
+    }
+
+    /// Reads the current content of a file, empty if the file doesn't exist
+    async fn read_current_content(path: &Path) -> anyhow::Result<String> {
+        if !PawsFS::exists(path) {
+            return Ok(String::new());
+        }
+        Ok(String::from_utf8_lossy(&PawsFS::read(path).await?).into_owned())
+    }
+
+    fn format_snapshot_time(snapshot: &Snapshot) -> String {
+        snapshot
+            .created_at()
+            .with_timezone(&chrono::Local)
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string()
+    }
+
+    async fn on_show_snapshots(&mut self, path: PathBuf, porcelain: bool) -> anyhow::Result<()> {
+        let snapshots = self.api.list_snapshots(&path).await?;
+
+        if snapshots.is_empty() {
+            if !porcelain {
+                self.writeln_title(TitleFormat::info(format!(
+                    "No snapshots found for {}",
+                    path.display()
+                )))?;
+            }
+            return Ok(());
+        }
+
+        let current = Self::read_current_content(&path).await?;
+        let mut info = Info::new();
+
+        for (index, snapshot) in snapshots.iter().rev().enumerate() {
+            let content = self.read_snapshot_content(snapshot).await?;
+            let diff = DiffFormat::format(&content, &current);
+
+            // Add snapshot: Created=<time>, Size=<bytes>, Changes=<diff stats>, with the
+            // version as section title
+            let size = if snapshot.removed {
+                "file removed".to_string()
+            } else {
+                format!("{} bytes", content.len())
+            };
+            info = info
+                .add_title(index + 1)
+                .add_key_value("Created", Self::format_snapshot_time(snapshot))
+                .add_key_value("Size", size)
+                .add_key_value(
+                    "Changes",
+                    format!("+{} -{}", diff.lines_added(), diff.lines_removed()),
+                );
+        }
+
+        if porcelain {
+            self.writeln(Porcelain::from(&info).uppercase_headers())?;
+        } else {
+            self.writeln(info)?;
+        }
+
+        Ok(())
+    }
+
     async fn validate_conversation_exists(
         &self,
         conversation_id: &ConversationId,
@@ -1371,8 +1681,13 @@ impl<A: API + 'static, F: Fn() -> A + Send + Sync> UI<A, F> {
             return Ok(());
         }
 
+        let api = self.api.clone();
+        let search = move |query: String| async move {
+            api.search_conversations(&query, Some(max_conversations))
+                .await
+        };
         if let Some(conversation) =
-            ConversationSelector::select_conversation(&conversations).await?
+            ConversationSelector::select_conversation(&conversations, search).await?
         {
             let conversation_id = conversation.id;
             self.state.conversation_id = Some(conversation_id);
@@ -1407,17 +1722,7 @@ impl<A: API + 'static, F: Fn() -> A + Send + Sync> UI<A, F> {
                 .map(|t| t.to_string())
                 .unwrap_or_else(|| markers::EMPTY.to_string());
 
-            // Format time using humantime library (same as conversation_selector.rs)
-            let duration = chrono::Utc::now().signed_duration_since(
-                conv.metadata.updated_at.unwrap_or(conv.metadata.created_at),
-            );
-            let duration =
-                std::time::Duration::from_secs((duration.num_minutes() * 60).max(0) as u64);
-            let time_ago = if duration.is_zero() {
-                "now".to_string()
-            } else {
-                format!("{} ago", humantime::format_duration(duration))
-            };
+            let time_ago = ConversationSelector::time_ago(&conv, chrono::Utc::now());
 
             // Add conversation: Title=<title>, Updated=<time_ago>, with ID as section title
             info = info
@@ -1440,6 +1745,86 @@ impl<A: API + 'static, F: Fn() -> A + Send + Sync> UI<A, F> {
         Ok(())
     }
 
+    async fn on_usage_report(&mut self, days: u32, porcelain: bool) -> anyhow::Result<()> {
+        let first_day = chrono::Utc::now().date_naive() - chrono::Days::new(u64::from(days) - 1);
+        let since = first_day.and_time(chrono::NaiveTime::MIN).and_utc();
+        let entries = self.api.usage_report(since).await?;
+
+        if entries.is_empty() {
+            if !porcelain {
+                self.writeln_title(TitleFormat::info(format!(
+                    "No usage recorded in the last {days} days."
+                )))?;
+            }
+            return Ok(());
+        }
+
+        let info = Info::from(entries.as_slice());
+        if porcelain {
+            self.writeln(
+                Porcelain::from(&info)
+                    .rename_header(0, "date")
+                    .uppercase_headers(),
+            )?;
+        } else {
+            let total = entries.iter().fold(Usage::default(), |total, entry| {
+                total.accumulate(&entry.usage)
+            });
+            let info = info
+                .add_title("TOTAL")
+                .add_key_value("Input Tokens", total.prompt_tokens.to_string())
+                .add_key_value("Cached Tokens", total.cached_tokens.to_string())
+                .add_key_value("Output Tokens", total.completion_tokens.to_string())
+                .add_key_value("Cost", total.cost.map(|cost| format!("${cost:.4}")));
+            self.writeln(info)?;
+        }
+
+        Ok(())
+    }
+
+    async fn on_search_conversations(
+        &mut self,
+        query: &str,
+        porcelain: bool,
+    ) -> anyhow::Result<()> {
+        let max_conversations = self.api.environment().max_conversations;
+        let matches = self
+            .api
+            .search_conversations(query, Some(max_conversations))
+            .await?;
+
+        if matches.is_empty() {
+            if !porcelain {
+                self.writeln_title(TitleFormat::error(format!(
+                    "No conversations match '{query}'."
+                )))?;
+            }
+            return Ok(());
+        }
+
+        let mut info = Info::new();
+        for found in matches {
+            let conv = found.conversation;
+            let time_ago = ConversationSelector::time_ago(&conv, chrono::Utc::now());
+            let title = conv.title.unwrap_or_else(|| markers::EMPTY.to_string());
+
+            info = info
+                .add_title(conv.id)
+                .add_key_value("Title", title)
+                .add_key_value("Updated", time_ago)
+                .add_key_value("Match", found.snippet.replace('\n', " "));
+        }
+
+        if porcelain {
+            let porcelain = Porcelain::from(&info).truncate(1, 60).uppercase_headers();
+            self.writeln(porcelain)?;
+        } else {
+            self.writeln(info)?;
+        }
+
+        Ok(())
+    }
+
     async fn on_command(&mut self, command: SlashCommand) -> anyhow::Result<bool> {
         match command {
             SlashCommand::Conversations => {
@@ -1449,6 +1834,10 @@ impl<A: API + 'static, F: Fn() -> A + Send + Sync> UI<A, F> {
                 self.spinner.start(Some("Compacting"))?;
                 self.on_compaction().await?;
             }
+            SlashCommand::Rewind { turn } => {
+                self.spinner.start(Some("Rewinding"))?;
+                self.on_rewind(turn).await?;
+            }
             SlashCommand::Delete => {
                 self.handle_delete_conversation().await?;
             }
@@ -1500,6 +1889,19 @@ impl<A: API + 'static, F: Fn() -> A + Send + Sync> UI<A, F> {
                 self.spinner.start(None)?;
                 self.on_custom_event(event.into()).await?;
             }
+            SlashCommand::McpPrompt { server, name, arguments } => {
+                self.spinner.start(None)?;
+                let messages = self
+                    .api
+                    .render_mcp_prompt(&ServerName::from(server), &name, arguments)
+                    .await?;
+                let content = messages
+                    .into_iter()
+                    .map(|message| message.text)
+                    .collect::<Vec<_>>()
+                    .join("\n\n");
+                self.on_message(Some(content)).await?;
+            }
             SlashCommand::Model => {
                 self.on_model_selection().await?;
             }
@@ -1602,6 +2004,19 @@ impl<A: API + 'static, F: Fn() -> A + Send + Sync> UI<A, F> {
         Ok(())
     }
 
+    async fn on_rewind(&mut self, turn: Option<usize>) -> anyhow::Result<()> {
+        let conversation_id = self.init_conversation().await?;
+        let result = self.api.rewind_conversation(&conversation_id, turn).await?;
+        let content =
+            TitleFormat::action(format!("Rewound to turn {}", result.turn)).sub_title(format!(
+                "{} files restored, {} messages removed",
+                result.restored_files.len(),
+                result.removed_messages
+            ));
+        self.writeln_title(content)?;
+        Ok(())
+    }
+
     async fn handle_delete_conversation(&mut self) -> anyhow::Result<()> {
         let conversation_id = self.init_conversation().await?;
         self.on_conversation_delete(conversation_id).await?;
@@ -2219,8 +2634,11 @@ impl<A: API + 'static, F: Fn() -> A + Send + Sync> UI<A, F> {
         }
 
         // Execute independent operations in parallel to improve performance
-        let (agents_result, commands_result) =
-            tokio::join!(self.api.get_agents(), self.api.get_commands());
+        let (agents_result, commands_result, mcp_result) = tokio::join!(
+            self.api.get_agents(),
+            self.api.get_commands(),
+            self.api.get_mcp_servers()
+        );
 
         // Register agent commands with proper error handling and user feedback
         match agents_result {
@@ -2244,6 +2662,12 @@ impl<A: API + 'static, F: Fn() -> A + Send + Sync> UI<A, F> {
         // Register all the commands
         self.command.register_all(commands_result?);
 
+        // MCP prompts and resources are optional, failing servers are reported by
+        // `paws list mcp`
+        if let Ok(mcp_servers) = mcp_result {
+            self.command.register_mcp(&mcp_servers);
+        }
+
         self.state = UIState::new(self.api.environment());
         self.update_model(operating_model);
 
@@ -2281,7 +2705,8 @@ impl<A: API + 'static, F: Fn() -> A + Send + Sync> UI<A, F> {
         self.on_chat(chat).await
     }
 
-    async fn on_chat(&mut self, chat: ChatRequest) -> Result<()> {
+    async fn on_chat(&mut self, mut chat: ChatRequest) -> Result<()> {
+        chat.budget = self.state.budget.clone();
         let mut stream = self.api.chat(chat).await?;
 
         while let Some(message) = stream.next().await {
@@ -2300,6 +2725,24 @@ impl<A: API + 'static, F: Fn() -> A + Send + Sync> UI<A, F> {
         Ok(())
     }
 
+    async fn on_follow_up(
+        &mut self,
+        question: FollowUpQuestion,
+        reply: FollowUpReply,
+    ) -> Result<()> {
+        if let Some(output) = self.output.as_mut() {
+            output.push(ChatResponse::FollowUp {
+                question: question.clone(),
+                reply: reply.clone(),
+            })?;
+        }
+        self.spinner.stop(None)?;
+        // An error drops the reply, dismissing the question
+        let answer = self.follow_up.answer(&question).await?;
+        reply.send(answer);
+        Ok(())
+    }
+
     /// Modified version of handle_dump that supports HTML format
     async fn on_dump(&mut self, html: bool) -> Result<()> {
         if let Some(conversation_id) = self.state.conversation_id {
@@ -2348,6 +2791,12 @@ impl<A: API + 'static, F: Fn() -> A + Send + Sync> UI<A, F> {
 
     async fn handle_chat_response(&mut self, message: ChatResponse) -> Result<()> {
         debug!(chat_response = ?message, "Chat Response");
+        if let Some(output) = self.output.as_mut()
+            && !matches!(message, ChatResponse::FollowUp { .. })
+        {
+            // Interruptions end the run instead of asking whether to continue
+            return output.push(message);
+        }
         if message.is_empty() {
             return Ok(());
         }
@@ -2374,26 +2823,37 @@ impl<A: API + 'static, F: Fn() -> A + Send + Sync> UI<A, F> {
                     return Ok(());
                 }
             }
-            ChatResponse::RetryAttempt { cause, duration: _ } => {
+            ChatResponse::RetryAttempt { cause, .. } => {
                 if !self.api.environment().retry_config.suppress_retry_errors {
                     self.spinner.start(Some("Retrying"))?;
                     self.writeln_title(TitleFormat::error(cause.as_str()))?;
                 }
             }
+            ChatResponse::FollowUp { question, reply } => {
+                self.on_follow_up(question, reply).await?;
+            }
             ChatResponse::Interrupt { reason } => {
                 self.spinner.stop(None)?;
 
-                let title = match reason {
+                let title = match &reason {
                     InterruptionReason::MaxRequestPerTurnLimitReached { limit } => {
                         format!("Maximum request ({limit}) per turn achieved")
                     }
                     InterruptionReason::MaxToolFailurePerTurnLimitReached { limit, .. } => {
                         format!("Maximum tool failure limit ({limit}) reached for this turn")
                     }
+                    InterruptionReason::BudgetLimitReached { budget } => {
+                        format!("Budget limit reached: {budget}")
+                    }
                 };
 
                 self.writeln_title(TitleFormat::action(title))?;
-                self.should_continue().await?;
+                match reason {
+                    InterruptionReason::BudgetLimitReached { budget } => {
+                        self.should_extend_budget(budget).await?
+                    }
+                    _ => self.should_continue().await?,
+                }
             }
             ChatResponse::TaskReasoning { content } => {
                 if !content.trim().is_empty() {
@@ -2430,6 +2890,21 @@ impl<A: API + 'static, F: Fn() -> A + Send + Sync> UI<A, F> {
         Ok(())
     }
 
+    async fn should_extend_budget(&mut self, exceeded: BudgetExceeded) -> anyhow::Result<()> {
+        let should_extend = PawsSelect::confirm("Do you want to extend the budget and continue?")
+            .with_default(false)
+            .prompt()?;
+
+        if should_extend.unwrap_or(false) {
+            let budget = self.state.budget.take().unwrap_or_default();
+            self.state.budget = Some(budget.extend(&exceeded));
+            self.spinner.start(None)?;
+            Box::pin(self.on_message(None)).await?;
+        }
+
+        Ok(())
+    }
+
     async fn should_continue(&mut self) -> anyhow::Result<()> {
         let should_continue = PawsSelect::confirm("Do you want to continue anyway?")
             .with_default(true)
@@ -203,6 +203,12 @@ impl From<&paws_domain::TokenCount> for TokenCountRecord {
     }
 }
 
+impl Default for TokenCountRecord {
+    fn default() -> Self {
+        Self::Actual(0)
+    }
+}
+
 impl From<TokenCountRecord> for paws_domain::TokenCount {
     fn from(record: TokenCountRecord) -> Self {
         match record {
@@ -219,6 +225,8 @@ pub(super) struct UsageRecord {
     completion_tokens: TokenCountRecord,
     total_tokens: TokenCountRecord,
     cached_tokens: TokenCountRecord,
+    #[serde(default)]
+    cache_write_tokens: TokenCountRecord,
     #[serde(skip_serializing_if = "Option::is_none")]
     cost: Option<f64>,
 }
@@ -230,6 +238,7 @@ impl From<&paws_domain::Usage> for UsageRecord {
             completion_tokens: TokenCountRecord::from(&usage.completion_tokens),
             total_tokens: TokenCountRecord::from(&usage.total_tokens),
             cached_tokens: TokenCountRecord::from(&usage.cached_tokens),
+            cache_write_tokens: TokenCountRecord::from(&usage.cache_write_tokens),
             cost: usage.cost,
         }
     }
@@ -242,6 +251,7 @@ impl From<UsageRecord> for paws_domain::Usage {
             completion_tokens: record.completion_tokens.into(),
             total_tokens: record.total_tokens.into(),
             cached_tokens: record.cached_tokens.into(),
+            cache_write_tokens: record.cache_write_tokens.into(),
             cost: record.cost,
         }
     }
@@ -811,11 +821,110 @@ pub(super) enum FileOperationOrArray {
     Array(Vec<FileChangeMetricsRecord>),
 }
 
+/// Repository-specific representation of Snapshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct SnapshotRecord {
+    id: String,
+    timestamp: std::time::Duration,
+    path: String,
+    #[serde(default, skip_serializing_if = "is_false")]
+    removed: bool,
+}
+
+impl From<&paws_domain::Snapshot> for SnapshotRecord {
+    fn from(snapshot: &paws_domain::Snapshot) -> Self {
+        Self {
+            id: snapshot.id.to_string(),
+            timestamp: snapshot.timestamp,
+            path: snapshot.path.clone(),
+            removed: snapshot.removed,
+        }
+    }
+}
+
+impl TryFrom<SnapshotRecord> for paws_domain::Snapshot {
+    type Error = anyhow::Error;
+
+    fn try_from(record: SnapshotRecord) -> anyhow::Result<Self> {
+        let id = paws_domain::SnapshotId::parse(&record.id)
+            .with_context(|| format!("Invalid snapshot ID: {}", record.id))?;
+        Ok(Self {
+            id,
+            timestamp: record.timestamp,
+            path: record.path,
+            removed: record.removed,
+        })
+    }
+}
+
+/// Repository-specific representation of FileCheckpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct FileCheckpointRecord {
+    path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    snapshot: Option<SnapshotRecord>,
+}
+
+impl From<&paws_domain::FileCheckpoint> for FileCheckpointRecord {
+    fn from(file: &paws_domain::FileCheckpoint) -> Self {
+        Self {
+            path: file.path.clone(),
+            snapshot: file.snapshot.as_ref().map(Into::into),
+        }
+    }
+}
+
+impl TryFrom<FileCheckpointRecord> for paws_domain::FileCheckpoint {
+    type Error = anyhow::Error;
+
+    fn try_from(record: FileCheckpointRecord) -> anyhow::Result<Self> {
+        Ok(Self::new(
+            record.path,
+            record.snapshot.map(TryInto::try_into).transpose()?,
+        ))
+    }
+}
+
+/// Repository-specific representation of Checkpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct CheckpointRecord {
+    turn: usize,
+    started_at: chrono::DateTime<chrono::Utc>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    files: Vec<FileCheckpointRecord>,
+}
+
+impl From<&paws_domain::Checkpoint> for CheckpointRecord {
+    fn from(checkpoint: &paws_domain::Checkpoint) -> Self {
+        Self {
+            turn: checkpoint.turn,
+            started_at: checkpoint.started_at,
+            files: checkpoint.files.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl TryFrom<CheckpointRecord> for paws_domain::Checkpoint {
+    type Error = anyhow::Error;
+
+    fn try_from(record: CheckpointRecord) -> anyhow::Result<Self> {
+        Ok(Self::new(record.turn, record.started_at).files(
+            record
+                .files
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<anyhow::Result<Vec<_>>>()?,
+        ))
+    }
+}
+
 /// Repository-specific representation of Metrics
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub(super) struct MetricsRecord {
     started_at: Option<chrono::DateTime<chrono::Utc>>,
     files_changed: std::collections::HashMap<String, FileOperationOrArray>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    checkpoints: Vec<CheckpointRecord>,
 }
 
 impl From<&paws_domain::Metrics> for MetricsRecord {
@@ -832,6 +941,7 @@ impl From<&paws_domain::Metrics> for MetricsRecord {
                     )
                 })
                 .collect(),
+            checkpoints: metrics.checkpoints.iter().map(Into::into).collect(),
         }
     }
 }
@@ -857,12 +967,33 @@ impl From<MetricsRecord> for paws_domain::Metrics {
                     Some((path, operation))
                 })
                 .collect(),
+            // Checkpoints referencing malformed snapshots can't be rewound to
+            checkpoints: record
+                .checkpoints
+                .into_iter()
+                .filter_map(|checkpoint| {
+                    let turn = checkpoint.turn;
+                    checkpoint
+                        .try_into()
+                        .inspect_err(|error| {
+                            tracing::warn!(turn, error = %error, "Dropping malformed checkpoint");
+                        })
+                        .ok()
+                })
+                .collect(),
         }
     }
 }
 
 /// Database model for conversations table
-#[derive(Debug, diesel::Queryable, diesel::Selectable, diesel::Insertable, diesel::AsChangeset)]
+#[derive(
+    Debug,
+    diesel::Queryable,
+    diesel::QueryableByName,
+    diesel::Selectable,
+    diesel::Insertable,
+    diesel::AsChangeset,
+)]
 #[diesel(table_name = crate::database::schema::conversations)]
 #[diesel(check_for_backend(diesel::sqlite::Sqlite))]
 pub(super) struct ConversationRecord {
@@ -1,10 +1,16 @@
 use std::sync::Arc;
 
+use chrono::{DateTime, Utc};
 use diesel::prelude::*;
-use paws_domain::{Conversation, ConversationId, ConversationRepository, WorkspaceHash};
+use diesel::sql_types::{BigInt, Nullable, Text, Timestamp};
+use paws_domain::{
+    Conversation, ConversationId, ConversationMatch, ConversationRepository, ModelId, ProviderId,
+    Usage, UsageReportEntry, WorkspaceHash,
+};
 
 use crate::conversation::conversation_record::ConversationRecord;
-use crate::database::schema::conversations;
+use crate::conversation::usage_record::{UsageRecord, UsageReportRecord, UsageTotalRecord};
+use crate::database::schema::{conversations, usage_records};
 use crate::database::DatabasePool;
 
 pub struct ConversationRepositoryImpl {
@@ -12,10 +18,48 @@ pub struct ConversationRepositoryImpl {
     wid: WorkspaceHash,
 }
 
+/// Row of a full-text search, the conversation along with its best matching
+/// excerpt
+#[derive(QueryableByName)]
+struct SearchRecord {
+    #[diesel(embed)]
+    record: ConversationRecord,
+    #[diesel(sql_type = Text)]
+    snippet: String,
+}
+
 impl ConversationRepositoryImpl {
     pub fn new(pool: Arc<DatabasePool>, workspace_id: WorkspaceHash) -> Self {
         Self { pool, wid: workspace_id }
     }
+
+    /// Replaces the full-text index entry of a conversation
+    fn index_conversation(
+        connection: &mut SqliteConnection,
+        conversation: &Conversation,
+    ) -> QueryResult<()> {
+        diesel::sql_query("DELETE FROM conversations_fts WHERE conversation_id = ?")
+            .bind::<Text, _>(conversation.id.into_string())
+            .execute(connection)?;
+        diesel::sql_query(
+            "INSERT INTO conversations_fts (conversation_id, title, content) VALUES (?, ?, ?)",
+        )
+        .bind::<Text, _>(conversation.id.into_string())
+        .bind::<Nullable<Text>, _>(conversation.title.as_deref())
+        .bind::<Text, _>(conversation.searchable_text())
+        .execute(connection)?;
+        Ok(())
+    }
+}
+
+/// Converts free text into an FTS5 query where every word must match as a
+/// prefix, so that the query syntax never has to be escaped by the user
+fn to_match_query(query: &str) -> String {
+    query
+        .split_whitespace()
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(" ")
 }
 
 #[async_trait::async_trait]
@@ -24,18 +68,21 @@ impl ConversationRepository for ConversationRepositoryImpl {
         let mut connection = self.pool.get_connection()?;
 
         let wid = self.wid;
-        let record = ConversationRecord::new(conversation, wid);
-        diesel::insert_into(conversations::table)
-            .values(&record)
-            .on_conflict(conversations::conversation_id)
-            .do_update()
-            .set((
-                conversations::title.eq(&record.title),
-                conversations::context.eq(&record.context),
-                conversations::updated_at.eq(record.updated_at),
-                conversations::metrics.eq(&record.metrics),
-            ))
-            .execute(&mut connection)?;
+        connection.transaction(|connection| {
+            Self::index_conversation(connection, &conversation)?;
+            let record = ConversationRecord::new(conversation, wid);
+            diesel::insert_into(conversations::table)
+                .values(&record)
+                .on_conflict(conversations::conversation_id)
+                .do_update()
+                .set((
+                    conversations::title.eq(&record.title),
+                    conversations::context.eq(&record.context),
+                    conversations::updated_at.eq(record.updated_at),
+                    conversations::metrics.eq(&record.metrics),
+                ))
+                .execute(connection)
+        })?;
         Ok(())
     }
 
@@ -100,26 +147,149 @@ impl ConversationRepository for ConversationRepositoryImpl {
         Ok(conversation)
     }
 
+    async fn search_conversations(
+        &self,
+        query: &str,
+        limit: Option<usize>,
+    ) -> anyhow::Result<Vec<ConversationMatch>> {
+        let query = to_match_query(query);
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut connection = self.pool.get_connection()?;
+
+        let workspace_id = self.wid.id() as i64;
+        let records: Vec<SearchRecord> = diesel::sql_query(
+            "SELECT c.conversation_id, c.title, c.workspace_id, c.context, c.created_at, \
+             c.updated_at, c.metrics, snippet(conversations_fts, -1, '', '', '…', 16) AS snippet \
+             FROM conversations_fts \
+             JOIN conversations c ON c.conversation_id = conversations_fts.conversation_id \
+             WHERE conversations_fts MATCH ? AND c.workspace_id = ? AND c.context IS NOT NULL \
+             ORDER BY bm25(conversations_fts) \
+             LIMIT ?",
+        )
+        .bind::<Text, _>(query)
+        .bind::<BigInt, _>(workspace_id)
+        .bind::<BigInt, _>(limit.map_or(-1, |limit| limit as i64))
+        .load(&mut connection)?;
+
+        records
+            .into_iter()
+            .map(|SearchRecord { record, snippet }| {
+                Ok(ConversationMatch::new(
+                    Conversation::try_from(record)?,
+                    snippet,
+                ))
+            })
+            .collect()
+    }
+
     async fn delete_conversation(&self, conversation_id: &ConversationId) -> anyhow::Result<()> {
         let mut connection = self.pool.get_connection()?;
         let workspace_id = self.wid.id() as i64;
 
         // Security: Ensure users can only delete conversations within their workspace
-        diesel::delete(conversations::table)
+        let deleted = diesel::delete(conversations::table)
             .filter(conversations::workspace_id.eq(&workspace_id))
             .filter(conversations::conversation_id.eq(conversation_id.into_string()))
             .execute(&mut connection)?;
+        if deleted > 0 {
+            diesel::sql_query("DELETE FROM conversations_fts WHERE conversation_id = ?")
+                .bind::<Text, _>(conversation_id.into_string())
+                .execute(&mut connection)?;
+        }
 
         Ok(())
     }
+
+    async fn record_usage(
+        &self,
+        conversation_id: &ConversationId,
+        usage: &Usage,
+        model: &ModelId,
+        provider: &ProviderId,
+    ) -> anyhow::Result<()> {
+        let mut connection = self.pool.get_connection()?;
+        let record = UsageRecord::new(conversation_id, usage, model, provider, self.wid);
+        diesel::insert_into(usage_records::table)
+            .values(&record)
+            .execute(&mut connection)?;
+        Ok(())
+    }
+
+    async fn get_usage_since(&self, since: DateTime<Utc>) -> anyhow::Result<Usage> {
+        let mut connection = self.pool.get_connection()?;
+        let workspace_id = self.wid.id() as i64;
+        let record: UsageTotalRecord = diesel::sql_query(
+            "SELECT COALESCE(SUM(prompt_tokens), 0) AS prompt_tokens, \
+             COALESCE(SUM(completion_tokens), 0) AS completion_tokens, \
+             COALESCE(SUM(cached_tokens), 0) AS cached_tokens, \
+             COALESCE(SUM(cache_write_tokens), 0) AS cache_write_tokens, \
+             COALESCE(SUM(total_tokens), 0) AS total_tokens, \
+             SUM(cost) AS cost \
+             FROM usage_records \
+             WHERE workspace_id = ? AND recorded_at >= ?",
+        )
+        .bind::<BigInt, _>(workspace_id)
+        .bind::<Timestamp, _>(since.naive_utc())
+        .get_result(&mut connection)?;
+        Ok(record.into())
+    }
+
+    async fn get_conversation_usage(
+        &self,
+        conversation_id: &ConversationId,
+    ) -> anyhow::Result<Usage> {
+        let mut connection = self.pool.get_connection()?;
+        let workspace_id = self.wid.id() as i64;
+        let record: UsageTotalRecord = diesel::sql_query(
+            "SELECT COALESCE(SUM(prompt_tokens), 0) AS prompt_tokens, \
+             COALESCE(SUM(completion_tokens), 0) AS completion_tokens, \
+             COALESCE(SUM(cached_tokens), 0) AS cached_tokens, \
+             COALESCE(SUM(cache_write_tokens), 0) AS cache_write_tokens, \
+             COALESCE(SUM(total_tokens), 0) AS total_tokens, \
+             SUM(cost) AS cost \
+             FROM usage_records \
+             WHERE workspace_id = ? AND conversation_id = ?",
+        )
+        .bind::<BigInt, _>(workspace_id)
+        .bind::<Text, _>(conversation_id.into_string())
+        .get_result(&mut connection)?;
+        Ok(record.into())
+    }
+
+    async fn get_usage_report(
+        &self,
+        since: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<UsageReportEntry>> {
+        let mut connection = self.pool.get_connection()?;
+        let records: Vec<UsageReportRecord> = diesel::sql_query(
+            "SELECT DATE(recorded_at) AS date, workspace_id, provider, model, \
+             SUM(prompt_tokens) AS prompt_tokens, \
+             SUM(completion_tokens) AS completion_tokens, \
+             SUM(cached_tokens) AS cached_tokens, \
+             SUM(cache_write_tokens) AS cache_write_tokens, \
+             SUM(total_tokens) AS total_tokens, \
+             SUM(cost) AS cost \
+             FROM usage_records \
+             WHERE recorded_at >= ? \
+             GROUP BY date, workspace_id, provider, model \
+             ORDER BY date, workspace_id, provider, model",
+        )
+        .bind::<Timestamp, _>(since.naive_utc())
+        .load(&mut connection)?;
+        Ok(records.into_iter().map(Into::into).collect())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use chrono::Utc;
     use paws_domain::{
-        Context, ContextMessage, Effort, FileOperation, Metrics, Role, ToolCallFull, ToolCallId,
-        ToolChoice, ToolDefinition, ToolKind, ToolName, ToolOutput, ToolResult, ToolValue, Usage,
+        Context, ContextMessage, Effort, FileCheckpoint, FileOperation, Metrics, Role, Snapshot,
+        TokenCount, ToolCallFull, ToolCallId, ToolChoice, ToolDefinition, ToolKind, ToolName,
+        ToolOutput, ToolResult, ToolValue,
     };
     use pretty_assertions::assert_eq;
 
@@ -432,6 +602,31 @@ mod tests {
         assert_eq!(actual_file.content_hash, expected_file.content_hash);
     }
 
+    #[tokio::test]
+    async fn test_upsert_and_retrieve_conversation_with_checkpoints() -> anyhow::Result<()> {
+        let repo = repository()?;
+        let snapshot = Snapshot::create("/project/src/main.rs".into())?;
+        let metrics = Metrics::default()
+            .begin_turn(0, Utc::now())
+            .record_snapshot("/project/src/main.rs".to_string(), Some(snapshot))
+            .begin_turn(1, Utc::now())
+            .record_snapshot("/project/src/new.rs".to_string(), None);
+        let fixture = Conversation::generate().metrics(metrics.clone());
+
+        repo.upsert_conversation(fixture.clone()).await?;
+        let actual = repo
+            .get_conversation(&fixture.id)
+            .await?
+            .expect("Conversation should exist");
+
+        assert_eq!(actual.metrics.checkpoints, metrics.checkpoints);
+        assert_eq!(
+            actual.metrics.checkpoints[1].files,
+            vec![FileCheckpoint::new("/project/src/new.rs", None)]
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_deserialize_old_format_without_tool_field() {
         // Old format from database: missing tool and content_hash fields
@@ -707,6 +902,7 @@ mod tests {
                     completion_tokens: paws_domain::TokenCount::Actual(50),
                     total_tokens: paws_domain::TokenCount::Actual(150),
                     cached_tokens: paws_domain::TokenCount::Actual(0),
+                    cache_write_tokens: paws_domain::TokenCount::Actual(0),
                     cost: Some(0.001),
                 }),
             },
@@ -890,4 +1086,308 @@ mod tests {
 
         Ok(())
     }
+
+    fn conversation_with_messages(title: &str, messages: &[&str]) -> Conversation {
+        let context = messages
+            .iter()
+            .fold(Context::default(), |context, message| {
+                context.add_message(ContextMessage::user(*message, None))
+            });
+        Conversation::new(ConversationId::generate())
+            .title(Some(title.to_string()))
+            .context(Some(context))
+    }
+
+    #[tokio::test]
+    async fn test_search_conversations_by_message_text() -> anyhow::Result<()> {
+        let repo = repository()?;
+        let migration = conversation_with_messages(
+            "Database work",
+            &["The migration fails on the metrics column"],
+        );
+        let other = conversation_with_messages("Styling", &["Make the banner blue"]);
+        repo.upsert_conversation(migration.clone()).await?;
+        repo.upsert_conversation(other).await?;
+
+        let actual = repo.search_conversations("migrat", None).await?;
+
+        assert_eq!(actual.len(), 1);
+        assert_eq!(actual[0].conversation.id, migration.id);
+        assert_eq!(
+            actual[0].snippet,
+            "The migration fails on the metrics column"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_conversations_by_title() -> anyhow::Result<()> {
+        let repo = repository()?;
+        let fixture = conversation_with_messages("Fix the login flow", &["hello"]);
+        repo.upsert_conversation(fixture.clone()).await?;
+
+        let actual = repo
+            .search_conversations("login", None)
+            .await?
+            .into_iter()
+            .map(|found| found.conversation.id)
+            .collect::<Vec<_>>();
+
+        let expected = vec![fixture.id];
+        assert_eq!(actual, expected);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_conversations_reflects_updates() -> anyhow::Result<()> {
+        let repo = repository()?;
+        let fixture = conversation_with_messages("Untitled", &["first draft"]);
+        repo.upsert_conversation(fixture.clone()).await?;
+        let updated = fixture.title(Some("Release notes".to_string()));
+        repo.upsert_conversation(updated).await?;
+
+        let stale = repo.search_conversations("untitled", None).await?;
+        let fresh = repo.search_conversations("release notes", None).await?;
+
+        assert!(stale.is_empty());
+        assert_eq!(fresh.len(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_conversations_ignores_query_syntax() -> anyhow::Result<()> {
+        let repo = repository()?;
+        let fixture = conversation_with_messages("Errors", &["the build-script panicked"]);
+        repo.upsert_conversation(fixture).await?;
+
+        let hyphenated = repo.search_conversations("build-script", None).await?;
+        let operators = repo.search_conversations("\"OR ( NEAR", None).await?;
+
+        assert_eq!(hyphenated.len(), 1);
+        assert!(operators.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_conversations_respects_workspace_and_deletion() -> anyhow::Result<()> {
+        let pool = Arc::new(DatabasePool::in_memory()?);
+        let repo = ConversationRepositoryImpl::new(pool.clone(), WorkspaceHash::new(0));
+        let other_repo = ConversationRepositoryImpl::new(pool, WorkspaceHash::new(1));
+        let fixture = conversation_with_messages("Shared words", &["parser rewrite"]);
+        repo.upsert_conversation(fixture.clone()).await?;
+
+        let other_workspace = other_repo.search_conversations("parser", None).await?;
+        repo.delete_conversation(&fixture.id).await?;
+        let after_delete = repo.search_conversations("parser", None).await?;
+
+        assert!(other_workspace.is_empty());
+        assert!(after_delete.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_backfill_indexes_existing_records() -> anyhow::Result<()> {
+        let repo = repository()?;
+        let context = Context::default()
+            .add_message(ContextMessage::system("system prompt"))
+            .add_message(ContextMessage::user("stored before", None))
+            .add_message(ContextMessage::user("the index", None));
+        let fixture = Conversation::new(ConversationId::generate())
+            .title(Some("Legacy".to_string()))
+            .context(Some(context));
+        repo.upsert_conversation(fixture.clone()).await?;
+        let mut connection = repo.pool.get_connection()?;
+        diesel::sql_query("DELETE FROM conversations_fts").execute(&mut connection)?;
+        diesel::sql_query(include_str!(
+            "../database/migrations/2025-11-01-000000_backfill_conversations_fts/up.sql"
+        ))
+        .execute(&mut connection)?;
+        drop(connection);
+
+        let actual = (
+            repo.search_conversations("index", None).await?,
+            repo.search_conversations("system", None).await?,
+        );
+
+        assert_eq!(actual.0.len(), 1);
+        assert_eq!(actual.0[0].conversation.id, fixture.id);
+        assert_eq!(actual.0[0].snippet, "stored before\nthe index");
+        assert!(actual.1.is_empty());
+        Ok(())
+    }
+
+    fn sonnet() -> ModelId {
+        ModelId::new("claude-sonnet-4-5")
+    }
+
+    fn usage(tokens: usize, cost: Option<f64>) -> Usage {
+        Usage {
+            prompt_tokens: TokenCount::Actual(tokens),
+            total_tokens: TokenCount::Actual(tokens),
+            cost,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_usage_since_sums_workspace_records() -> anyhow::Result<()> {
+        let pool = Arc::new(DatabasePool::in_memory()?);
+        let repo = ConversationRepositoryImpl::new(pool.clone(), WorkspaceHash::new(0));
+        let other_repo = ConversationRepositoryImpl::new(pool, WorkspaceHash::new(1));
+        let since = Utc::now() - chrono::Duration::hours(1);
+        repo.record_usage(
+            &ConversationId::generate(),
+            &usage(100, Some(0.5)),
+            &sonnet(),
+            &ProviderId::ANTHROPIC,
+        )
+        .await?;
+        repo.record_usage(
+            &ConversationId::generate(),
+            &usage(50, None),
+            &sonnet(),
+            &ProviderId::ANTHROPIC,
+        )
+        .await?;
+        other_repo
+            .record_usage(
+                &ConversationId::generate(),
+                &usage(1000, Some(9.0)),
+                &sonnet(),
+                &ProviderId::ANTHROPIC,
+            )
+            .await?;
+
+        let actual = repo.get_usage_since(since).await?;
+
+        let expected = Usage {
+            prompt_tokens: TokenCount::Actual(150),
+            total_tokens: TokenCount::Actual(150),
+            cost: Some(0.5),
+            ..Default::default()
+        };
+        assert_eq!(actual, expected);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_conversation_usage_sums_conversation_records() -> anyhow::Result<()> {
+        let repo = repository()?;
+        let conversation_id = ConversationId::generate();
+        repo.record_usage(
+            &conversation_id,
+            &usage(100, Some(0.5)),
+            &sonnet(),
+            &ProviderId::ANTHROPIC,
+        )
+        .await?;
+        repo.record_usage(
+            &conversation_id,
+            &usage(50, None),
+            &sonnet(),
+            &ProviderId::ANTHROPIC,
+        )
+        .await?;
+        repo.record_usage(
+            &ConversationId::generate(),
+            &usage(1000, Some(9.0)),
+            &sonnet(),
+            &ProviderId::ANTHROPIC,
+        )
+        .await?;
+
+        let actual = repo.get_conversation_usage(&conversation_id).await?;
+
+        let expected = Usage {
+            prompt_tokens: TokenCount::Actual(150),
+            total_tokens: TokenCount::Actual(150),
+            cost: Some(0.5),
+            ..Default::default()
+        };
+        assert_eq!(actual, expected);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_usage_since_excludes_older_records() -> anyhow::Result<()> {
+        let repo = repository()?;
+        repo.record_usage(
+            &ConversationId::generate(),
+            &usage(100, Some(0.5)),
+            &sonnet(),
+            &ProviderId::ANTHROPIC,
+        )
+        .await?;
+
+        let actual = repo
+            .get_usage_since(Utc::now() + chrono::Duration::hours(1))
+            .await?;
+
+        let expected = Usage {
+            prompt_tokens: TokenCount::Actual(0),
+            total_tokens: TokenCount::Actual(0),
+            completion_tokens: TokenCount::Actual(0),
+            cached_tokens: TokenCount::Actual(0),
+            cache_write_tokens: TokenCount::Actual(0),
+            cost: None,
+        };
+        assert_eq!(actual, expected);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_usage_report_groups_records() -> anyhow::Result<()> {
+        let pool = Arc::new(DatabasePool::in_memory()?);
+        let repo = ConversationRepositoryImpl::new(pool.clone(), WorkspaceHash::new(0));
+        let other_repo = ConversationRepositoryImpl::new(pool, WorkspaceHash::new(1));
+        let since = Utc::now() - chrono::Duration::hours(1);
+        let gpt = ModelId::new("gpt-4o");
+        repo.record_usage(
+            &ConversationId::generate(),
+            &usage(100, Some(0.5)),
+            &sonnet(),
+            &ProviderId::ANTHROPIC,
+        )
+        .await?;
+        repo.record_usage(
+            &ConversationId::generate(),
+            &usage(50, Some(0.25)),
+            &sonnet(),
+            &ProviderId::ANTHROPIC,
+        )
+        .await?;
+        repo.record_usage(
+            &ConversationId::generate(),
+            &usage(10, None),
+            &gpt,
+            &ProviderId::OPENAI,
+        )
+        .await?;
+        other_repo
+            .record_usage(
+                &ConversationId::generate(),
+                &usage(1000, Some(9.0)),
+                &sonnet(),
+                &ProviderId::ANTHROPIC,
+            )
+            .await?;
+
+        let actual = repo.get_usage_report(since).await?;
+
+        let today = Utc::now().date_naive();
+        let entry = |workspace_id, provider: ProviderId, model: &ModelId, usage| UsageReportEntry {
+            date: today,
+            workspace_id: WorkspaceHash::new(workspace_id),
+            provider: Some(provider),
+            model: Some(model.clone()),
+            usage,
+        };
+        let expected = vec![
+            entry(0, ProviderId::ANTHROPIC, &sonnet(), usage(150, Some(0.75))),
+            entry(0, ProviderId::OPENAI, &gpt, usage(10, None)),
+            entry(1, ProviderId::ANTHROPIC, &sonnet(), usage(1000, Some(9.0))),
+        ];
+        assert_eq!(actual, expected);
+        Ok(())
+    }
 }
@@ -1,4 +1,5 @@
 mod conversation_record;
 mod conversation_repo;
+mod usage_record;
 
 pub use conversation_repo::*;
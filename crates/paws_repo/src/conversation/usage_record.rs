@@ -0,0 +1,106 @@
+use chrono::NaiveDate;
+use diesel::sql_types::{BigInt, Date, Double, Nullable, Text};
+use diesel::QueryableByName;
+use paws_domain::{
+    ConversationId, ModelId, ProviderId, TokenCount, Usage, UsageReportEntry, WorkspaceHash,
+};
+
+/// Database model for usage_records table
+#[derive(Debug, diesel::Insertable)]
+#[diesel(table_name = crate::database::schema::usage_records)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(super) struct UsageRecord {
+    pub workspace_id: i64,
+    pub conversation_id: String,
+    pub recorded_at: chrono::NaiveDateTime,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub cached_tokens: i64,
+    pub cache_write_tokens: i64,
+    pub total_tokens: i64,
+    pub cost: Option<f64>,
+    pub model: Option<String>,
+    pub provider: Option<String>,
+}
+
+impl UsageRecord {
+    /// Creates a new UsageRecord for a completion made now
+    pub fn new(
+        conversation_id: &ConversationId,
+        usage: &Usage,
+        model: &ModelId,
+        provider: &ProviderId,
+        workspace_id: WorkspaceHash,
+    ) -> Self {
+        Self {
+            workspace_id: workspace_id.id() as i64,
+            conversation_id: conversation_id.into_string(),
+            recorded_at: chrono::Utc::now().naive_utc(),
+            prompt_tokens: *usage.prompt_tokens as i64,
+            completion_tokens: *usage.completion_tokens as i64,
+            cached_tokens: *usage.cached_tokens as i64,
+            cache_write_tokens: *usage.cache_write_tokens as i64,
+            total_tokens: *usage.total_tokens as i64,
+            cost: usage.cost,
+            model: Some(model.as_str().to_string()),
+            provider: Some(provider.as_str().to_string()),
+        }
+    }
+}
+
+/// Sum of the usage records matching a query
+#[derive(Debug, QueryableByName)]
+pub(super) struct UsageTotalRecord {
+    #[diesel(sql_type = BigInt)]
+    pub prompt_tokens: i64,
+    #[diesel(sql_type = BigInt)]
+    pub completion_tokens: i64,
+    #[diesel(sql_type = BigInt)]
+    pub cached_tokens: i64,
+    #[diesel(sql_type = BigInt)]
+    pub cache_write_tokens: i64,
+    #[diesel(sql_type = BigInt)]
+    pub total_tokens: i64,
+    #[diesel(sql_type = Nullable<Double>)]
+    pub cost: Option<f64>,
+}
+
+impl From<UsageTotalRecord> for Usage {
+    fn from(record: UsageTotalRecord) -> Self {
+        Usage {
+            prompt_tokens: TokenCount::Actual(record.prompt_tokens as usize),
+            completion_tokens: TokenCount::Actual(record.completion_tokens as usize),
+            cached_tokens: TokenCount::Actual(record.cached_tokens as usize),
+            cache_write_tokens: TokenCount::Actual(record.cache_write_tokens as usize),
+            total_tokens: TokenCount::Actual(record.total_tokens as usize),
+            cost: record.cost,
+        }
+    }
+}
+
+/// Sum of the usage records of a day, workspace, provider and model
+#[derive(Debug, QueryableByName)]
+pub(super) struct UsageReportRecord {
+    #[diesel(sql_type = Date)]
+    pub date: NaiveDate,
+    #[diesel(sql_type = BigInt)]
+    pub workspace_id: i64,
+    #[diesel(sql_type = Nullable<Text>)]
+    pub provider: Option<String>,
+    #[diesel(sql_type = Nullable<Text>)]
+    pub model: Option<String>,
+    #[diesel(embed)]
+    pub total: UsageTotalRecord,
+}
+
+impl From<UsageReportRecord> for UsageReportEntry {
+    fn from(record: UsageReportRecord) -> Self {
+        UsageReportEntry {
+            date: record.date,
+            workspace_id: WorkspaceHash::new(record.workspace_id as u64),
+            provider: record.provider.map(ProviderId::from),
+            model: record.model.map(ModelId::from),
+            usage: record.total.into(),
+        }
+    }
+}
@@ -0,0 +1,2 @@
+-- Drop the conversations full-text index
+DROP TABLE IF EXISTS conversations_fts;
@@ -0,0 +1,7 @@
+-- Full-text index over conversation titles and message text
+CREATE VIRTUAL TABLE IF NOT EXISTS conversations_fts USING fts5(
+    conversation_id UNINDEXED,
+    title,
+    content,
+    tokenize = 'porter unicode61'
+);
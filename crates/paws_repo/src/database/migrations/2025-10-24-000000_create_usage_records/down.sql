@@ -0,0 +1,3 @@
+-- Drop the usage ledger
+DROP INDEX IF EXISTS idx_usage_records_workspace_recorded_at;
+DROP TABLE IF EXISTS usage_records;
@@ -0,0 +1,16 @@
+-- Ledger of the tokens and cost of every completion, used to enforce budgets
+-- that span several conversations
+CREATE TABLE IF NOT EXISTS usage_records (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    workspace_id BIGINT NOT NULL,
+    conversation_id TEXT NOT NULL,
+    recorded_at TIMESTAMP NOT NULL,
+    prompt_tokens BIGINT NOT NULL,
+    completion_tokens BIGINT NOT NULL,
+    cached_tokens BIGINT NOT NULL,
+    total_tokens BIGINT NOT NULL,
+    cost DOUBLE
+);
+
+CREATE INDEX IF NOT EXISTS idx_usage_records_workspace_recorded_at
+    ON usage_records(workspace_id, recorded_at);
@@ -0,0 +1,4 @@
+-- Remove the model, provider and cache_write_tokens columns from usage_records
+ALTER TABLE usage_records DROP COLUMN cache_write_tokens;
+ALTER TABLE usage_records DROP COLUMN provider;
+ALTER TABLE usage_records DROP COLUMN model;
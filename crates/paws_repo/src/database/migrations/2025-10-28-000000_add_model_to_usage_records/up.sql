@@ -0,0 +1,5 @@
+-- Record which model and provider served each completion, and the prompt
+-- tokens written to the cache, so usage can be reported per model
+ALTER TABLE usage_records ADD COLUMN model TEXT;
+ALTER TABLE usage_records ADD COLUMN provider TEXT;
+ALTER TABLE usage_records ADD COLUMN cache_write_tokens BIGINT NOT NULL DEFAULT 0;
@@ -0,0 +1,3 @@
+-- The backfilled entries are the same as the ones written when saving, so
+-- they are kept
+SELECT 1;
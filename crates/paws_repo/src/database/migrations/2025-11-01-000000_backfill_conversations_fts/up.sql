@@ -0,0 +1,19 @@
+-- Index the conversations stored before the full-text index existed. Newer
+-- conversations are indexed when they are saved. Messages are read from both
+-- the current `{"message": {"text": ...}}` and the older `{"text": ...}` shapes.
+INSERT INTO conversations_fts (conversation_id, title, content)
+SELECT c.conversation_id, c.title, COALESCE((
+    SELECT group_concat(json_extract(m.text, '$.content'), char(10))
+    FROM (
+        SELECT COALESCE(
+            json_extract(entry.value, '$.message.text'),
+            json_extract(entry.value, '$.text')
+        ) AS text
+        FROM json_each(c.context, '$.messages') AS entry
+    ) AS m
+    WHERE m.text IS NOT NULL AND json_extract(m.text, '$.role') <> 'System'
+), '')
+FROM conversations c
+WHERE c.context IS NOT NULL
+  AND json_valid(c.context)
+  AND c.conversation_id NOT IN (SELECT conversation_id FROM conversations_fts);
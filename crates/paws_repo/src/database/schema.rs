@@ -12,4 +12,21 @@ diesel::table! {
     }
 }
 
-diesel::allow_tables_to_appear_in_same_query!(conversations,);
+diesel::table! {
+    usage_records (id) {
+        id -> Integer,
+        workspace_id -> BigInt,
+        conversation_id -> Text,
+        recorded_at -> Timestamp,
+        prompt_tokens -> BigInt,
+        completion_tokens -> BigInt,
+        cached_tokens -> BigInt,
+        total_tokens -> BigInt,
+        cost -> Nullable<Double>,
+        model -> Nullable<Text>,
+        provider -> Nullable<Text>,
+        cache_write_tokens -> BigInt,
+    }
+}
+
+diesel::allow_tables_to_appear_in_same_query!(conversations, usage_records,);
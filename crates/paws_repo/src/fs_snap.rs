@@ -29,4 +29,20 @@ impl SnapshotRepository for PawsFileSnapshotService {
     async fn undo_snapshot(&self, file_path: &Path) -> Result<()> {
         self.inner.undo_snapshot(file_path.to_path_buf()).await
     }
+
+    async fn read_snapshot(&self, snapshot: &Snapshot) -> Result<Option<Vec<u8>>> {
+        self.inner.read_snapshot(snapshot).await
+    }
+
+    async fn list_snapshots(&self, file_path: &Path) -> Result<Vec<Snapshot>> {
+        self.inner.list_snapshots(file_path.to_path_buf()).await
+    }
+
+    async fn restore_snapshot(&self, snapshot: &Snapshot) -> Result<()> {
+        self.inner.restore_snapshot(snapshot).await
+    }
+
+    async fn redo_snapshot(&self, file_path: &Path) -> Result<()> {
+        self.inner.redo_snapshot(file_path.to_path_buf()).await
+    }
 }
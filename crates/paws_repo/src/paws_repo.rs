@@ -1,24 +1,26 @@
 use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 
 use bytes::Bytes;
+use chrono::{DateTime, Utc};
 use paws_app::{
-    AgentRepository, CommandInfra, DirectoryReaderInfra, EnvironmentInfra, FileDirectoryInfra,
-    FileInfoInfra, FileReaderInfra, FileRemoverInfra, FileWriterInfra, HttpInfra, KVStore,
-    McpServerInfra, StrategyFactory, UserInfra, WalkedFile, Walker, WalkerInfra,
+    AgentRepository, CommandInfra, DirectoryReaderInfra, EnvironmentInfra, EventStream,
+    FileDirectoryInfra, FileInfoInfra, FileReaderInfra, FileRemoverInfra, FileWriterInfra,
+    HttpInfra, KVStore, McpServerInfra, StrategyFactory, UserInfra, WalkedFile, Walker,
+    WalkerInfra,
 };
 use paws_domain::{
     AnyProvider, AppConfig, AppConfigRepository, AuthCredential, CommandOutput, Conversation,
-    ConversationId, ConversationRepository, Environment, FileInfo, McpServerConfig,
-    MigrationResult, Provider, ProviderId, ProviderRepository, Skill, SkillRepository, Snapshot,
-    SnapshotRepository,
+    ConversationId, ConversationMatch, ConversationRepository, Environment, FileInfo, JobId,
+    JobOutput, McpServerConfig, MigrationResult, ModelId, Provider, ProviderId, ProviderRepository,
+    Skill, SkillRepository, Snapshot, SnapshotRepository, Usage, UsageReportEntry,
 };
 // Re-export CacacheStorage from paws_infra
 pub use paws_infra::CacacheStorage;
 use reqwest::header::HeaderMap;
 use reqwest::Response;
-use reqwest_eventsource::EventSource;
 use url::Url;
 
 use crate::agent::PawsAgentRepository;
@@ -89,6 +91,22 @@ impl<F: Send + Sync> SnapshotRepository for PawsRepo<F> {
     async fn undo_snapshot(&self, file_path: &Path) -> anyhow::Result<()> {
         self.file_snapshot_service.undo_snapshot(file_path).await
     }
+
+    async fn read_snapshot(&self, snapshot: &Snapshot) -> anyhow::Result<Option<Vec<u8>>> {
+        self.file_snapshot_service.read_snapshot(snapshot).await
+    }
+
+    async fn list_snapshots(&self, file_path: &Path) -> anyhow::Result<Vec<Snapshot>> {
+        self.file_snapshot_service.list_snapshots(file_path).await
+    }
+
+    async fn restore_snapshot(&self, snapshot: &Snapshot) -> anyhow::Result<()> {
+        self.file_snapshot_service.restore_snapshot(snapshot).await
+    }
+
+    async fn redo_snapshot(&self, file_path: &Path) -> anyhow::Result<()> {
+        self.file_snapshot_service.redo_snapshot(file_path).await
+    }
 }
 
 #[async_trait::async_trait]
@@ -121,11 +139,53 @@ impl<F: Send + Sync> ConversationRepository for PawsRepo<F> {
         self.conversation_repository.get_last_conversation().await
     }
 
+    async fn search_conversations(
+        &self,
+        query: &str,
+        limit: Option<usize>,
+    ) -> anyhow::Result<Vec<ConversationMatch>> {
+        self.conversation_repository
+            .search_conversations(query, limit)
+            .await
+    }
+
     async fn delete_conversation(&self, conversation_id: &ConversationId) -> anyhow::Result<()> {
         self.conversation_repository
             .delete_conversation(conversation_id)
             .await
     }
+
+    async fn record_usage(
+        &self,
+        conversation_id: &ConversationId,
+        usage: &Usage,
+        model: &ModelId,
+        provider: &ProviderId,
+    ) -> anyhow::Result<()> {
+        self.conversation_repository
+            .record_usage(conversation_id, usage, model, provider)
+            .await
+    }
+
+    async fn get_usage_since(&self, since: DateTime<Utc>) -> anyhow::Result<Usage> {
+        self.conversation_repository.get_usage_since(since).await
+    }
+
+    async fn get_conversation_usage(
+        &self,
+        conversation_id: &ConversationId,
+    ) -> anyhow::Result<Usage> {
+        self.conversation_repository
+            .get_conversation_usage(conversation_id)
+            .await
+    }
+
+    async fn get_usage_report(
+        &self,
+        since: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<UsageReportEntry>> {
+        self.conversation_repository.get_usage_report(since).await
+    }
 }
 
 #[async_trait::async_trait]
@@ -201,8 +261,13 @@ impl<F: HttpInfra> HttpInfra for PawsRepo<F> {
         self.infra.http_get(url, headers).await
     }
 
-    async fn http_post(&self, url: &Url, body: Bytes) -> anyhow::Result<Response> {
-        self.infra.http_post(url, body).await
+    async fn http_post(
+        &self,
+        url: &Url,
+        headers: Option<HeaderMap>,
+        body: Bytes,
+    ) -> anyhow::Result<Response> {
+        self.infra.http_post(url, headers, body).await
     }
 
     async fn http_delete(&self, url: &Url) -> anyhow::Result<Response> {
@@ -214,7 +279,7 @@ impl<F: HttpInfra> HttpInfra for PawsRepo<F> {
         url: &Url,
         headers: Option<HeaderMap>,
         body: Bytes,
-    ) -> anyhow::Result<EventSource> {
+    ) -> anyhow::Result<EventStream> {
         self.infra.http_eventsource(url, headers, body).await
     }
 }
@@ -415,6 +480,54 @@ where
             .execute_command_raw(command, working_dir, env_vars)
             .await
     }
+
+    async fn execute_command_with_input(
+        &self,
+        command: String,
+        working_dir: PathBuf,
+        input: String,
+    ) -> anyhow::Result<CommandOutput> {
+        self.infra
+            .execute_command_with_input(command, working_dir, input)
+            .await
+    }
+
+    async fn spawn_job(
+        &self,
+        conversation_id: ConversationId,
+        command: String,
+        working_dir: PathBuf,
+        env_vars: Option<Vec<String>>,
+    ) -> anyhow::Result<JobOutput> {
+        self.infra
+            .spawn_job(conversation_id, command, working_dir, env_vars)
+            .await
+    }
+
+    async fn read_job(
+        &self,
+        conversation_id: ConversationId,
+        job_id: JobId,
+    ) -> anyhow::Result<JobOutput> {
+        self.infra.read_job(conversation_id, job_id).await
+    }
+
+    async fn wait_job(
+        &self,
+        conversation_id: ConversationId,
+        job_id: JobId,
+        timeout: Duration,
+    ) -> anyhow::Result<JobOutput> {
+        self.infra.wait_job(conversation_id, job_id, timeout).await
+    }
+
+    async fn kill_job(
+        &self,
+        conversation_id: ConversationId,
+        job_id: JobId,
+    ) -> anyhow::Result<JobOutput> {
+        self.infra.kill_job(conversation_id, job_id).await
+    }
 }
 
 #[async_trait::async_trait]
@@ -7,8 +7,8 @@ use merge::Merge;
 use paws_app::domain::{ProviderId, ProviderResponse};
 use paws_app::{EnvironmentInfra, FileReaderInfra, FileWriterInfra};
 use paws_domain::{
-    AnyProvider, ApiKey, AuthCredential, AuthDetails, Error, MigrationResult, Provider,
-    ProviderRepository, ProviderType, URLParam, URLParamValue,
+    AnyProvider, ApiKey, AuthCredential, AuthDetails, Error, MigrationResult, PricingCatalogue,
+    Provider, ProviderRepository, ProviderType, URLParam, URLParamValue,
 };
 use serde::Deserialize;
 use url::Url;
@@ -46,12 +46,19 @@ struct ProviderConfig {
     models: Option<Models>,
     #[merge(strategy = merge::vec::append)]
     auth_methods: Vec<paws_domain::AuthMethod>,
+    #[serde(default)]
+    #[merge(strategy = merge_pricing)]
+    pricing: PricingCatalogue,
 }
 
 fn overwrite<T>(base: &mut T, other: T) {
     *base = other;
 }
 
+fn merge_pricing(base: &mut PricingCatalogue, other: PricingCatalogue) {
+    base.merge(other);
+}
+
 /// Transparent wrapper for Vec<ProviderConfig> that implements custom merge
 /// logic
 #[derive(Debug, Clone, Deserialize, Merge)]
@@ -98,6 +105,7 @@ impl From<&ProviderConfig>
                 .collect(),
             credential: None,
             models,
+            pricing: config.pricing.clone(),
         }
     }
 }
@@ -325,6 +333,7 @@ impl<F: EnvironmentInfra + FileReaderInfra + FileWriterInfra> PawsProviderReposi
                 .collect(),
             credential: Some(credential),
             models,
+            pricing: config.pricing.clone(),
         })
     }
 
@@ -440,7 +449,7 @@ impl<F: EnvironmentInfra + FileReaderInfra + FileWriterInfra + Sync> ProviderRep
 
 #[cfg(test)]
 mod tests {
-    use paws_app::domain::ProviderResponse;
+    use paws_app::domain::{ModelId, ProviderResponse};
     use pretty_assertions::assert_eq;
 
     use super::*;
@@ -470,6 +479,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_merge_pricing() {
+        let mut fixture = ProviderConfigs(get_provider_configs().clone());
+        let custom: ProviderConfigs = serde_json::from_str(
+            r#"[{
+                "id": "anthropic",
+                "url": "https://api.anthropic.com/v1/messages",
+                "auth_methods": [],
+                "pricing": {"claude-sonnet-4-5": {"input": 2.0, "output": 10.0}}
+            }]"#,
+        )
+        .unwrap();
+
+        fixture.merge(custom);
+
+        let anthropic = fixture
+            .0
+            .iter()
+            .find(|c| c.id == ProviderId::ANTHROPIC)
+            .unwrap();
+        let actual = [
+            anthropic.pricing.get(&ModelId::new("claude-sonnet-4-5")),
+            anthropic.pricing.get(&ModelId::new("claude-haiku-4-5")),
+        ]
+        .map(|pricing| pricing.map(|pricing| (pricing.input, pricing.cache_read)));
+        let expected = [Some((2.0, None)), Some((1.0, Some(0.1)))];
+        assert_eq!(actual, expected);
+    }
+
     #[test]
     fn test_vertex_ai_config() {
         let configs = get_provider_configs();
@@ -491,6 +529,32 @@ mod tests {
         assert!(&config.url.contains("}}"));
     }
 
+    #[test]
+    fn test_ai_studio_config() {
+        let configs = get_provider_configs();
+        let config = configs
+            .iter()
+            .find(|c| c.id == ProviderId::from("ai_studio".to_string()))
+            .unwrap();
+        assert_eq!(config.api_key_vars, Some("GEMINI_API_KEY".to_string()));
+        assert_eq!(config.response_type, Some(ProviderResponse::Gemini));
+        assert_eq!(
+            config.url.as_str(),
+            "https://generativelanguage.googleapis.com/v1beta/models"
+        );
+    }
+
+    #[test]
+    fn test_openai_config_uses_responses_api() {
+        let configs = get_provider_configs();
+        let config = configs.iter().find(|c| c.id == ProviderId::OPENAI).unwrap();
+        assert_eq!(
+            config.response_type,
+            Some(ProviderResponse::OpenAIResponses)
+        );
+        assert_eq!(config.url.as_str(), "https://api.openai.com/v1/responses");
+    }
+
     #[test]
     fn test_azure_config() {
         let configs = get_provider_configs();
@@ -980,7 +1044,7 @@ mod env_tests {
         // Regular OpenAI and Anthropic providers use hardcoded URLs
         assert_eq!(
             openai_provider.url.as_str(),
-            "https://api.openai.com/v1/chat/completions"
+            "https://api.openai.com/v1/responses"
         );
         assert_eq!(
             anthropic_provider.url.as_str(),
@@ -297,7 +297,11 @@ mod tests {
     fn fixture_skill_repo() -> (PawsSkillRepository<PawsInfra>, std::path::PathBuf) {
         let skill_dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
             .join("src/fixtures/skills_with_resources");
-        let infra = Arc::new(PawsInfra::new(false, std::env::current_dir().unwrap()));
+        let infra = Arc::new(PawsInfra::new(
+            false,
+            false,
+            std::env::current_dir().unwrap(),
+        ));
         let repo = PawsSkillRepository::new(infra);
         (repo, skill_dir)
     }
@@ -139,6 +139,7 @@ mod tests {
                             supports_parallel_tool_calls: Some(true),
                             supports_reasoning: Some(false),
                         }])),
+                        pricing: Default::default(),
                     },
                     Provider {
                         id: ProviderId::ANTHROPIC,
@@ -163,6 +164,7 @@ mod tests {
                             supports_parallel_tool_calls: Some(true),
                             supports_reasoning: Some(true),
                         }])),
+                        pricing: Default::default(),
                     },
                 ],
             }
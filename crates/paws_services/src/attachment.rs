@@ -6,27 +6,51 @@ use paws_app::domain::{
 use paws_app::utils::format_display_path;
 use paws_app::{
     AttachmentService, DirectoryReaderInfra, EnvironmentInfra, FileInfoInfra, FileReaderInfra,
+    McpService,
 };
 
 use crate::range::resolve_range;
 
 #[derive(Clone)]
-pub struct PawsChatRequest<F> {
+pub struct PawsChatRequest<F, M> {
     infra: Arc<F>,
+    mcp_service: Arc<M>,
 }
 
-impl<F: FileReaderInfra + EnvironmentInfra + FileInfoInfra + DirectoryReaderInfra>
-    PawsChatRequest<F>
+impl<F: FileReaderInfra + EnvironmentInfra + FileInfoInfra + DirectoryReaderInfra, M: McpService>
+    PawsChatRequest<F, M>
 {
-    pub fn new(infra: Arc<F>) -> Self {
-        Self { infra }
+    pub fn new(infra: Arc<F>, mcp_service: Arc<M>) -> Self {
+        Self { infra, mcp_service }
     }
 
     async fn prepare_attachments(&self, paths: Vec<FileTag>) -> anyhow::Result<Vec<Attachment>> {
-        futures::future::join_all(paths.into_iter().map(|v| self.populate_attachments(v)))
-            .await
+        let attachments = futures::future::join_all(paths.into_iter().map(|tag| async move {
+            if tag.is_resource() {
+                self.populate_resource_attachments(tag).await
+            } else {
+                self.populate_attachments(tag).await.map(|v| vec![v])
+            }
+        }))
+        .await
+        .into_iter()
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok(attachments.into_iter().flatten().collect())
+    }
+
+    /// Reads a resource exposed by an MCP server, a single resource can be made
+    /// of several contents
+    async fn populate_resource_attachments(&self, tag: FileTag) -> anyhow::Result<Vec<Attachment>> {
+        let contents = self.mcp_service.read_mcp_resource(&tag.path).await?;
+
+        Ok(contents
             .into_iter()
-            .collect::<anyhow::Result<Vec<_>>>()
+            .map(|content| Attachment {
+                path: content.uri().to_string(),
+                content: content.into_attachment_content(),
+            })
+            .collect())
     }
 
     async fn populate_attachments(&self, tag: FileTag) -> anyhow::Result<Attachment> {
@@ -110,8 +134,8 @@ impl<F: FileReaderInfra + EnvironmentInfra + FileInfoInfra + DirectoryReaderInfr
 }
 
 #[async_trait::async_trait]
-impl<F: FileReaderInfra + EnvironmentInfra + FileInfoInfra + DirectoryReaderInfra> AttachmentService
-    for PawsChatRequest<F>
+impl<F: FileReaderInfra + EnvironmentInfra + FileInfoInfra + DirectoryReaderInfra, M: McpService>
+    AttachmentService for PawsChatRequest<F, M>
 {
     async fn attachments(&self, url: &str) -> anyhow::Result<Vec<Attachment>> {
         self.prepare_attachments(Attachment::parse_all(url)).await
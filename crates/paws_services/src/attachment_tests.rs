@@ -14,7 +14,7 @@ use crate::test_fixtures::MockCompositeService;
 async fn test_add_url_with_text_file() {
     // Setup
     let infra = Arc::new(MockCompositeService::new());
-    let chat_request = PawsChatRequest::new(infra.clone());
+    let chat_request = PawsChatRequest::new(infra.clone(), infra.clone());
 
     // Test with a text file path in chat message
     let url = "@[/test/file1.txt]".to_string();
@@ -36,7 +36,7 @@ async fn test_add_url_with_text_file() {
 async fn test_add_url_with_image() {
     // Setup
     let infra = Arc::new(MockCompositeService::new());
-    let chat_request = PawsChatRequest::new(infra.clone());
+    let chat_request = PawsChatRequest::new(infra.clone(), infra.clone());
 
     // Test with an image file
     let url = "@[/test/image.png]".to_string();
@@ -62,7 +62,7 @@ async fn test_add_url_with_image() {
 async fn test_add_url_with_jpg_image_with_spaces() {
     // Setup
     let infra = Arc::new(MockCompositeService::new());
-    let chat_request = PawsChatRequest::new(infra.clone());
+    let chat_request = PawsChatRequest::new(infra.clone(), infra.clone());
 
     // Test with an image file that has spaces in the path
     let url = "@[/test/image with spaces.jpg]".to_string();
@@ -95,7 +95,7 @@ async fn test_add_url_with_multiple_files() {
         "This is another text file".to_string(),
     );
 
-    let chat_request = PawsChatRequest::new(infra.clone());
+    let chat_request = PawsChatRequest::new(infra.clone(), infra.clone());
 
     // Test with multiple files mentioned
     let url = "@[/test/file1.txt] @[/test/file2.txt] @[/test/image.png]".to_string();
@@ -127,7 +127,7 @@ async fn test_add_url_with_multiple_files() {
 async fn test_add_url_with_nonexistent_file() {
     // Setup
     let infra = Arc::new(MockCompositeService::new());
-    let chat_request = PawsChatRequest::new(infra.clone());
+    let chat_request = PawsChatRequest::new(infra.clone(), infra.clone());
 
     // Test with a file that doesn't exist
     let url = "@[/test/nonexistent.txt]".to_string();
@@ -144,7 +144,7 @@ async fn test_add_url_with_nonexistent_file() {
 async fn test_add_url_empty() {
     // Setup
     let infra = Arc::new(MockCompositeService::new());
-    let chat_request = PawsChatRequest::new(infra.clone());
+    let chat_request = PawsChatRequest::new(infra.clone(), infra.clone());
 
     // Test with an empty message
     let url = "".to_string();
@@ -167,7 +167,7 @@ async fn test_add_url_with_unsupported_extension() {
         "Some content".to_string(),
     );
 
-    let chat_request = PawsChatRequest::new(infra.clone());
+    let chat_request = PawsChatRequest::new(infra.clone(), infra.clone());
 
     // Test with the file
     let url = "@[/test/unknown.xyz]".to_string();
@@ -195,7 +195,7 @@ async fn test_attachment_range_information() {
         "Line 1\nLine 2\nLine 3\nLine 4\nLine 5".to_string(),
     );
 
-    let chat_request = PawsChatRequest::new(infra.clone());
+    let chat_request = PawsChatRequest::new(infra.clone(), infra.clone());
     let url = "@[/test/multiline.txt]".to_string();
 
     // Execute
@@ -237,7 +237,7 @@ async fn test_range_functionality_single_line() {
         "Line 1\nLine 2\nLine 3\nLine 4\nLine 5".to_string(),
     );
 
-    let chat_request = PawsChatRequest::new(infra.clone());
+    let chat_request = PawsChatRequest::new(infra.clone(), infra.clone());
 
     // Test reading line 2 only
     let url = "@[/test/multiline.txt:2:2]";
@@ -265,7 +265,7 @@ async fn test_range_functionality_multiple_lines() {
         "Line 1\nLine 2\nLine 3\nLine 4\nLine 5\nLine 6".to_string(),
     );
 
-    let chat_request = PawsChatRequest::new(infra.clone());
+    let chat_request = PawsChatRequest::new(infra.clone(), infra.clone());
 
     // Test reading lines 2-4
     let url = "@[/test/range_test.txt:2:4]";
@@ -293,7 +293,7 @@ async fn test_range_functionality_from_start() {
         "First\nSecond\nThird\nFourth".to_string(),
     );
 
-    let chat_request = PawsChatRequest::new(infra.clone());
+    let chat_request = PawsChatRequest::new(infra.clone(), infra.clone());
 
     // Test reading from start to line 2
     let url = "@[/test/start_range.txt:1:2]";
@@ -318,7 +318,7 @@ async fn test_range_functionality_to_end() {
         "Alpha\nBeta\nGamma\nDelta\nEpsilon".to_string(),
     );
 
-    let chat_request = PawsChatRequest::new(infra.clone());
+    let chat_request = PawsChatRequest::new(infra.clone(), infra.clone());
 
     // Test reading from line 3 to end
     let url = "@[/test/end_range.txt:3:5]";
@@ -343,7 +343,7 @@ async fn test_range_functionality_edge_cases() {
         "Only line".to_string(),
     );
 
-    let chat_request = PawsChatRequest::new(infra.clone());
+    let chat_request = PawsChatRequest::new(infra.clone(), infra.clone());
 
     // Test reading beyond file length
     let url = "@[/test/edge_case.txt:1:10]";
@@ -369,7 +369,7 @@ async fn test_range_functionality_combined_with_multiple_files() {
         "B1\nB2\nB3\nB4".to_string(),
     );
 
-    let chat_request = PawsChatRequest::new(infra.clone());
+    let chat_request = PawsChatRequest::new(infra.clone(), infra.clone());
 
     // Test multiple files with different ranges
     let url = "Check @[/test/file_a.txt:1:2] and @[/test/file_b.txt:3:4]";
@@ -405,7 +405,7 @@ async fn test_range_functionality_preserves_metadata() {
         "Meta1\nMeta2\nMeta3\nMeta4\nMeta5\nMeta6\nMeta7".to_string(),
     );
 
-    let chat_request = PawsChatRequest::new(infra.clone());
+    let chat_request = PawsChatRequest::new(infra.clone(), infra.clone());
 
     // Test that metadata is preserved correctly with ranges
     let url = "@[/test/metadata_test.txt:3:5]";
@@ -433,7 +433,7 @@ async fn test_range_functionality_vs_full_file() {
         "Full1\nFull2\nFull3\nFull4\nFull5".to_string(),
     );
 
-    let chat_request = PawsChatRequest::new(infra.clone());
+    let chat_request = PawsChatRequest::new(infra.clone(), infra.clone());
 
     // Test full file vs ranged file to ensure they're different
     let url_full = "@[/test/comparison.txt]";
@@ -496,7 +496,7 @@ async fn test_add_url_with_directory() {
         .file_service
         .add_dir(PathBuf::from("/test/mydir/subdir"));
 
-    let chat_request = PawsChatRequest::new(infra.clone());
+    let chat_request = PawsChatRequest::new(infra.clone(), infra.clone());
 
     // Test with directory path
     let url = "@[/test/mydir]";
@@ -537,7 +537,7 @@ async fn test_add_url_with_empty_directory() {
     // Add empty directory
     infra.file_service.add_dir(PathBuf::from("/test/emptydir"));
 
-    let chat_request = PawsChatRequest::new(infra.clone());
+    let chat_request = PawsChatRequest::new(infra.clone(), infra.clone());
 
     // Test with empty directory path
     let url = "@[/test/emptydir]";
@@ -575,7 +575,7 @@ async fn test_add_url_with_mixed_files_and_directory() {
         "Standalone file".to_string(),
     );
 
-    let chat_request = PawsChatRequest::new(infra.clone());
+    let chat_request = PawsChatRequest::new(infra.clone(), infra.clone());
 
     // Test with both file and directory
     let url = "@[/test/mixdir] @[/test/standalone.txt]";
@@ -638,7 +638,7 @@ async fn test_directory_sorting_dirs_first() {
         .file_service
         .add_dir(PathBuf::from("/test/sortdir/berry_dir"));
 
-    let chat_request = PawsChatRequest::new(infra.clone());
+    let chat_request = PawsChatRequest::new(infra.clone(), infra.clone());
     let url = "@[/test/sortdir]";
     let attachments = chat_request.attachments(url).await.unwrap();
 
@@ -690,7 +690,7 @@ async fn test_directory_sorting_only_directories() {
         .file_service
         .add_dir(PathBuf::from("/test/onlydirs/middle_dir"));
 
-    let chat_request = PawsChatRequest::new(infra.clone());
+    let chat_request = PawsChatRequest::new(infra.clone(), infra.clone());
     let url = "@[/test/onlydirs]";
     let attachments = chat_request.attachments(url).await.unwrap();
 
@@ -722,7 +722,7 @@ async fn test_directory_sorting_only_files() {
     infra.add_file(PathBuf::from("/test/onlyfiles/alpha.txt"), "A".to_string());
     infra.add_file(PathBuf::from("/test/onlyfiles/middle.txt"), "M".to_string());
 
-    let chat_request = PawsChatRequest::new(infra.clone());
+    let chat_request = PawsChatRequest::new(infra.clone(), infra.clone());
     let url = "@[/test/onlyfiles]";
     let attachments = chat_request.attachments(url).await.unwrap();
 
@@ -759,7 +759,7 @@ async fn test_directory_sorting_case_insensitive() {
     infra.add_file(PathBuf::from("/test/casetest/Zebra.txt"), "Z".to_string());
     infra.add_file(PathBuf::from("/test/casetest/apple.txt"), "A".to_string());
 
-    let chat_request = PawsChatRequest::new(infra.clone());
+    let chat_request = PawsChatRequest::new(infra.clone(), infra.clone());
     let url = "@[/test/casetest]";
     let attachments = chat_request.attachments(url).await.unwrap();
 
@@ -783,3 +783,30 @@ async fn test_directory_sorting_case_insensitive() {
         _ => panic!("Expected DirectoryListing attachment"),
     }
 }
+
+#[tokio::test]
+async fn test_add_url_with_mcp_resource() {
+    let infra = Arc::new(MockCompositeService::new());
+    let chat_request = PawsChatRequest::new(infra.clone(), infra.clone());
+
+    let url = "Summarize @[file:///docs/readme.md] and @[/test/file1.txt]";
+    let attachments = chat_request.attachments(url).await.unwrap();
+
+    let actual = attachments
+        .iter()
+        .map(|attachment| attachment.path.as_str())
+        .collect::<Vec<_>>();
+    let expected = vec!["file:///docs/readme.md", "/test/file1.txt"];
+    assert_eq!(actual, expected);
+    assert_eq!(attachments[0].content.file_content(), Some("1:# Readme"));
+}
+
+#[tokio::test]
+async fn test_add_url_with_unknown_mcp_resource() {
+    let infra = Arc::new(MockCompositeService::new());
+    let chat_request = PawsChatRequest::new(infra.clone(), infra.clone());
+
+    let actual = chat_request.attachments("@[file:///missing.md]").await;
+
+    assert!(actual.is_err());
+}
@@ -0,0 +1,169 @@
+use std::ops::RangeInclusive;
+use std::path::Path;
+
+use paws_domain::CodeChunk;
+use tree_sitter::{Node, Parser};
+
+use crate::tool_services::syn::language;
+
+/// Chunks longer than this are split along the syntax tree, or into windows
+/// when the node has no children to split on
+const MAX_CHUNK_LINES: usize = 60;
+
+/// Size of the windows used for files without a supported grammar
+const WINDOW_LINES: usize = 40;
+
+/// Maximum number of characters kept from a chunk. Minified sources can have
+/// lines long enough to exceed the input limit of embedding models.
+const MAX_CHUNK_CHARS: usize = 6000;
+
+/// Splits a file into chunks that follow its syntax: top-level items are kept
+/// whole, large items are split along their children, and consecutive small
+/// items are grouped. Files in languages without a grammar are split into
+/// fixed size windows.
+pub fn chunk(path: &Path, content: &str) -> Vec<CodeChunk> {
+    let lines: Vec<&str> = content.lines().collect();
+    let ranges = syntax_ranges(path, content, lines.len())
+        .unwrap_or_else(|| windows(0..=lines.len().saturating_sub(1)));
+
+    ranges
+        .into_iter()
+        .filter_map(|range| {
+            let (start, end) = (
+                *range.start(),
+                (*range.end()).min(lines.len().checked_sub(1)?),
+            );
+            let text = lines.get(start..=end)?.join("\n");
+            if text.trim().is_empty() {
+                return None;
+            }
+            Some(CodeChunk {
+                start_line: start + 1,
+                end_line: end + 1,
+                content: text.chars().take(MAX_CHUNK_CHARS).collect(),
+            })
+        })
+        .collect()
+}
+
+/// Zero-based line ranges of the syntax-aware chunks, `None` when the file
+/// can't be parsed
+fn syntax_ranges(
+    path: &Path,
+    content: &str,
+    line_count: usize,
+) -> Option<Vec<RangeInclusive<usize>>> {
+    let language = language(path)?;
+    let mut parser = Parser::new();
+    parser.set_language(&language).ok()?;
+    let tree = parser.parse(content, None)?;
+    if line_count == 0 {
+        return Some(Vec::new());
+    }
+
+    let mut ranges = Vec::new();
+    split(tree.root_node(), &mut ranges);
+    Some(ranges)
+}
+
+/// Groups the children of `node` into chunks of at most `MAX_CHUNK_LINES`,
+/// recursing into children that are too large on their own
+fn split(node: Node<'_>, ranges: &mut Vec<RangeInclusive<usize>>) {
+    let mut group: Option<RangeInclusive<usize>> = None;
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        let span = child.start_position().row..=child.end_position().row;
+        if span_len(&span) > MAX_CHUNK_LINES {
+            ranges.extend(group.take());
+            if child.child_count() > 0 {
+                split(child, ranges);
+            } else {
+                ranges.extend(windows(span));
+            }
+            continue;
+        }
+        group = match group {
+            Some(current) if span_len(&(*current.start()..=*span.end())) <= MAX_CHUNK_LINES => {
+                Some(*current.start()..=*span.end())
+            }
+            Some(current) => {
+                ranges.push(current);
+                Some(span)
+            }
+            None => Some(span),
+        };
+    }
+    ranges.extend(group);
+}
+
+fn span_len(span: &RangeInclusive<usize>) -> usize {
+    span.end().saturating_sub(*span.start()) + 1
+}
+
+/// Splits a range of lines into consecutive windows of `WINDOW_LINES`
+fn windows(span: RangeInclusive<usize>) -> Vec<RangeInclusive<usize>> {
+    let (start, end) = (*span.start(), *span.end());
+    (start..=end)
+        .step_by(WINDOW_LINES)
+        .map(|window_start| window_start..=(window_start + WINDOW_LINES - 1).min(end))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn line_ranges(chunks: &[CodeChunk]) -> Vec<(usize, usize)> {
+        chunks
+            .iter()
+            .map(|chunk| (chunk.start_line, chunk.end_line))
+            .collect()
+    }
+
+    #[test]
+    fn test_chunk_groups_small_items() {
+        let fixture = "use std::fs;\n\nfn a() {}\n\nfn b() {\n    a();\n}\n";
+
+        let actual = chunk(Path::new("lib.rs"), fixture);
+
+        assert_eq!(line_ranges(&actual), vec![(1, 7)]);
+        assert_eq!(actual[0].content, fixture.trim_end());
+    }
+
+    #[test]
+    fn test_chunk_splits_large_items_along_children() {
+        let body = (0..40)
+            .map(|i| format!("    fn f{i}() {{}}\n"))
+            .collect::<String>();
+        let fixture = format!("impl Foo {{\n{body}{body}}}\n");
+
+        let actual = chunk(Path::new("lib.rs"), &fixture);
+
+        assert!(actual.len() > 1);
+        assert!(
+            actual
+                .iter()
+                .all(|chunk| chunk.end_line - chunk.start_line < MAX_CHUNK_LINES)
+        );
+        assert_eq!(actual.first().unwrap().start_line, 1);
+        assert_eq!(actual.last().unwrap().end_line, 82);
+    }
+
+    #[test]
+    fn test_chunk_unsupported_language_in_windows() {
+        let fixture = (1..=100).map(|i| format!("line {i}\n")).collect::<String>();
+
+        let actual = chunk(Path::new("notes.txt"), &fixture);
+
+        assert_eq!(line_ranges(&actual), vec![(1, 40), (41, 80), (81, 100)]);
+    }
+
+    #[test]
+    fn test_chunk_skips_blank_content() {
+        let actual = chunk(Path::new("notes.txt"), "\n\n   \n");
+
+        assert_eq!(actual, vec![]);
+    }
+}
@@ -0,0 +1,245 @@
+use anyhow::Context;
+use bytes::Bytes;
+use paws_app::HttpInfra;
+use paws_domain::{AuthDetails, Embedding, Provider, ProviderId};
+use reqwest::header::{AUTHORIZATION, CONTENT_TYPE, HeaderMap, HeaderValue};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Identifier of the local embedding, bumped whenever its features change so
+/// that existing indexes get rebuilt
+pub const LOCAL_EMBEDDER: &str = "local-hash-v1";
+
+/// Number of dimensions of the local embedding
+const LOCAL_DIMENSIONS: usize = 256;
+
+/// Model requested from the embedding provider when none is configured
+pub const DEFAULT_EMBEDDING_MODEL: &str = "text-embedding-3-small";
+
+/// Number of texts sent in a single embeddings request
+const BATCH_SIZE: usize = 64;
+
+/// Computes the embeddings of the indexed chunks and of search queries
+pub enum Embedder {
+    /// Offline stand-in hashing identifiers and character trigrams into a
+    /// fixed size vector. It has no notion of synonyms but needs no network
+    /// access or credentials.
+    Local,
+    /// An OpenAI-compatible `/embeddings` endpoint
+    Remote(Box<RemoteEmbedder>),
+}
+
+pub struct RemoteEmbedder {
+    url: Url,
+    headers: HeaderMap,
+    model: String,
+    id: String,
+}
+
+impl Embedder {
+    /// Creates an embedder for the OpenAI-compatible endpoint of `provider`
+    pub fn remote(provider: &Provider<Url>, model: String) -> anyhow::Result<Self> {
+        let url = embeddings_url(&provider.url).with_context(|| {
+            format!(
+                "Provider '{}' does not expose an OpenAI-compatible embeddings endpoint",
+                provider.id
+            )
+        })?;
+
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        if let Some(credential) = &provider.credential {
+            let token = match &credential.auth_details {
+                AuthDetails::ApiKey(key) => key.as_str(),
+                AuthDetails::OAuthWithApiKey { api_key, .. } => api_key.as_str(),
+                AuthDetails::OAuth { tokens, .. } => tokens.access_token.as_str(),
+            };
+            headers.insert(AUTHORIZATION, format!("Bearer {token}").parse()?);
+        }
+
+        let id = remote_id(&provider.id, &model);
+        Ok(Self::Remote(Box::new(RemoteEmbedder {
+            url,
+            headers,
+            model,
+            id,
+        })))
+    }
+
+    /// Identifies the embeddings produced by this embedder
+    pub fn id(&self) -> &str {
+        match self {
+            Embedder::Local => LOCAL_EMBEDDER,
+            Embedder::Remote(remote) => &remote.id,
+        }
+    }
+
+    pub async fn embed<H: HttpInfra>(
+        &self,
+        http: &H,
+        texts: &[String],
+    ) -> anyhow::Result<Vec<Embedding>> {
+        match self {
+            Embedder::Local => Ok(texts.iter().map(|text| local_embedding(text)).collect()),
+            Embedder::Remote(remote) => {
+                let RemoteEmbedder { url, headers, model, .. } = remote.as_ref();
+                let mut embeddings = Vec::with_capacity(texts.len());
+                for batch in texts.chunks(BATCH_SIZE) {
+                    let body = serde_json::to_vec(&EmbeddingRequest { model, input: batch })?;
+                    let response = http
+                        .http_post(url, Some(headers.clone()), Bytes::from(body))
+                        .await?
+                        .error_for_status()?;
+                    let mut response: EmbeddingResponse = response
+                        .json()
+                        .await
+                        .with_context(|| format!("Failed to parse the response of {url}"))?;
+                    anyhow::ensure!(
+                        response.data.len() == batch.len(),
+                        "{url} returned {} embeddings for {} inputs",
+                        response.data.len(),
+                        batch.len()
+                    );
+                    response.data.sort_by_key(|data| data.index);
+                    embeddings.extend(
+                        response
+                            .data
+                            .into_iter()
+                            .map(|data| Embedding(data.embedding)),
+                    );
+                }
+                Ok(embeddings)
+            }
+        }
+    }
+}
+
+/// Identifier of the embeddings of `model` served by `provider`
+pub fn remote_id(provider: &ProviderId, model: &str) -> String {
+    format!("{provider}/{model}")
+}
+
+/// Derives the embeddings endpoint from the chat endpoint of a provider
+fn embeddings_url(url: &Url) -> Option<Url> {
+    let path = url.path();
+    let base = path
+        .strip_suffix("chat/completions")
+        .or_else(|| path.strip_suffix("responses"))?;
+    let mut url = url.clone();
+    url.set_path(&format!("{base}embeddings"));
+    Some(url)
+}
+
+#[derive(Serialize)]
+struct EmbeddingRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+/// Hashes the identifiers of `text`, split on case and underscores, and their
+/// character trigrams into a normalized vector
+fn local_embedding(text: &str) -> Embedding {
+    let mut vector = vec![0.0f32; LOCAL_DIMENSIONS];
+    let mut add = |feature: &str, weight: f32| {
+        let hash = fnv1a(feature);
+        let bucket = (hash % LOCAL_DIMENSIONS as u64) as usize;
+        let sign = if hash & (1 << 63) == 0 { 1.0 } else { -1.0 };
+        vector[bucket] += sign * weight;
+    };
+
+    for word in words(text) {
+        add(&word, 1.0);
+        let padded: Vec<char> = format!("^{word}$").chars().collect();
+        for trigram in padded.windows(3) {
+            add(&trigram.iter().collect::<String>(), 0.5);
+        }
+    }
+
+    let norm = vector.iter().map(|value| value * value).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|value| *value /= norm);
+    }
+    Embedding(vector)
+}
+
+/// Lowercase words of `text`, splitting identifiers such as `retryCount` and
+/// `retry_count` into their parts
+fn words(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    for token in text.split(|c: char| !c.is_alphanumeric()) {
+        let mut current = String::new();
+        let mut previous: Option<char> = None;
+        for c in token.chars() {
+            let boundary = previous.is_some_and(|p| p.is_lowercase() && c.is_uppercase());
+            if boundary && !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            current.extend(c.to_lowercase());
+            previous = Some(c);
+        }
+        if !current.is_empty() {
+            words.push(current);
+        }
+    }
+    words.retain(|word| word.len() > 1);
+    words
+}
+
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_words_split_identifiers() {
+        let actual = words("fn retryWithBackoff(max_retries: u8)");
+
+        let expected = vec!["fn", "retry", "with", "backoff", "max", "retries", "u8"];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_local_embedding_ranks_related_text_higher() {
+        let query = local_embedding("retry with backoff");
+        let related = local_embedding("fn retry_with_backoff(attempts: u32) { sleep(delay * 2) }");
+        let unrelated = local_embedding("fn render_login_form(user: &User) -> Html");
+
+        assert!(query.similarity(&related) > query.similarity(&unrelated));
+    }
+
+    #[test]
+    fn test_embeddings_url() {
+        let fixture = Url::parse("https://api.openai.com/v1/chat/completions").unwrap();
+
+        let actual = embeddings_url(&fixture).unwrap();
+
+        assert_eq!(actual.as_str(), "https://api.openai.com/v1/embeddings");
+    }
+
+    #[test]
+    fn test_embeddings_url_of_other_apis() {
+        let fixture = Url::parse("https://api.anthropic.com/v1/messages").unwrap();
+
+        let actual = embeddings_url(&fixture);
+
+        assert_eq!(actual, None);
+    }
+}
@@ -0,0 +1,421 @@
+mod chunker;
+mod embedder;
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Context;
+use bytes::Bytes;
+use chrono::Utc;
+use paws_app::{
+    CodeIndexService, EnvironmentInfra, FileDirectoryInfra, FileInfoInfra, FileReaderInfra,
+    FileRemoverInfra, FileWriterInfra, HttpInfra, QueryHits, Walker, WalkerInfra, compute_hash,
+};
+use paws_domain::{
+    CodeIndex, Environment, IndexChanges, IndexStatus, IndexedChunk, IndexedFile,
+    ProviderRepository, SearchQuery,
+};
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+use self::embedder::{DEFAULT_EMBEDDING_MODEL, Embedder, LOCAL_EMBEDDER, remote_id};
+
+/// Files larger than this are skipped, they are usually generated or data
+const MAX_FILE_SIZE: u64 = 512 * 1024;
+
+/// How long searches use the index as is before rescanning the workspace,
+/// so that the searches of a turn don't each rehash every file
+const SYNC_INTERVAL: Duration = Duration::from_secs(30);
+
+/// A file of the workspace as seen by the indexer
+struct SourceFile {
+    content: String,
+    hash: String,
+}
+
+/// Maintains a local semantic search index of the workspace. The index is
+/// stored as JSON under the paws base path and refreshed incrementally: only
+/// files whose content hash changed since the last build are re-embedded.
+pub struct PawsCodeIndex<F> {
+    infra: Arc<F>,
+    /// When the index was last brought in sync with the workspace
+    synced_at: Mutex<Option<Instant>>,
+}
+
+impl<F> PawsCodeIndex<F> {
+    pub fn new(infra: Arc<F>) -> Self {
+        Self { infra, synced_at: Mutex::new(None) }
+    }
+}
+
+/// Returns whether the index has to be synced with the workspace before
+/// searching it
+fn is_stale(synced_at: Option<Instant>) -> bool {
+    synced_at.is_none_or(|synced_at| synced_at.elapsed() >= SYNC_INTERVAL)
+}
+
+impl<
+    F: EnvironmentInfra
+        + WalkerInfra
+        + FileReaderInfra
+        + FileWriterInfra
+        + FileRemoverInfra
+        + FileInfoInfra
+        + FileDirectoryInfra
+        + HttpInfra
+        + ProviderRepository,
+> PawsCodeIndex<F>
+{
+    /// Identifier of the embedder configured in the environment
+    fn configured_embedder(env: &Environment) -> String {
+        match &env.embedding_provider {
+            Some(provider) => remote_id(
+                provider,
+                env.embedding_model
+                    .as_deref()
+                    .unwrap_or(DEFAULT_EMBEDDING_MODEL),
+            ),
+            None => LOCAL_EMBEDDER.to_string(),
+        }
+    }
+
+    async fn embedder(&self, env: &Environment) -> anyhow::Result<Embedder> {
+        match &env.embedding_provider {
+            Some(provider_id) => {
+                let provider = self.infra.get_provider(provider_id.clone()).await?;
+                let model = env
+                    .embedding_model
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_EMBEDDING_MODEL.to_string());
+                Embedder::remote(&provider, model)
+            }
+            None => Ok(Embedder::Local),
+        }
+    }
+
+    async fn load(&self, path: &Path) -> anyhow::Result<Option<CodeIndex>> {
+        if !self.infra.exists(path).await? {
+            return Ok(None);
+        }
+        let content = self.infra.read_utf8(path).await?;
+        let index = serde_json::from_str(&content).with_context(|| {
+            format!(
+                "Failed to parse the index at {}, run `paws index clear` and rebuild it",
+                path.display()
+            )
+        })?;
+        Ok(Some(index))
+    }
+
+    async fn save(&self, path: &Path, index: &CodeIndex) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            self.infra.create_dirs(parent).await?;
+        }
+        let content = serde_json::to_vec(index)?;
+        self.infra.write(path, Bytes::from(content)).await
+    }
+
+    /// Reads the text files of the workspace, keyed by their path relative to
+    /// `cwd`. Files that aren't valid UTF-8 are skipped.
+    async fn scan(&self, cwd: &Path) -> anyhow::Result<BTreeMap<String, SourceFile>> {
+        let walked = self
+            .infra
+            .walk(
+                Walker::unlimited()
+                    .cwd(cwd.to_path_buf())
+                    .skip_binary(true)
+                    .max_file_size(MAX_FILE_SIZE),
+            )
+            .await
+            .with_context(|| format!("Failed to walk directory '{}'", cwd.display()))?;
+
+        let mut files = BTreeMap::new();
+        for file in walked.into_iter().filter(|file| !file.is_dir()) {
+            let Ok(content) = self.infra.read_utf8(&cwd.join(&file.path)).await else {
+                continue;
+            };
+            let hash = compute_hash(&content);
+            files.insert(file.path, SourceFile { content, hash });
+        }
+        Ok(files)
+    }
+
+    /// Brings the index in sync with the workspace, embedding the chunks of
+    /// added and modified files
+    async fn refresh(
+        &self,
+        index: Option<CodeIndex>,
+        embedder: &Embedder,
+        files: BTreeMap<String, SourceFile>,
+    ) -> anyhow::Result<(CodeIndex, IndexChanges)> {
+        let changes = changes(index.as_ref(), embedder.id(), &files);
+        let mut index = index
+            .filter(|index| index.embedder == embedder.id())
+            .unwrap_or_else(|| CodeIndex::new(embedder.id()));
+        index.files.retain(|path, _| files.contains_key(path));
+
+        let stale: Vec<(String, SourceFile)> = files
+            .into_iter()
+            .filter(|(path, file)| {
+                index
+                    .files
+                    .get(path)
+                    .is_none_or(|indexed| indexed.hash != file.hash)
+            })
+            .collect();
+
+        let chunks: Vec<(usize, paws_domain::CodeChunk)> = stale
+            .iter()
+            .enumerate()
+            .flat_map(|(position, (path, file))| {
+                chunker::chunk(Path::new(path), &file.content)
+                    .into_iter()
+                    .map(move |chunk| (position, chunk))
+            })
+            .collect();
+        // The path is part of the embedded text as it often names the concept
+        // the code implements
+        let texts: Vec<String> = chunks
+            .iter()
+            .map(|(position, chunk)| format!("{}\n{}", stale[*position].0, chunk.content))
+            .collect();
+        let embeddings = embedder.embed(self.infra.as_ref(), &texts).await?;
+
+        for (path, file) in &stale {
+            index.files.insert(
+                path.clone(),
+                IndexedFile { hash: file.hash.clone(), chunks: Vec::new() },
+            );
+        }
+        for ((position, chunk), embedding) in chunks.into_iter().zip(embeddings) {
+            if let Some(file) = index.files.get_mut(&stale[position].0) {
+                file.chunks.push(IndexedChunk { chunk, embedding });
+            }
+        }
+        if !changes.is_empty() {
+            index.updated_at = Utc::now();
+        }
+        Ok((index, changes))
+    }
+
+    fn status(env: &Environment, index: &CodeIndex, changes: IndexChanges) -> IndexStatus {
+        IndexStatus {
+            path: env.index_path(),
+            embedder: index.embedder.clone(),
+            files: index.files.len(),
+            chunks: index.chunk_count(),
+            updated_at: Some(index.updated_at),
+            changes,
+        }
+    }
+}
+
+/// Compares the workspace with the index, treating an index built with
+/// another embedder as empty
+fn changes(
+    index: Option<&CodeIndex>,
+    embedder: &str,
+    files: &BTreeMap<String, SourceFile>,
+) -> IndexChanges {
+    let indexed = index
+        .filter(|index| index.embedder == embedder)
+        .map(|index| &index.files);
+    let mut changes = IndexChanges::default();
+    for (path, file) in files {
+        match indexed.and_then(|indexed| indexed.get(path)) {
+            None => changes.added += 1,
+            Some(indexed) if indexed.hash != file.hash => changes.modified += 1,
+            Some(_) => changes.unchanged += 1,
+        }
+    }
+    changes.removed = indexed.map_or(0, |indexed| {
+        indexed
+            .keys()
+            .filter(|path| !files.contains_key(*path))
+            .count()
+    });
+    changes
+}
+
+#[async_trait::async_trait]
+impl<
+    F: EnvironmentInfra
+        + WalkerInfra
+        + FileReaderInfra
+        + FileWriterInfra
+        + FileRemoverInfra
+        + FileInfoInfra
+        + FileDirectoryInfra
+        + HttpInfra
+        + ProviderRepository,
+> CodeIndexService for PawsCodeIndex<F>
+{
+    async fn build_index(&self) -> anyhow::Result<IndexStatus> {
+        let env = self.infra.get_environment();
+        let path = env.index_path();
+        let embedder = self.embedder(&env).await?;
+        let mut synced_at = self.synced_at.lock().await;
+        let index = self.load(&path).await?;
+        let files = self.scan(&env.cwd).await?;
+
+        let (index, changes) = self.refresh(index, &embedder, files).await?;
+        self.save(&path, &index).await?;
+        *synced_at = Some(Instant::now());
+        Ok(Self::status(&env, &index, changes))
+    }
+
+    async fn index_status(&self) -> anyhow::Result<IndexStatus> {
+        let env = self.infra.get_environment();
+        let path = env.index_path();
+        let embedder = Self::configured_embedder(&env);
+        let index = self.load(&path).await?;
+        let files = self.scan(&env.cwd).await?;
+
+        let changes = changes(index.as_ref(), &embedder, &files);
+        Ok(match index {
+            Some(index) => Self::status(&env, &index, changes),
+            None => IndexStatus {
+                path,
+                embedder,
+                files: 0,
+                chunks: 0,
+                updated_at: None,
+                changes,
+            },
+        })
+    }
+
+    async fn clear_index(&self) -> anyhow::Result<bool> {
+        let path = self.infra.get_environment().index_path();
+        if !self.infra.exists(&path).await? {
+            return Ok(false);
+        }
+        self.infra.remove(&path).await?;
+        *self.synced_at.lock().await = None;
+        Ok(true)
+    }
+
+    async fn search_index(
+        &self,
+        queries: Vec<SearchQuery>,
+        file_extension: Option<String>,
+    ) -> anyhow::Result<Vec<QueryHits>> {
+        let env = self.infra.get_environment();
+        let path = env.index_path();
+        let embedder = self.embedder(&env).await?;
+        // Held while syncing so that concurrent searches don't scan together
+        let mut synced_at = self.synced_at.lock().await;
+        let index = self.load(&path).await?.ok_or_else(|| {
+            anyhow::anyhow!(
+                "The workspace has not been indexed yet. Ask the user to run `paws index build`, or use the regex search tool instead"
+            )
+        })?;
+
+        // Keep the index in sync with edits made since the last sync
+        let index = if is_stale(*synced_at) || index.embedder != embedder.id() {
+            let files = self.scan(&env.cwd).await?;
+            let (index, changes) = self.refresh(Some(index), &embedder, files).await?;
+            if !changes.is_empty() {
+                self.save(&path, &index).await?;
+            }
+            *synced_at = Some(Instant::now());
+            index
+        } else {
+            index
+        };
+        drop(synced_at);
+
+        let texts: Vec<String> = queries
+            .iter()
+            .flat_map(|query| [query.query.clone(), query.use_case.clone()])
+            .collect();
+        let embeddings = embedder.embed(self.infra.as_ref(), &texts).await?;
+
+        Ok(queries
+            .into_iter()
+            .zip(embeddings.chunks_exact(2))
+            .map(|(query, embeddings)| {
+                let hits = index.search(
+                    &embeddings[0],
+                    &embeddings[1],
+                    env.sem_search_limit,
+                    env.sem_search_top_k,
+                    file_extension.as_deref(),
+                );
+                QueryHits { query, hits }
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn source(content: &str) -> SourceFile {
+        SourceFile { content: content.to_string(), hash: compute_hash(content) }
+    }
+
+    fn indexed(content: &str) -> IndexedFile {
+        IndexedFile { hash: compute_hash(content), chunks: Vec::new() }
+    }
+
+    fn workspace() -> BTreeMap<String, SourceFile> {
+        BTreeMap::from([
+            ("a.rs".to_string(), source("fn a() {}")),
+            ("b.rs".to_string(), source("fn b() -> u8 { 2 }")),
+            ("c.rs".to_string(), source("fn c() {}")),
+        ])
+    }
+
+    fn index(embedder: &str) -> CodeIndex {
+        let mut index = CodeIndex::new(embedder);
+        index.files.insert("a.rs".to_string(), indexed("fn a() {}"));
+        index.files.insert("b.rs".to_string(), indexed("fn b() {}"));
+        index.files.insert("d.rs".to_string(), indexed("fn d() {}"));
+        index
+    }
+
+    #[test]
+    fn test_changes_against_index() {
+        let actual = changes(Some(&index(LOCAL_EMBEDDER)), LOCAL_EMBEDDER, &workspace());
+
+        let expected = IndexChanges { added: 1, modified: 1, removed: 1, unchanged: 1 };
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_changes_without_index() {
+        let actual = changes(None, LOCAL_EMBEDDER, &workspace());
+
+        let expected = IndexChanges { added: 3, ..Default::default() };
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_changes_with_another_embedder() {
+        let actual = changes(
+            Some(&index("openai/text-embedding-3-small")),
+            LOCAL_EMBEDDER,
+            &workspace(),
+        );
+
+        let expected = IndexChanges { added: 3, ..Default::default() };
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_is_stale() {
+        let synced_at = Instant::now();
+        let fresh = is_stale(Some(synced_at));
+        tokio::time::advance(SYNC_INTERVAL).await;
+
+        let actual = (is_stale(None), fresh, is_stale(Some(synced_at)));
+
+        assert_eq!(actual, (true, false, true));
+    }
+}
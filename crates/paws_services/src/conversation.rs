@@ -1,8 +1,11 @@
 use std::sync::Arc;
 
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use paws_app::ConversationService;
-use paws_app::domain::{Conversation, ConversationId};
+use paws_app::domain::{
+    Conversation, ConversationId, ConversationMatch, ModelId, ProviderId, Usage, UsageReportEntry,
+};
 use paws_domain::ConversationRepository;
 
 /// Service for managing conversations, including creation, retrieval, and
@@ -61,9 +64,45 @@ impl<S: ConversationRepository> ConversationService for PawsConversationService<
         self.conversation_repository.get_last_conversation().await
     }
 
+    async fn search_conversations(
+        &self,
+        query: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<ConversationMatch>> {
+        self.conversation_repository
+            .search_conversations(query, limit)
+            .await
+    }
+
     async fn delete_conversation(&self, conversation_id: &ConversationId) -> Result<()> {
         self.conversation_repository
             .delete_conversation(conversation_id)
             .await
     }
+
+    async fn record_usage(
+        &self,
+        conversation_id: &ConversationId,
+        usage: &Usage,
+        model: &ModelId,
+        provider: &ProviderId,
+    ) -> Result<()> {
+        self.conversation_repository
+            .record_usage(conversation_id, usage, model, provider)
+            .await
+    }
+
+    async fn usage_since(&self, since: DateTime<Utc>) -> Result<Usage> {
+        self.conversation_repository.get_usage_since(since).await
+    }
+
+    async fn conversation_usage(&self, conversation_id: &ConversationId) -> Result<Usage> {
+        self.conversation_repository
+            .get_conversation_usage(conversation_id)
+            .await
+    }
+
+    async fn usage_report(&self, since: DateTime<Utc>) -> Result<Vec<UsageReportEntry>> {
+        self.conversation_repository.get_usage_report(since).await
+    }
 }
@@ -0,0 +1,290 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use paws_app::domain::{Hook, HookOutcome, HookPayload};
+use paws_app::{CommandInfra, HookService};
+use tracing::warn;
+
+/// Seconds a hook may run for when it doesn't set its own timeout
+const DEFAULT_HOOK_TIMEOUT: u64 = 60;
+
+/// Runs hook commands with the event payload as JSON on stdin
+#[derive(Clone)]
+pub struct PawsHookService<F> {
+    infra: Arc<F>,
+}
+
+impl<F> PawsHookService<F> {
+    pub fn new(infra: Arc<F>) -> Self {
+        Self { infra }
+    }
+}
+
+#[async_trait::async_trait]
+impl<F: CommandInfra> HookService for PawsHookService<F> {
+    async fn run_hooks(
+        &self,
+        hooks: &[Hook],
+        mut payload: HookPayload,
+    ) -> anyhow::Result<HookOutcome> {
+        let mut modified = None;
+
+        for hook in hooks
+            .iter()
+            .filter(|hook| hook.matches(payload.tool_name.as_ref()))
+        {
+            let input = serde_json::to_string(&payload)?;
+            let timeout = Duration::from_secs(hook.timeout.unwrap_or(DEFAULT_HOOK_TIMEOUT));
+            let execution = self.infra.execute_command_with_input(
+                hook.command.clone(),
+                payload.cwd.clone(),
+                input,
+            );
+
+            // A hook that can't run only stops the agent when it fails closed
+            let output = match tokio::time::timeout(timeout, execution).await {
+                Ok(Ok(output)) => output,
+                Ok(Err(error)) => {
+                    warn!(
+                        command = %hook.command,
+                        event = %payload.event,
+                        error = ?error,
+                        "Failed to run hook"
+                    );
+                    if hook.is_fail_closed(payload.event) {
+                        return Ok(HookOutcome::Block(format!(
+                            "Hook `{}` failed to run: {error}",
+                            hook.command
+                        )));
+                    }
+                    continue;
+                }
+                Err(_) => {
+                    warn!(
+                        command = %hook.command,
+                        event = %payload.event,
+                        timeout = timeout.as_secs(),
+                        "Hook timed out"
+                    );
+                    if hook.is_fail_closed(payload.event) {
+                        return Ok(HookOutcome::Block(format!(
+                            "Hook `{}` timed out after {}s",
+                            hook.command,
+                            timeout.as_secs()
+                        )));
+                    }
+                    continue;
+                }
+            };
+
+            match HookOutcome::from_output(&output) {
+                Some(HookOutcome::Block(reason)) => return Ok(HookOutcome::Block(reason)),
+                Some(HookOutcome::Modify(tool_input)) => {
+                    payload.tool_input = Some(tool_input.clone());
+                    modified = Some(tool_input);
+                }
+                Some(HookOutcome::Continue) => {}
+                None => {
+                    warn!(
+                        command = %hook.command,
+                        event = %payload.event,
+                        exit_code = ?output.exit_code,
+                        stderr = %output.stderr,
+                        "Hook failed"
+                    );
+                }
+            }
+        }
+
+        Ok(modified.map_or(HookOutcome::Continue, HookOutcome::Modify))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use paws_app::domain::{CommandOutput, ConversationId, HookEvent, JobId, JobOutput, ToolName};
+    use pretty_assertions::assert_eq;
+    use serde_json::{Value, json};
+
+    use super::*;
+
+    /// Replies to every command with the given exit code and stdout, and
+    /// records the payloads it receives. `missing` fails to start and `hang`
+    /// never completes.
+    #[derive(Default)]
+    struct MockCommandInfra {
+        outputs: Vec<(&'static str, i32, &'static str)>,
+        inputs: Mutex<Vec<(String, Value)>>,
+    }
+
+    #[async_trait]
+    impl CommandInfra for MockCommandInfra {
+        async fn execute_command(
+            &self,
+            _command: String,
+            _working_dir: PathBuf,
+            _silent: bool,
+            _env_vars: Option<Vec<String>>,
+        ) -> anyhow::Result<CommandOutput> {
+            unimplemented!()
+        }
+
+        async fn execute_command_raw(
+            &self,
+            _command: &str,
+            _working_dir: PathBuf,
+            _env_vars: Option<Vec<String>>,
+        ) -> anyhow::Result<std::process::ExitStatus> {
+            unimplemented!()
+        }
+
+        async fn execute_command_with_input(
+            &self,
+            command: String,
+            _working_dir: PathBuf,
+            input: String,
+        ) -> anyhow::Result<CommandOutput> {
+            self.inputs
+                .lock()
+                .unwrap()
+                .push((command.clone(), serde_json::from_str(&input)?));
+            match command.as_str() {
+                "missing" => anyhow::bail!("No such file or directory"),
+                "hang" => std::future::pending().await,
+                _ => {}
+            }
+            let (_, exit_code, stdout) = self
+                .outputs
+                .iter()
+                .find(|(name, _, _)| *name == command)
+                .copied()
+                .unwrap_or(("", 0, ""));
+
+            Ok(CommandOutput {
+                stdout: stdout.to_string(),
+                stderr: "".to_string(),
+                command,
+                exit_code: Some(exit_code),
+            })
+        }
+
+        async fn spawn_job(
+            &self,
+            _conversation_id: ConversationId,
+            _command: String,
+            _working_dir: PathBuf,
+            _env_vars: Option<Vec<String>>,
+        ) -> anyhow::Result<JobOutput> {
+            unimplemented!()
+        }
+
+        async fn read_job(
+            &self,
+            _conversation_id: ConversationId,
+            _job_id: JobId,
+        ) -> anyhow::Result<JobOutput> {
+            unimplemented!()
+        }
+
+        async fn wait_job(
+            &self,
+            _conversation_id: ConversationId,
+            _job_id: JobId,
+            _timeout: Duration,
+        ) -> anyhow::Result<JobOutput> {
+            unimplemented!()
+        }
+
+        async fn kill_job(
+            &self,
+            _conversation_id: ConversationId,
+            _job_id: JobId,
+        ) -> anyhow::Result<JobOutput> {
+            unimplemented!()
+        }
+    }
+
+    fn payload() -> HookPayload {
+        HookPayload::new(HookEvent::PreToolUse, "/workspace")
+            .tool_name(ToolName::new("shell"))
+            .tool_input(json!({"command": "rm -rf build"}))
+    }
+
+    #[tokio::test]
+    async fn test_run_hooks_passes_modified_input_on() {
+        let infra = Arc::new(MockCommandInfra {
+            outputs: vec![("rewrite", 0, r#"{"tool_input": {"command": "ls"}}"#)],
+            ..Default::default()
+        });
+        let fixture = PawsHookService::new(infra.clone());
+        let hooks = [
+            Hook::new("rewrite"),
+            Hook::new("format").matcher("write|patch"),
+            Hook::new("log"),
+        ];
+
+        let actual = fixture.run_hooks(&hooks, payload()).await.unwrap();
+
+        assert_eq!(actual, HookOutcome::Modify(json!({"command": "ls"})));
+        let inputs = infra.inputs.lock().unwrap().clone();
+        let commands: Vec<_> = inputs.iter().map(|(command, _)| command.as_str()).collect();
+        assert_eq!(commands, vec!["rewrite", "log"]);
+        assert_eq!(inputs[1].1["tool_input"], json!({"command": "ls"}));
+        assert_eq!(inputs[1].1["event"], json!("pre_tool_use"));
+    }
+
+    #[tokio::test]
+    async fn test_run_hooks_stops_at_first_block() {
+        let infra = Arc::new(MockCommandInfra {
+            outputs: vec![("broken", 1, ""), ("guard", 2, "")],
+            ..Default::default()
+        });
+        let fixture = PawsHookService::new(infra.clone());
+        let hooks = [Hook::new("broken"), Hook::new("guard"), Hook::new("log")];
+
+        let actual = fixture.run_hooks(&hooks, payload()).await.unwrap();
+
+        assert_eq!(
+            actual,
+            HookOutcome::Block("Blocked by hook `guard`".to_string())
+        );
+        assert_eq!(infra.inputs.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_run_hooks_fails_closed_before_tool_use() {
+        let fixture = PawsHookService::new(Arc::new(MockCommandInfra::default()));
+        let hooks = |command| [Hook::new(command).timeout(5u64)];
+        let post_tool_use = payload().event(HookEvent::PostToolUse);
+
+        let actual = vec![
+            fixture
+                .run_hooks(&hooks("missing"), payload())
+                .await
+                .unwrap(),
+            fixture.run_hooks(&hooks("hang"), payload()).await.unwrap(),
+            fixture
+                .run_hooks(&hooks("hang"), post_tool_use)
+                .await
+                .unwrap(),
+            fixture
+                .run_hooks(&[Hook::new("missing").fail_closed(false)], payload())
+                .await
+                .unwrap(),
+        ];
+
+        let expected = vec![
+            HookOutcome::Block(
+                "Hook `missing` failed to run: No such file or directory".to_string(),
+            ),
+            HookOutcome::Block("Hook `hang` timed out after 5s".to_string()),
+            HookOutcome::Continue,
+            HookOutcome::Continue,
+        ];
+        assert_eq!(actual, expected);
+    }
+}
@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use bytes::Bytes;
-use paws_app::{HttpClientService, HttpInfra};
+use paws_app::{EventStream, HttpClientService, HttpInfra};
 use reqwest::Response;
 use reqwest::header::HeaderMap;
 use url::Url;
@@ -21,7 +21,7 @@ impl<T: HttpInfra> HttpClientService for HttpClient<T> {
         self.0.http_get(url, headers).await
     }
     async fn post(&self, url: &Url, body: bytes::Bytes) -> anyhow::Result<Response> {
-        self.0.http_post(url, body).await
+        self.0.http_post(url, None, body).await
     }
     async fn delete(&self, url: &Url) -> anyhow::Result<Response> {
         self.0.http_delete(url).await
@@ -33,7 +33,7 @@ impl<T: HttpInfra> HttpClientService for HttpClient<T> {
         url: &Url,
         headers: Option<HeaderMap>,
         body: Bytes,
-    ) -> anyhow::Result<reqwest_eventsource::EventSource> {
+    ) -> anyhow::Result<EventStream> {
         self.0.http_eventsource(url, headers, body).await
     }
 }
@@ -3,11 +3,13 @@ mod app_config;
 mod attachment;
 mod auth;
 mod clipper;
+mod code_index;
 mod command;
 mod conversation;
 mod discovery;
 mod env;
 mod error;
+mod hook;
 mod http;
 mod instructions;
 pub mod log;
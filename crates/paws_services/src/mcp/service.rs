@@ -1,10 +1,10 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 
 use anyhow::Context;
 use paws_app::domain::{
-    McpConfig, McpServerConfig, McpServers, ServerName, ToolCallFull, ToolDefinition, ToolName,
-    ToolOutput,
+    McpConfig, McpPrompt, McpPromptMessage, McpResource, McpResourceContent, McpServerConfig,
+    McpServers, ServerName, ToolCallFull, ToolDefinition, ToolName, ToolOutput,
 };
 use paws_app::{
     EnvironmentInfra, KVStore, McpClientInfra, McpConfigManager, McpServerInfra, McpService,
@@ -16,12 +16,20 @@ use crate::mcp::tool::McpExecutor;
 #[derive(Clone)]
 pub struct PawsMcpService<M, I, C> {
     tools: Arc<RwLock<HashMap<ToolName, ToolHolder<McpExecutor<C>>>>>,
+    servers: Arc<RwLock<HashMap<ServerName, ServerHolder<C>>>>,
     failed_servers: Arc<RwLock<HashMap<ServerName, String>>>,
     previous_config_hash: Arc<Mutex<u64>>,
     manager: Arc<M>,
     infra: Arc<I>,
 }
 
+/// Resources and prompts exposed by a connected server
+struct ServerHolder<C> {
+    client: Arc<C>,
+    resources: Vec<McpResource>,
+    prompts: Vec<McpPrompt>,
+}
+
 #[derive(Clone)]
 struct ToolHolder<T> {
     definition: ToolDefinition,
@@ -39,6 +47,7 @@ where
     pub fn new(manager: Arc<M>, infra: Arc<I>) -> Self {
         Self {
             tools: Default::default(),
+            servers: Default::default(),
             failed_servers: Default::default(),
             previous_config_hash: Arc::new(Mutex::new(Default::default())),
             manager,
@@ -53,6 +62,15 @@ where
     async fn insert_clients(&self, server_name: &ServerName, client: Arc<C>) -> anyhow::Result<()> {
         let tools = client.list().await?;
 
+        // Resources and prompts are optional, a server failing to list them can still
+        // be used for its tools
+        let resources = client.list_resources().await.unwrap_or_default();
+        let prompts = client.list_prompts().await.unwrap_or_default();
+        self.servers.write().await.insert(
+            server_name.clone(),
+            ServerHolder { client: client.clone(), resources, prompts },
+        );
+
         let mut tool_map = self.tools.write().await;
 
         for mut tool in tools.into_iter() {
@@ -162,10 +180,23 @@ where
 
         let failures = self.failed_servers.read().await.clone();
 
-        Ok(McpServers::new(grouped_tools, failures))
+        let servers = self.servers.read().await;
+        let resources = servers
+            .iter()
+            .map(|(name, server)| (name.clone(), server.resources.clone()))
+            .collect::<HashMap<_, _>>();
+        let prompts = servers
+            .iter()
+            .map(|(name, server)| (name.clone(), server.prompts.clone()))
+            .collect::<HashMap<_, _>>();
+
+        Ok(McpServers::new(grouped_tools, failures)
+            .resources(resources)
+            .prompts(prompts))
     }
     async fn clear_tools(&self) {
-        self.tools.write().await.clear()
+        self.tools.write().await.clear();
+        self.servers.write().await.clear();
     }
 
     async fn call(&self, call: ToolCallFull) -> anyhow::Result<ToolOutput> {
@@ -179,6 +210,34 @@ where
         tool.executable.call_tool(call.arguments.parse()?).await
     }
 
+    async fn read_resource(&self, uri: &str) -> anyhow::Result<Vec<McpResourceContent>> {
+        self.init_mcp().await?;
+
+        let servers = self.servers.read().await;
+        let server = servers
+            .values()
+            .find(|server| server.resources.iter().any(|resource| resource.uri == uri))
+            .context(format!("MCP resource not found: {uri}"))?;
+
+        server.client.read_resource(uri).await
+    }
+
+    async fn get_prompt(
+        &self,
+        server_name: &ServerName,
+        name: &str,
+        arguments: BTreeMap<String, String>,
+    ) -> anyhow::Result<Vec<McpPromptMessage>> {
+        self.init_mcp().await?;
+
+        let servers = self.servers.read().await;
+        let server = servers
+            .get(server_name)
+            .context(format!("MCP server not found: {server_name}"))?;
+
+        server.client.get_prompt(name, arguments).await
+    }
+
     /// Refresh the MCP cache by fetching fresh data
     async fn refresh_cache(&self) -> anyhow::Result<()> {
         // Fetch fresh tools by calling list() which connects to MCPs
@@ -220,4 +279,17 @@ where
     async fn reload_mcp(&self) -> anyhow::Result<()> {
         self.refresh_cache().await
     }
+
+    async fn read_mcp_resource(&self, uri: &str) -> anyhow::Result<Vec<McpResourceContent>> {
+        self.read_resource(uri).await
+    }
+
+    async fn render_mcp_prompt(
+        &self,
+        server: &ServerName,
+        name: &str,
+        arguments: BTreeMap<String, String>,
+    ) -> anyhow::Result<Vec<McpPromptMessage>> {
+        self.get_prompt(server, name, arguments).await
+    }
 }
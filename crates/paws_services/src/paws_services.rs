@@ -15,18 +15,21 @@ use crate::agent_registry::PawsAgentRegistryService;
 use crate::app_config::PawsAppConfigService;
 use crate::attachment::PawsChatRequest;
 use crate::auth::PawsAuthService;
+use crate::code_index::PawsCodeIndex;
 use crate::command::CommandLoaderService as PawsCommandLoaderService;
 use crate::conversation::PawsConversationService;
 use crate::discovery::PawsDiscoveryService;
 use crate::env::PawsEnvironmentService;
+use crate::hook::PawsHookService;
 use crate::instructions::PawsCustomInstructionsService;
 use crate::mcp::{PawsMcpManager, PawsMcpService};
 use crate::policy::PawsPolicyService;
 use crate::provider::PawsProviderService;
 use crate::template::PawsTemplateService;
 use crate::tool_services::{
-    PawsFetch, PawsFollowup, PawsFsCreate, PawsFsPatch, PawsFsRead, PawsFsRemove, PawsFsSearch,
-    PawsFsUndo, PawsImageRead, PawsPlanCreate, PawsShell, PawsSkillFetch,
+    PawsCodeOutline, PawsFetch, PawsFollowup, PawsFsCreate, PawsFsMultiPatch, PawsFsPatch,
+    PawsFsRead, PawsFsRemove, PawsFsSearch, PawsFsUndo, PawsImageRead, PawsPlanCreate, PawsShell,
+    PawsSkillFetch,
 };
 use crate::workflow::PawsWorkflowService;
 
@@ -58,7 +61,7 @@ pub struct PawsServices<
     config_service: Arc<PawsAppConfigService<F>>,
     conversation_service: Arc<PawsConversationService<F>>,
     template_service: Arc<PawsTemplateService<F>>,
-    attachment_service: Arc<PawsChatRequest<F>>,
+    attachment_service: Arc<PawsChatRequest<F, McpService<F>>>,
     workflow_service: Arc<PawsWorkflowService<F>>,
     discovery_service: Arc<PawsDiscoveryService<F>>,
     mcp_manager: Arc<PawsMcpManager<F>>,
@@ -67,8 +70,11 @@ pub struct PawsServices<
     file_read_service: Arc<PawsFsRead<F>>,
     image_read_service: Arc<PawsImageRead<F>>,
     file_search_service: Arc<PawsFsSearch<F>>,
+    code_index_service: Arc<PawsCodeIndex<F>>,
+    code_outline_service: Arc<PawsCodeOutline<F>>,
     file_remove_service: Arc<PawsFsRemove<F>>,
     file_patch_service: Arc<PawsFsPatch<F>>,
+    file_multi_patch_service: Arc<PawsFsMultiPatch<F>>,
     file_undo_service: Arc<PawsFsUndo<F>>,
     shell_service: Arc<PawsShell<F>>,
     fetch_service: Arc<PawsFetch>,
@@ -80,6 +86,7 @@ pub struct PawsServices<
     agent_registry_service: Arc<PawsAgentRegistryService<F>>,
     command_loader_service: Arc<PawsCommandLoaderService<F>>,
     policy_service: PawsPolicyService<F>,
+    hook_service: PawsHookService<F>,
     provider_auth_service: PawsProviderAuthService<F>,
     skill_service: Arc<PawsSkillFetch<F>>,
 }
@@ -108,7 +115,7 @@ impl<
         let mcp_manager = Arc::new(PawsMcpManager::new(infra.clone()));
         let mcp_service = Arc::new(PawsMcpService::new(mcp_manager.clone(), infra.clone()));
         let template_service = Arc::new(PawsTemplateService::new(infra.clone()));
-        let attachment_service = Arc::new(PawsChatRequest::new(infra.clone()));
+        let attachment_service = Arc::new(PawsChatRequest::new(infra.clone(), mcp_service.clone()));
         let workflow_service = Arc::new(PawsWorkflowService::new(infra.clone()));
         let suggestion_service = Arc::new(PawsDiscoveryService::new(infra.clone()));
         let conversation_service = Arc::new(PawsConversationService::new(infra.clone()));
@@ -120,8 +127,11 @@ impl<
         let file_read_service = Arc::new(PawsFsRead::new(infra.clone()));
         let image_read_service = Arc::new(PawsImageRead::new(infra.clone()));
         let file_search_service = Arc::new(PawsFsSearch::new(infra.clone()));
+        let code_index_service = Arc::new(PawsCodeIndex::new(infra.clone()));
+        let code_outline_service = Arc::new(PawsCodeOutline::new(infra.clone()));
         let file_remove_service = Arc::new(PawsFsRemove::new(infra.clone()));
         let file_patch_service = Arc::new(PawsFsPatch::new(infra.clone()));
+        let file_multi_patch_service = Arc::new(PawsFsMultiPatch::new(infra.clone()));
         let file_undo_service = Arc::new(PawsFsUndo::new(infra.clone()));
         let shell_service = Arc::new(PawsShell::new(infra.clone()));
         let fetch_service = Arc::new(PawsFetch::new());
@@ -132,6 +142,7 @@ impl<
         let agent_registry_service = Arc::new(PawsAgentRegistryService::new(infra.clone()));
         let command_loader_service = Arc::new(PawsCommandLoaderService::new(infra.clone()));
         let policy_service = PawsPolicyService::new(infra.clone());
+        let hook_service = PawsHookService::new(infra.clone());
         let provider_auth_service = PawsProviderAuthService::new(infra.clone());
         let skill_service = Arc::new(PawsSkillFetch::new(infra.clone()));
 
@@ -147,8 +158,11 @@ impl<
             file_read_service,
             image_read_service,
             file_search_service,
+            code_index_service,
+            code_outline_service,
             file_remove_service,
             file_patch_service,
+            file_multi_patch_service,
             file_undo_service,
             shell_service,
             fetch_service,
@@ -162,6 +176,7 @@ impl<
             agent_registry_service,
             command_loader_service,
             policy_service,
+            hook_service,
             provider_auth_service,
             skill_service,
         }
@@ -203,7 +218,7 @@ impl<
     fn provider_auth_service(&self) -> &Self::ProviderAuthService {
         &self.provider_auth_service
     }
-    type AttachmentService = PawsChatRequest<F>;
+    type AttachmentService = PawsChatRequest<F, McpService<F>>;
     type EnvironmentService = PawsEnvironmentService<F>;
     type CustomInstructionsService = PawsCustomInstructionsService<F>;
     type WorkflowService = PawsWorkflowService<F>;
@@ -212,10 +227,13 @@ impl<
     type FsCreateService = PawsFsCreate<F>;
     type PlanCreateService = PawsPlanCreate<F>;
     type FsPatchService = PawsFsPatch<F>;
+    type FsMultiPatchService = PawsFsMultiPatch<F>;
     type FsReadService = PawsFsRead<F>;
     type ImageReadService = PawsImageRead<F>;
     type FsRemoveService = PawsFsRemove<F>;
     type FsSearchService = PawsFsSearch<F>;
+    type CodeIndexService = PawsCodeIndex<F>;
+    type CodeOutlineService = PawsCodeOutline<F>;
     type FollowUpService = PawsFollowup<F>;
     type FsUndoService = PawsFsUndo<F>;
     type NetFetchService = PawsFetch;
@@ -225,6 +243,7 @@ impl<
     type AgentRegistry = PawsAgentRegistryService<F>;
     type CommandLoaderService = PawsCommandLoaderService<F>;
     type PolicyService = PawsPolicyService<F>;
+    type HookService = PawsHookService<F>;
     type SkillFetchService = PawsSkillFetch<F>;
 
     fn provider_service(&self) -> &Self::ProviderService {
@@ -278,6 +297,10 @@ impl<
         &self.file_patch_service
     }
 
+    fn fs_multi_patch_service(&self) -> &Self::FsMultiPatchService {
+        &self.file_multi_patch_service
+    }
+
     fn fs_read_service(&self) -> &Self::FsReadService {
         &self.file_read_service
     }
@@ -294,6 +317,14 @@ impl<
         &self.file_search_service
     }
 
+    fn code_index_service(&self) -> &Self::CodeIndexService {
+        &self.code_index_service
+    }
+
+    fn code_outline_service(&self) -> &Self::CodeOutlineService {
+        &self.code_outline_service
+    }
+
     fn follow_up_service(&self) -> &Self::FollowUpService {
         &self.followup_service
     }
@@ -330,6 +361,10 @@ impl<
         &self.policy_service
     }
 
+    fn hook_service(&self) -> &Self::HookService {
+        &self.hook_service
+    }
+
     fn skill_fetch_service(&self) -> &Self::SkillFetchService {
         &self.skill_service
     }
@@ -13,6 +13,7 @@ use paws_app::{
     PolicyDecision, PolicyService, UserInfra,
 };
 use strum_macros::{Display, EnumIter};
+use tokio::sync::Mutex;
 
 /// User response for permission confirmation requests
 #[derive(Debug, Clone, PartialEq, Eq, Display, EnumIter, strum_macros::EnumString)]
@@ -31,6 +32,9 @@ pub enum PolicyPermission {
 #[derive(Clone)]
 pub struct PawsPolicyService<I> {
     infra: Arc<I>,
+    // Tool calls can be executed concurrently, so permission checks are
+    // serialized to avoid overlapping prompts and lost policy updates.
+    lock: Arc<Mutex<()>>,
 }
 lazy_static! {
     /// Default policies loaded once at startup from the embedded YAML file
@@ -46,7 +50,7 @@ where
     I: FileReaderInfra + FileWriterInfra + FileInfoInfra + EnvironmentInfra + DirectoryReaderInfra,
 {
     pub fn new(infra: Arc<I>) -> Self {
-        Self { infra }
+        Self { infra, lock: Arc::new(Mutex::new(())) }
     }
 
     fn permissions_path(&self) -> PathBuf {
@@ -164,6 +168,7 @@ where
         &self,
         operation: &PermissionOperation,
     ) -> anyhow::Result<PolicyDecision> {
+        let _guard = self.lock.lock().await;
         let (policies, path) = self.get_or_create_policies().await?;
 
         let engine = PolicyEngine::new(&policies);
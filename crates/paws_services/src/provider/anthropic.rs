@@ -152,13 +152,12 @@ impl<T: HttpClientService> Anthropic<T> {
 mod tests {
 
     use bytes::Bytes;
-    use paws_app::HttpClientService;
     use paws_app::domain::{
         Context, ContextMessage, ToolCallFull, ToolCallId, ToolChoice, ToolName, ToolOutput,
         ToolResult,
     };
+    use paws_app::{EventStream, HttpClientService};
     use reqwest::header::HeaderMap;
-    use reqwest_eventsource::EventSource;
 
     use super::*;
     use crate::provider::mock_server::{MockServer, normalize_ports};
@@ -202,7 +201,7 @@ mod tests {
             _url: &Url,
             _headers: Option<HeaderMap>,
             _body: Bytes,
-        ) -> anyhow::Result<EventSource> {
+        ) -> anyhow::Result<EventStream> {
             // For now, return an error since eventsource is not used in the failing tests
             Err(anyhow::anyhow!("EventSource not implemented in mock"))
         }
@@ -3,7 +3,7 @@ use aws_sdk_bedrockruntime::Client;
 use aws_sdk_bedrockruntime::config::Token;
 use paws_app::HttpClientService;
 use paws_domain::{
-    AuthDetails, ChatCompletionMessage, Context, Model, ModelId, Provider, ResultStream,
+    AuthDetails, ChatCompletionMessage, Context, Model, ModelId, Provider, RateLimit, ResultStream,
     Transformer,
 };
 use reqwest::Url;
@@ -202,15 +202,25 @@ impl<H: HttpClientService> BedrockProvider<H> {
                     _ => Self::is_retryable_sdk_error(&sdk_error),
                 };
 
+                let rate_limit = sdk_error.raw_response().and_then(|response| {
+                    RateLimit::parse(response.headers().iter(), chrono::Utc::now())
+                });
+
                 // Extract the source error for better error messages
                 // SAFETY: into_source() always returns Ok for all SdkError variants
                 // (see aws-smithy-runtime-api/src/client/result.rs:448-459)
                 let source = sdk_error.into_source().unwrap();
 
+                // Kept in the error chain to honour the limits when retrying
+                let error = match rate_limit {
+                    Some(rate_limit) => anyhow::Error::msg(rate_limit).context(source.to_string()),
+                    None => anyhow::anyhow!("{}", source),
+                };
+
                 if is_retryable {
-                    paws_domain::Error::Retryable(anyhow::anyhow!("{}", source)).into()
+                    paws_domain::Error::Retryable(error).into()
                 } else {
-                    anyhow::anyhow!("{}", source)
+                    error
                 }
             })?;
 
@@ -919,7 +929,7 @@ mod tests {
             _url: &reqwest::Url,
             _headers: Option<reqwest::header::HeaderMap>,
             _body: bytes::Bytes,
-        ) -> anyhow::Result<reqwest_eventsource::EventSource> {
+        ) -> anyhow::Result<paws_app::EventStream> {
             Err(anyhow::anyhow!("Mock HTTP client - no real requests"))
         }
     }
@@ -951,6 +961,7 @@ mod tests {
                 auth_details: AuthDetails::ApiKey(ApiKey::from(token.to_string())),
                 url_params,
             }),
+            pricing: Default::default(),
         }
     }
 
@@ -10,14 +10,16 @@ use paws_app::domain::{
     ChatCompletionMessage, Context, HttpConfig, Model, ModelId, ProviderResponse, ResultStream,
     RetryConfig,
 };
-use paws_domain::Provider;
+use paws_domain::{Provider, ProviderId};
 use reqwest::Url;
 use reqwest::header::HeaderMap;
 use tokio::sync::RwLock;
 use tokio_stream::StreamExt;
 
 use crate::provider::anthropic::Anthropic;
+use crate::provider::gemini::Gemini;
 use crate::provider::openai::OpenAIProvider;
+use crate::provider::openai_responses::OpenAIResponsesProvider;
 use crate::provider::retry::into_retry;
 
 #[derive(Setters)]
@@ -54,11 +56,38 @@ impl ClientBuilder {
         })?;
 
         let inner = match response_type {
+            ProviderResponse::OpenAI if provider.id == ProviderId::VERTEX_AI => {
+                let token = provider
+                    .api_key()
+                    .context("Vertex AI provider requires an access token")?
+                    .as_str()
+                    .to_string();
+                // Only Google's models need the native endpoint, the others stay
+                // usable when it can't be derived from the configured URL
+                let gemini = vertex_gemini_url(&provider.url).map(|url| {
+                    Box::new(Gemini::new(
+                        http.clone(),
+                        token,
+                        url,
+                        paws_domain::ModelSource::Hardcoded(Vec::new()),
+                        true,
+                    ))
+                });
+                InnerClient::VertexAI(
+                    Box::new(OpenAIProvider::new(provider.clone(), http.clone())),
+                    gemini,
+                )
+            }
+
             ProviderResponse::OpenAI => InnerClient::OpenAICompat(Box::new(OpenAIProvider::new(
                 provider.clone(),
                 http.clone(),
             ))),
 
+            ProviderResponse::OpenAIResponses => InnerClient::OpenAIResponses(Box::new(
+                OpenAIResponsesProvider::new(provider.clone(), http.clone()),
+            )),
+
             ProviderResponse::Anthropic => {
                 let url = provider.url.clone();
                 let models = provider
@@ -96,9 +125,54 @@ impl ClientBuilder {
                 }
             }
 
-            ProviderResponse::Bedrock => InnerClient::Bedrock(Box::new(
-                crate::provider::bedrock::BedrockProvider::new(provider.clone())?,
-            )),
+            ProviderResponse::Bedrock => {
+                // The AWS SDK sends its own requests, which can't be routed
+                // through the cassette server
+                if let Some(cassette) = &self.timeout_config.cassette {
+                    anyhow::bail!(
+                        "Bedrock doesn't support HTTP cassettes, unset FORGE_HTTP_CASSETTE ({}) to use it",
+                        cassette.path.display()
+                    );
+                }
+                InnerClient::Bedrock(Box::new(crate::provider::bedrock::BedrockProvider::new(
+                    provider.clone(),
+                )?))
+            }
+
+            ProviderResponse::Gemini => {
+                let url = provider.url.clone();
+                let models = provider
+                    .models
+                    .clone()
+                    .ok_or_else(|| anyhow::anyhow!("Provider models configuration is required"))?;
+                let creds = provider
+                    .credential
+                    .context("Gemini provider requires credentials")?
+                    .auth_details;
+                match creds {
+                    paws_domain::AuthDetails::ApiKey(api_key) => {
+                        InnerClient::Gemini(Box::new(Gemini::new(
+                            http.clone(),
+                            api_key.as_str().to_string(),
+                            url,
+                            models,
+                            false,
+                        )))
+                    }
+                    paws_domain::AuthDetails::OAuth { tokens, .. } => {
+                        InnerClient::Gemini(Box::new(Gemini::new(
+                            http.clone(),
+                            tokens.access_token.as_str().to_string(),
+                            url,
+                            models,
+                            true,
+                        )))
+                    }
+                    _ => {
+                        anyhow::bail!("Unsupported authentication method for Gemini provider",);
+                    }
+                }
+            }
         };
 
         Ok(Client {
@@ -127,8 +201,14 @@ impl<T> Clone for Client<T> {
 
 enum InnerClient<T> {
     OpenAICompat(Box<OpenAIProvider<T>>),
+    OpenAIResponses(Box<OpenAIResponsesProvider<T>>),
     Anthropic(Box<Anthropic<T>>),
     Bedrock(Box<crate::provider::bedrock::BedrockProvider<T>>),
+    Gemini(Box<Gemini<T>>),
+    /// Vertex AI serves models of several publishers through its OpenAI
+    /// compatible endpoint, Google's own models go through the native Gemini
+    /// endpoints so reasoning details are kept
+    VertexAI(Box<OpenAIProvider<T>>, anyhow::Result<Box<Gemini<T>>>),
 }
 
 impl<T: HttpClientService> Client<T> {
@@ -140,8 +220,11 @@ impl<T: HttpClientService> Client<T> {
     pub async fn refresh_models(&self) -> anyhow::Result<Vec<Model>> {
         let models = self.clone().retry(match self.inner.as_ref() {
             InnerClient::OpenAICompat(provider) => provider.models().await,
+            InnerClient::OpenAIResponses(provider) => provider.models().await,
             InnerClient::Anthropic(provider) => provider.models().await,
             InnerClient::Bedrock(provider) => provider.models().await,
+            InnerClient::Gemini(provider) => provider.models().await,
+            InnerClient::VertexAI(provider, _) => provider.models().await,
         })?;
 
         // Update the cache with all fetched models
@@ -165,8 +248,19 @@ impl<T: HttpClientService> Client<T> {
     ) -> ResultStream<ChatCompletionMessage, anyhow::Error> {
         let chat_stream = self.clone().retry(match self.inner.as_ref() {
             InnerClient::OpenAICompat(provider) => provider.chat(model, context).await,
+            InnerClient::OpenAIResponses(provider) => provider.chat(model, context).await,
             InnerClient::Anthropic(provider) => provider.chat(model, context).await,
             InnerClient::Bedrock(provider) => provider.chat(model, context).await,
+            InnerClient::Gemini(provider) => provider.chat(model, context).await,
+            InnerClient::VertexAI(provider, gemini) => {
+                match model.as_str().strip_prefix(VERTEX_GOOGLE_PREFIX) {
+                    Some(gemini_model) => match gemini {
+                        Ok(gemini) => gemini.chat(&ModelId::new(gemini_model), context).await,
+                        Err(error) => Err(anyhow::anyhow!("{error:#}")),
+                    },
+                    None => provider.chat(model, context).await,
+                }
+            }
         })?;
 
         let this: Client<T> = self.clone();
@@ -196,6 +290,20 @@ impl<T: HttpClientService> Client<T> {
     }
 }
 
+/// Prefix of the ids of Google's models on Vertex AI
+const VERTEX_GOOGLE_PREFIX: &str = "google/";
+
+/// Derives the native endpoint of Google's models from the OpenAI compatible
+/// chat endpoint of a Vertex AI location
+fn vertex_gemini_url(chat_url: &Url) -> anyhow::Result<Url> {
+    let url = chat_url.as_str();
+    let location = url
+        .strip_suffix("/endpoints/openapi/chat/completions")
+        .with_context(|| format!("Unexpected Vertex AI endpoint: {url}"))?;
+    Url::parse(&format!("{location}/publishers/google/models"))
+        .with_context(|| format!("Invalid Vertex AI endpoint: {url}"))
+}
+
 pub fn join_url(base_url: &str, path: &str) -> anyhow::Result<Url> {
     // Validate the path doesn't contain certain patterns
     if path.contains("://") || path.contains("..") {
@@ -228,11 +336,10 @@ mod tests {
     use std::sync::Arc;
 
     use bytes::Bytes;
-    use paws_app::HttpClientService;
     use paws_app::domain::ProviderId;
+    use paws_app::{EventStream, HttpClientService};
     use reqwest::Url;
     use reqwest::header::HeaderMap;
-    use reqwest_eventsource::EventSource;
 
     use super::*;
 
@@ -262,7 +369,7 @@ mod tests {
             _url: &Url,
             _headers: Option<HeaderMap>,
             _body: Bytes,
-        ) -> anyhow::Result<EventSource> {
+        ) -> anyhow::Result<EventStream> {
             Err(anyhow::anyhow!("Mock HTTP client - no real requests"))
         }
     }
@@ -277,6 +384,59 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_vertex_gemini_url() {
+        let fixture = Url::parse(
+            "https://us-central1-aiplatform.googleapis.com/v1/projects/p/locations/us-central1/endpoints/openapi/chat/completions",
+        )
+        .unwrap();
+
+        let actual = vertex_gemini_url(&fixture).unwrap();
+
+        let expected = Url::parse(
+            "https://us-central1-aiplatform.googleapis.com/v1/projects/p/locations/us-central1/publishers/google/models",
+        )
+        .unwrap();
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_vertex_with_unexpected_url_only_fails_for_google_models() {
+        let provider = paws_domain::Provider {
+            id: ProviderId::VERTEX_AI,
+            provider_type: paws_domain::ProviderType::Llm,
+            response: Some(ProviderResponse::OpenAI),
+            url: Url::parse("https://proxy.example.com/v1/chat/completions").unwrap(),
+            credential: make_test_credential(),
+            auth_methods: vec![paws_domain::AuthMethod::ApiKey],
+            url_params: vec![],
+            models: None,
+            pricing: Default::default(),
+        };
+        let client = ClientBuilder::new(provider, "dev")
+            .build(Arc::new(MockHttpClient))
+            .unwrap();
+
+        let chat = |model: &'static str| {
+            let client = client.clone();
+            async move {
+                client
+                    .chat(&ModelId::new(model), Context::default())
+                    .await
+                    .err()
+                    .unwrap()
+                    .to_string()
+            }
+        };
+        let actual = (
+            chat("google/gemini-2.5-pro").await,
+            chat("meta/llama-4").await,
+        );
+
+        assert!(actual.0.contains("Unexpected Vertex AI endpoint"));
+        assert!(!actual.1.contains("Unexpected Vertex AI endpoint"));
+    }
+
     #[tokio::test]
     async fn test_cache_initialization() {
         let provider = paws_domain::Provider {
@@ -290,6 +450,7 @@ mod tests {
             models: Some(paws_domain::ModelSource::Url(
                 Url::parse("https://api.openai.com/v1/models").unwrap(),
             )),
+            pricing: Default::default(),
         };
         let client = ClientBuilder::new(provider, "dev")
             .build(Arc::new(MockHttpClient))
@@ -313,6 +474,7 @@ mod tests {
             models: Some(paws_domain::ModelSource::Url(
                 Url::parse("https://api.openai.com/v1/models").unwrap(),
             )),
+            pricing: Default::default(),
         };
         let client = ClientBuilder::new(provider, "dev")
             .build(Arc::new(MockHttpClient))
@@ -338,6 +500,7 @@ mod tests {
             models: Some(paws_domain::ModelSource::Url(
                 Url::parse("https://api.openai.com/v1/models").unwrap(),
             )),
+            pricing: Default::default(),
         };
 
         // Test the builder pattern API
@@ -366,6 +529,7 @@ mod tests {
             models: Some(paws_domain::ModelSource::Url(
                 Url::parse("https://api.openai.com/v1/models").unwrap(),
             )),
+            pricing: Default::default(),
         };
 
         // Test that ClientBuilder::new works with minimal parameters
@@ -377,4 +541,64 @@ mod tests {
         let cache = client.models_cache.read().await;
         assert!(cache.is_empty());
     }
+
+    #[test]
+    fn test_openai_responses_provider_uses_responses_client() {
+        let provider = paws_domain::Provider {
+            id: ProviderId::OPENAI,
+            provider_type: paws_domain::ProviderType::Llm,
+            response: Some(ProviderResponse::OpenAIResponses),
+            url: Url::parse("https://api.openai.com/v1/responses").unwrap(),
+            credential: make_test_credential(),
+            auth_methods: vec![paws_domain::AuthMethod::ApiKey],
+            url_params: vec![],
+            models: Some(paws_domain::ModelSource::Url(
+                Url::parse("https://api.openai.com/v1/models").unwrap(),
+            )),
+            pricing: Default::default(),
+        };
+
+        let client = ClientBuilder::new(provider, "dev")
+            .build(Arc::new(MockHttpClient))
+            .unwrap();
+
+        assert!(matches!(
+            client.inner.as_ref(),
+            InnerClient::OpenAIResponses(_)
+        ));
+    }
+
+    #[test]
+    fn test_bedrock_rejects_cassette() {
+        let provider = paws_domain::Provider {
+            id: ProviderId::BEDROCK,
+            provider_type: paws_domain::ProviderType::Llm,
+            response: Some(ProviderResponse::Bedrock),
+            url: Url::parse("https://bedrock-runtime.us-east-1.amazonaws.com").unwrap(),
+            credential: make_test_credential(),
+            auth_methods: vec![paws_domain::AuthMethod::ApiKey],
+            url_params: vec![],
+            models: None,
+            pricing: Default::default(),
+        };
+        let timeout_config = HttpConfig {
+            cassette: Some(paws_domain::CassetteConfig {
+                path: "/tmp/session.json".into(),
+                mode: paws_domain::CassetteMode::Replay,
+            }),
+            ..Default::default()
+        };
+
+        let actual = ClientBuilder::new(provider, "dev")
+            .timeout_config(timeout_config)
+            .build(Arc::new(MockHttpClient));
+
+        assert!(
+            actual
+                .err()
+                .unwrap()
+                .to_string()
+                .contains("Bedrock doesn't support HTTP cassettes")
+        );
+    }
 }
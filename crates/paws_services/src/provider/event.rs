@@ -1,83 +1,148 @@
 use anyhow::Context;
+use paws_app::EventStream;
 use paws_app::domain::ChatCompletionMessage;
 use paws_app::dto::openai::Error;
 use reqwest::Url;
-use reqwest_eventsource::{Event, EventSource};
+use reqwest_eventsource::Event;
 use serde::de::DeserializeOwned;
 use tokio_stream::{Stream, StreamExt};
 use tracing::debug;
 
-use super::utils::format_http_context;
+use super::utils::{format_http_context, rate_limit};
 
 pub fn into_chat_completion_message<Response>(
     url: Url,
-    source: EventSource,
+    source: EventStream,
 ) -> impl Stream<Item = anyhow::Result<ChatCompletionMessage>>
 where
     Response: DeserializeOwned,
     ChatCompletionMessage: TryFrom<Response, Error = anyhow::Error>,
 {
+    let mut reported = source.rate_limit;
     source
-            .take_while(|message| !matches!(message, Err(reqwest_eventsource::Error::StreamEnded)))
-            .then(|event| async {
-                match event {
-                    Ok(event) => match event {
-                        Event::Open => None,
-                        Event::Message(event) if ["[DONE]", ""].contains(&event.data.as_str()) => {
-
-                            debug!("Received completion from Upstream");
-                            None
-                        }
-                        Event::Message(message) => Some(
-                            serde_json::from_str::<Response>(&message.data)
-                                .with_context(|| {
-                                    format!(
-                                        "Failed to parse provider response: {}",
-                                        message.data
-                                    )
+        .events
+        .take_while(|message| !matches!(message, Err(reqwest_eventsource::Error::StreamEnded)))
+        .then(|event| async {
+            match event {
+                Ok(event) => match event {
+                    Event::Open => None,
+                    Event::Message(event) if ["[DONE]", ""].contains(&event.data.as_str()) => {
+                        debug!("Received completion from Upstream");
+                        None
+                    }
+                    Event::Message(message) => Some(
+                        serde_json::from_str::<Response>(&message.data)
+                            .with_context(|| {
+                                format!("Failed to parse provider response: {}", message.data)
+                            })
+                            .and_then(|response| {
+                                ChatCompletionMessage::try_from(response).with_context(|| {
+                                    format!("Failed to create completion message: {}", message.data)
                                 })
-                                .and_then(|response| {
-                                    ChatCompletionMessage::try_from(response).with_context(
-                                        || {
-                                            format!(
-                                                "Failed to create completion message: {}",
-                                                message.data
-                                            )
-                                        },
-                                    )
-                                }),
-                        ),
-                    },
-                    Err(error) => match error {
-                        reqwest_eventsource::Error::StreamEnded => None,
-                        reqwest_eventsource::Error::InvalidStatusCode(_, response) => {
-                            let status = response.status();
-                            let body = response.text().await.ok();
-                            Some(Err(Error::InvalidStatusCode(status.as_u16())).with_context(
-                                || match body {
-                                    Some(body) => {
-                                        format!("{status} Reason: {body}")
-                                    }
-                                    None => {
-                                        format!("{status} Reason: [Unknown]")
-                                    }
-                                },
-                            ))
-                        }
-                        reqwest_eventsource::Error::InvalidContentType(_, ref response) => {
-                            let status_code = response.status();
-                            debug!(response = ?response, "Invalid content type");
-                            Some(Err(error).with_context(|| format!("Http Status: {status_code}")))
-                        }
-                        error => {
-                            tracing::error!(error = ?error, "Failed to receive chat completion event");
-                            Some(Err(error.into()))
-                        }
-                    },
-                }
+                            }),
+                    ),
+                },
+                Err(error) => match error {
+                    reqwest_eventsource::Error::StreamEnded => None,
+                    reqwest_eventsource::Error::InvalidStatusCode(_, response) => {
+                        let status = response.status();
+                        let rate_limit = rate_limit(response.headers());
+                        let body = response.text().await.ok();
+                        let error = anyhow::Error::from(Error::InvalidStatusCode(status.as_u16()));
+                        // Kept in the error chain to honour the limits when retrying
+                        let error = match rate_limit {
+                            Some(rate_limit) => error.context(rate_limit),
+                            None => error,
+                        };
+                        Some(Err(error).with_context(|| match body {
+                            Some(body) => {
+                                format!("{status} Reason: {body}")
+                            }
+                            None => {
+                                format!("{status} Reason: [Unknown]")
+                            }
+                        }))
+                    }
+                    reqwest_eventsource::Error::InvalidContentType(_, ref response) => {
+                        let status_code = response.status();
+                        debug!(response = ?response, "Invalid content type");
+                        Some(Err(error).with_context(|| format!("Http Status: {status_code}")))
+                    }
+                    error => {
+                        tracing::error!(error = ?error, "Failed to receive chat completion event");
+                        Some(Err(error.into()))
+                    }
+                },
+            }
+        })
+        .filter_map(move |response| {
+            response.map(|result| {
+                result.with_context(|| format_http_context(None, "POST", url.clone()))
             })
-            .filter_map(move |response| {
-                response
-                    .map(|result| result.with_context(|| format_http_context(None, "POST", url.clone())))
+        })
+        .map(move |message| {
+            message.map(|message| match reported.take() {
+                Some(rate_limit) => message.rate_limit(rate_limit),
+                None => message,
             })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use paws_app::domain::{RateLimit, RateLimitWindow};
+    use paws_app::dto::openai::Response;
+    use pretty_assertions::assert_eq;
+    use reqwest_eventsource::RequestBuilderExt;
+
+    use super::*;
+    use crate::provider::mock_server::MockServer;
+
+    #[tokio::test]
+    async fn test_rate_limit_is_kept_in_error() {
+        let mut fixture = MockServer::new().await;
+        let _mock = fixture
+            .mock_rate_limited(
+                "/chat/completions",
+                &[
+                    ("retry-after", "7"),
+                    ("x-ratelimit-limit-requests", "60"),
+                    ("x-ratelimit-remaining-requests", "0"),
+                ],
+            )
+            .await;
+        let url = Url::parse(&fixture.url())
+            .unwrap()
+            .join("chat/completions")
+            .unwrap();
+        let source = reqwest::Client::new()
+            .post(url.clone())
+            .eventsource()
+            .unwrap()
+            .into();
+
+        let error = Box::pin(into_chat_completion_message::<Response>(url, source))
+            .next()
+            .await
+            .unwrap()
+            .unwrap_err();
+
+        let actual = RateLimit::find(&error).cloned();
+        let expected = Some(RateLimit {
+            retry_after: Some(Duration::from_secs(7)),
+            requests: Some(RateLimitWindow {
+                limit: Some(60),
+                remaining: Some(0),
+                reset_after: None,
+            }),
+            tokens: None,
+        });
+        assert_eq!(actual, expected);
+        assert!(matches!(
+            error.downcast_ref::<Error>(),
+            Some(Error::InvalidStatusCode(429))
+        ));
+    }
 }
@@ -0,0 +1,271 @@
+use std::sync::Arc;
+
+use anyhow::Context as _;
+use paws_app::HttpClientService;
+use paws_app::domain::{ChatCompletionMessage, Context, Model, ModelId, ResultStream};
+use paws_app::dto::gemini::{EventData, ListModelResponse, Request};
+use reqwest::Url;
+use tracing::debug;
+
+use crate::provider::client::create_headers;
+use crate::provider::event::into_chat_completion_message;
+use crate::provider::utils::format_http_context;
+
+/// Talks to the native Gemini API (`generateContent`) instead of its OpenAI
+/// compatibility layer, so thought signatures and tool schemas survive the
+/// round trip.
+#[derive(Clone)]
+pub struct Gemini<T> {
+    http: Arc<T>,
+    api_key: String,
+    /// Base URL of the model resources, e.g. `.../v1beta/models`
+    url: Url,
+    models: paws_domain::ModelSource<Url>,
+    use_oauth: bool,
+}
+
+impl<H: HttpClientService> Gemini<H> {
+    pub fn new(
+        http: Arc<H>,
+        api_key: String,
+        url: Url,
+        models: paws_domain::ModelSource<Url>,
+        use_oauth: bool,
+    ) -> Self {
+        Self { http, api_key, url, models, use_oauth }
+    }
+
+    fn get_headers(&self) -> Vec<(String, String)> {
+        // Use Authorization: Bearer for OAuth, x-goog-api-key for API key auth
+        if self.use_oauth {
+            vec![(
+                "authorization".to_string(),
+                format!("Bearer {}", self.api_key),
+            )]
+        } else {
+            vec![("x-goog-api-key".to_string(), self.api_key.clone())]
+        }
+    }
+
+    /// Builds the streaming endpoint for `model`
+    fn chat_url(&self, model: &ModelId) -> anyhow::Result<Url> {
+        let model = model.as_str();
+        let model = model.strip_prefix("models/").unwrap_or(model);
+        let mut url = Url::parse(&format!(
+            "{}/{model}:streamGenerateContent",
+            self.url.as_str().trim_end_matches('/')
+        ))
+        .with_context(|| format!("Invalid Gemini model: {model}"))?;
+        url.query_pairs_mut().append_pair("alt", "sse");
+        Ok(url)
+    }
+}
+
+impl<T: HttpClientService> Gemini<T> {
+    pub async fn chat(
+        &self,
+        model: &ModelId,
+        context: Context,
+    ) -> ResultStream<ChatCompletionMessage, anyhow::Error> {
+        let request = Request::try_from(context)?;
+        let url = self.chat_url(model)?;
+        debug!(url = %url, model = %model, "Connecting Upstream");
+
+        let json_bytes =
+            serde_json::to_vec(&request).with_context(|| "Failed to serialize request")?;
+
+        let source = self
+            .http
+            .eventsource(
+                &url,
+                Some(create_headers(self.get_headers())),
+                json_bytes.into(),
+            )
+            .await
+            .with_context(|| format_http_context(None, "POST", &url))?;
+
+        let stream = into_chat_completion_message::<EventData>(url, source);
+
+        Ok(Box::pin(stream))
+    }
+
+    pub async fn models(&self) -> anyhow::Result<Vec<Model>> {
+        match &self.models {
+            paws_domain::ModelSource::Url(url) => {
+                debug!(url = %url, "Fetching models");
+
+                let response = self
+                    .http
+                    .get(url, Some(create_headers(self.get_headers())))
+                    .await
+                    .with_context(|| format_http_context(None, "GET", url))
+                    .with_context(|| "Failed to fetch models")?;
+
+                let status = response.status();
+                let ctx_msg = format_http_context(Some(status), "GET", url);
+                let text = response
+                    .text()
+                    .await
+                    .with_context(|| ctx_msg.clone())
+                    .with_context(|| "Failed to decode response into text")?;
+
+                if status.is_success() {
+                    let response: ListModelResponse = serde_json::from_str(&text)
+                        .with_context(|| ctx_msg)
+                        .with_context(|| "Failed to deserialize models response")?;
+                    Ok(response
+                        .models
+                        .into_iter()
+                        .filter(|model| model.supports_generate_content())
+                        .map(Into::into)
+                        .collect())
+                } else {
+                    // treat non 200 response as error.
+                    Err(anyhow::anyhow!(text))
+                        .with_context(|| ctx_msg)
+                        .with_context(|| "Failed to fetch the models")
+                }
+            }
+            paws_domain::ModelSource::Hardcoded(models) => {
+                debug!("Using hardcoded models");
+                Ok(models.clone())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use paws_app::EventStream;
+    use pretty_assertions::assert_eq;
+    use reqwest::header::HeaderMap;
+
+    use super::*;
+    use crate::provider::mock_server::MockServer;
+
+    #[derive(Clone)]
+    struct MockHttpClient {
+        client: reqwest::Client,
+    }
+
+    #[async_trait::async_trait]
+    impl HttpClientService for MockHttpClient {
+        async fn get(
+            &self,
+            url: &Url,
+            headers: Option<HeaderMap>,
+        ) -> anyhow::Result<reqwest::Response> {
+            let mut request = self.client.get(url.clone());
+            if let Some(headers) = headers {
+                request = request.headers(headers);
+            }
+            Ok(request.send().await?)
+        }
+
+        async fn post(&self, _url: &Url, _body: Bytes) -> anyhow::Result<reqwest::Response> {
+            unimplemented!()
+        }
+
+        async fn delete(&self, _url: &Url) -> anyhow::Result<reqwest::Response> {
+            unimplemented!()
+        }
+
+        async fn eventsource(
+            &self,
+            _url: &Url,
+            _headers: Option<HeaderMap>,
+            _body: Bytes,
+        ) -> anyhow::Result<EventStream> {
+            Err(anyhow::anyhow!("EventSource not implemented in mock"))
+        }
+    }
+
+    fn create_gemini(base_url: &str) -> anyhow::Result<Gemini<MockHttpClient>> {
+        let url = Url::parse(base_url)?.join("models")?;
+        Ok(Gemini::new(
+            Arc::new(MockHttpClient { client: reqwest::Client::new() }),
+            "test-key".to_string(),
+            url.clone(),
+            paws_domain::ModelSource::Url(url),
+            false,
+        ))
+    }
+
+    #[test]
+    fn test_chat_url() {
+        let fixture = create_gemini("https://generativelanguage.googleapis.com/v1beta/").unwrap();
+
+        let actual = fixture
+            .chat_url(&ModelId::new("models/gemini-2.5-pro"))
+            .unwrap();
+
+        let expected = "https://generativelanguage.googleapis.com/v1beta/models/gemini-2.5-pro:streamGenerateContent?alt=sse";
+        assert_eq!(actual.as_str(), expected);
+    }
+
+    #[test]
+    fn test_api_key_header() {
+        let fixture = create_gemini("https://generativelanguage.googleapis.com/v1beta/").unwrap();
+
+        let actual = fixture.get_headers();
+
+        let expected = vec![("x-goog-api-key".to_string(), "test-key".to_string())];
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_models_keeps_generate_content_models() -> anyhow::Result<()> {
+        let mut fixture = MockServer::new().await;
+        let mock = fixture
+            .mock_models(
+                serde_json::json!({
+                    "models": [
+                        {
+                            "name": "models/gemini-2.5-flash",
+                            "displayName": "Gemini 2.5 Flash",
+                            "inputTokenLimit": 1048576,
+                            "supportedGenerationMethods": ["generateContent", "countTokens"],
+                            "thinking": true
+                        },
+                        {
+                            "name": "models/text-embedding-004",
+                            "displayName": "Text Embedding 004",
+                            "supportedGenerationMethods": ["embedContent"]
+                        }
+                    ]
+                }),
+                200,
+            )
+            .await;
+        let gemini = create_gemini(&format!("{}/", fixture.url()))?;
+
+        let actual = gemini.models().await?;
+
+        mock.assert_async().await;
+        let expected = vec![ModelId::new("gemini-2.5-flash")];
+        assert_eq!(
+            actual.into_iter().map(|model| model.id).collect::<Vec<_>>(),
+            expected
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fetch_models_http_error_status() -> anyhow::Result<()> {
+        let mut fixture = MockServer::new().await;
+        let mock = fixture
+            .mock_models(
+                serde_json::json!({"error": {"code": 400, "message": "API key not valid", "status": "INVALID_ARGUMENT"}}),
+                400,
+            )
+            .await;
+        let gemini = create_gemini(&format!("{}/", fixture.url()))?;
+
+        let actual = gemini.models().await;
+
+        mock.assert_async().await;
+        assert!(actual.is_err());
+        Ok(())
+    }
+}
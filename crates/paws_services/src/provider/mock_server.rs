@@ -19,6 +19,18 @@ impl MockServer {
             .await
     }
 
+    pub async fn mock_rate_limited(&mut self, path: &str, headers: &[(&str, &str)]) -> Mock {
+        let mut mock = self
+            .server
+            .mock("POST", path)
+            .with_status(429)
+            .with_body("Too Many Requests");
+        for (name, value) in headers {
+            mock = mock.with_header(*name, value);
+        }
+        mock.create_async().await
+    }
+
     pub fn url(&self) -> String {
         self.server.url()
     }
@@ -2,9 +2,11 @@ mod anthropic;
 mod bedrock;
 mod client;
 mod event;
+mod gemini;
 #[cfg(test)]
 mod mock_server;
 mod openai;
+mod openai_responses;
 mod retry;
 mod service;
 mod utils;
@@ -30,7 +30,7 @@ impl<H: HttpClientService> OpenAIProvider<H> {
     // OpenRouter optional headers ref: https://openrouter.ai/docs/api-reference/overview#headers
     // - `HTTP-Referer`: Identifies your app on openrouter.ai
     // - `X-Title`: Sets/modifies your app's title
-    fn get_headers(&self) -> Vec<(String, String)> {
+    pub(crate) fn get_headers(&self) -> Vec<(String, String)> {
         let mut headers = Vec::new();
         if let Some(api_key) = self
             .provider
@@ -219,10 +219,9 @@ mod tests {
 
     use anyhow::Context;
     use bytes::Bytes;
-    use paws_app::HttpClientService;
     use paws_app::domain::{Provider, ProviderId, ProviderResponse};
+    use paws_app::{EventStream, HttpClientService};
     use reqwest::header::HeaderMap;
-    use reqwest_eventsource::EventSource;
     use url::Url;
 
     use super::*;
@@ -251,6 +250,7 @@ mod tests {
             models: Some(paws_domain::ModelSource::Url(
                 Url::parse("https://api.openai.com/v1/models").unwrap(),
             )),
+            pricing: Default::default(),
         }
     }
 
@@ -266,6 +266,7 @@ mod tests {
             models: Some(paws_domain::ModelSource::Url(
                 Url::parse("https://api.z.ai/api/paas/v4/models").unwrap(),
             )),
+            pricing: Default::default(),
         }
     }
 
@@ -281,6 +282,7 @@ mod tests {
             models: Some(paws_domain::ModelSource::Url(
                 Url::parse("https://api.z.ai/api/paas/v4/models").unwrap(),
             )),
+            pricing: Default::default(),
         }
     }
 
@@ -296,6 +298,7 @@ mod tests {
             models: Some(paws_domain::ModelSource::Url(
                 Url::parse("https://api.anthropic.com/v1/models").unwrap(),
             )),
+            pricing: Default::default(),
         }
     }
 
@@ -342,7 +345,7 @@ mod tests {
             _url: &reqwest::Url,
             _headers: Option<HeaderMap>,
             _body: Bytes,
-        ) -> anyhow::Result<EventSource> {
+        ) -> anyhow::Result<EventStream> {
             unimplemented!()
         }
     }
@@ -359,6 +362,7 @@ mod tests {
             models: Some(paws_domain::ModelSource::Url(
                 reqwest::Url::parse(base_url)?.join("models")?,
             )),
+            pricing: Default::default(),
         };
 
         Ok(OpenAIProvider::new(
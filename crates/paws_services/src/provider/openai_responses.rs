@@ -0,0 +1,161 @@
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result};
+use paws_app::HttpClientService;
+use paws_app::domain::{ChatCompletionMessage, Context as ChatContext, ModelId, ResultStream};
+use paws_app::dto::openai_responses::{EventData, Request};
+use paws_domain::Provider;
+use tracing::info;
+use url::Url;
+
+use crate::provider::client::create_headers;
+use crate::provider::event::into_chat_completion_message;
+use crate::provider::openai::OpenAIProvider;
+use crate::provider::utils::{format_http_context, sanitize_headers};
+
+/// Streams completions through the OpenAI Responses API. Authentication and
+/// model listing are shared with the Chat Completions provider.
+#[derive(Clone)]
+pub struct OpenAIResponsesProvider<H> {
+    provider: Provider<Url>,
+    http: Arc<H>,
+    openai: OpenAIProvider<H>,
+}
+
+impl<H: HttpClientService> OpenAIResponsesProvider<H> {
+    pub fn new(provider: Provider<Url>, http: Arc<H>) -> Self {
+        let openai = OpenAIProvider::new(provider.clone(), http.clone());
+        Self { provider, http, openai }
+    }
+
+    pub async fn chat(
+        &self,
+        model: &ModelId,
+        context: ChatContext,
+    ) -> ResultStream<ChatCompletionMessage, anyhow::Error> {
+        let request = Request::try_from(context)?.model(model.as_str().to_string());
+
+        let url = self.provider.url.clone();
+        let headers = create_headers(self.openai.get_headers());
+
+        info!(
+            url = %url,
+            model = %model,
+            headers = ?sanitize_headers(&headers),
+            item_count = %request.input.len(),
+            "Connecting Upstream"
+        );
+
+        let json_bytes =
+            serde_json::to_vec(&request).with_context(|| "Failed to serialize request")?;
+
+        let es = self
+            .http
+            .eventsource(&url, Some(headers), json_bytes.into())
+            .await
+            .with_context(|| format_http_context(None, "POST", &url))?;
+
+        let stream = into_chat_completion_message::<EventData>(url, es);
+
+        Ok(Box::pin(stream))
+    }
+
+    pub async fn models(&self) -> Result<Vec<paws_app::domain::Model>> {
+        self.openai.models().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use bytes::Bytes;
+    use paws_app::EventStream;
+    use paws_app::domain::{ProviderId, ProviderResponse};
+    use pretty_assertions::assert_eq;
+    use reqwest::header::HeaderMap;
+
+    use super::*;
+    use crate::provider::mock_server::MockServer;
+
+    #[derive(Clone)]
+    struct MockHttpClient {
+        client: reqwest::Client,
+    }
+
+    #[async_trait::async_trait]
+    impl HttpClientService for MockHttpClient {
+        async fn get(&self, url: &Url, headers: Option<HeaderMap>) -> Result<reqwest::Response> {
+            let mut request = self.client.get(url.clone());
+            if let Some(headers) = headers {
+                request = request.headers(headers);
+            }
+            Ok(request.send().await?)
+        }
+
+        async fn post(&self, _url: &Url, _body: Bytes) -> Result<reqwest::Response> {
+            unimplemented!()
+        }
+
+        async fn delete(&self, _url: &Url) -> Result<reqwest::Response> {
+            unimplemented!()
+        }
+
+        async fn eventsource(
+            &self,
+            _url: &Url,
+            _headers: Option<HeaderMap>,
+            _body: Bytes,
+        ) -> Result<EventStream> {
+            unimplemented!()
+        }
+    }
+
+    fn create_provider(base_url: &str) -> Result<OpenAIResponsesProvider<MockHttpClient>> {
+        let provider = Provider {
+            id: ProviderId::OPENAI,
+            provider_type: paws_domain::ProviderType::Llm,
+            response: Some(ProviderResponse::OpenAIResponses),
+            url: Url::parse(base_url)?.join("responses")?,
+            credential: Some(paws_domain::AuthCredential {
+                id: ProviderId::OPENAI,
+                auth_details: paws_domain::AuthDetails::ApiKey(paws_domain::ApiKey::from(
+                    "test-api-key".to_string(),
+                )),
+                url_params: HashMap::new(),
+            }),
+            auth_methods: vec![paws_domain::AuthMethod::ApiKey],
+            url_params: vec![],
+            models: Some(paws_domain::ModelSource::Url(
+                Url::parse(base_url)?.join("models")?,
+            )),
+            pricing: Default::default(),
+        };
+        Ok(OpenAIResponsesProvider::new(
+            provider,
+            Arc::new(MockHttpClient { client: reqwest::Client::new() }),
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_fetch_models_uses_openai_listing() -> Result<()> {
+        let mut fixture = MockServer::new().await;
+        let mock = fixture
+            .mock_models(
+                serde_json::json!({"data": [{"id": "o3"}, {"id": "gpt-5"}]}),
+                200,
+            )
+            .await;
+        let provider = create_provider(&format!("{}/", fixture.url()))?;
+
+        let actual = provider.models().await?;
+
+        mock.assert_async().await;
+        let expected = vec![ModelId::new("o3"), ModelId::new("gpt-5")];
+        assert_eq!(
+            actual.into_iter().map(|model| model.id).collect::<Vec<_>>(),
+            expected
+        );
+        Ok(())
+    }
+}
@@ -2,12 +2,13 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use anyhow::{Context, Result};
+use futures::StreamExt;
 use paws_app::domain::{
     AnyProvider, ChatCompletionMessage, Context as ChatContext, HttpConfig, Model, ModelId,
-    ProviderId, ResultStream, RetryConfig,
+    ModelPricing, ProviderId, ResultStream, RetryConfig,
 };
 use paws_app::{EnvironmentInfra, HttpInfra, ProviderService};
-use paws_domain::{Provider, ProviderRepository};
+use paws_domain::{AppConfigRepository, Provider, ProviderRepository};
 use tokio::sync::Mutex;
 use url::Url;
 
@@ -69,8 +70,24 @@ impl<I: EnvironmentInfra + HttpInfra> PawsProviderService<I> {
     }
 }
 
+impl<I: AppConfigRepository> PawsProviderService<I> {
+    /// Prices of the model, those of the user configuration taking precedence
+    /// over the ones of the provider
+    async fn pricing(&self, model: &ModelId, provider: &Provider<Url>) -> Option<ModelPricing> {
+        let app_config = self
+            .infra
+            .get_app_config()
+            .await
+            .inspect_err(|error| tracing::warn!(error = ?error, "Failed to read model prices"))
+            .ok();
+        app_config
+            .and_then(|config| config.pricing.get(model).copied())
+            .or_else(|| provider.pricing.get(model).copied())
+    }
+}
+
 #[async_trait::async_trait]
-impl<I: EnvironmentInfra + HttpInfra + ProviderRepository> ProviderService
+impl<I: EnvironmentInfra + HttpInfra + ProviderRepository + AppConfigRepository> ProviderService
     for PawsProviderService<I>
 {
     async fn chat(
@@ -79,12 +96,19 @@ impl<I: EnvironmentInfra + HttpInfra + ProviderRepository> ProviderService
         request: ChatContext,
         provider: Provider<Url>,
     ) -> ResultStream<ChatCompletionMessage, anyhow::Error> {
+        let pricing = self.pricing(model, &provider).await;
         let client = self.client(provider).await?;
 
-        client
+        let stream = client
             .chat(model, request)
             .await
-            .with_context(|| format!("Failed to chat with model: {model}"))
+            .with_context(|| format!("Failed to chat with model: {model}"))?;
+
+        // Fill in the cost of completions for providers that do not report it
+        Ok(match pricing {
+            Some(pricing) => Box::pin(stream.map(move |message| message.map(|m| pricing.apply(m)))),
+            None => stream,
+        })
     }
 
     async fn models(&self, provider: Provider<Url>) -> Result<Vec<Model>> {
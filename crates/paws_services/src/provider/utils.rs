@@ -1,3 +1,5 @@
+use chrono::Utc;
+use paws_app::domain::RateLimit;
 use reqwest::StatusCode;
 use reqwest::header::{AUTHORIZATION, HeaderMap, HeaderValue};
 
@@ -15,6 +17,14 @@ pub(crate) fn format_http_context<U: AsRef<str>>(
     }
 }
 
+/// Reads the rate limits reported in the headers of a response
+pub(crate) fn rate_limit(headers: &HeaderMap) -> Option<RateLimit> {
+    let headers = headers
+        .iter()
+        .filter_map(|(name, value)| Some((name.as_str(), value.to_str().ok()?)));
+    RateLimit::parse(headers, Utc::now())
+}
+
 /// Sanitizes headers for logging by redacting sensitive values
 pub fn sanitize_headers(headers: &HeaderMap) -> HeaderMap {
     let sensitive_headers = [AUTHORIZATION.as_str()];
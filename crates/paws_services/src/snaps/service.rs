@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
 use paws_common::fs::PawsFS;
@@ -18,9 +18,42 @@ impl SnapshotService {
     }
 }
 
+/// Extension of the files holding the content of a snapshot
+const SNAPSHOT_EXTENSION: &str = "snap";
+
+/// Extension of the entries recording that the file didn't exist at that
+/// point, so that undoing or redoing to it removes the file
+const REMOVED_EXTENSION: &str = "removed";
+
 impl SnapshotService {
+    /// All the snaps for a path are stored in the `snapshot.path_hash()`
+    /// directory
+    fn snapshot_dir(&self, snapshot: &Snapshot) -> PathBuf {
+        self.snapshots_directory.join(snapshot.path_hash())
+    }
+
+    /// Content replaced by undos that can be restored by a redo
+    fn redo_dir(&self, snapshot: &Snapshot) -> PathBuf {
+        self.snapshot_dir(snapshot).join("redo")
+    }
+
     pub async fn create_snapshot(&self, path: PathBuf) -> Result<Snapshot> {
-        let snapshot = Snapshot::create(path)?;
+        let snapshot = self.push_snapshot(path).await?;
+
+        // A new modification invalidates everything that was undone before it
+        let redo_dir = self.redo_dir(&snapshot);
+        for entry in Self::entries(&redo_dir, &[SNAPSHOT_EXTENSION, REMOVED_EXTENSION]).await? {
+            PawsFS::remove_file(entry).await?;
+        }
+
+        Ok(snapshot)
+    }
+
+    /// Pushes the current state of the file on the undo stack. A missing file
+    /// is recorded as a removed entry so that undoing to it deletes the file.
+    async fn push_snapshot(&self, path: PathBuf) -> Result<Snapshot> {
+        let mut snapshot = Snapshot::create(path)?;
+        snapshot.removed = !PawsFS::exists(&snapshot.path);
 
         // Create intermediary directories if they don't exist
         let snapshot_path = snapshot.snapshot_path(Some(self.snapshots_directory.clone()));
@@ -28,37 +61,74 @@ impl SnapshotService {
             PawsFS::create_dir_all(parent).await?;
         }
 
-        let content = PawsFS::read(&snapshot.path).await?;
-        let path = snapshot.snapshot_path(Some(self.snapshots_directory.clone()));
-        PawsFS::write(path, content).await?;
+        let content = if snapshot.removed {
+            Vec::new()
+        } else {
+            PawsFS::read(&snapshot.path).await?
+        };
+        PawsFS::write(snapshot_path, content).await?;
         Ok(snapshot)
     }
 
-    /// Find the most recent snapshot for a given path based on filename
-    /// timestamp
-    async fn find_recent_snapshot(snapshot_dir: &PathBuf) -> Result<Option<PathBuf>> {
-        let mut latest_path = None;
-        let mut latest_filename = None;
-        let mut dir = PawsFS::read_dir(&snapshot_dir).await?;
-
-        while let Some(entry) = dir.next_entry().await? {
-            let filename = entry.file_name().to_string_lossy().to_string();
-            if filename.ends_with(".snap")
-                && (latest_filename.is_none() || filename > latest_filename.clone().unwrap())
-            {
-                latest_filename = Some(filename);
-                latest_path = Some(entry.path());
+    /// Reads the content stored in a snapshot, returns `None` if the snapshot
+    /// no longer exists. A removed file has no content.
+    pub async fn read_snapshot(&self, snapshot: &Snapshot) -> Result<Option<Vec<u8>>> {
+        let snapshot_path = snapshot.snapshot_path(Some(self.snapshots_directory.clone()));
+        if !PawsFS::exists(&snapshot_path) {
+            return Ok(None);
+        }
+
+        Ok(Some(PawsFS::read(&snapshot_path).await?))
+    }
+
+    /// Lists the snapshots available for a given path, oldest first. This is
+    /// the undo stack, including the points at which the file didn't exist.
+    pub async fn list_snapshots(&self, path: PathBuf) -> Result<Vec<Snapshot>> {
+        let snapshot = Snapshot::create(path)?;
+        let entries = Self::entries(
+            &self.snapshot_dir(&snapshot),
+            &[SNAPSHOT_EXTENSION, REMOVED_EXTENSION],
+        )
+        .await?;
+
+        Ok(entries
+            .iter()
+            .filter_map(|entry| {
+                let stem = entry.file_stem()?.to_str()?;
+                let snapshot = Snapshot::from_file_stem(snapshot.path.clone(), stem)?;
+                Some(Snapshot { removed: Self::is_removed(entry), ..snapshot })
+            })
+            .collect())
+    }
+
+    /// Returns the files in `dir` with one of the given extensions, sorted by
+    /// filename. Since filenames are timestamps, the most recent file comes
+    /// last.
+    async fn entries(dir: &Path, extensions: &[&str]) -> Result<Vec<PathBuf>> {
+        if !PawsFS::exists(dir) {
+            return Ok(Vec::new());
+        }
+
+        let mut entries = Vec::new();
+        let mut read_dir = PawsFS::read_dir(dir).await?;
+        while let Some(entry) = read_dir.next_entry().await? {
+            let path = entry.path();
+            let matches = path
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .is_some_and(|extension| extensions.contains(&extension));
+            if matches && path.is_file() {
+                entries.push(path);
             }
         }
 
-        Ok(latest_path)
+        entries.sort();
+        Ok(entries)
     }
 
     pub async fn undo_snapshot(&self, path: PathBuf) -> Result<()> {
         let snapshot = Snapshot::create(path.clone())?;
-
-        // All the snaps for `path` are stored in `snapshot.path_hash()` directory.
-        let snapshot_dir = self.snapshots_directory.join(snapshot.path_hash());
+        let snapshot_dir = self.snapshot_dir(&snapshot);
 
         // Check if the `snapshot_dir` exists
         if !PawsFS::exists(&snapshot_dir) {
@@ -66,19 +136,90 @@ impl SnapshotService {
         }
 
         // Retrieve the latest snapshot path
-        let snapshot_path = Self::find_recent_snapshot(&snapshot_dir)
+        let snapshot_path = Self::entries(&snapshot_dir, &[SNAPSHOT_EXTENSION, REMOVED_EXTENSION])
             .await?
+            .pop()
             .context(format!("No valid snapshots found for {path:?}"))?;
 
-        // Restore the content
-        let content = PawsFS::read(&snapshot_path).await?;
-        PawsFS::write(&path, content).await?;
+        // Keep the content being replaced so that the undo can be redone
+        let redo_dir = self.redo_dir(&snapshot);
+        PawsFS::create_dir_all(&redo_dir).await?;
+        if PawsFS::exists(&path) {
+            let redo_path = redo_dir.join(format!("{}.{SNAPSHOT_EXTENSION}", snapshot.file_stem()));
+            PawsFS::write(redo_path, PawsFS::read(&path).await?).await?;
+        } else {
+            let redo_path = redo_dir.join(format!("{}.{REMOVED_EXTENSION}", snapshot.file_stem()));
+            PawsFS::write(redo_path, []).await?;
+        }
+
+        // Restore the content, or remove the file if it didn't exist back then
+        if Self::is_removed(&snapshot_path) {
+            if PawsFS::exists(&path) {
+                PawsFS::remove_file(&path).await?;
+            }
+        } else {
+            let content = PawsFS::read(&snapshot_path).await?;
+            PawsFS::write(&path, content).await?;
+        }
 
         // Remove the used snapshot
         PawsFS::remove_file(&snapshot_path).await?;
 
         Ok(())
     }
+
+    /// Reverts the most recent undo of the given path
+    pub async fn redo_snapshot(&self, path: PathBuf) -> Result<()> {
+        let snapshot = Snapshot::create(path.clone())?;
+
+        let redo_path = Self::entries(
+            &self.redo_dir(&snapshot),
+            &[SNAPSHOT_EXTENSION, REMOVED_EXTENSION],
+        )
+        .await?
+        .pop()
+        .context(format!("Nothing to redo for {path:?}"))?;
+
+        // Keep the current state on the undo stack so that the redo can be undone
+        self.push_snapshot(path.clone()).await?;
+
+        if !Self::is_removed(&redo_path) {
+            PawsFS::write(&path, PawsFS::read(&redo_path).await?).await?;
+        } else if PawsFS::exists(&path) {
+            PawsFS::remove_file(&path).await?;
+        }
+
+        PawsFS::remove_file(&redo_path).await?;
+
+        Ok(())
+    }
+
+    /// Restores the file of the given snapshot to the snapshot's content. The
+    /// current state is snapshotted first so that the restore can be undone,
+    /// even when the file doesn't exist anymore.
+    pub async fn restore_snapshot(&self, snapshot: &Snapshot) -> Result<()> {
+        let content = self
+            .read_snapshot(snapshot)
+            .await?
+            .context(format!("Snapshot of {:?} no longer exists", snapshot.path))?;
+
+        let path = PathBuf::from(&snapshot.path);
+        self.create_snapshot(path.clone()).await?;
+
+        if !snapshot.removed {
+            PawsFS::write(&path, content).await?;
+        } else if PawsFS::exists(&path) {
+            PawsFS::remove_file(&path).await?;
+        }
+        Ok(())
+    }
+
+    /// Whether the entry records that the file didn't exist
+    fn is_removed(entry: &Path) -> bool {
+        entry
+            .extension()
+            .is_some_and(|extension| extension == REMOVED_EXTENSION)
+    }
 }
 
 #[cfg(test)]
@@ -131,6 +272,14 @@ mod tests {
         async fn undo_snapshot(&self) -> Result<()> {
             self.service.undo_snapshot(self.test_file.clone()).await
         }
+
+        async fn redo_snapshot(&self) -> Result<()> {
+            self.service.redo_snapshot(self.test_file.clone()).await
+        }
+
+        async fn list_snapshots(&self) -> Result<Vec<Snapshot>> {
+            self.service.list_snapshots(self.test_file.clone()).await
+        }
     }
 
     #[tokio::test]
@@ -229,6 +378,40 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_read_snapshot() -> Result<()> {
+        // Arrange
+        let ctx = TestContext::new().await?;
+        ctx.write_content("Initial content").await?;
+        let snapshot = ctx.create_snapshot().await?;
+        ctx.write_content("Modified content").await?;
+
+        // Act
+        let actual = ctx.service.read_snapshot(&snapshot).await?;
+
+        // Assert
+        assert_eq!(actual, Some(b"Initial content".to_vec()));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_snapshot_consumed_by_undo() -> Result<()> {
+        // Arrange
+        let ctx = TestContext::new().await?;
+        ctx.write_content("Initial content").await?;
+        let snapshot = ctx.create_snapshot().await?;
+        ctx.undo_snapshot().await?;
+
+        // Act
+        let actual = ctx.service.read_snapshot(&snapshot).await?;
+
+        // Assert
+        assert_eq!(actual, None);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_multiple_snapshots_undo_twice() -> Result<()> {
         // Arrange
@@ -250,4 +433,204 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_list_snapshots() -> Result<()> {
+        // Arrange
+        let ctx = TestContext::new().await?;
+        ctx.write_content("Initial content").await?;
+        let first = ctx.create_snapshot().await?;
+        ctx.write_content("Second content").await?;
+        let second = ctx.create_snapshot().await?;
+
+        // Act
+        let actual = ctx
+            .list_snapshots()
+            .await?
+            .into_iter()
+            .map(|snapshot| snapshot.timestamp)
+            .collect::<Vec<_>>();
+
+        // Assert
+        let expected = vec![first.timestamp, second.timestamp];
+        assert_eq!(actual, expected);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_snapshots_empty() -> Result<()> {
+        let ctx = TestContext::new().await?;
+
+        let actual = ctx.list_snapshots().await?;
+
+        assert!(actual.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_redo_snapshot() -> Result<()> {
+        // Arrange
+        let ctx = TestContext::new().await?;
+        ctx.write_content("Initial content").await?;
+        ctx.create_snapshot().await?;
+        ctx.write_content("Modified content").await?;
+        ctx.undo_snapshot().await?;
+
+        // Act
+        ctx.redo_snapshot().await?;
+
+        // Assert
+        assert_eq!(ctx.read_content().await?, "Modified content");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_redo_snapshot_can_be_undone() -> Result<()> {
+        // Arrange
+        let ctx = TestContext::new().await?;
+        ctx.write_content("Initial content").await?;
+        ctx.create_snapshot().await?;
+        ctx.write_content("Modified content").await?;
+        ctx.undo_snapshot().await?;
+        ctx.redo_snapshot().await?;
+
+        // Act
+        ctx.undo_snapshot().await?;
+
+        // Assert
+        assert_eq!(ctx.read_content().await?, "Initial content");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_redo_snapshot_removes_file_created_by_undo() -> Result<()> {
+        // Arrange
+        let ctx = TestContext::new().await?;
+        ctx.write_content("Initial content").await?;
+        ctx.create_snapshot().await?;
+        PawsFS::remove_file(&ctx.test_file).await?;
+        ctx.undo_snapshot().await?;
+
+        // Act
+        ctx.redo_snapshot().await?;
+
+        // Assert
+        assert!(!PawsFS::exists(&ctx.test_file));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_redo_snapshot_cleared_by_new_snapshot() -> Result<()> {
+        // Arrange
+        let ctx = TestContext::new().await?;
+        ctx.write_content("Initial content").await?;
+        ctx.create_snapshot().await?;
+        ctx.write_content("Modified content").await?;
+        ctx.undo_snapshot().await?;
+        ctx.create_snapshot().await?;
+
+        // Act
+        let result = ctx.redo_snapshot().await;
+
+        // Assert
+        assert!(result.unwrap_err().to_string().contains("Nothing to redo"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_restore_snapshot() -> Result<()> {
+        // Arrange
+        let ctx = TestContext::new().await?;
+        ctx.write_content("Initial content").await?;
+        let first = ctx.create_snapshot().await?;
+        ctx.write_content("Second content").await?;
+        ctx.create_snapshot().await?;
+        ctx.write_content("Final content").await?;
+
+        // Act
+        ctx.service.restore_snapshot(&first).await?;
+
+        // Assert
+        assert_eq!(ctx.read_content().await?, "Initial content");
+        ctx.undo_snapshot().await?;
+        assert_eq!(ctx.read_content().await?, "Final content");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_snapshots_includes_removed_entries() -> Result<()> {
+        // Arrange
+        let ctx = TestContext::new().await?;
+        ctx.write_content("Initial content").await?;
+        let snapshot = ctx.create_snapshot().await?;
+        PawsFS::remove_file(&ctx.test_file).await?;
+        ctx.service.restore_snapshot(&snapshot).await?;
+
+        // Act
+        let removed = |snapshots: Vec<Snapshot>| {
+            snapshots
+                .into_iter()
+                .map(|snapshot| snapshot.removed)
+                .collect::<Vec<_>>()
+        };
+        let before_undo = removed(ctx.list_snapshots().await?);
+        ctx.undo_snapshot().await?;
+        let after_undo = removed(ctx.list_snapshots().await?);
+
+        // Assert
+        assert_eq!(before_undo, vec![false, true]);
+        assert_eq!(after_undo, vec![false]);
+        assert!(!PawsFS::exists(&ctx.test_file));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_restore_removed_snapshot_removes_file() -> Result<()> {
+        // Arrange
+        let ctx = TestContext::new().await?;
+        ctx.write_content("Initial content").await?;
+        let snapshot = ctx.create_snapshot().await?;
+        PawsFS::remove_file(&ctx.test_file).await?;
+        ctx.service.restore_snapshot(&snapshot).await?;
+        let removed = ctx.list_snapshots().await?.pop().unwrap();
+
+        // Act
+        ctx.service.restore_snapshot(&removed).await?;
+
+        // Assert
+        assert!(!PawsFS::exists(&ctx.test_file));
+        ctx.undo_snapshot().await?;
+        assert_eq!(ctx.read_content().await?, "Initial content");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_restore_snapshot_of_removed_file_can_be_undone_and_redone() -> Result<()> {
+        // Arrange
+        let ctx = TestContext::new().await?;
+        ctx.write_content("Initial content").await?;
+        let snapshot = ctx.create_snapshot().await?;
+        PawsFS::remove_file(&ctx.test_file).await?;
+        ctx.service.restore_snapshot(&snapshot).await?;
+
+        // Act
+        ctx.undo_snapshot().await?;
+        let removed = !PawsFS::exists(&ctx.test_file);
+        ctx.redo_snapshot().await?;
+
+        // Assert
+        assert!(removed);
+        assert_eq!(ctx.read_content().await?, "Initial content");
+
+        Ok(())
+    }
 }
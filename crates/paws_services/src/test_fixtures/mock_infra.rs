@@ -6,12 +6,18 @@
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use bytes::Bytes;
-use paws_app::domain::{CommandOutput, Environment, ToolDefinition, ToolName, ToolOutput};
+use paws_app::domain::{
+    CommandOutput, ConversationId, Environment, JobId, JobOutput, McpPrompt, McpPromptMessage,
+    McpResource, McpResourceContent, McpServers, ServerName, ToolCallFull, ToolDefinition,
+    ToolName, ToolOutput,
+};
 use paws_app::{
     CommandInfra, DirectoryReaderInfra, EnvironmentInfra, FileDirectoryInfra, FileInfoInfra,
-    FileReaderInfra, FileRemoverInfra, FileWriterInfra, McpClientInfra, McpServerInfra, UserInfra,
+    FileReaderInfra, FileRemoverInfra, FileWriterInfra, McpClientInfra, McpServerInfra, McpService,
+    UserInfra,
 };
 use paws_domain::FileInfo;
 use serde_json::Value;
@@ -303,6 +309,26 @@ impl McpClientInfra for Mock {
     async fn call(&self, _: &ToolName, _: Value) -> anyhow::Result<ToolOutput> {
         Ok(ToolOutput::default())
     }
+
+    async fn list_resources(&self) -> anyhow::Result<Vec<McpResource>> {
+        Ok(vec![])
+    }
+
+    async fn read_resource(&self, _: &str) -> anyhow::Result<Vec<McpResourceContent>> {
+        Ok(vec![])
+    }
+
+    async fn list_prompts(&self) -> anyhow::Result<Vec<McpPrompt>> {
+        Ok(vec![])
+    }
+
+    async fn get_prompt(
+        &self,
+        _: &str,
+        _: BTreeMap<String, String>,
+    ) -> anyhow::Result<Vec<McpPromptMessage>> {
+        Ok(vec![])
+    }
 }
 
 #[async_trait::async_trait]
@@ -435,6 +461,42 @@ impl CommandInfra for Mock {
     ) -> anyhow::Result<std::process::ExitStatus> {
         unimplemented!()
     }
+
+    async fn execute_command_with_input(
+        &self,
+        _: String,
+        _: PathBuf,
+        _: String,
+    ) -> anyhow::Result<CommandOutput> {
+        unimplemented!()
+    }
+
+    async fn spawn_job(
+        &self,
+        _: ConversationId,
+        _: String,
+        _: PathBuf,
+        _: Option<Vec<String>>,
+    ) -> anyhow::Result<JobOutput> {
+        unimplemented!()
+    }
+
+    async fn read_job(&self, _: ConversationId, _: JobId) -> anyhow::Result<JobOutput> {
+        unimplemented!()
+    }
+
+    async fn wait_job(
+        &self,
+        _: ConversationId,
+        _: JobId,
+        _: Duration,
+    ) -> anyhow::Result<JobOutput> {
+        unimplemented!()
+    }
+
+    async fn kill_job(&self, _: ConversationId, _: JobId) -> anyhow::Result<JobOutput> {
+        unimplemented!()
+    }
 }
 
 #[async_trait::async_trait]
@@ -573,3 +635,39 @@ impl DirectoryReaderInfra for MockCompositeService {
             .await
     }
 }
+
+#[async_trait::async_trait]
+impl McpService for MockCompositeService {
+    async fn get_mcp_servers(&self) -> anyhow::Result<McpServers> {
+        Ok(McpServers::default())
+    }
+
+    async fn execute_mcp(&self, _: ToolCallFull) -> anyhow::Result<ToolOutput> {
+        Ok(ToolOutput::default())
+    }
+
+    async fn reload_mcp(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Serves a single text resource at `file:///docs/readme.md`
+    async fn read_mcp_resource(&self, uri: &str) -> anyhow::Result<Vec<McpResourceContent>> {
+        match uri {
+            "file:///docs/readme.md" => Ok(vec![McpResourceContent::Text {
+                uri: uri.to_string(),
+                mime_type: Some("text/markdown".to_string()),
+                text: "# Readme".to_string(),
+            }]),
+            _ => Err(anyhow::anyhow!("MCP resource not found: {uri}")),
+        }
+    }
+
+    async fn render_mcp_prompt(
+        &self,
+        _: &ServerName,
+        _: &str,
+        _: BTreeMap<String, String>,
+    ) -> anyhow::Result<Vec<McpPromptMessage>> {
+        Ok(vec![])
+    }
+}
@@ -1,6 +1,7 @@
 use std::sync::Arc;
 
 use paws_app::{FollowUpService, UserInfra};
+use paws_domain::{FollowUpAnswer, FollowUpQuestion};
 
 /// Use this tool when you encounter ambiguities, need clarification, or require
 /// more details to proceed effectively. Use this tool judiciously to maintain a
@@ -26,23 +27,23 @@ impl<F: UserInfra> FollowUpService for PawsFollowup<F> {
         multiple: Option<bool>,
     ) -> anyhow::Result<Option<String>> {
         let inquire = &self.infra;
-        let result = match (options.is_empty(), multiple.unwrap_or_default()) {
-            (true, _) => inquire.prompt_question(&question).await?,
+        let question =
+            FollowUpQuestion { question, options, multiple: multiple.unwrap_or_default() };
+        let answer = match (question.options.is_empty(), question.multiple) {
+            (true, _) => inquire
+                .prompt_question(&question.question)
+                .await?
+                .map(FollowUpAnswer::Text),
             (false, true) => inquire
-                .select_many(&question, options)
+                .select_many(&question.question, question.options.clone())
                 .await?
-                .map(|selected| {
-                    format!(
-                        "User selected {} option(s): {}",
-                        selected.len(),
-                        selected.join(", ")
-                    )
-                }),
+                .map(FollowUpAnswer::Selected),
             (false, false) => inquire
-                .select_one(&question, options)
+                .select_one(&question.question, question.options.clone())
                 .await?
-                .map(|selected| format!("User selected: {selected}")),
+                .map(|selected| FollowUpAnswer::Selected(vec![selected])),
         };
+        let result = answer.map(|answer| question.feedback(answer));
 
         Ok(result)
     }
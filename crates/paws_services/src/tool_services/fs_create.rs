@@ -4,11 +4,12 @@ use std::sync::Arc;
 use anyhow::Context;
 use bytes::Bytes;
 use paws_app::{
-    FileDirectoryInfra, FileInfoInfra, FileReaderInfra, FileWriterInfra, FsCreateOutput,
-    FsCreateService, compute_hash,
+    EnvironmentInfra, FileDirectoryInfra, FileInfoInfra, FileReaderInfra, FileWriterInfra,
+    FsCreateOutput, FsCreateService, compute_hash,
 };
 use paws_domain::SnapshotRepository;
 
+use crate::tool_services::syn;
 use crate::utils::assert_absolute_path;
 
 /// Service for creating files with snapshot coordination
@@ -27,7 +28,8 @@ impl<F> PawsFsCreate<F> {
 
 #[async_trait::async_trait]
 impl<
-    F: FileDirectoryInfra
+    F: EnvironmentInfra
+        + FileDirectoryInfra
         + FileInfoInfra
         + FileReaderInfra
         + FileWriterInfra
@@ -63,6 +65,14 @@ impl<
             .with_context(|| format!("File already exists at {}", path.display()));
         }
 
+        // Parse the new content so that errors are reported, or the write
+        // rejected, before anything is touched on disk
+        let syntax_errors = syn::check(
+            path,
+            &content,
+            self.infra.get_environment().reject_syntax_errors,
+        )?;
+
         // Record the file content before modification
         let old_content = if file_exists && overwrite {
             Some(self.infra.read_utf8(path).await?)
@@ -71,9 +81,11 @@ impl<
         };
 
         // SNAPSHOT COORDINATION: Capture snapshot before writing if file exists
-        if file_exists {
-            self.infra.insert_snapshot(path).await?;
-        }
+        let snapshot = if file_exists {
+            Some(self.infra.insert_snapshot(path).await?)
+        } else {
+            None
+        };
 
         // Write file only after validation passes and directories are created
         self.infra.write(path, Bytes::from(content.clone())).await?;
@@ -85,6 +97,8 @@ impl<
             path: path.display().to_string(),
             before: old_content,
             content_hash,
+            snapshot,
+            syntax_errors,
         })
     }
 }
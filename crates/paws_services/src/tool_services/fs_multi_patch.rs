@@ -0,0 +1,697 @@
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::Context;
+use bytes::Bytes;
+use paws_app::{
+    EnvironmentInfra, FileDirectoryInfra, FileInfoInfra, FilePatchOutput, FileReaderInfra,
+    FileRemoverInfra, FileWriterInfra, FsMultiPatchService, HunkMatch, HunkOutcome,
+    MultiPatchOutput, compute_hash,
+};
+use paws_domain::{DiffLine, FilePatch, Hunk, SnapshotRepository};
+
+use crate::tool_services::syn;
+use crate::utils::assert_absolute_path;
+
+/// Maximum number of leading and trailing context lines that may be ignored
+/// when the full context of a hunk can't be found
+const MAX_FUZZ: usize = 2;
+
+/// A line of the replacement produced by a hunk
+#[derive(Debug, Clone, Copy)]
+enum NewLine<'a> {
+    /// The line of the matched region at this index, kept as it is in the
+    /// file
+    Keep(usize),
+    /// A line added by the hunk
+    Add(&'a str),
+}
+
+/// The lines a hunk expects to find and the lines replacing them
+#[derive(Debug)]
+struct Change<'a> {
+    old: Vec<&'a str>,
+    new: Vec<NewLine<'a>>,
+    /// Number of context lines at the start of `old`
+    leading: usize,
+    /// Number of context lines at the end of `old`
+    trailing: usize,
+}
+
+impl<'a> Change<'a> {
+    fn new(hunk: &'a Hunk) -> Self {
+        match hunk {
+            Hunk::Diff { lines, .. } => {
+                let mut old = Vec::new();
+                let mut new = Vec::new();
+                for line in lines {
+                    match line {
+                        DiffLine::Context(text) => {
+                            new.push(NewLine::Keep(old.len()));
+                            old.push(text.as_str());
+                        }
+                        DiffLine::Remove(text) => old.push(text.as_str()),
+                        DiffLine::Add(text) => new.push(NewLine::Add(text.as_str())),
+                    }
+                }
+                let is_context = |line: &&DiffLine| matches!(line, DiffLine::Context(_));
+                let leading = lines.iter().take_while(is_context).count();
+                let trailing = if leading == lines.len() {
+                    0
+                } else {
+                    lines.iter().rev().take_while(is_context).count()
+                };
+                Self { old, new, leading, trailing }
+            }
+            Hunk::Replace { replace, .. } => {
+                let new = split_lines(replace).into_iter().map(NewLine::Add).collect();
+                Self { old: hunk.old_lines(), new, leading: 0, trailing: 0 }
+            }
+        }
+    }
+
+    /// Drops up to `fuzz` context lines from each end, returning the number of
+    /// leading lines dropped. Returns `None` when there are no more context
+    /// lines to drop than with a lower fuzz.
+    fn fuzzed(&self, fuzz: usize) -> Option<(usize, Self)> {
+        if fuzz > 0 && fuzz > self.leading.max(self.trailing) {
+            return None;
+        }
+        let leading = fuzz.min(self.leading);
+        let trailing = fuzz.min(self.trailing);
+        let old = self.old[leading..self.old.len() - trailing].to_vec();
+        let new = self.new[leading..self.new.len() - trailing]
+            .iter()
+            .map(|line| match line {
+                NewLine::Keep(index) => NewLine::Keep(index - leading),
+                NewLine::Add(text) => NewLine::Add(text),
+            })
+            .collect();
+        let change = Self {
+            old,
+            new,
+            leading: self.leading - leading,
+            trailing: self.trailing - trailing,
+        };
+        Some((leading, change))
+    }
+}
+
+/// Splits text into lines, ignoring the empty line after a trailing newline
+fn split_lines(text: &str) -> Vec<&str> {
+    if text.is_empty() {
+        return Vec::new();
+    }
+    let text = text.strip_suffix('\n').unwrap_or(text);
+    text.split('\n').collect()
+}
+
+fn lines_match(actual: &str, expected: &str, matching: HunkMatch) -> bool {
+    match matching {
+        HunkMatch::Exact => actual == expected,
+        HunkMatch::Whitespace => actual.trim_end() == expected.trim_end(),
+        HunkMatch::Indentation => actual.trim() == expected.trim(),
+    }
+}
+
+/// Returns the start of every region of `lines` matching `old`
+fn find_all(lines: &[String], old: &[&str], matching: HunkMatch) -> Vec<usize> {
+    if old.len() > lines.len() {
+        return Vec::new();
+    }
+    (0..=lines.len() - old.len())
+        .filter(|&start| {
+            old.iter()
+                .zip(&lines[start..])
+                .all(|(expected, actual)| lines_match(actual, expected, matching))
+        })
+        .collect()
+}
+
+fn indentation(line: &str) -> &str {
+    &line[..line.len() - line.trim_start().len()]
+}
+
+/// Rewrites the indentation of `line` from the hunk's indentation to the
+/// file's
+fn reindent(line: &str, from: &str, to: &str) -> String {
+    if line.trim().is_empty() {
+        return line.to_string();
+    }
+    match line.strip_prefix(from) {
+        Some(rest) => format!("{to}{rest}"),
+        None => line.to_string(),
+    }
+}
+
+/// Why a hunk could not be applied
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Failure {
+    NotFound,
+    Ambiguous(usize),
+    EmptySearch,
+}
+
+impl std::fmt::Display for Failure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Failure::NotFound => write!(f, "the expected lines were not found"),
+            Failure::Ambiguous(count) => write!(
+                f,
+                "the search text matches {count} locations, include more surrounding lines"
+            ),
+            Failure::EmptySearch => write!(f, "the search text is empty"),
+        }
+    }
+}
+
+/// Status of a single hunk, used to report every hunk of a failed patch
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum HunkStatus {
+    Applied(HunkOutcome),
+    Failed { label: String, failure: Failure },
+}
+
+/// The content of a file being patched, split into lines
+#[derive(Debug)]
+struct Document {
+    lines: Vec<String>,
+    crlf: bool,
+    trailing_newline: bool,
+    /// Lines added minus lines removed by the hunks applied so far, used to
+    /// locate the following diff hunks
+    offset: isize,
+}
+
+impl Document {
+    fn new(content: &str) -> Self {
+        let crlf = content.contains("\r\n");
+        let content = content.replace("\r\n", "\n");
+        Self {
+            lines: split_lines(&content)
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            crlf,
+            trailing_newline: content.is_empty() || content.ends_with('\n'),
+            offset: 0,
+        }
+    }
+
+    fn content(&self) -> String {
+        let newline = if self.crlf { "\r\n" } else { "\n" };
+        let mut content = self.lines.join(newline);
+        if self.trailing_newline && !self.lines.is_empty() {
+            content.push_str(newline);
+        }
+        content
+    }
+
+    /// Applies the hunk if it can be located unambiguously, leaving the
+    /// document untouched otherwise
+    fn apply(&mut self, hunk: &Hunk) -> HunkStatus {
+        match self.locate(hunk) {
+            Ok(outcome) => HunkStatus::Applied(outcome),
+            Err(failure) => HunkStatus::Failed { label: hunk.label(), failure },
+        }
+    }
+
+    fn locate(&mut self, hunk: &Hunk) -> Result<HunkOutcome, Failure> {
+        let label = hunk.label();
+        let outcome =
+            |line, matching, fuzz| HunkOutcome { label: label.clone(), line, matching, fuzz };
+
+        if let Hunk::Replace { search, replace } = hunk {
+            if search.is_empty() {
+                return Err(Failure::EmptySearch);
+            }
+            // Search/replace edits are not limited to whole lines, so an exact
+            // substring match is tried first
+            let content = self.lines.join("\n");
+            let mut matches = content.match_indices(search.as_str());
+            if let Some((start, _)) = matches.next() {
+                let count = 1 + matches.count();
+                if count > 1 {
+                    return Err(Failure::Ambiguous(count));
+                }
+                let line = content[..start].matches('\n').count() + 1;
+                let patched = format!(
+                    "{}{replace}{}",
+                    &content[..start],
+                    &content[start + search.len()..]
+                );
+                self.offset +=
+                    patched.matches('\n').count() as isize - content.matches('\n').count() as isize;
+                self.lines = patched.split('\n').map(String::from).collect();
+                return Ok(outcome(line, HunkMatch::Exact, 0));
+            }
+        }
+
+        let hint = match hunk {
+            Hunk::Diff { old_start, .. } => Some(*old_start as isize + self.offset),
+            Hunk::Replace { .. } => None,
+        };
+        let change = Change::new(hunk);
+
+        // Pure insertions have nothing to match and go where the header says
+        if change.old.is_empty() {
+            let start = hint.unwrap_or_default().clamp(0, self.lines.len() as isize) as usize;
+            self.splice(start, &change, HunkMatch::Exact);
+            return Ok(outcome(start + 1, HunkMatch::Exact, 0));
+        }
+
+        for fuzz in 0..=MAX_FUZZ {
+            let Some((dropped, change)) = change.fuzzed(fuzz) else {
+                break;
+            };
+            if change.old.is_empty() {
+                break;
+            }
+            for matching in [
+                HunkMatch::Exact,
+                HunkMatch::Whitespace,
+                HunkMatch::Indentation,
+            ] {
+                let candidates = find_all(&self.lines, &change.old, matching);
+                let start = match (hint, candidates.as_slice()) {
+                    (_, []) => continue,
+                    (Some(hint), candidates) => {
+                        // Diff headers are 1-based, and the dropped context
+                        // lines move the expected start down
+                        let expected = hint - 1 + dropped as isize;
+                        candidates
+                            .iter()
+                            .copied()
+                            .min_by_key(|&start| (start as isize - expected).abs())
+                            .unwrap_or_default()
+                    }
+                    (None, [start]) => *start,
+                    (None, candidates) => return Err(Failure::Ambiguous(candidates.len())),
+                };
+                self.splice(start, &change, matching);
+                return Ok(outcome(start + 1, matching, fuzz));
+            }
+        }
+        Err(Failure::NotFound)
+    }
+
+    /// Replaces the lines matched by `change` at `start` with its new lines
+    fn splice(&mut self, start: usize, change: &Change<'_>, matching: HunkMatch) {
+        let matched = &self.lines[start..start + change.old.len()];
+        let (from, to) = match matching {
+            HunkMatch::Indentation => change
+                .old
+                .iter()
+                .zip(matched)
+                .find(|(expected, _)| !expected.trim().is_empty())
+                .map(|(expected, actual)| (indentation(expected), indentation(actual)))
+                .unwrap_or_default(),
+            HunkMatch::Exact | HunkMatch::Whitespace => ("", ""),
+        };
+        let replacement: Vec<String> = change
+            .new
+            .iter()
+            .map(|line| match line {
+                NewLine::Keep(index) => matched[*index].clone(),
+                NewLine::Add(text) => reindent(text, from, to),
+            })
+            .collect();
+        self.offset += replacement.len() as isize - change.old.len() as isize;
+        self.lines
+            .splice(start..start + change.old.len(), replacement);
+    }
+}
+
+/// Applies every hunk to `content`, returning the patched content or, when
+/// any hunk fails, the status of every hunk
+fn apply_hunks(
+    content: &str,
+    hunks: &[Hunk],
+) -> Result<(String, Vec<HunkOutcome>), Vec<HunkStatus>> {
+    let mut document = Document::new(content);
+    let statuses: Vec<HunkStatus> = hunks.iter().map(|hunk| document.apply(hunk)).collect();
+    let mut outcomes = Vec::new();
+    for status in &statuses {
+        match status {
+            HunkStatus::Applied(outcome) => outcomes.push(outcome.clone()),
+            HunkStatus::Failed { .. } => return Err(statuses),
+        }
+    }
+    Ok((document.content(), outcomes))
+}
+
+/// Status of every file of a patch that could not be applied
+#[derive(Debug, Default)]
+struct Report {
+    failed: bool,
+    text: String,
+}
+
+impl Report {
+    fn file_error(&mut self, path: &Path, error: impl std::fmt::Display) {
+        self.failed = true;
+        let _ = writeln!(self.text, "{}: {error}", path.display());
+    }
+
+    fn hunks(&mut self, path: &Path, statuses: &[HunkStatus]) {
+        self.failed = true;
+        let _ = writeln!(self.text, "{}:", path.display());
+        for status in statuses {
+            let _ = match status {
+                HunkStatus::Applied(outcome) => writeln!(
+                    self.text,
+                    "  ok: {} (line {}, {} match)",
+                    outcome.label, outcome.line, outcome.matching
+                ),
+                HunkStatus::Failed { label, failure } => {
+                    writeln!(self.text, "  failed: {label}: {failure}")
+                }
+            };
+        }
+    }
+
+    fn applied(&mut self, path: &Path, outcomes: &[HunkOutcome]) {
+        let _ = writeln!(
+            self.text,
+            "{}: all {} hunks apply",
+            path.display(),
+            outcomes.len()
+        );
+    }
+}
+
+/// A file whose hunks all applied, waiting to be written
+struct Prepared {
+    path: PathBuf,
+    before: Option<String>,
+    after: String,
+    hunks: Vec<HunkOutcome>,
+}
+
+/// Service applying unified diffs and multi-hunk edits to several files at
+/// once
+///
+/// Every hunk of every file is applied in memory first, so that a single
+/// failing hunk leaves all files untouched. Files are snapshotted before any
+/// of them is written.
+pub struct PawsFsMultiPatch<F> {
+    infra: Arc<F>,
+}
+
+impl<F> PawsFsMultiPatch<F> {
+    pub fn new(infra: Arc<F>) -> Self {
+        Self { infra }
+    }
+}
+
+impl<
+    F: FileDirectoryInfra
+        + FileInfoInfra
+        + FileReaderInfra
+        + FileWriterInfra
+        + FileRemoverInfra
+        + SnapshotRepository,
+> PawsFsMultiPatch<F>
+{
+    /// Writes every prepared file, restoring the files already written when a
+    /// write fails
+    async fn write_all(&self, prepared: &[Prepared]) -> anyhow::Result<()> {
+        for (index, file) in prepared.iter().enumerate() {
+            let result = async {
+                if let Some(parent) = file.path.parent() {
+                    self.infra.create_dirs(parent).await?;
+                }
+                self.infra
+                    .write(&file.path, Bytes::from(file.after.clone()))
+                    .await
+            }
+            .await;
+
+            if let Err(error) = result {
+                for written in &prepared[..index] {
+                    let _ = match &written.before {
+                        Some(before) => {
+                            self.infra
+                                .write(&written.path, Bytes::from(before.clone()))
+                                .await
+                        }
+                        None => self.infra.remove(&written.path).await,
+                    };
+                }
+                return Err(error)
+                    .with_context(|| format!("Failed to write {}", file.path.display()));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl<
+    F: EnvironmentInfra
+        + FileDirectoryInfra
+        + FileInfoInfra
+        + FileReaderInfra
+        + FileWriterInfra
+        + FileRemoverInfra
+        + SnapshotRepository
+        + Send
+        + Sync,
+> FsMultiPatchService for PawsFsMultiPatch<F>
+{
+    async fn multi_patch(&self, patches: Vec<FilePatch>) -> anyhow::Result<MultiPatchOutput> {
+        let mut report = Report::default();
+        let mut prepared = Vec::new();
+
+        for patch in patches {
+            assert_absolute_path(&patch.path)?;
+            let before = if self.infra.is_file(&patch.path).await? {
+                Some(self.infra.read_utf8(&patch.path).await?)
+            } else {
+                None
+            };
+            match (patch.create, &before) {
+                (true, Some(_)) => {
+                    report.file_error(&patch.path, "the diff creates a file that already exists");
+                    continue;
+                }
+                (false, None) => {
+                    report.file_error(&patch.path, "file not found");
+                    continue;
+                }
+                _ => {}
+            }
+
+            match apply_hunks(before.as_deref().unwrap_or_default(), &patch.hunks) {
+                Ok((after, hunks)) => {
+                    report.applied(&patch.path, &hunks);
+                    prepared.push(Prepared { path: patch.path, before, after, hunks });
+                }
+                Err(statuses) => report.hunks(&patch.path, &statuses),
+            }
+        }
+
+        if report.failed {
+            anyhow::bail!(
+                "Patch rejected, no file was changed. Read the affected files again and retry with corrected hunks.\n{}",
+                report.text.trim_end()
+            );
+        }
+
+        // Validate every file before touching the disk so that a rejected
+        // file leaves the others unchanged too
+        let reject = self.infra.get_environment().reject_syntax_errors;
+        let syntax_errors = prepared
+            .iter()
+            .map(|file| syn::check(&file.path, &file.after, reject))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        // SNAPSHOT COORDINATION: Capture every snapshot before writing any file
+        let mut snapshots = Vec::new();
+        for file in &prepared {
+            let snapshot = match file.before {
+                Some(_) => Some(self.infra.insert_snapshot(&file.path).await?),
+                None => None,
+            };
+            snapshots.push(snapshot);
+        }
+
+        self.write_all(&prepared).await?;
+
+        let files = prepared
+            .into_iter()
+            .zip(snapshots)
+            .zip(syntax_errors)
+            .map(|((file, snapshot), syntax_errors)| FilePatchOutput {
+                content_hash: compute_hash(&file.after),
+                path: file.path,
+                before: file.before,
+                after: file.after,
+                snapshot,
+                syntax_errors,
+                hunks: file.hunks,
+            })
+            .collect();
+        Ok(MultiPatchOutput { files })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn diff(old_start: usize, lines: &str) -> Hunk {
+        let lines = lines
+            .lines()
+            .map(|line| match line.split_at(1) {
+                ("-", text) => DiffLine::Remove(text.to_string()),
+                ("+", text) => DiffLine::Add(text.to_string()),
+                (_, text) => DiffLine::Context(text.to_string()),
+            })
+            .collect();
+        Hunk::Diff { header: format!("@@ -{old_start} @@"), old_start, lines }
+    }
+
+    fn replace(search: &str, replace: &str) -> Hunk {
+        Hunk::Replace { search: search.to_string(), replace: replace.to_string() }
+    }
+
+    fn apply(content: &str, hunks: &[Hunk]) -> (String, Vec<(usize, HunkMatch, usize)>) {
+        let (content, outcomes) = apply_hunks(content, hunks).unwrap();
+        let outcomes = outcomes
+            .into_iter()
+            .map(|outcome| (outcome.line, outcome.matching, outcome.fuzz))
+            .collect();
+        (content, outcomes)
+    }
+
+    #[test]
+    fn test_apply_exact_hunks_with_offset() {
+        let fixture = "a\nb\nc\nd\ne\nf\ng\n";
+        let hunks = [diff(1, " a\n-b\n+b1\n+b2\n c\n"), diff(5, " e\n-f\n g\n")];
+
+        let actual = apply(fixture, &hunks);
+
+        let expected = (
+            "a\nb1\nb2\nc\nd\ne\ng\n".to_string(),
+            vec![(1, HunkMatch::Exact, 0), (6, HunkMatch::Exact, 0)],
+        );
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_apply_picks_match_nearest_to_header() {
+        let fixture = "x\ny\nx\ny\n";
+        let hunks = [diff(3, " x\n-y\n+z\n")];
+
+        let actual = apply(fixture, &hunks);
+
+        let expected = ("x\ny\nx\nz\n".to_string(), vec![(3, HunkMatch::Exact, 0)]);
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_apply_ignores_trailing_whitespace() {
+        let fixture = "fn a() {  \n    1\n}\n";
+        let hunks = [diff(1, " fn a() {\n-    1\n+    2\n }\n")];
+
+        let actual = apply(fixture, &hunks);
+
+        let expected = (
+            "fn a() {  \n    2\n}\n".to_string(),
+            vec![(1, HunkMatch::Whitespace, 0)],
+        );
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_apply_reindents_added_lines() {
+        let fixture = "impl A {\n        fn a() {\n            1\n        }\n}\n";
+        let hunks = [diff(2, " fn a() {\n-    1\n+    2\n+    3\n }\n")];
+
+        let actual = apply(fixture, &hunks);
+
+        let expected = (
+            "impl A {\n        fn a() {\n            2\n            3\n        }\n}\n".to_string(),
+            vec![(2, HunkMatch::Indentation, 0)],
+        );
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_apply_fuzz_drops_stale_context() {
+        let fixture = "a\nb\nc\nd\n";
+        let hunks = [diff(1, " stale\n b\n-c\n+C\n d\n")];
+
+        let actual = apply(fixture, &hunks);
+
+        let expected = ("a\nb\nC\nd\n".to_string(), vec![(2, HunkMatch::Exact, 1)]);
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_apply_preserves_crlf() {
+        let fixture = "a\r\nb\r\n";
+        let hunks = [diff(1, " a\n-b\n+c\n")];
+
+        let actual = apply(fixture, &hunks).0;
+
+        assert_eq!(actual, "a\r\nc\r\n");
+    }
+
+    #[test]
+    fn test_apply_insertion_into_new_file() {
+        let hunks = [diff(0, "+fn main() {}\n")];
+
+        let actual = apply("", &hunks).0;
+
+        assert_eq!(actual, "fn main() {}\n");
+    }
+
+    #[test]
+    fn test_apply_replace_edits() {
+        let fixture = "let a = 1;\nlet b = 2;\n";
+        let hunks = [
+            replace("a = 1", "a = 10"),
+            replace("  let b = 2;", "let b = 20;"),
+        ];
+
+        let actual = apply(fixture, &hunks);
+
+        let expected = (
+            "let a = 10;\nlet b = 20;\n".to_string(),
+            vec![(1, HunkMatch::Exact, 0), (2, HunkMatch::Indentation, 0)],
+        );
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_apply_reports_every_hunk_on_failure() {
+        let fixture = "a\nb\na\n";
+        let hunks = [
+            diff(1, " a\n-b\n+c\n"),
+            replace("a", "z"),
+            diff(1, "-missing\n"),
+        ];
+
+        let actual = apply_hunks(fixture, &hunks).unwrap_err();
+
+        let expected = vec![
+            HunkStatus::Applied(HunkOutcome {
+                label: "@@ -1 @@".to_string(),
+                line: 1,
+                matching: HunkMatch::Exact,
+                fuzz: 0,
+            }),
+            HunkStatus::Failed {
+                label: "replace `a`".to_string(),
+                failure: Failure::Ambiguous(2),
+            },
+            HunkStatus::Failed { label: "@@ -1 @@".to_string(), failure: Failure::NotFound },
+        ];
+        assert_eq!(actual, expected);
+    }
+}
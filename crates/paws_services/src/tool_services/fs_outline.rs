@@ -0,0 +1,343 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::Context;
+use paws_app::{
+    CodeOutlineService, FileInfoInfra, FileOutline, FileReaderInfra, OutlineOutput, SymbolLocation,
+    SymbolSearchOutput, Walker, WalkerInfra,
+};
+
+use super::syn;
+use crate::utils::assert_absolute_path;
+
+/// Maximum number of files outlined for a directory
+const MAX_OUTLINE_FILES: usize = 100;
+
+/// Maximum number of references reported by a symbol search
+const MAX_REFERENCES: usize = 100;
+
+/// Files larger than this are skipped, they are usually generated
+const MAX_FILE_SIZE: u64 = 512 * 1024;
+
+/// Lists the functions, types and other definitions of source files using
+/// their syntax tree, and finds where a symbol is defined and referenced.
+/// Supports Rust, Python, JavaScript, TypeScript and Go. Requires absolute
+/// paths.
+pub struct PawsCodeOutline<F> {
+    infra: Arc<F>,
+}
+
+impl<F> PawsCodeOutline<F> {
+    pub fn new(infra: Arc<F>) -> Self {
+        Self { infra }
+    }
+}
+
+impl<F: WalkerInfra + FileInfoInfra> PawsCodeOutline<F> {
+    /// Supported source files under `path` paired with their displayed path,
+    /// relative to `path` when it is a directory
+    async fn source_files(&self, path: &Path) -> anyhow::Result<Vec<(PathBuf, String)>> {
+        if self.infra.is_file(path).await? {
+            return Ok(vec![(path.to_path_buf(), path.display().to_string())]);
+        }
+
+        let mut files: Vec<(PathBuf, String)> = self
+            .infra
+            .walk(
+                Walker::unlimited()
+                    .cwd(path.to_path_buf())
+                    .skip_binary(true)
+                    .max_file_size(MAX_FILE_SIZE),
+            )
+            .await
+            .with_context(|| format!("Failed to walk directory '{}'", path.display()))?
+            .into_iter()
+            .filter(|file| !file.is_dir() && syn::is_outlinable(Path::new(&file.path)))
+            .map(|file| (path.join(&file.path), file.path))
+            .collect();
+        files.sort_by(|a, b| a.1.cmp(&b.1));
+        Ok(files)
+    }
+}
+
+#[async_trait::async_trait]
+impl<F: WalkerInfra + FileReaderInfra + FileInfoInfra> CodeOutlineService for PawsCodeOutline<F> {
+    async fn outline(&self, path: String) -> anyhow::Result<OutlineOutput> {
+        let path = PathBuf::from(path);
+        assert_absolute_path(&path)?;
+
+        let is_file = self.infra.is_file(&path).await?;
+        if is_file && !syn::is_outlinable(&path) {
+            anyhow::bail!(
+                "Outlines are not supported for '{}', supported languages are Rust, Python, JavaScript, TypeScript and Go",
+                path.display()
+            );
+        }
+
+        let mut output = OutlineOutput::default();
+        for (absolute, display) in self.source_files(&path).await? {
+            // Unreadable and non UTF-8 files of a directory are skipped
+            let content = match self.infra.read_utf8(&absolute).await {
+                Ok(content) => content,
+                Err(_) if !is_file => continue,
+                Err(error) => {
+                    return Err(error.context(format!("Failed to read '{}'", absolute.display())));
+                }
+            };
+            let Some(symbols) = syn::outline(&absolute, &content) else {
+                continue;
+            };
+            // Files without definitions, such as tests fixtures or entry
+            // points only importing modules, are noise in a directory outline
+            if symbols.is_empty() && !is_file {
+                continue;
+            }
+            if output.files.len() == MAX_OUTLINE_FILES {
+                output.truncated = true;
+                break;
+            }
+            output.files.push(FileOutline {
+                path: display,
+                total_lines: content.lines().count(),
+                symbols,
+            });
+        }
+        Ok(output)
+    }
+
+    async fn find_symbol(&self, name: String, path: String) -> anyhow::Result<SymbolSearchOutput> {
+        anyhow::ensure!(
+            !name.is_empty() && !name.contains(char::is_whitespace),
+            "Symbol name must be a single identifier, got '{name}'"
+        );
+        let path = PathBuf::from(path);
+        assert_absolute_path(&path)?;
+
+        let mut output = SymbolSearchOutput::default();
+        for (absolute, display) in self.source_files(&path).await? {
+            let Ok(content) = self.infra.read_utf8(&absolute).await else {
+                continue;
+            };
+            if !content.contains(&name) {
+                continue;
+            }
+            let Some((definitions, references)) = syn::find_symbol(&absolute, &content, &name)
+            else {
+                continue;
+            };
+
+            output
+                .definitions
+                .extend(definitions.into_iter().map(|symbol| SymbolLocation {
+                    path: display.clone(),
+                    line: symbol.start_line,
+                    end_line: Some(symbol.end_line),
+                    text: symbol.signature,
+                }));
+            for reference in references {
+                if output.references.len() == MAX_REFERENCES {
+                    output.truncated = true;
+                    break;
+                }
+                output.references.push(SymbolLocation {
+                    path: display.clone(),
+                    line: reference.line,
+                    end_line: None,
+                    text: reference.text,
+                });
+            }
+        }
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use paws_app::WalkedFile;
+    use paws_domain::FileInfo;
+    use pretty_assertions::assert_eq;
+    use tokio::fs;
+
+    use super::*;
+    use crate::utils::TempDir;
+
+    struct MockInfra;
+
+    #[async_trait::async_trait]
+    impl FileReaderInfra for MockInfra {
+        async fn read_utf8(&self, path: &Path) -> anyhow::Result<String> {
+            Ok(fs::read_to_string(path).await?)
+        }
+
+        async fn read(&self, path: &Path) -> anyhow::Result<Vec<u8>> {
+            Ok(fs::read(path).await?)
+        }
+
+        async fn range_read_utf8(
+            &self,
+            _path: &Path,
+            _start_line: u64,
+            _end_line: u64,
+        ) -> anyhow::Result<(String, FileInfo)> {
+            unimplemented!()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl FileInfoInfra for MockInfra {
+        async fn is_file(&self, path: &Path) -> anyhow::Result<bool> {
+            Ok(fs::metadata(path).await.is_ok_and(|meta| meta.is_file()))
+        }
+
+        async fn is_binary(&self, _path: &Path) -> anyhow::Result<bool> {
+            Ok(false)
+        }
+
+        async fn exists(&self, path: &Path) -> anyhow::Result<bool> {
+            Ok(fs::try_exists(path).await?)
+        }
+
+        async fn file_size(&self, _path: &Path) -> anyhow::Result<u64> {
+            unimplemented!()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl WalkerInfra for MockInfra {
+        async fn walk(&self, config: Walker) -> anyhow::Result<Vec<WalkedFile>> {
+            let mut files = Vec::new();
+            let mut entries = fs::read_dir(&config.cwd).await?;
+            while let Some(entry) = entries.next_entry().await? {
+                let file_name = entry.file_name().to_string_lossy().to_string();
+                let size = entry.metadata().await?.len();
+                files.push(WalkedFile {
+                    path: file_name.clone(),
+                    file_name: Some(file_name),
+                    size,
+                });
+            }
+            Ok(files)
+        }
+    }
+
+    async fn fixture() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("user.rs"),
+            "pub struct User;\n\nimpl User {\n    pub fn new() -> User {\n        User\n    }\n}\n",
+        )
+        .await
+        .unwrap();
+        fs::write(
+            dir.path().join("main.py"),
+            "from user import User\n\nUser()\n",
+        )
+        .await
+        .unwrap();
+        fs::write(dir.path().join("notes.md"), "# User\n")
+            .await
+            .unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn test_outline_directory() {
+        let dir = fixture().await;
+
+        let actual = PawsCodeOutline::new(Arc::new(MockInfra))
+            .outline(dir.path().display().to_string())
+            .await
+            .unwrap();
+
+        let paths: Vec<&str> = actual.files.iter().map(|file| file.path.as_str()).collect();
+        assert_eq!(paths, vec!["user.rs"]);
+        assert_eq!(actual.files[0].total_lines, 7);
+        assert_eq!(actual.files[0].symbols[1].children[0].name, "new");
+        assert!(!actual.truncated);
+    }
+
+    #[tokio::test]
+    async fn test_outline_directory_skips_non_utf8_files() {
+        let dir = fixture().await;
+        fs::write(dir.path().join("latin1.rs"), b"// caf\xe9\nfn f() {}\n")
+            .await
+            .unwrap();
+
+        let actual = PawsCodeOutline::new(Arc::new(MockInfra))
+            .outline(dir.path().display().to_string())
+            .await
+            .unwrap();
+
+        let paths: Vec<&str> = actual.files.iter().map(|file| file.path.as_str()).collect();
+        assert_eq!(paths, vec!["user.rs"]);
+    }
+
+    #[tokio::test]
+    async fn test_outline_unsupported_file() {
+        let dir = fixture().await;
+
+        let actual = PawsCodeOutline::new(Arc::new(MockInfra))
+            .outline(dir.path().join("notes.md").display().to_string())
+            .await;
+
+        assert!(actual.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_outline_data_file_is_unsupported() {
+        let dir = fixture().await;
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "[user]\nname = \"User\"\n").await.unwrap();
+
+        let actual = PawsCodeOutline::new(Arc::new(MockInfra))
+            .outline(path.display().to_string())
+            .await
+            .unwrap_err()
+            .to_string();
+
+        assert!(actual.contains("supported languages are Rust, Python"));
+    }
+
+    #[tokio::test]
+    async fn test_find_symbol_across_files() {
+        let dir = fixture().await;
+
+        let actual = PawsCodeOutline::new(Arc::new(MockInfra))
+            .find_symbol("User".to_string(), dir.path().display().to_string())
+            .await
+            .unwrap();
+
+        let definitions: Vec<(&str, usize)> = actual
+            .definitions
+            .iter()
+            .map(|location| (location.path.as_str(), location.line))
+            .collect();
+        assert_eq!(definitions, vec![("user.rs", 1)]);
+        let references: Vec<(&str, usize)> = actual
+            .references
+            .iter()
+            .map(|location| (location.path.as_str(), location.line))
+            .collect();
+        assert_eq!(
+            references,
+            vec![
+                ("main.py", 1),
+                ("main.py", 3),
+                ("user.rs", 3),
+                ("user.rs", 4),
+                ("user.rs", 5)
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_find_symbol_rejects_expressions() {
+        let dir = fixture().await;
+
+        let actual = PawsCodeOutline::new(Arc::new(MockInfra))
+            .find_symbol("new User".to_string(), dir.path().display().to_string())
+            .await;
+
+        assert!(actual.is_err());
+    }
+}
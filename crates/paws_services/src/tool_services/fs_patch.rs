@@ -3,11 +3,12 @@ use std::sync::Arc;
 
 use bytes::Bytes;
 use paws_app::domain::PatchOperation;
-use paws_app::{FileWriterInfra, FsPatchService, PatchOutput, compute_hash};
+use paws_app::{EnvironmentInfra, FileWriterInfra, FsPatchService, PatchOutput, compute_hash};
 use paws_domain::SnapshotRepository;
 use thiserror::Error;
 use tokio::fs;
 
+use crate::tool_services::syn;
 use crate::utils::assert_absolute_path;
 
 /// A match found in the source text. Represents a range in the source text that
@@ -202,7 +203,7 @@ impl<F> PawsFsPatch<F> {
 }
 
 #[async_trait::async_trait]
-impl<F: FileWriterInfra + SnapshotRepository> FsPatchService for PawsFsPatch<F> {
+impl<F: EnvironmentInfra + FileWriterInfra + SnapshotRepository> FsPatchService for PawsFsPatch<F> {
     async fn patch(
         &self,
         input_path: String,
@@ -223,8 +224,14 @@ impl<F: FileWriterInfra + SnapshotRepository> FsPatchService for PawsFsPatch<F>
         // Apply the replacement
         current_content = apply_replacement(current_content, search, &operation, &content)?;
 
+        let syntax_errors = syn::check(
+            path,
+            &current_content,
+            self.infra.get_environment().reject_syntax_errors,
+        )?;
+
         // SNAPSHOT COORDINATION: Always capture snapshot before modifying
-        self.infra.insert_snapshot(path).await?;
+        let snapshot = self.infra.insert_snapshot(path).await?;
 
         // Write final content to file after all patches are applied
         self.infra
@@ -234,7 +241,13 @@ impl<F: FileWriterInfra + SnapshotRepository> FsPatchService for PawsFsPatch<F>
         // Compute hash of the final file content
         let content_hash = compute_hash(&current_content);
 
-        Ok(PatchOutput { before: old_content, after: current_content, content_hash })
+        Ok(PatchOutput {
+            before: old_content,
+            after: current_content,
+            content_hash,
+            snapshot: Some(snapshot),
+            syntax_errors,
+        })
     }
 }
 
@@ -32,10 +32,10 @@ impl<F: FileReaderInfra + FileRemoverInfra + SnapshotRepository> FsRemoveService
         let content = self.infra.read_utf8(path).await.unwrap_or_default();
 
         // SNAPSHOT COORDINATION: Always capture snapshot before removing
-        self.infra.insert_snapshot(path).await?;
+        let snapshot = self.infra.insert_snapshot(path).await?;
 
         self.infra.remove(path).await?;
 
-        Ok(FsRemoveOutput { content })
+        Ok(FsRemoveOutput { content, snapshot: Some(snapshot) })
     }
 }
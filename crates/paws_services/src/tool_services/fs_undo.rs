@@ -1,8 +1,12 @@
 use std::path::Path;
 use std::sync::Arc;
 
-use paws_app::{FileInfoInfra, FileReaderInfra, FsUndoOutput, FsUndoService};
-use paws_domain::SnapshotRepository;
+use bytes::Bytes;
+use paws_app::{
+    FileDirectoryInfra, FileInfoInfra, FileReaderInfra, FileRemoverInfra, FileWriterInfra,
+    FsUndoOutput, FsUndoService,
+};
+use paws_domain::{FileCheckpoint, SnapshotRepository};
 
 use crate::utils::assert_absolute_path;
 
@@ -21,7 +25,15 @@ impl<F> PawsFsUndo<F> {
 }
 
 #[async_trait::async_trait]
-impl<F: FileInfoInfra + FileReaderInfra + SnapshotRepository> FsUndoService for PawsFsUndo<F> {
+impl<
+    F: FileInfoInfra
+        + FileReaderInfra
+        + FileWriterInfra
+        + FileRemoverInfra
+        + FileDirectoryInfra
+        + SnapshotRepository,
+> FsUndoService for PawsFsUndo<F>
+{
     async fn undo(&self, path: String) -> anyhow::Result<FsUndoOutput> {
         let mut output = FsUndoOutput::default();
         let path = Path::new(&path);
@@ -36,4 +48,89 @@ impl<F: FileInfoInfra + FileReaderInfra + SnapshotRepository> FsUndoService for
 
         Ok(output)
     }
+    async fn rewind(&self, files: Vec<FileCheckpoint>) -> anyhow::Result<Vec<String>> {
+        // Group the modifications by file, preserving the order in which files were
+        // first modified
+        let mut paths: Vec<String> = Vec::new();
+        for file in &files {
+            if !paths.contains(&file.path) {
+                paths.push(file.path.clone());
+            }
+        }
+
+        // Resolve the content of every file before touching the workspace, so that
+        // an unreadable snapshot doesn't leave it partially restored. `None` means
+        // the file didn't exist at the checkpoint and has to be removed.
+        let mut restores: Vec<(String, Option<Vec<u8>>)> = Vec::new();
+        for path in paths {
+            let mut modifications = files.iter().filter(|file| file.path == path);
+            let Some(first) = modifications.next() else {
+                continue;
+            };
+            let Some(snapshot) = &first.snapshot else {
+                restores.push((path, None));
+                continue;
+            };
+
+            // Undo consumes snapshots, the earliest one that is left always holds the
+            // content the file had at the checkpoint. When none are left, every
+            // modification was undone already.
+            for snapshot in std::iter::once(snapshot)
+                .chain(modifications.filter_map(|file| file.snapshot.as_ref()))
+            {
+                if let Some(content) = self.infra.read_snapshot(snapshot).await? {
+                    restores.push((path.clone(), (!snapshot.removed).then_some(content)));
+                    break;
+                }
+            }
+        }
+
+        // Keep the current content so a failed restore can be rolled back
+        let mut current: Vec<(String, Option<Vec<u8>>)> = Vec::new();
+        for (path, _) in &restores {
+            let file_path = Path::new(path);
+            let content = if self.infra.exists(file_path).await? {
+                Some(self.infra.read(file_path).await?)
+            } else {
+                None
+            };
+            current.push((path.clone(), content));
+        }
+
+        match self.restore(restores).await {
+            Ok(restored) => Ok(restored),
+            Err(error) => {
+                if let Err(rollback_error) = self.restore(current).await {
+                    tracing::error!(error = %rollback_error, "Failed to roll back the rewind");
+                }
+                Err(error)
+            }
+        }
+    }
+}
+
+impl<F: FileInfoInfra + FileWriterInfra + FileRemoverInfra + FileDirectoryInfra> PawsFsUndo<F> {
+    /// Writes the given content to each file, removing the files without
+    /// content. Returns the paths that were changed.
+    async fn restore(&self, files: Vec<(String, Option<Vec<u8>>)>) -> anyhow::Result<Vec<String>> {
+        let mut restored = Vec::new();
+        for (path, content) in files {
+            let file_path = Path::new(&path);
+            match content {
+                Some(content) => {
+                    if let Some(parent) = file_path.parent() {
+                        self.infra.create_dirs(parent).await?;
+                    }
+                    self.infra.write(file_path, Bytes::from(content)).await?;
+                }
+                None if self.infra.exists(file_path).await? => {
+                    self.infra.remove(file_path).await?;
+                }
+                None => continue,
+            }
+            restored.push(path);
+        }
+
+        Ok(restored)
+    }
 }
@@ -1,6 +1,8 @@
 mod fetch;
 mod followup;
 mod fs_create;
+mod fs_multi_patch;
+mod fs_outline;
 mod fs_patch;
 mod fs_read;
 mod fs_remove;
@@ -10,10 +12,13 @@ mod image_read;
 mod plan_create;
 mod shell;
 mod skill;
+pub(crate) mod syn;
 
 pub use fetch::*;
 pub use followup::*;
 pub use fs_create::*;
+pub use fs_multi_patch::*;
+pub use fs_outline::*;
 pub use fs_patch::*;
 pub use fs_read::*;
 pub use fs_remove::*;
@@ -1,8 +1,9 @@
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::bail;
-use paws_app::domain::Environment;
+use paws_app::domain::{ConversationId, Environment, JobId, JobOutput};
 use paws_app::{CommandInfra, EnvironmentInfra, ShellOutput, ShellService};
 use strip_ansi_escapes::strip;
 
@@ -36,6 +37,14 @@ impl<I: EnvironmentInfra> PawsShell<I> {
         }
         Ok(())
     }
+
+    /// Background job output is always stripped of ANSI codes since it is
+    /// only ever read by the model
+    fn strip_job_output(mut job: JobOutput) -> JobOutput {
+        job.output.stdout = strip_ansi(job.output.stdout);
+        job.output.stderr = strip_ansi(job.output.stderr);
+        job
+    }
 }
 
 #[async_trait::async_trait]
@@ -62,6 +71,53 @@ impl<I: CommandInfra + EnvironmentInfra> ShellService for PawsShell<I> {
 
         Ok(ShellOutput { output, shell: self.env.shell.clone() })
     }
+
+    async fn spawn_job(
+        &self,
+        conversation_id: ConversationId,
+        command: String,
+        cwd: PathBuf,
+        env_vars: Option<Vec<String>>,
+    ) -> anyhow::Result<JobOutput> {
+        Self::validate_command(&command)?;
+
+        let job = self
+            .infra
+            .spawn_job(conversation_id, command, cwd, env_vars)
+            .await?;
+        Ok(Self::strip_job_output(job))
+    }
+
+    async fn read_job(
+        &self,
+        conversation_id: ConversationId,
+        job_id: JobId,
+    ) -> anyhow::Result<JobOutput> {
+        let job = self.infra.read_job(conversation_id, job_id).await?;
+        Ok(Self::strip_job_output(job))
+    }
+
+    async fn wait_job(
+        &self,
+        conversation_id: ConversationId,
+        job_id: JobId,
+        timeout: Duration,
+    ) -> anyhow::Result<JobOutput> {
+        let job = self
+            .infra
+            .wait_job(conversation_id, job_id, timeout)
+            .await?;
+        Ok(Self::strip_job_output(job))
+    }
+
+    async fn kill_job(
+        &self,
+        conversation_id: ConversationId,
+        job_id: JobId,
+    ) -> anyhow::Result<JobOutput> {
+        let job = self.infra.kill_job(conversation_id, job_id).await?;
+        Ok(Self::strip_job_output(job))
+    }
 }
 #[cfg(test)]
 mod tests {
@@ -108,6 +164,50 @@ mod tests {
         ) -> anyhow::Result<std::process::ExitStatus> {
             unimplemented!()
         }
+
+        async fn execute_command_with_input(
+            &self,
+            _command: String,
+            _working_dir: PathBuf,
+            _input: String,
+        ) -> anyhow::Result<CommandOutput> {
+            unimplemented!()
+        }
+
+        async fn spawn_job(
+            &self,
+            _conversation_id: ConversationId,
+            _command: String,
+            _working_dir: PathBuf,
+            _env_vars: Option<Vec<String>>,
+        ) -> anyhow::Result<JobOutput> {
+            unimplemented!()
+        }
+
+        async fn read_job(
+            &self,
+            _conversation_id: ConversationId,
+            _job_id: JobId,
+        ) -> anyhow::Result<JobOutput> {
+            unimplemented!()
+        }
+
+        async fn wait_job(
+            &self,
+            _conversation_id: ConversationId,
+            _job_id: JobId,
+            _timeout: Duration,
+        ) -> anyhow::Result<JobOutput> {
+            unimplemented!()
+        }
+
+        async fn kill_job(
+            &self,
+            _conversation_id: ConversationId,
+            _job_id: JobId,
+        ) -> anyhow::Result<JobOutput> {
+            unimplemented!()
+        }
     }
 
     impl EnvironmentInfra for MockCommandInfra {
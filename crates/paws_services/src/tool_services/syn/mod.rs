@@ -1,3 +1,5 @@
+mod outline;
 mod validate;
 
-pub use validate::validate;
+pub use outline::{find_symbol, is_outlinable, outline};
+pub use validate::{check, language};
@@ -0,0 +1,420 @@
+use std::path::Path;
+
+use paws_app::{Symbol, SymbolKind};
+use tree_sitter::{Node, Parser, Tree};
+
+use super::validate::language;
+
+/// Maximum length of a signature, longer ones are cut with an ellipsis
+const MAX_SIGNATURE_CHARS: usize = 160;
+
+/// A line referencing a symbol
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reference {
+    /// 1-based line of the reference
+    pub line: usize,
+    /// The referencing line, trimmed
+    pub text: String,
+}
+
+/// Data formats such as JSON, YAML and TOML have a grammar for validation but
+/// define no symbols
+const DATA_EXTENSIONS: [&str; 4] = ["json", "yaml", "yml", "toml"];
+
+/// Returns whether symbols of the file at `path` can be listed, based on its
+/// extension
+pub fn is_outlinable(path: &Path) -> bool {
+    let is_data = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| DATA_EXTENSIONS.contains(&extension.to_lowercase().as_str()));
+    !is_data && language(path).is_some()
+}
+
+fn parse(path: &Path, content: &str) -> Option<Tree> {
+    if !is_outlinable(path) {
+        return None;
+    }
+    let language = language(path)?;
+    let mut parser = Parser::new();
+    parser.set_language(&language).ok()?;
+    parser.parse(content, None)
+}
+
+/// Lists the symbols defined in `content`, nested by scope. Returns `None`
+/// when the language of `path` isn't supported.
+pub fn outline(path: impl AsRef<Path>, content: &str) -> Option<Vec<Symbol>> {
+    let tree = parse(path.as_ref(), content)?;
+    let mut symbols = Vec::new();
+    collect(tree.root_node(), content, false, &mut symbols);
+    Some(symbols)
+}
+
+/// Finds the definitions of the symbol called `name` and the lines
+/// referencing it. Returns `None` when the language of `path` isn't
+/// supported.
+pub fn find_symbol(
+    path: impl AsRef<Path>,
+    content: &str,
+    name: &str,
+) -> Option<(Vec<Symbol>, Vec<Reference>)> {
+    let tree = parse(path.as_ref(), content)?;
+
+    let mut symbols = Vec::new();
+    collect(tree.root_node(), content, false, &mut symbols);
+    let mut definitions = Vec::new();
+    flatten_matching(symbols, name, &mut definitions);
+
+    let lines: Vec<&str> = content.lines().collect();
+    let mut references: Vec<Reference> = Vec::new();
+    let mut cursor = tree.walk();
+    loop {
+        let node = cursor.node();
+        if node.child_count() == 0
+            && node.kind().ends_with("identifier")
+            && text(node, content) == name
+            && !is_definition_name(node)
+        {
+            let line = node.start_position().row + 1;
+            if references.last().is_none_or(|last| last.line != line) {
+                let text = lines
+                    .get(line - 1)
+                    .map(|text| text.trim())
+                    .unwrap_or_default();
+                references.push(Reference { line, text: truncate(text) });
+            }
+        }
+
+        if cursor.goto_first_child() || cursor.goto_next_sibling() {
+            continue;
+        }
+        loop {
+            if !cursor.goto_parent() {
+                return Some((definitions, references));
+            }
+            if cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+}
+
+fn flatten_matching(symbols: Vec<Symbol>, name: &str, definitions: &mut Vec<Symbol>) {
+    for mut symbol in symbols {
+        let children = std::mem::take(&mut symbol.children);
+        // An impl block extends a type rather than defining it
+        if symbol.name == name && symbol.kind != SymbolKind::Impl {
+            definitions.push(symbol);
+        }
+        flatten_matching(children, name, definitions);
+    }
+}
+
+/// Whether `node` is the name of a definition rather than a reference
+fn is_definition_name(node: Node<'_>) -> bool {
+    node.parent().is_some_and(|parent| {
+        classify(parent, false).is_some() && parent.child_by_field_name("name") == Some(node)
+    })
+}
+
+/// Returns the kind of symbol `node` defines and whether it can contain other
+/// symbols. `in_type` is set within impls, traits, classes and interfaces,
+/// where functions are methods.
+fn classify(node: Node<'_>, in_type: bool) -> Option<(SymbolKind, bool)> {
+    let function = if in_type {
+        SymbolKind::Method
+    } else {
+        SymbolKind::Function
+    };
+    let symbol = match node.kind() {
+        // Rust
+        "function_item" | "function_signature_item" => (function, false),
+        "struct_item" => (SymbolKind::Struct, false),
+        "enum_item" => (SymbolKind::Enum, false),
+        "union_item" => (SymbolKind::Union, false),
+        "trait_item" => (SymbolKind::Trait, true),
+        "impl_item" => (SymbolKind::Impl, true),
+        "mod_item" => (SymbolKind::Module, true),
+        "const_item" | "static_item" => (SymbolKind::Constant, false),
+        "type_item" => (SymbolKind::Type, false),
+        "macro_definition" => (SymbolKind::Macro, false),
+        // Python
+        "function_definition" => (function, false),
+        "class_definition" => (SymbolKind::Class, true),
+        // JavaScript and TypeScript, Go shares `function_declaration`
+        "function_declaration" | "generator_function_declaration" => (function, false),
+        "method_definition" | "method_signature" | "abstract_method_signature" => {
+            (SymbolKind::Method, false)
+        }
+        "class_declaration" | "abstract_class_declaration" => (SymbolKind::Class, true),
+        "interface_declaration" => (SymbolKind::Interface, true),
+        "type_alias_declaration" => (SymbolKind::Type, false),
+        "enum_declaration" => (SymbolKind::Enum, false),
+        "internal_module" => (SymbolKind::Module, true),
+        "variable_declarator" => {
+            let value = node.child_by_field_name("value")?;
+            match value.kind() {
+                "arrow_function" | "function_expression" | "function" => (function, false),
+                _ => return None,
+            }
+        }
+        // Go
+        "method_declaration" => (SymbolKind::Method, false),
+        "type_spec" => match node.child_by_field_name("type").map(|node| node.kind()) {
+            Some("struct_type") => (SymbolKind::Struct, false),
+            Some("interface_type") => (SymbolKind::Interface, false),
+            _ => (SymbolKind::Type, false),
+        },
+        _ => return None,
+    };
+    Some(symbol)
+}
+
+fn collect(node: Node<'_>, content: &str, in_type: bool, symbols: &mut Vec<Symbol>) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        let Some((kind, container)) = classify(child, in_type) else {
+            collect(child, content, in_type, symbols);
+            continue;
+        };
+
+        let body = child.child_by_field_name("body");
+        let mut children = Vec::new();
+        if container {
+            let in_type = kind != SymbolKind::Module;
+            collect(body.unwrap_or(child), content, in_type, &mut children);
+        }
+        symbols.push(Symbol {
+            kind,
+            name: name(child, content),
+            signature: signature(child, body, content),
+            start_line: child.start_position().row + 1,
+            end_line: child.end_position().row + 1,
+            children,
+        });
+    }
+}
+
+fn name(node: Node<'_>, content: &str) -> String {
+    node.child_by_field_name("name")
+        .or_else(|| node.child_by_field_name("type"))
+        .map(|name| text(name, content).to_string())
+        .unwrap_or_default()
+}
+
+/// The declaration of `node` up to its body, or its first line when it has
+/// none, on a single line
+fn signature(node: Node<'_>, body: Option<Node<'_>>, content: &str) -> String {
+    let end = body.map_or(node.end_byte(), |body| body.start_byte());
+    let declaration = content.get(node.start_byte()..end).unwrap_or_default();
+    let declaration = if body.is_some() {
+        declaration
+    } else {
+        declaration.lines().next().unwrap_or_default()
+    };
+    let signature = declaration.split_whitespace().collect::<Vec<_>>().join(" ");
+    let signature = signature
+        .trim_end_matches(['{', ':', '=', ' '])
+        .trim_end_matches("=>")
+        .trim_end();
+    truncate(signature)
+}
+
+fn truncate(text: &str) -> String {
+    if text.chars().count() > MAX_SIGNATURE_CHARS {
+        let truncated: String = text.chars().take(MAX_SIGNATURE_CHARS).collect();
+        format!("{truncated}…")
+    } else {
+        text.to_string()
+    }
+}
+
+fn text<'a>(node: Node<'_>, content: &'a str) -> &'a str {
+    content.get(node.byte_range()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    /// Renders symbols as `kind name (start-end): signature` lines
+    fn render(symbols: &[Symbol]) -> Vec<String> {
+        let mut lines = Vec::new();
+        fn walk(symbols: &[Symbol], depth: usize, lines: &mut Vec<String>) {
+            for symbol in symbols {
+                lines.push(format!(
+                    "{}{} {} ({}-{}): {}",
+                    "  ".repeat(depth),
+                    symbol.kind,
+                    symbol.name,
+                    symbol.start_line,
+                    symbol.end_line,
+                    symbol.signature
+                ));
+                walk(&symbol.children, depth + 1, lines);
+            }
+        }
+        walk(symbols, 0, &mut lines);
+        lines
+    }
+
+    #[test]
+    fn test_outline_rust() {
+        let fixture = r#"use std::fmt;
+
+pub const LIMIT: usize = 10;
+
+/// A user
+pub struct User {
+    name: String,
+}
+
+impl User {
+    pub fn new(name: String) -> Self {
+        Self { name }
+    }
+
+    fn helper(&self) {}
+}
+
+pub trait Greet {
+    fn greet(&self) -> String;
+}
+
+mod inner {
+    pub fn nested() {}
+}
+"#;
+
+        let actual = render(&outline("lib.rs", fixture).unwrap());
+
+        let expected = vec![
+            "constant LIMIT (3-3): pub const LIMIT: usize = 10;",
+            "struct User (6-8): pub struct User",
+            "impl User (10-16): impl User",
+            "  method new (11-13): pub fn new(name: String) -> Self",
+            "  method helper (15-15): fn helper(&self)",
+            "trait Greet (18-20): pub trait Greet",
+            "  method greet (19-19): fn greet(&self) -> String;",
+            "module inner (22-24): mod inner",
+            "  function nested (23-23): pub fn nested()",
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_outline_python() {
+        let fixture = r#"import os
+
+class Service(Base):
+    def run(self, arg):
+        return arg
+
+@cache
+def helper(value):
+    return value
+"#;
+
+        let actual = render(&outline("service.py", fixture).unwrap());
+
+        let expected = vec![
+            "class Service (3-5): class Service(Base)",
+            "  method run (4-5): def run(self, arg)",
+            "function helper (8-9): def helper(value)",
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_outline_typescript() {
+        let fixture = r#"export interface Props {
+  name: string;
+}
+
+export class Widget {
+  render(props: Props): string {
+    return props.name;
+  }
+}
+
+export const build = (name: string) => {
+  return new Widget();
+};
+
+type Id = string;
+"#;
+
+        let actual = render(&outline("widget.ts", fixture).unwrap());
+
+        let expected = vec![
+            "interface Props (1-3): interface Props",
+            "class Widget (5-9): class Widget",
+            "  method render (6-8): render(props: Props): string",
+            "function build (11-13): build = (name: string)",
+            "type Id (15-15): type Id = string;",
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_outline_go() {
+        let fixture = r#"package main
+
+type Server struct {
+	addr string
+}
+
+func (s *Server) Start() error {
+	return nil
+}
+
+func main() {}
+"#;
+
+        let actual = render(&outline("main.go", fixture).unwrap());
+
+        let expected = vec![
+            "struct Server (3-5): Server struct",
+            "method Start (7-9): func (s *Server) Start() error",
+            "function main (11-11): func main()",
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_outline_unsupported_language() {
+        let actual = outline("notes.txt", "fn main() {}");
+
+        assert_eq!(actual, None);
+    }
+
+    #[test]
+    fn test_find_symbol() {
+        let fixture = r#"pub struct User;
+
+impl User {
+    pub fn new() -> User {
+        // User in a comment is ignored
+        User
+    }
+}
+
+fn make() -> User { User::new() }
+"#;
+
+        let (definitions, references) = find_symbol("lib.rs", fixture, "User").unwrap();
+
+        let actual = render(&definitions);
+        let expected = vec!["struct User (1-1): pub struct User;"];
+        assert_eq!(actual, expected);
+
+        let actual = references
+            .into_iter()
+            .map(|reference| reference.line)
+            .collect::<Vec<_>>();
+        let expected = vec![3, 4, 6, 10];
+        assert_eq!(actual, expected);
+    }
+}
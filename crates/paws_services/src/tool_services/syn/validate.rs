@@ -0,0 +1,195 @@
+use std::path::Path;
+
+use paws_app::SyntaxError;
+use tree_sitter::{Language, Node, Parser};
+
+/// Maximum number of errors reported for a single file. A single mistake such
+/// as an unclosed delimiter tends to cascade, and the first few locations are
+/// enough to find it.
+const MAX_ERRORS: usize = 10;
+
+/// Maximum length of the unexpected source text quoted in an error message
+const MAX_SNIPPET_CHARS: usize = 40;
+
+/// Returns the tree-sitter grammar used to parse the file at `path`, based on
+/// its extension
+pub fn language(path: &Path) -> Option<Language> {
+    let extension = path.extension()?.to_str()?.to_lowercase();
+    let language = match extension.as_str() {
+        "rs" => tree_sitter_rust::LANGUAGE,
+        "ts" | "mts" | "cts" => tree_sitter_typescript::LANGUAGE_TYPESCRIPT,
+        "tsx" => tree_sitter_typescript::LANGUAGE_TSX,
+        "js" | "mjs" | "cjs" | "jsx" => tree_sitter_javascript::LANGUAGE,
+        "py" | "pyi" => tree_sitter_python::LANGUAGE,
+        "go" => tree_sitter_go::LANGUAGE,
+        "json" => tree_sitter_json::LANGUAGE,
+        "yaml" | "yml" => tree_sitter_yaml::LANGUAGE,
+        "toml" => tree_sitter_toml_ng::LANGUAGE,
+        _ => return None,
+    };
+    Some(language.into())
+}
+
+/// Parses `content` with the grammar matching the extension of `path` and
+/// returns the location of every parse error. Files in unsupported languages
+/// are never reported as invalid.
+pub fn validate(path: impl AsRef<Path>, content: &str) -> Vec<SyntaxError> {
+    let Some(language) = language(path.as_ref()) else {
+        return Vec::new();
+    };
+    let mut parser = Parser::new();
+    if parser.set_language(&language).is_err() {
+        return Vec::new();
+    }
+    let Some(tree) = parser.parse(content, None) else {
+        return Vec::new();
+    };
+
+    let mut errors = Vec::new();
+    collect_errors(tree.root_node(), content, &mut errors);
+    errors
+}
+
+/// Validates `content` before it is written to `path`, failing instead of
+/// returning the errors when `reject` is set
+pub fn check(
+    path: impl AsRef<Path>,
+    content: &str,
+    reject: bool,
+) -> anyhow::Result<Vec<SyntaxError>> {
+    let path = path.as_ref();
+    let errors = validate(path, content);
+    if reject && !errors.is_empty() {
+        let locations = errors
+            .iter()
+            .map(|error| format!("{}:{}: {}", error.line, error.column, error.message))
+            .collect::<Vec<_>>()
+            .join("\n");
+        anyhow::bail!(
+            "Edit rejected because it leaves {} with syntax errors. Fix the following and try again:\n{locations}",
+            path.display()
+        );
+    }
+    Ok(errors)
+}
+
+fn collect_errors(node: Node<'_>, content: &str, errors: &mut Vec<SyntaxError>) {
+    if errors.len() >= MAX_ERRORS || !node.has_error() {
+        return;
+    }
+
+    if node.is_missing() || node.is_error() {
+        let position = node.start_position();
+        let message = if node.is_missing() {
+            format!("missing `{}`", node.kind())
+        } else {
+            unexpected(node, content)
+        };
+        errors.push(SyntaxError { line: position.row + 1, column: position.column + 1, message });
+        return;
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_errors(child, content, errors);
+    }
+}
+
+fn unexpected(node: Node<'_>, content: &str) -> String {
+    let text = content
+        .get(node.byte_range())
+        .unwrap_or_default()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if text.is_empty() {
+        return "unexpected end of input".to_string();
+    }
+    let snippet: String = text.chars().take(MAX_SNIPPET_CHARS).collect();
+    if snippet.len() < text.len() {
+        format!("unexpected `{snippet}…`")
+    } else {
+        format!("unexpected `{snippet}`")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_validate_valid_sources() {
+        let fixtures = [
+            ("main.rs", "fn main() {\n    println!(\"hi\");\n}\n"),
+            (
+                "index.ts",
+                "const x: number = 1;\nexport function f(a: string) { return a; }\n",
+            ),
+            ("app.tsx", "export const App = () => <div>{1}</div>;\n"),
+            ("app.js", "const f = (a) => a + 1;\n"),
+            ("main.py", "def f(a):\n    return a + 1\n"),
+            ("main.go", "package main\n\nfunc main() {}\n"),
+            ("data.json", "{\"a\": [1, 2, 3]}\n"),
+            ("config.yaml", "a:\n  b: 1\n  c: [1, 2]\n"),
+            ("Cargo.toml", "[package]\nname = \"paws\"\n"),
+        ];
+
+        for (path, content) in fixtures {
+            let actual = validate(path, content);
+            assert_eq!(actual, vec![], "{path} should be valid");
+        }
+    }
+
+    #[test]
+    fn test_validate_reports_error_location() {
+        let fixture = "fn main() {\n    let x = ;\n}\n";
+
+        let actual = validate("main.rs", fixture);
+
+        let expected =
+            vec![SyntaxError { line: 2, column: 11, message: "unexpected `=`".to_string() }];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_validate_reports_missing_token() {
+        let fixture = "{\"a\": 1, \"b\": [1, 2}\n";
+
+        let actual = validate("data.json", fixture);
+
+        assert!(!actual.is_empty());
+        assert_eq!(actual[0].line, 1);
+    }
+
+    #[test]
+    fn test_validate_invalid_python() {
+        let fixture = "def f(a:\n    return a\n";
+
+        let actual = validate("main.py", fixture);
+
+        assert!(!actual.is_empty());
+    }
+
+    #[test]
+    fn test_validate_unsupported_extension() {
+        let actual = validate("notes.txt", "fn main( {");
+
+        assert_eq!(actual, vec![]);
+    }
+
+    #[test]
+    fn test_check_rejects_invalid_content() {
+        let actual = check("/project/main.rs", "fn main( {", true).unwrap_err();
+
+        assert!(actual.to_string().contains("/project/main.rs"));
+    }
+
+    #[test]
+    fn test_check_reports_without_rejecting() {
+        let actual = check("/project/main.rs", "fn main( {", false).unwrap();
+
+        assert!(!actual.is_empty());
+    }
+}